@@ -1,14 +1,18 @@
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use aideon_engine::{
     BaselineDataset, GraphSnapshot, MetaModelRegistry, PraxisEngine, PraxisEngineConfig,
 };
+use aideon_mneme::meta::MetaRuleSeverity;
 use aideon_mneme::temporal::{ChangeSet, CommitSummary};
 use aideon_mneme::{
-    MemoryStore, PersistedCommit, SqliteDb, Store, create_datastore, datastore_path,
+    CommitBatch, CompactionOptions, GcOptions, KeySource, MemoryStore, MigrationKind,
+    PersistedCommit, RefUpdate, SqliteDb, Store, backups_dir, bundle, create_datastore,
+    datastore_encryption, datastore_path, lock_holder, restore_backup,
 };
 use anyhow::{Context, Result, anyhow};
 use clap::{Parser, Subcommand};
@@ -20,6 +24,15 @@ async fn main() -> Result<()> {
         Command::MigrateState(args) => migrate_state(args).await,
         Command::ImportDataset(args) => import_dataset(args).await,
         Command::Health(args) => check_health(args).await,
+        Command::Gc(args) => collect_garbage(args).await,
+        Command::BundleExport(args) => export_bundle(args).await,
+        Command::BundleImport(args) => import_bundle(args).await,
+        Command::Reencode(args) => reencode_commits(args).await,
+        Command::RotateKey(args) => rotate_key(args).await,
+        Command::Backup(args) => backup_datastore(args).await,
+        Command::Restore(args) => restore_datastore(args).await,
+        Command::Schema(args) => schema_migrations(args).await,
+        Command::MetaSchema(args) => export_meta_schema(args),
     }
 }
 
@@ -42,6 +55,46 @@ enum Command {
     ImportDataset(ImportDatasetArgs),
     /// Validate datastore integrity by scanning commits, heads, and snapshots.
     Health(HealthArgs),
+    /// Remove unreachable commits and optionally compact old history into a checkpoint.
+    Gc(GcArgs),
+    /// Export branch histories and the blobs they reference into a bundle directory.
+    BundleExport(BundleExportArgs),
+    /// Import a bundle directory's blobs and commits (branch refs are left untouched).
+    BundleImport(BundleImportArgs),
+    /// Convert commits stored as plain JSON to compressed, deduplicated change sets.
+    Reencode(ReencodeArgs),
+    /// Re-wrap an encrypted datastore's keys under a new passphrase or key file.
+    RotateKey(RotateKeyArgs),
+    /// Take a consistent online backup of a datastore (rotating under `<datastore>/backups`).
+    Backup(BackupArgs),
+    /// Restore a backup directory into a new, empty datastore.
+    Restore(RestoreArgs),
+    /// Report applied and pending schema migrations, print their SQL, or apply them.
+    Schema(SchemaArgs),
+    /// Export the meta-model as JSON Schema for validating dataset import files.
+    MetaSchema(MetaSchemaArgs),
+}
+
+/// Key for an encrypted datastore; required when `datastore.json` records an encryption mode.
+#[derive(Parser)]
+struct KeyArgs {
+    /// Environment variable holding the datastore passphrase.
+    #[arg(long, conflicts_with = "key_file")]
+    passphrase_env: Option<String>,
+    /// File holding at least 32 bytes of key material.
+    #[arg(long)]
+    key_file: Option<PathBuf>,
+}
+
+impl KeyArgs {
+    fn source(&self) -> Result<Option<KeySource>> {
+        if let Some(var) = &self.passphrase_env {
+            let passphrase =
+                std::env::var(var).with_context(|| format!("read passphrase from ${var}"))?;
+            return Ok(Some(KeySource::Passphrase(passphrase)));
+        }
+        Ok(self.key_file.clone().map(KeySource::KeyFile))
+    }
 }
 
 #[derive(Parser)]
@@ -71,6 +124,9 @@ struct ImportDatasetArgs {
     /// Remove any existing datastore contents before importing.
     #[arg(long, default_value_t = false)]
     force: bool,
+    /// Encrypt a new datastore with this key (or unlock an existing encrypted one).
+    #[command(flatten)]
+    key: KeyArgs,
 }
 
 #[derive(Parser)]
@@ -84,6 +140,126 @@ struct HealthArgs {
     /// Reduce output to errors only.
     #[arg(long, default_value_t = false)]
     quiet: bool,
+    #[command(flatten)]
+    key: KeyArgs,
+}
+
+#[derive(Parser)]
+struct GcArgs {
+    /// Directory where the datastore lives (contains datastore.json and sqlite file).
+    #[arg(long, default_value = ".praxis")]
+    datastore: PathBuf,
+    /// Keep unreachable commits and reflog entries younger than this many days.
+    #[arg(long, default_value_t = 30)]
+    retention_days: u64,
+    /// Collapse the first-parent history up to and including this commit into a checkpoint.
+    #[arg(long)]
+    compact_before: Option<String>,
+    /// Report what would be removed without modifying the datastore.
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+    #[command(flatten)]
+    key: KeyArgs,
+}
+
+#[derive(Parser)]
+struct BundleExportArgs {
+    /// Directory where the datastore lives (contains datastore.json and sqlite file).
+    #[arg(long, default_value = ".praxis")]
+    datastore: PathBuf,
+    /// Branches whose history is exported.
+    #[arg(long = "branch", default_value = "main")]
+    branches: Vec<String>,
+    /// Bundle directory to write.
+    #[arg(long)]
+    output: PathBuf,
+    #[command(flatten)]
+    key: KeyArgs,
+}
+
+#[derive(Parser)]
+struct BundleImportArgs {
+    /// Directory where the datastore lives (contains datastore.json and sqlite file).
+    #[arg(long, default_value = ".praxis")]
+    datastore: PathBuf,
+    /// Bundle directory to read.
+    #[arg(long)]
+    input: PathBuf,
+    #[command(flatten)]
+    key: KeyArgs,
+}
+
+#[derive(Parser)]
+struct ReencodeArgs {
+    /// Directory where the datastore lives (contains datastore.json and sqlite file).
+    #[arg(long, default_value = ".praxis")]
+    datastore: PathBuf,
+    #[command(flatten)]
+    key: KeyArgs,
+}
+
+#[derive(Parser)]
+struct RotateKeyArgs {
+    /// Directory where the datastore lives (contains datastore.json and sqlite file).
+    #[arg(long, default_value = ".praxis")]
+    datastore: PathBuf,
+    /// Current key.
+    #[command(flatten)]
+    key: KeyArgs,
+    /// Environment variable holding the new passphrase.
+    #[arg(long, conflicts_with = "new_key_file")]
+    new_passphrase_env: Option<String>,
+    /// File holding the new key material.
+    #[arg(long)]
+    new_key_file: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+struct BackupArgs {
+    /// Directory where the datastore lives (contains datastore.json and sqlite file).
+    #[arg(long, default_value = ".praxis")]
+    datastore: PathBuf,
+    /// Backup directory to write; defaults to a new rotating backup under `<datastore>/backups`.
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Rotating backups to keep (ignored with --output).
+    #[arg(long, default_value_t = 7)]
+    keep: usize,
+    #[command(flatten)]
+    key: KeyArgs,
+}
+
+#[derive(Parser)]
+struct RestoreArgs {
+    /// Backup directory to restore (contains manifest.json).
+    #[arg(long)]
+    input: PathBuf,
+    /// Directory for the new datastore; must not already hold data.
+    #[arg(long)]
+    datastore: PathBuf,
+    /// File name of the restored sqlite database.
+    #[arg(long)]
+    name: Option<String>,
+}
+
+#[derive(Parser)]
+struct SchemaArgs {
+    /// Directory where the datastore lives (contains datastore.json and sqlite file).
+    #[arg(long, default_value = ".praxis")]
+    datastore: PathBuf,
+    /// Print the SQL pending migrations would run without applying them.
+    #[arg(long, default_value_t = false, conflicts_with = "apply")]
+    dry_run: bool,
+    /// Apply pending migrations (no key needed for encrypted datastores).
+    #[arg(long, default_value_t = false)]
+    apply: bool,
+}
+
+#[derive(Parser)]
+struct MetaSchemaArgs {
+    /// Write the schema to this file instead of stdout.
+    #[arg(long)]
+    out: Option<PathBuf>,
 }
 
 async fn migrate_state(args: MigrateStateArgs) -> Result<()> {
@@ -116,51 +292,55 @@ async fn migrate_state(args: MigrateStateArgs) -> Result<()> {
         .await
         .map_err(|err| anyhow!(err.to_string()))?;
 
-    let mut snapshots: HashMap<String, GraphSnapshot> = HashMap::new();
-    let mut last_commit_id: Option<String> = None;
+    // Snapshot and schema after each replayed commit; commits may change the meta-model entries.
+    let mut snapshots: HashMap<String, (GraphSnapshot, Arc<MetaModelRegistry>)> = HashMap::new();
+    let mut batch = CommitBatch::default();
 
-    let registry = MetaModelRegistry::embedded().map_err(|err| anyhow!(err.to_string()))?;
+    let configured =
+        Arc::new(MetaModelRegistry::embedded().map_err(|err| anyhow!(err.to_string()))?);
 
+    // Replay every commit before writing anything, then persist them in one transaction.
     for commit in legacy.commits {
-        let base = match commit.summary.parents.first() {
+        let (base, registry) = match commit.summary.parents.first() {
             Some(parent) => snapshots.get(parent).cloned().ok_or_else(|| {
                 anyhow!("missing parent '{parent}' for commit {}", commit.summary.id)
             })?,
-            None => GraphSnapshot::empty(),
+            None => (GraphSnapshot::empty(), Arc::clone(&configured)),
         };
         let next = base
             .apply(&commit.change_set, &registry)
             .map_err(|err| anyhow!("apply commit {} failed: {err}", commit.summary.id))?;
+        let blobs = registry.blob_refs(&commit.change_set);
+        let registry = registry
+            .after_change(&commit.change_set, &base, &next)
+            .map_err(|err| anyhow!("apply commit {} failed: {err}", commit.summary.id))?;
 
-        let persisted = PersistedCommit {
-            summary: commit.summary.clone(),
-            change_set: commit.change_set.clone(),
-        };
-        db.put_commit(&persisted)
-            .await
-            .map_err(|err| anyhow!(err.to_string()))?;
-
-        last_commit_id = Some(persisted.summary.id.clone());
-        snapshots.insert(persisted.summary.id, next);
+        snapshots.insert(commit.summary.id.clone(), (next, registry));
+        batch.commits.push(PersistedCommit {
+            summary: commit.summary,
+            change_set: commit.change_set,
+            blobs,
+        });
     }
 
     if legacy.branches.is_empty() {
-        db.ensure_branch("main")
-            .await
-            .map_err(|err| anyhow!(err.to_string()))?;
-        db.compare_and_swap_branch("main", None, last_commit_id.as_deref())
-            .await
-            .map_err(|err| anyhow!(err.to_string()))?;
+        batch.refs.push(RefUpdate {
+            branch: "main".into(),
+            expected: None,
+            next: batch.commits.last().map(|commit| commit.summary.id.clone()),
+        });
     } else {
-        for branch in legacy.branches {
-            db.ensure_branch(&branch.name)
-                .await
-                .map_err(|err| anyhow!(err.to_string()))?;
-            db.compare_and_swap_branch(&branch.name, None, branch.head.as_deref())
-                .await
-                .map_err(|err| anyhow!(err.to_string()))?;
-        }
+        batch
+            .refs
+            .extend(legacy.branches.into_iter().map(|branch| RefUpdate {
+                branch: branch.name,
+                expected: None,
+                next: branch.head,
+            }));
     }
+    db.put_commits(&batch)
+        .await
+        .map_err(|err| anyhow!(err.to_string()))?;
 
     println!(
         "Migrated {} commits into {}",
@@ -185,11 +365,10 @@ async fn import_dataset(args: ImportDatasetArgs) -> Result<()> {
             .with_context(|| format!("failed to clean {}", args.datastore.display()))?;
     }
 
-    let db_path =
-        create_datastore(&args.datastore, None).map_err(|err| anyhow!(err.to_string()))?;
-    let storage = SqliteDb::open(&db_path)
-        .await
+    let key = args.key.source()?;
+    create_datastore(&args.datastore, None, key.as_ref().map(KeySource::mode))
         .map_err(|err| anyhow!(err.to_string()))?;
+    let (db_path, storage) = open_datastore(&args.datastore, &args.key).await?;
     let engine = PraxisEngine::with_stores_unseeded(
         PraxisEngineConfig::default(),
         Arc::new(storage.clone()),
@@ -245,9 +424,22 @@ async fn import_dataset(args: ImportDatasetArgs) -> Result<()> {
 async fn check_health(args: HealthArgs) -> Result<()> {
     let db_path = datastore_path(&args.datastore)
         .with_context(|| format!("resolve datastore under {}", args.datastore.display()))?;
-    let storage = SqliteDb::open(&db_path)
+    // Read before opening: opening applies pending migrations.
+    let schema = SqliteDb::schema_status(&db_path)
         .await
         .map_err(|err| anyhow!(err.to_string()))?;
+    if schema.is_newer_than_supported() {
+        return Err(anyhow!(
+            "health check failed: datastore schema is newer than this build (unknown migrations: {})",
+            schema.unknown.join(", ")
+        ));
+    }
+    // A current schema needs no writes, so check without the writer lock.
+    let (db_path, storage) = if schema.is_current() {
+        open_datastore_read_only(&args.datastore, &args.key).await?
+    } else {
+        open_datastore(&args.datastore, &args.key).await?
+    };
     let engine = PraxisEngine::with_stores_unseeded(
         PraxisEngineConfig::default(),
         Arc::new(storage.clone()),
@@ -357,10 +549,38 @@ async fn check_health(args: HealthArgs) -> Result<()> {
                 }),
             }
         }
+
+        if let Some(head) = &branch.head {
+            match engine.multiplicity_violations(head).await {
+                Ok(violations) => {
+                    findings.extend(violations.into_iter().map(|violation| Finding {
+                        kind: match violation.severity {
+                            MetaRuleSeverity::Error => "error",
+                            MetaRuleSeverity::Warning => "warning",
+                        },
+                        message: format!("branch '{}': {}", branch_name, violation),
+                    }))
+                }
+                Err(err) => findings.push(Finding {
+                    kind: "error",
+                    message: format!("multiplicity check failed for {}: {}", head, err),
+                }),
+            }
+        }
     }
 
     if !args.quiet {
         println!("Datastore: {}", db_path.display());
+        println!(
+            "Schema: {} ({} pending migrations applied on open)",
+            schema.version().unwrap_or("empty"),
+            schema.pending().count()
+        );
+        match lock_holder(&db_path).map_err(|err| anyhow!(err.to_string()))? {
+            Some(holder) if holder.is_stale() => println!("Writer lock: stale ({holder})"),
+            Some(holder) => println!("Writer lock: held by {holder}"),
+            None => println!("Writer lock: free"),
+        }
         println!("Branches scanned: {}", filtered.len());
         println!("Commits scanned: {}", commit_total);
     }
@@ -391,6 +611,266 @@ async fn check_health(args: HealthArgs) -> Result<()> {
     Ok(())
 }
 
+async fn collect_garbage(args: GcArgs) -> Result<()> {
+    let (_, storage) = open_datastore(&args.datastore, &args.key).await?;
+    let options = GcOptions {
+        retention: Duration::from_secs(args.retention_days * 24 * 60 * 60),
+        compact: args.compact_before.map(CompactionOptions::new),
+        dry_run: args.dry_run,
+    };
+    let report = storage
+        .collect_garbage(&options)
+        .await
+        .map_err(|err| anyhow!(err.to_string()))?;
+
+    let verb = if report.dry_run {
+        "would remove"
+    } else {
+        "removed"
+    };
+    if let Some(checkpoint) = &report.checkpoint {
+        println!(
+            "compacted {} commits into checkpoint {}",
+            report.compacted_commits, checkpoint
+        );
+    }
+    println!(
+        "gc {verb} {} commits ({} events, {} node changes, {} edge changes, {} tags); \
+         reachable={} retained={} expired reflog entries={}",
+        report.removed_commits.len(),
+        report.removed_events,
+        report.removed_node_changes,
+        report.removed_edge_changes,
+        report.removed_tags,
+        report.reachable_commits,
+        report.retained_commits,
+        report.expired_reflog_entries
+    );
+    if report.removed_blobs > 0 {
+        println!(
+            "gc {verb} {} unreferenced blobs ({} bytes)",
+            report.removed_blobs, report.removed_blob_bytes
+        );
+    }
+    Ok(())
+}
+
+async fn export_bundle(args: BundleExportArgs) -> Result<()> {
+    let (_, storage) = open_datastore(&args.datastore, &args.key).await?;
+    let mut heads = Vec::new();
+    for branch in &args.branches {
+        let head = storage
+            .get_branch_head(branch)
+            .await
+            .map_err(|err| anyhow!(err.to_string()))?
+            .ok_or_else(|| anyhow!("branch '{branch}' has no commits"))?;
+        heads.push(head);
+    }
+    let manifest = bundle::export_bundle(&storage, &heads, &args.output)
+        .await
+        .map_err(|err| anyhow!(err.to_string()))?;
+    println!(
+        "exported {} commits and {} blobs to {}",
+        manifest.commits.len(),
+        manifest.blobs.len(),
+        args.output.display()
+    );
+    Ok(())
+}
+
+async fn import_bundle(args: BundleImportArgs) -> Result<()> {
+    let (_, storage) = open_datastore(&args.datastore, &args.key).await?;
+    let report = bundle::import_bundle(&storage, &args.input)
+        .await
+        .map_err(|err| anyhow!(err.to_string()))?;
+    println!(
+        "imported {} commits ({} already present) and {} blobs from {}",
+        report.imported_commits.len(),
+        report.skipped_commits.len(),
+        report.imported_blobs,
+        args.input.display()
+    );
+    Ok(())
+}
+
+async fn reencode_commits(args: ReencodeArgs) -> Result<()> {
+    let (_, storage) = open_datastore(&args.datastore, &args.key).await?;
+    let report = storage
+        .spawn_reencode()
+        .await
+        .context("join re-encode task")?
+        .map_err(|err| anyhow!(err.to_string()))?;
+    println!(
+        "re-encoded {} commits ({} bytes of JSON replaced by {} bytes); {} remaining",
+        report.reencoded_commits,
+        report.legacy_bytes,
+        report.encoded_bytes,
+        report.remaining_commits
+    );
+    Ok(())
+}
+
+async fn rotate_key(args: RotateKeyArgs) -> Result<()> {
+    let next = match (&args.new_passphrase_env, &args.new_key_file) {
+        (Some(var), _) => KeySource::Passphrase(
+            std::env::var(var).with_context(|| format!("read new passphrase from ${var}"))?,
+        ),
+        (None, Some(path)) => KeySource::KeyFile(path.clone()),
+        (None, None) => return Err(anyhow!("pass --new-passphrase-env or --new-key-file")),
+    };
+    let (db_path, storage) = open_datastore(&args.datastore, &args.key).await?;
+    storage
+        .rotate_key(&next)
+        .await
+        .map_err(|err| anyhow!(err.to_string()))?;
+    create_datastore(&args.datastore, None, Some(next.mode()))
+        .map_err(|err| anyhow!(err.to_string()))?;
+    println!(
+        "rotated key for {} (now {})",
+        db_path.display(),
+        next.mode()
+    );
+    Ok(())
+}
+
+async fn backup_datastore(args: BackupArgs) -> Result<()> {
+    let (_, storage) = open_datastore(&args.datastore, &args.key).await?;
+    let (dir, manifest) = match &args.output {
+        Some(output) => {
+            let manifest = storage
+                .backup_to(output)
+                .await
+                .map_err(|err| anyhow!(err.to_string()))?;
+            (output.clone(), manifest)
+        }
+        None => {
+            let info = storage
+                .backup_rotating(&backups_dir(&args.datastore), args.keep)
+                .await
+                .map_err(|err| anyhow!(err.to_string()))?;
+            (info.dir, info.manifest)
+        }
+    };
+    println!(
+        "backed up {} commits and {} blobs ({} bytes) to {}",
+        manifest.commit_count,
+        manifest.blob_count,
+        manifest.database_bytes,
+        dir.display()
+    );
+    for (branch, head) in &manifest.heads {
+        println!("  {branch} -> {}", head.as_deref().unwrap_or("(empty)"));
+    }
+    Ok(())
+}
+
+async fn restore_datastore(args: RestoreArgs) -> Result<()> {
+    let db_path = restore_backup(&args.input, &args.datastore, args.name.as_deref())
+        .await
+        .map_err(|err| anyhow!(err.to_string()))?;
+    println!(
+        "restored {} into {}",
+        args.input.display(),
+        db_path.display()
+    );
+    Ok(())
+}
+
+fn export_meta_schema(args: MetaSchemaArgs) -> Result<()> {
+    let registry = MetaModelRegistry::embedded().map_err(|err| anyhow!(err.to_string()))?;
+    let schema = serde_json::to_string_pretty(&registry.json_schema())?;
+    match args.out {
+        Some(path) => fs::write(&path, schema)
+            .with_context(|| format!("failed to write {}", path.display()))?,
+        None => println!("{schema}"),
+    }
+    Ok(())
+}
+
+async fn schema_migrations(args: SchemaArgs) -> Result<()> {
+    let db_path = datastore_path(&args.datastore)
+        .with_context(|| format!("resolve datastore under {}", args.datastore.display()))?;
+    if args.dry_run {
+        let pending = SqliteDb::plan_migrations(&db_path)
+            .await
+            .map_err(|err| anyhow!(err.to_string()))?;
+        if pending.is_empty() {
+            println!("{} is up to date", db_path.display());
+        }
+        for migration in pending {
+            println!("-- {} ({:?})", migration.id, migration.kind);
+            for statement in migration.statements {
+                match migration.kind {
+                    MigrationKind::Schema => println!("{statement};"),
+                    MigrationKind::Data => println!("{statement}"),
+                }
+            }
+        }
+        return Ok(());
+    }
+    let status = if args.apply {
+        SqliteDb::migrate(&db_path).await
+    } else {
+        SqliteDb::schema_status(&db_path).await
+    }
+    .map_err(|err| anyhow!(err.to_string()))?;
+    println!("Datastore: {}", db_path.display());
+    for migration in &status.migrations {
+        match migration.applied_at_ms {
+            Some(at_ms) => println!("  applied  {} (at {at_ms})", migration.id),
+            None => println!("  pending  {}", migration.id),
+        }
+    }
+    for id in &status.unknown {
+        println!("  unknown  {id}");
+    }
+    if status.is_newer_than_supported() {
+        return Err(anyhow!(
+            "datastore schema is newer than this build; upgrade before opening it"
+        ));
+    }
+    println!(
+        "schema {} ({} pending)",
+        status.version().unwrap_or("empty"),
+        status.pending().count()
+    );
+    Ok(())
+}
+
+/// Resolve and open an existing datastore, unlocking it when `datastore.json` records a key.
+async fn open_datastore(base: &Path, key: &KeyArgs) -> Result<(PathBuf, SqliteDb)> {
+    open_datastore_with(base, key, false).await
+}
+
+/// [`open_datastore`] without the writer lock, so it works while the desktop app is running.
+async fn open_datastore_read_only(base: &Path, key: &KeyArgs) -> Result<(PathBuf, SqliteDb)> {
+    open_datastore_with(base, key, true).await
+}
+
+async fn open_datastore_with(
+    base: &Path,
+    key: &KeyArgs,
+    read_only: bool,
+) -> Result<(PathBuf, SqliteDb)> {
+    let db_path = datastore_path(base)
+        .with_context(|| format!("resolve datastore under {}", base.display()))?;
+    let storage = match (key.source()?, read_only) {
+        (Some(key), false) => SqliteDb::open_encrypted(&db_path, &key).await,
+        (Some(key), true) => SqliteDb::open_encrypted_read_only(&db_path, &key).await,
+        (None, _) if datastore_encryption(base).is_encrypted() => {
+            return Err(anyhow!(
+                "datastore '{}' is encrypted ({}); pass --passphrase-env or --key-file",
+                base.display(),
+                datastore_encryption(base)
+            ));
+        }
+        (None, false) => SqliteDb::open(&db_path).await,
+        (None, true) => SqliteDb::open_read_only(&db_path).await,
+    }
+    .map_err(|err| anyhow!(err.to_string()))?;
+    Ok((db_path, storage))
+}
+
 async fn dry_run_dataset(dataset: &BaselineDataset) -> Result<()> {
     let store: Arc<dyn Store> = Arc::new(MemoryStore::default());
     let engine = PraxisEngine::with_stores_unseeded(PraxisEngineConfig::default(), store)
@@ -3,13 +3,16 @@
 //! Chrona keeps the IPC-friendly API exposed to the Tauri host while delegating
 //! persistence, validation, and diff computation to the Praxis engine.
 
-use aideon_engine::{PraxisEngine, PraxisResult};
+use aideon_engine::meta::{MetaModelDiff, OverlayReport};
+use aideon_engine::{CommitPreview, PraxisEngine, PraxisResult, ValidationReport};
 use aideon_mneme::meta::MetaModelDocument;
 use aideon_mneme::temporal::{
     BranchInfo, CommitChangesRequest, CommitRef, CommitSummary, DiffArgs, DiffSummary,
     ListBranchesResponse, MergeRequest, MergeResponse, StateAtArgs, StateAtResult,
     TopologyDeltaArgs, TopologyDeltaResult,
 };
+use aideon_mneme::{ChangeSubscription, SearchArgs, SearchResult};
+use serde_json::Value;
 
 /// Thin wrapper that keeps the previous `TemporalEngine` name stable for the host.
 #[derive(Clone)]
@@ -40,6 +43,22 @@ impl TemporalEngine {
         self.inner.commit(request).await
     }
 
+    /// Check a change set against the meta-model without committing it.
+    pub async fn validate_changes(
+        &self,
+        request: CommitChangesRequest,
+    ) -> PraxisResult<ValidationReport> {
+        self.inner.validate_changes(request).await
+    }
+
+    /// Preview the diff, stats delta and validation report of a commit without persisting it.
+    pub async fn preview_commit(
+        &self,
+        request: CommitChangesRequest,
+    ) -> PraxisResult<CommitPreview> {
+        self.inner.preview_commit(request).await
+    }
+
     /// Create a new branch from the optional reference point.
     pub async fn create_branch(
         &self,
@@ -78,15 +97,41 @@ impl TemporalEngine {
         self.inner.topology_delta(args).await
     }
 
+    /// Full-text search over node properties as of the given reference.
+    pub async fn search(&self, args: SearchArgs) -> PraxisResult<SearchResult> {
+        self.inner.search(args).await
+    }
+
+    /// Subscribe to commit, ref, and tag change events, resuming from `since_ms` when given.
+    pub async fn subscribe(&self, since_ms: Option<i64>) -> PraxisResult<ChangeSubscription> {
+        self.inner.subscribe(since_ms).await
+    }
+
     /// Return the active meta-model document.
-    pub async fn meta_model(&self) -> MetaModelDocument {
+    pub async fn meta_model(&self) -> PraxisResult<MetaModelDocument> {
         self.inner.meta_model().await
     }
+
+    /// JSON Schema generated from the current meta-model.
+    pub async fn meta_model_json_schema(&self) -> PraxisResult<Value> {
+        self.inner.meta_model_json_schema().await
+    }
+
+    /// Classify the meta-model changes between two commit references.
+    pub async fn meta_model_diff(&self, args: DiffArgs) -> PraxisResult<MetaModelDiff> {
+        self.inner.meta_model_diff(args).await
+    }
+
+    /// Report what the configured overlays change in the base meta-model.
+    pub async fn overlay_report(&self) -> PraxisResult<OverlayReport> {
+        self.inner.overlay_report().await
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::TemporalEngine;
+    use aideon_mneme::ChangeEvent;
     use aideon_mneme::temporal::{
         ChangeSet, CommitChangesRequest, CommitRef, EdgeTombstone, EdgeVersion, NodeTombstone,
         NodeVersion, StateAtArgs, TopologyDeltaArgs,
@@ -226,4 +271,47 @@ mod tests {
         assert_eq!(delta_trim.node_dels, 1);
         assert_eq!(delta_trim.edge_dels, 1);
     }
+
+    #[tokio::test]
+    async fn subscribe_reports_commit_and_ref_move() {
+        let engine = TemporalEngine::new().await.expect("engine");
+        let mut changes = engine.subscribe(None).await.expect("subscribe");
+        let head = engine
+            .list_commits("main".into())
+            .await
+            .expect("commits")
+            .last()
+            .map(|commit| commit.id.clone());
+        let commit_id = engine
+            .commit(CommitChangesRequest {
+                branch: "main".into(),
+                parent: head,
+                author: None,
+                time: None,
+                message: "feed".into(),
+                tags: vec![],
+                changes: ChangeSet {
+                    node_creates: vec![capability_node("cap-feed")],
+                    ..ChangeSet::default()
+                },
+            })
+            .await
+            .expect("commit");
+
+        let mut saw_commit = false;
+        loop {
+            match changes.recv().await.expect("event") {
+                ChangeEvent::CommitPersisted { commit, .. } => {
+                    saw_commit |= commit.id == commit_id;
+                }
+                ChangeEvent::RefMoved { branch, commit, .. } => {
+                    assert_eq!(branch, "main");
+                    assert_eq!(commit.expect("head summary").id, commit_id);
+                    break;
+                }
+                ChangeEvent::TagChanged { .. } => {}
+            }
+        }
+        assert!(saw_commit, "commit event precedes the ref move");
+    }
 }
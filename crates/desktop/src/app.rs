@@ -44,16 +44,29 @@ pub fn run() {
             crate::setup::set_complete,
             crate::temporal::temporal_state_at,
             crate::temporal::temporal_metamodel_get,
+            crate::temporal::temporal_metamodel_schema,
+            crate::temporal::temporal_metamodel_diff,
+            crate::temporal::temporal_metamodel_overlays,
             crate::health::worker_health,
             crate::temporal::temporal_diff,
             crate::temporal::topology_delta,
             crate::temporal::commit_changes,
+            crate::temporal::temporal_validate_changes,
+            crate::temporal::temporal_preview_commit,
             crate::temporal::list_commits,
             crate::temporal::create_branch,
             crate::temporal::list_branches,
             crate::temporal::merge_branches,
+            crate::temporal::temporal_search,
             crate::scene::canvas_scene,
             crate::scene::canvas_save_layout,
+            crate::workspaces::workspace_list,
+            crate::workspaces::workspace_create,
+            crate::workspaces::workspace_update,
+            crate::workspaces::workspace_duplicate,
+            crate::workspaces::workspace_delete,
+            crate::workspaces::workspace_set_default,
+            crate::workspaces::workspace_switch,
             crate::praxis_api::praxis_graph_view,
             crate::praxis_api::praxis_catalogue_view,
             crate::praxis_api::praxis_matrix_view,
@@ -10,6 +10,7 @@ mod setup;
 mod temporal;
 mod windows;
 mod worker;
+mod workspaces;
 
 pub use aideon_praxis_facade::mneme::WorkerHealth;
 pub use aideon_praxis_facade::mneme::temporal::{
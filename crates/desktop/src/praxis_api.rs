@@ -1,5 +1,7 @@
 //! Praxis API-facing DTOs and Tauri command handlers bridging React IPC calls.
 
+use aideon_praxis_facade::mneme::search::{SearchQuery, node_text, score_text};
+use aideon_praxis_facade::mneme::temporal::NodeVersion;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value, json};
 use time::{OffsetDateTime, format_description::well_known::Rfc3339};
@@ -225,7 +227,7 @@ pub struct GraphViewModel {
 
 impl GraphViewModel {
     fn demo(definition: GraphViewDefinition) -> Self {
-        let nodes = vec![
+        let mut nodes = vec![
             GraphNodeView {
                 id: "cap-customer-onboarding".into(),
                 label: "Customer Onboarding".into(),
@@ -255,7 +257,7 @@ impl GraphViewModel {
                 props: None,
             },
         ];
-        let edges = vec![
+        let mut edges = vec![
             GraphEdgeView {
                 id: Some("edge-1".into()),
                 from: nodes[0].id.clone(),
@@ -281,6 +283,20 @@ impl GraphViewModel {
                 props: None,
             },
         ];
+        if let Some(terms) = search_terms(definition.filters.as_ref()) {
+            nodes.retain(|node| {
+                let text = searchable(
+                    &node.id,
+                    node.r#type.as_deref(),
+                    json!({ "label": node.label }),
+                );
+                score_text(&text, &terms).is_some()
+            });
+            edges.retain(|edge| {
+                nodes.iter().any(|node| node.id == edge.from)
+                    && nodes.iter().any(|node| node.id == edge.to)
+            });
+        }
         Self {
             metadata: metadata_from(
                 &definition.id,
@@ -352,7 +368,7 @@ impl CatalogueViewModel {
         } else {
             definition.columns
         };
-        let rows = vec![
+        let mut rows = vec![
             CatalogueRow {
                 id: "cap-customer-onboarding".into(),
                 values: map_from(
@@ -372,6 +388,12 @@ impl CatalogueViewModel {
                 ),
             },
         ];
+        if let Some(terms) = search_terms(definition.filters.as_ref()) {
+            rows.retain(|row| {
+                let text = searchable(&row.id, None, Value::Object(row.values.clone()));
+                score_text(&text, &terms).is_some()
+            });
+        }
         Self {
             metadata: metadata_from(
                 &definition.id,
@@ -671,6 +693,24 @@ fn metadata_from(id: &str, name: &str, as_of: &str, scenario: Option<String>) ->
     }
 }
 
+/// Search terms from the view filters; `None` when no search is active. View models are still
+/// demo data that the engine does not hold, so views match these terms locally with the store's
+/// `score_text` rules; indexed search over committed nodes is `temporal_search`.
+fn search_terms(filters: Option<&ViewFilters>) -> Option<Vec<String>> {
+    let text = filters?.search.as_deref()?;
+    let terms = SearchQuery::new(text).terms();
+    (!terms.is_empty()).then_some(terms)
+}
+
+/// Searchable text for a view row, matching what the store indexes for nodes.
+fn searchable(id: &str, node_type: Option<&str>, props: Value) -> String {
+    node_text(&NodeVersion {
+        id: id.into(),
+        r#type: node_type.map(str::to_string),
+        props: Some(props),
+    })
+}
+
 fn map_from(value: Value) -> Map<String, Value> {
     match value {
         Value::Object(map) => map,
@@ -825,6 +865,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn graph_view_search_keeps_matching_nodes_and_their_edges() {
+        let def = GraphViewDefinition {
+            id: "graph-2".into(),
+            name: "Graph".into(),
+            kind: "graph".into(),
+            as_of: "2025-12-01".into(),
+            scenario: None,
+            confidence: None,
+            filters: Some(ViewFilters {
+                node_types: None,
+                edge_types: None,
+                tags: None,
+                search: Some("customer".into()),
+            }),
+            scope: None,
+        };
+        let model = GraphViewModel::demo(def);
+        let ids: Vec<&str> = model.nodes.iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(ids, vec!["cap-customer-onboarding", "cap-customer-support"]);
+        assert_eq!(model.edges.len(), 1);
+        assert_eq!(model.stats.edges, 1);
+    }
+
+    #[test]
+    fn catalogue_view_search_filters_rows_by_values() {
+        let def = CatalogueViewDefinition {
+            id: "cat-3".into(),
+            name: "Catalogue".into(),
+            kind: "catalogue".into(),
+            as_of: "2025-12-01".into(),
+            scenario: None,
+            confidence: None,
+            filters: Some(ViewFilters {
+                node_types: None,
+                edge_types: None,
+                tags: None,
+                search: Some("sre".into()),
+            }),
+            columns: Vec::new(),
+        };
+        let model = CatalogueViewModel::demo(def);
+        let ids: Vec<&str> = model.rows.iter().map(|row| row.id.as_str()).collect();
+        assert_eq!(ids, vec!["cap-incident-response"]);
+    }
+
     #[test]
     fn catalogue_view_demo_defaults_columns_when_missing() {
         let def = CatalogueViewDefinition {
@@ -1,9 +1,12 @@
 //! Host IPC commands for scene/canvas data.
 
+use crate::worker::WorkerState;
 use aideon_praxis_facade::chrona::scene::generate_demo_scene;
 use aideon_praxis_facade::continuum::{FileSnapshotStore, SnapshotStore};
+use aideon_praxis_facade::mneme::SealedSnapshotStore;
 use aideon_praxis_facade::praxis::canvas::{CanvasLayoutSaveRequest, CanvasShape};
 use log::info;
+use tauri::{AppHandle, Manager, Wry};
 
 /// Return a raw scene for the canvas. The renderer performs layout when needed.
 #[tauri::command]
@@ -21,8 +24,12 @@ fn canvas_store_key(doc_id: &str, as_of: &str) -> String {
 }
 
 /// Persist a canvas layout snapshot (geometry, z-order, grouping) for a document and asOf.
+/// Snapshots are sealed with the datastore keyring when the datastore is encrypted.
 #[tauri::command]
-pub async fn canvas_save_layout(payload: CanvasLayoutSaveRequest) -> Result<(), String> {
+pub async fn canvas_save_layout(
+    app: AppHandle<Wry>,
+    payload: CanvasLayoutSaveRequest,
+) -> Result<(), String> {
     info!(
         "host: canvas_save_layout doc_id={} as_of={} nodes={} edges={} groups={}",
         payload.doc_id,
@@ -37,7 +44,13 @@ pub async fn canvas_save_layout(payload: CanvasLayoutSaveRequest) -> Result<(),
     let store = FileSnapshotStore::new(base.clone());
     let key = canvas_store_key(&payload.doc_id, &payload.as_of);
     let json = serde_json::to_vec_pretty(&payload).map_err(|e| format!("serialize failed: {e}"))?;
-    store.put(&key, &json)?;
+    let keyring = app
+        .try_state::<WorkerState>()
+        .and_then(|state| state.keyring());
+    match keyring {
+        Some(keyring) => SealedSnapshotStore::new(store, keyring).put(&key, &json)?,
+        None => store.put(&key, &json)?,
+    }
     info!("host: canvas_save_layout wrote {}/{}", base.display(), key);
     Ok(())
 }
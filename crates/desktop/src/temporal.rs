@@ -4,14 +4,17 @@
 //! crate, reinforcing the boundary guidance spelled out in `AGENTS.md`.
 
 use aideon_praxis_facade::mneme::meta::MetaModelDocument;
+use aideon_praxis_facade::mneme::search::{SearchArgs, SearchResult};
 use aideon_praxis_facade::mneme::temporal::{
     BranchInfo, CommitChangesRequest, CommitChangesResponse, CreateBranchRequest, DiffArgs,
     DiffSummary, ListBranchesResponse, ListCommitsResponse, MergeRequest, MergeResponse,
     StateAtArgs, StateAtResult, TopologyDeltaArgs, TopologyDeltaResult,
 };
-use aideon_praxis_facade::praxis::{PraxisError, PraxisErrorCode};
+use aideon_praxis_facade::praxis::meta::{MetaModelDiff, OverlayReport};
+use aideon_praxis_facade::praxis::{CommitPreview, PraxisError, PraxisErrorCode, ValidationReport};
 use log::{debug, error, info};
 use serde::Serialize;
+use serde_json::Value;
 use std::time::Instant;
 use tauri::State;
 
@@ -83,6 +86,40 @@ pub async fn commit_changes(
     Ok(CommitChangesResponse { id })
 }
 
+#[tauri::command]
+/// Check a change set against the meta-model without committing it, returning every issue for
+/// the renderer to tabulate.
+pub async fn temporal_validate_changes(
+    state: State<'_, WorkerState>,
+    payload: CommitChangesRequest,
+) -> Result<ValidationReport, HostError> {
+    let engine = state.engine();
+    let report = engine.validate_changes(payload).await.map_err(host_error)?;
+    debug!(
+        "host: temporal_validate_changes issues={}",
+        report.issues.len()
+    );
+    Ok(report)
+}
+
+#[tauri::command]
+/// Dry-run a commit: the diff, stats delta and validation report it would produce, without
+/// persisting anything or moving the branch head.
+pub async fn temporal_preview_commit(
+    state: State<'_, WorkerState>,
+    payload: CommitChangesRequest,
+) -> Result<CommitPreview, HostError> {
+    let engine = state.engine();
+    let preview = engine.preview_commit(payload).await.map_err(host_error)?;
+    debug!(
+        "host: temporal_preview_commit nodes={:+} edges={:+} issues={}",
+        preview.stats.nodes,
+        preview.stats.edges,
+        preview.report.issues.len()
+    );
+    Ok(preview)
+}
+
 #[tauri::command]
 pub async fn list_commits(
     state: State<'_, WorkerState>,
@@ -140,18 +177,75 @@ pub async fn topology_delta(
     engine.topology_delta(payload).await.map_err(host_error)
 }
 
+#[tauri::command]
+/// Full-text search over node properties as of a commit or branch head.
+pub async fn temporal_search(
+    state: State<'_, WorkerState>,
+    payload: SearchArgs,
+) -> Result<SearchResult, HostError> {
+    debug!("host: temporal_search payload={:?}", payload);
+    let engine = state.engine();
+    let result = engine.search(payload).await.map_err(host_error)?;
+    debug!(
+        "host: temporal_search commit={} hits={}",
+        result.commit_id,
+        result.hits.len()
+    );
+    Ok(result)
+}
+
 #[tauri::command]
 pub async fn temporal_metamodel_get(
     state: State<'_, WorkerState>,
 ) -> Result<MetaModelDocument, HostError> {
     let engine = state.engine();
-    Ok(engine.meta_model().await)
+    engine.meta_model().await.map_err(host_error)
+}
+
+#[tauri::command]
+/// JSON Schema (draft 2020-12) for the current meta-model, used to build inspector forms.
+pub async fn temporal_metamodel_schema(state: State<'_, WorkerState>) -> Result<Value, HostError> {
+    let engine = state.engine();
+    engine.meta_model_json_schema().await.map_err(host_error)
+}
+
+#[tauri::command]
+/// Classify the meta-model changes between two commits or branch heads for review tooling.
+pub async fn temporal_metamodel_diff(
+    state: State<'_, WorkerState>,
+    payload: DiffArgs,
+) -> Result<MetaModelDiff, HostError> {
+    debug!(
+        "host: temporal_metamodel_diff from={:?} to={:?}",
+        payload.from, payload.to
+    );
+    let engine = state.engine();
+    let diff = engine.meta_model_diff(payload).await.map_err(host_error)?;
+    info!(
+        "host: temporal_metamodel_diff changes={} breaking={}",
+        diff.changes.len(),
+        diff.breaking().count()
+    );
+    Ok(diff)
+}
+
+#[tauri::command]
+/// Report what the configured meta-model overlays change, which layer defined each attribute and
+/// which layer removed what.
+pub async fn temporal_metamodel_overlays(
+    state: State<'_, WorkerState>,
+) -> Result<OverlayReport, HostError> {
+    let engine = state.engine();
+    engine.overlay_report().await.map_err(host_error)
 }
 
 #[derive(Debug, Serialize)]
 pub struct HostError {
     code: &'static str,
     message: String,
+    /// Every rule violation when a change set was rejected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report: Option<ValidationReport>,
 }
 
 pub(crate) fn host_error(error: PraxisError) -> HostError {
@@ -167,6 +261,7 @@ pub(crate) fn host_error(error: PraxisError) -> HostError {
     HostError {
         code,
         message: error.to_string(),
+        report: error.report().cloned(),
     }
 }
 
@@ -188,6 +283,28 @@ mod tests {
         };
         let mapped = host_error(err);
         assert_eq!(mapped.code, "integrity_violation");
+        assert!(mapped.report.is_none());
+    }
+
+    #[test]
+    fn host_error_carries_validation_report() {
+        let report: ValidationReport = serde_json::from_value(serde_json::json!({
+            "issues": [{
+                "elementId": "a1",
+                "attribute": "name",
+                "rule": "required",
+                "severity": "error",
+                "message": "node 'a1' attribute 'name' is required"
+            }]
+        }))
+        .expect("report");
+        let mapped = host_error(PraxisError::InvalidChanges {
+            report: report.clone(),
+        });
+        assert_eq!(mapped.code, "validation_failed");
+        assert_eq!(mapped.report, Some(report));
+        let json = serde_json::to_value(&mapped).expect("serialize");
+        assert_eq!(json["report"]["issues"][0]["attribute"], "name");
     }
 
     #[test]
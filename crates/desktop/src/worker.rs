@@ -1,31 +1,80 @@
 //! Worker lifecycle glue for hosting the Chrona temporal engine inside Tauri.
 //!
 //! The host keeps the engine behind a managed state container so renderer IPC
-//! handlers can access it without leaking internal mutability.
+//! handlers can access it without leaking internal mutability. The engine serves one
+//! workspace from the catalogue at a time; switching opens the new workspace first and then
+//! swaps it in, stopping the previous workspace's change feed and backups.
 
 use aideon_praxis_facade::chrona::TemporalEngine;
-use aideon_praxis_facade::mneme::{WorkerHealth, datastore::create_datastore};
-use aideon_praxis_facade::praxis::PraxisEngine;
-use log::{debug, info};
-use std::fs;
+use aideon_praxis_facade::mneme::{
+    BackupPolicy, EncryptionMode, KeySource, Keyring, SqliteDb, WorkerHealth, Workspace,
+    WorkspaceCatalogue, backups_dir, create_datastore, datastore_encryption,
+};
+use aideon_praxis_facade::praxis::{PraxisEngine, PraxisEngineConfig};
+use log::{debug, error, info, warn};
+use std::path::PathBuf;
+use std::sync::RwLock;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tauri::{AppHandle, Manager, Wry};
+use tauri::async_runtime::{JoinHandle, spawn};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+/// Tauri event carrying Mneme change-feed events (commit persisted, ref moved, tag changed).
+pub const TEMPORAL_CHANGE_EVENT: &str = "temporal://change";
+
+/// Tauri event carrying the [`Workspace`] the worker switched to.
+pub const WORKSPACE_CHANGED_EVENT: &str = "workspace://changed";
 
 /// Shared application state giving command handlers access to the temporal engine.
 pub struct WorkerState {
+    active: RwLock<ActiveWorkspace>,
+    catalogue: WorkspaceCatalogue,
+    switching: tokio::sync::Mutex<()>,
+}
+
+/// Engine and background tasks of the open workspace; the tasks stop when it is replaced.
+struct ActiveWorkspace {
+    workspace_id: String,
     engine: TemporalEngine,
+    keyring: Option<Keyring>,
+    feed: JoinHandle<()>,
+    backups: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for ActiveWorkspace {
+    fn drop(&mut self) {
+        self.feed.abort();
+        self.backups.abort();
+    }
 }
 
 impl WorkerState {
-    /// Create a new worker state wrapper around the provided engine instance.
-    pub fn new(engine: TemporalEngine) -> Self {
+    fn from_active(catalogue: WorkspaceCatalogue, active: ActiveWorkspace) -> Self {
         debug!("host: WorkerState constructed");
-        Self { engine }
+        Self {
+            active: RwLock::new(active),
+            catalogue,
+            switching: tokio::sync::Mutex::new(()),
+        }
     }
 
-    /// Borrow the underlying temporal engine for read-only operations.
-    pub fn engine(&self) -> &TemporalEngine {
-        &self.engine
+    /// Engine of the active workspace. Handlers keep the returned handle for the whole
+    /// request, so a concurrent switch does not change engines mid-request.
+    pub fn engine(&self) -> TemporalEngine {
+        self.read().engine.clone()
+    }
+
+    /// Keyring of the active workspace's encrypted datastore, if any.
+    pub fn keyring(&self) -> Option<Keyring> {
+        self.read().keyring.clone()
+    }
+
+    /// Id of the workspace the engine serves.
+    pub fn active_workspace(&self) -> String {
+        self.read().workspace_id.clone()
+    }
+
+    pub fn catalogue(&self) -> &WorkspaceCatalogue {
+        &self.catalogue
     }
 
     /// Produce a lightweight health snapshot for IPC exposure.
@@ -36,9 +85,16 @@ impl WorkerState {
             .as_millis() as u64;
         WorkerHealth::healthy(timestamp_ms)
     }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, ActiveWorkspace> {
+        self.active
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
 }
 
-/// Lazily initialize the temporal engine and store it in Tauri managed state.
+/// Lazily initialize the temporal engine on the default workspace and store it in Tauri
+/// managed state.
 pub async fn init_temporal(app: &AppHandle<Wry>) -> Result<(), String> {
     let storage_root = app
         .path()
@@ -46,15 +102,142 @@ pub async fn init_temporal(app: &AppHandle<Wry>) -> Result<(), String> {
         .map_err(|err| err.to_string())?
         .join("AideonPraxis")
         .join(".praxis");
-    fs::create_dir_all(&storage_root)
+    let catalogue = WorkspaceCatalogue::open(&storage_root)
         .map_err(|err| format!("failed to prepare storage dir: {err}"))?;
-    let db_path = create_datastore(&storage_root, None)
+    let workspace = catalogue
+        .ensure_default()
+        .map_err(|err| format!("workspace catalogue init failed: {err}"))?;
+    let (active, _) = open_workspace(app, &catalogue, &workspace.id).await?;
+    app.manage(WorkerState::from_active(catalogue, active));
+    info!("host: temporal engine registered with application state");
+    Ok(())
+}
+
+/// Re-initialise the worker on workspace `id`. The current workspace keeps serving until the
+/// new one is open, so a failed switch leaves it in place.
+pub async fn switch_workspace(
+    app: &AppHandle<Wry>,
+    state: &WorkerState,
+    id: &str,
+) -> Result<Workspace, String> {
+    let _switching = state.switching.lock().await;
+    if state.active_workspace() == id {
+        return state.catalogue().get(id).map_err(|err| err.to_string());
+    }
+    let (active, workspace) = open_workspace(app, state.catalogue(), id).await?;
+    let previous = std::mem::replace(
+        &mut *state
+            .active
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        active,
+    );
+    drop(previous);
+    info!("host: switched to workspace '{id}'");
+    if let Err(err) = app.emit(WORKSPACE_CHANGED_EVENT, &workspace) {
+        warn!("host: failed to emit workspace change: {err}");
+    }
+    Ok(workspace)
+}
+
+/// Open a workspace's datastore and start its engine, change feed and scheduled backups.
+async fn open_workspace(
+    app: &AppHandle<Wry>,
+    catalogue: &WorkspaceCatalogue,
+    id: &str,
+) -> Result<(ActiveWorkspace, Workspace), String> {
+    let workspace = catalogue.get(id).map_err(|err| err.to_string())?;
+    let base = catalogue.base(&workspace);
+    let db_path = create_datastore(&base, None, None)
         .map_err(|err| format!("datastore init failed: {err}"))?;
-    let engine = PraxisEngine::with_sqlite(&db_path)
+    let storage = match datastore_key(datastore_encryption(&base))? {
+        Some(key) => SqliteDb::open_encrypted(&db_path, &key)
+            .await
+            .map_err(|err| format!("datastore unlock failed: {err}"))?,
+        None => SqliteDb::open(&db_path)
+            .await
+            .map_err(|err| format!("datastore open failed: {err}"))?,
+    };
+    let keyring = storage.keyring();
+    let schema = storage
+        .schema()
+        .await
+        .map_err(|err| format!("datastore schema check failed: {err}"))?;
+    let engine = PraxisEngine::with_sqlite_store(storage.clone(), PraxisEngineConfig::default())
         .await
         .map_err(|err| format!("temporal engine init failed: {err}"))?;
+    let workspace = catalogue
+        .mark_opened(id, schema.version())
+        .map_err(|err| err.to_string())?;
+    let backups =
+        storage.spawn_backups(
+            backups_dir(&base),
+            BackupPolicy::default(),
+            |outcome| match outcome {
+                Ok(backup) => info!("host: datastore backed up to {}", backup.dir.display()),
+                Err(err) => error!("host: datastore backup failed: {err}"),
+            },
+        );
     let temporal = TemporalEngine::from_engine(engine);
-    app.manage(WorkerState::new(temporal));
-    info!("host: temporal engine registered with application state");
-    Ok(())
+    let feed = forward_change_feed(app.clone(), temporal.clone());
+    Ok((
+        ActiveWorkspace {
+            workspace_id: workspace.id.clone(),
+            engine: temporal,
+            keyring,
+            feed,
+            backups,
+        },
+        workspace,
+    ))
+}
+
+/// Key for an encrypted datastore, read from `AIDEON_PRAXIS_PASSPHRASE` or
+/// `AIDEON_PRAXIS_KEY_FILE` according to the mode recorded in `datastore.json`.
+fn datastore_key(mode: EncryptionMode) -> Result<Option<KeySource>, String> {
+    let read = |var: &str| {
+        std::env::var(var)
+            .map_err(|_| format!("datastore is encrypted ({mode}); set {var} to unlock it"))
+    };
+    Ok(match mode {
+        EncryptionMode::None => None,
+        EncryptionMode::Passphrase => {
+            Some(KeySource::Passphrase(read("AIDEON_PRAXIS_PASSPHRASE")?))
+        }
+        EncryptionMode::KeyFile => Some(KeySource::KeyFile(PathBuf::from(read(
+            "AIDEON_PRAXIS_KEY_FILE",
+        )?))),
+    })
+}
+
+/// Relay change-feed events to the renderer, resubscribing from the last cursor if the
+/// subscription lags or drops.
+fn forward_change_feed(app: AppHandle<Wry>, engine: TemporalEngine) -> JoinHandle<()> {
+    spawn(async move {
+        let mut cursor: Option<i64> = None;
+        loop {
+            let mut subscription = match engine.subscribe(cursor).await {
+                Ok(subscription) => subscription,
+                Err(err) => {
+                    error!("host: change feed subscription failed: {err}");
+                    return;
+                }
+            };
+            debug!("host: change feed subscribed (cursor={cursor:?})");
+            loop {
+                match subscription.recv().await {
+                    Ok(event) => {
+                        cursor = Some(event.at_ms());
+                        if let Err(err) = app.emit(TEMPORAL_CHANGE_EVENT, &event) {
+                            warn!("host: failed to emit change event: {err}");
+                        }
+                    }
+                    Err(err) => {
+                        warn!("host: change feed interrupted: {err}");
+                        break;
+                    }
+                }
+            }
+        }
+    })
 }
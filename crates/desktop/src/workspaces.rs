@@ -0,0 +1,150 @@
+//! Host commands for the workspace catalogue: one datastore per client model.
+//!
+//! Catalogue edits go straight to Mneme's `WorkspaceCatalogue`; switching re-initialises the
+//! worker through [`switch_workspace`].
+
+use aideon_praxis_facade::mneme::Workspace;
+use log::info;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State, Wry};
+
+use crate::worker::{WorkerState, switch_workspace};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceListResponse {
+    /// Workspace the worker currently serves.
+    pub active: String,
+    /// Workspace opened at startup.
+    pub default: Option<String>,
+    pub workspaces: Vec<Workspace>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceCreateRequest {
+    pub display_name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceUpdateRequest {
+    pub id: String,
+    pub display_name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceDuplicateRequest {
+    pub id: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceIdRequest {
+    pub id: String,
+}
+
+#[tauri::command]
+pub async fn workspace_list(
+    state: State<'_, WorkerState>,
+) -> Result<WorkspaceListResponse, String> {
+    let catalogue = state.catalogue();
+    Ok(WorkspaceListResponse {
+        active: state.active_workspace(),
+        default: catalogue.default_id().map_err(|err| err.to_string())?,
+        workspaces: catalogue.list().map_err(|err| err.to_string())?,
+    })
+}
+
+#[tauri::command]
+pub async fn workspace_create(
+    state: State<'_, WorkerState>,
+    payload: WorkspaceCreateRequest,
+) -> Result<Workspace, String> {
+    let workspace = state
+        .catalogue()
+        .create(&payload.display_name, payload.description.as_deref(), None)
+        .map_err(|err| err.to_string())?;
+    info!("host: workspace_create id={}", workspace.id);
+    Ok(workspace)
+}
+
+#[tauri::command]
+/// Rename a workspace and replace its description; the id stays the same.
+pub async fn workspace_update(
+    state: State<'_, WorkerState>,
+    payload: WorkspaceUpdateRequest,
+) -> Result<Workspace, String> {
+    let catalogue = state.catalogue();
+    catalogue
+        .rename(&payload.id, &payload.display_name)
+        .map_err(|err| err.to_string())?;
+    catalogue
+        .set_description(&payload.id, payload.description.as_deref())
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn workspace_duplicate(
+    state: State<'_, WorkerState>,
+    payload: WorkspaceDuplicateRequest,
+) -> Result<Workspace, String> {
+    let workspace = state
+        .catalogue()
+        .duplicate(&payload.id, &payload.display_name)
+        .await
+        .map_err(|err| err.to_string())?;
+    info!(
+        "host: workspace_duplicate from={} id={}",
+        payload.id, workspace.id
+    );
+    Ok(workspace)
+}
+
+#[tauri::command]
+/// Delete a workspace and its files. The active and default workspaces are refused.
+pub async fn workspace_delete(
+    state: State<'_, WorkerState>,
+    payload: WorkspaceIdRequest,
+) -> Result<(), String> {
+    if state.active_workspace() == payload.id {
+        return Err(format!(
+            "workspace '{}' is open; switch to another workspace first",
+            payload.id
+        ));
+    }
+    state
+        .catalogue()
+        .delete(&payload.id)
+        .map_err(|err| err.to_string())?;
+    info!("host: workspace_delete id={}", payload.id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn workspace_set_default(
+    state: State<'_, WorkerState>,
+    payload: WorkspaceIdRequest,
+) -> Result<(), String> {
+    state
+        .catalogue()
+        .set_default(&payload.id)
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+/// Re-initialise the worker on another workspace and emit `workspace://changed`.
+pub async fn workspace_switch(
+    app: AppHandle<Wry>,
+    state: State<'_, WorkerState>,
+    payload: WorkspaceIdRequest,
+) -> Result<Workspace, String> {
+    info!("host: workspace_switch id={}", payload.id);
+    switch_workspace(&app, state.inner(), &payload.id).await
+}
@@ -8,7 +8,7 @@ use serde_json::Value;
 
 use crate::error::{PraxisError, PraxisResult};
 
-const BASELINE_YAML: &str = include_str!("../../../docs/data/base/baseline.yaml");
+pub(crate) const BASELINE_YAML: &str = include_str!("../../../docs/data/base/baseline.yaml");
 
 #[derive(Debug, Clone)]
 pub struct BaselineDataset {
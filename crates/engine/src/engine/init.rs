@@ -36,6 +36,15 @@ impl PraxisEngine {
         Self::with_sqlite_inner(path, config, false).await
     }
 
+    /// Build on an already opened SQLite store, e.g. an encrypted one or one the host also
+    /// backs up.
+    pub async fn with_sqlite_store(
+        storage: SqliteDb,
+        config: PraxisEngineConfig,
+    ) -> PraxisResult<Self> {
+        Self::with_sqlite_store_inner(storage, config, true).await
+    }
+
     pub async fn with_stores(
         config: PraxisEngineConfig,
         store: Arc<dyn Store>,
@@ -56,6 +65,16 @@ impl PraxisEngine {
         seed: bool,
     ) -> PraxisResult<Self> {
         let storage = SqliteDb::open(path).await?;
+        Self::with_sqlite_store_inner(storage, config, seed).await
+    }
+
+    async fn with_sqlite_store_inner(
+        storage: SqliteDb,
+        config: PraxisEngineConfig,
+        seed: bool,
+    ) -> PraxisResult<Self> {
+        // Convert commits written before compressed change sets; unconverted rows stay readable.
+        storage.spawn_reencode();
         let commit_store: Arc<dyn Store> = Arc::new(storage.clone());
         Self::with_stores_inner(config, commit_store, seed).await
     }
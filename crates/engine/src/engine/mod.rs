@@ -1,17 +1,28 @@
 //! The core Praxis engine for temporal graph operations.
 
+use serde_json::Value;
 use std::sync::Arc;
 use tokio::sync::{Mutex, MutexGuard};
 
+use aideon_mneme::blob::BlobReader;
 use aideon_mneme::meta::MetaModelDocument;
 use aideon_mneme::temporal::{
     BranchInfo, CommitChangesRequest, CommitRef, CommitSummary, DiffArgs, DiffSummary,
     MergeRequest, MergeResponse, StateAtArgs, StateAtResult, TopologyDeltaArgs,
     TopologyDeltaResult,
 };
+use aideon_mneme::{
+    BlobInfo, BlobRef, BlobStore, ChangeSubscription, ElementRef, ElementVersion, GraphFilter,
+    GraphState, SearchArgs, SearchResult, Store,
+};
 
-use crate::error::PraxisResult;
+use crate::error::{PraxisError, PraxisResult};
 use crate::graph::GraphSnapshot;
+use crate::meta::{
+    MetaModelDiff, MetaModelRegistry, MigrationHints, MigrationPlan, MultiplicityViolation,
+    OverlayReport, check_overlays,
+};
+use crate::report::{CommitPreview, ValidationReport};
 
 mod config;
 mod init;
@@ -38,6 +49,37 @@ impl PraxisEngine {
         ops::commit(&mut guard, request).await
     }
 
+    /// Commit `requests` in order as one atomic store write, returning their ids. Requests
+    /// without a parent build on their branch's head, including earlier commits in the batch;
+    /// if any request is invalid or the store write fails, nothing is committed.
+    pub async fn commit_batch(
+        &self,
+        requests: Vec<CommitChangesRequest>,
+    ) -> PraxisResult<Vec<String>> {
+        let mut guard = self.lock().await;
+        ops::commit_batch(&mut guard, requests).await
+    }
+
+    /// Check `request` against the meta-model without committing it, reporting every
+    /// violation, warnings included.
+    pub async fn validate_changes(
+        &self,
+        request: CommitChangesRequest,
+    ) -> PraxisResult<ValidationReport> {
+        let mut guard = self.lock().await;
+        ops::validate_changes(&mut guard, &request).await
+    }
+
+    /// Apply `request` to its branch head in memory and return the resulting diff, stats
+    /// delta and validation report. Nothing is persisted and the branch head does not move.
+    pub async fn preview_commit(
+        &self,
+        request: CommitChangesRequest,
+    ) -> PraxisResult<CommitPreview> {
+        let mut guard = self.lock().await;
+        ops::preview_commit(&mut guard, &request).await
+    }
+
     pub async fn create_branch(
         &self,
         name: String,
@@ -91,14 +133,140 @@ impl PraxisEngine {
         Ok(record.snapshot.stats())
     }
 
+    /// Multiplicity violations in the snapshot at `commit_id`, including warning-severity ones
+    /// that commits let through.
+    pub async fn multiplicity_violations(
+        &self,
+        commit_id: &str,
+    ) -> PraxisResult<Vec<MultiplicityViolation>> {
+        let mut guard = self.lock().await;
+        let record = guard.record_for(commit_id).await?;
+        Ok(record
+            .snapshot
+            .multiplicity_violations(record.registry.as_ref()))
+    }
+
     pub async fn snapshot_for_commit(&self, commit_id: &str) -> PraxisResult<Arc<GraphSnapshot>> {
         let mut guard = self.lock().await;
         guard.snapshot_for(commit_id).await
     }
 
-    pub async fn meta_model(&self) -> MetaModelDocument {
-        let guard = self.lock().await;
-        guard.registry.document()
+    /// Upload content for a `Blob` attribute; the returned reference goes into node or edge props.
+    pub async fn put_blob(&self, bytes: &[u8]) -> PraxisResult<BlobInfo> {
+        let store = self.lock().await.store.clone();
+        Ok(blob_store(store.as_ref())?.put_blob(bytes).await?)
+    }
+
+    /// Stream a stored blob's content.
+    pub async fn open_blob(&self, blob: &BlobRef) -> PraxisResult<Option<BlobReader>> {
+        let store = self.lock().await.store.clone();
+        Ok(blob_store(store.as_ref())?.open_blob(blob).await?)
+    }
+
+    /// Size and reference count of a stored blob.
+    pub async fn blob_info(&self, blob: &BlobRef) -> PraxisResult<Option<BlobInfo>> {
+        let store = self.lock().await.store.clone();
+        Ok(blob_store(store.as_ref())?.blob_info(blob).await?)
+    }
+
+    /// Subscribe to store change events, replaying those at or after `since_ms` first.
+    pub async fn subscribe(&self, since_ms: Option<i64>) -> PraxisResult<ChangeSubscription> {
+        let store = self.lock().await.store.clone();
+        Ok(store.subscribe(since_ms).await?)
+    }
+
+    /// Live nodes and edges at a commit, answered by the store without replaying snapshots.
+    /// Type filters also match subtypes.
+    pub async fn graph_state(
+        &self,
+        commit_id: &str,
+        filter: &GraphFilter,
+    ) -> PraxisResult<GraphState> {
+        let (store, filter) = {
+            let mut guard = self.lock().await;
+            let registry = guard.registry_at(commit_id).await?;
+            let filter = GraphFilter {
+                node_types: util::with_subtypes(&registry, &filter.node_types),
+                ..filter.clone()
+            };
+            (guard.store.clone(), filter)
+        };
+        Ok(store.graph_state(commit_id, &filter).await?)
+    }
+
+    /// True when `type_id` is `ancestor` or one of its subtypes in the active meta-model.
+    pub async fn is_a(&self, type_id: &str, ancestor: &str) -> PraxisResult<bool> {
+        Ok(self.active_registry().await?.is_a(type_id, ancestor))
+    }
+
+    /// Types inheriting from `type_id` in the active meta-model.
+    pub async fn subtypes_of(&self, type_id: &str) -> PraxisResult<Vec<String>> {
+        Ok(self.active_registry().await?.subtypes_of(type_id))
+    }
+
+    /// Versions of one node or edge along the lineage ending at `commit_id`.
+    pub async fn element_history(
+        &self,
+        commit_id: &str,
+        element: &ElementRef,
+    ) -> PraxisResult<Vec<ElementVersion>> {
+        let store = self.lock().await.store.clone();
+        Ok(store.element_history(commit_id, element).await?)
+    }
+
+    /// Ranked full-text matches over node properties as of a commit or branch head.
+    pub async fn search(&self, args: SearchArgs) -> PraxisResult<SearchResult> {
+        let mut guard = self.lock().await;
+        ops::search(&mut guard, args).await
+    }
+
+    /// The active meta-model: the schema at the head of `main`.
+    pub async fn meta_model(&self) -> PraxisResult<MetaModelDocument> {
+        Ok(self.active_registry().await?.document())
+    }
+
+    /// JSON Schema for the meta-model at the head of `main`: one props schema per type and
+    /// relationship, and a root that validates dataset import files.
+    pub async fn meta_model_json_schema(&self) -> PraxisResult<Value> {
+        Ok(self.active_registry().await?.json_schema())
+    }
+
+    /// The meta-model in effect at `commit_id`.
+    pub async fn meta_model_at(&self, commit_id: &str) -> PraxisResult<MetaModelDocument> {
+        let mut guard = self.lock().await;
+        Ok(guard.registry_at(commit_id).await?.document())
+    }
+
+    /// Schema changes between two commits or branch heads, classified as breaking or
+    /// compatible.
+    pub async fn meta_model_diff(&self, args: DiffArgs) -> PraxisResult<MetaModelDiff> {
+        let mut guard = self.lock().await;
+        ops::meta_model_diff(&mut guard, args).await
+    }
+
+    /// Diff the configured base document against its merged overlays, recording which layer
+    /// defined each attribute and which layer removed what.
+    pub async fn overlay_report(&self) -> PraxisResult<OverlayReport> {
+        let config = self.lock().await.config.meta_model.clone();
+        check_overlays(&config)
+    }
+
+    /// Compare the schema at the head of `branch` with `target`, check the head's elements
+    /// against `target`, and propose the entry changes and data fix-ups that migrate the
+    /// branch. Nothing is committed.
+    pub async fn plan_schema_migration(
+        &self,
+        branch: &str,
+        target: &MetaModelDocument,
+        hints: &MigrationHints,
+    ) -> PraxisResult<MigrationPlan> {
+        let mut guard = self.lock().await;
+        ops::plan_schema_migration(&mut guard, branch, target, hints).await
+    }
+
+    /// Schema at the head of `main`.
+    async fn active_registry(&self) -> PraxisResult<Arc<MetaModelRegistry>> {
+        self.lock().await.registry_for_branch("main").await
     }
 
     pub async fn merge(&self, request: MergeRequest) -> PraxisResult<MergeResponse> {
@@ -106,3 +274,9 @@ impl PraxisEngine {
         ops::merge(&mut guard, request).await
     }
 }
+
+fn blob_store(store: &dyn Store) -> PraxisResult<&dyn BlobStore> {
+    store.blobs().ok_or_else(|| PraxisError::ValidationFailed {
+        message: "store has no blob storage".into(),
+    })
+}
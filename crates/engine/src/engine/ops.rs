@@ -3,17 +3,26 @@
 use crate::engine::state::{BranchState, CommitRecord, Inner};
 use crate::engine::util::{
     change_count, current_timestamp, derive_commit_id, find_common_ancestor, normalize_change_set,
-    resolve_commit_id, resolve_snapshot, validate_branch_name,
+    resolve_commit_id, resolve_snapshot, snapshot_tag, validate_branch_name,
+    with_declared_defaults, with_subtypes,
 };
-use crate::error::{PraxisError, PraxisResult};
+use crate::error::{PraxisError, PraxisErrorCode, PraxisResult};
 use crate::graph::GraphSnapshot;
-use aideon_mneme::PersistedCommit;
+use crate::meta::{
+    MetaModelDiff, MetaModelRegistry, MigrationHints, MigrationPlan, plan_migration,
+};
+use crate::report::{CommitPreview, StatsDelta, ValidationIssue, ValidationReport, ValidationRule};
+use aideon_mneme::blob::BlobRef;
+use aideon_mneme::meta::MetaModelDocument;
 use aideon_mneme::temporal::{
     BranchInfo, ChangeSet, CommitChangesRequest, CommitRef, CommitSummary, DiffArgs, DiffPatch,
     DiffSummary, EdgeTombstone, EdgeVersion, MergeConflict, MergeRequest, MergeResponse,
     NodeTombstone, StateAtArgs, StateAtResult, TopologyDeltaArgs, TopologyDeltaResult,
 };
-use std::collections::HashSet;
+use aideon_mneme::{
+    CommitBatch, GraphFilter, PersistedCommit, RefUpdate, SearchArgs, SearchResult,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 
 pub(super) async fn commit(
@@ -40,33 +49,284 @@ pub(super) async fn commit(
         .get(&request.branch)
         .and_then(|state| state.head.clone());
 
-    let expected_parent = match (&request.parent, &current_head) {
-        (Some(explicit), Some(head)) if explicit != head => {
-            return Err(PraxisError::ConcurrencyConflict {
-                branch: request.branch.clone(),
-                expected: Some(explicit.clone()),
-                actual: Some(head.clone()),
+    let expected_parent = expected_parent(&request, current_head.as_ref())?;
+    let base = inner.base_for(expected_parent.as_ref()).await?;
+    let record = stage_commit(inner, &request, expected_parent, base).await?;
+    let commit_id = record.summary.id.clone();
+
+    if inner.store.get_commit(&commit_id).await?.is_some() {
+        return Err(PraxisError::IntegrityViolation {
+            message: format!("commit '{commit_id}' already exists"),
+        });
+    }
+
+    let persisted = PersistedCommit {
+        summary: record.summary.clone(),
+        change_set: record.change_set.clone(),
+        blobs: record.blobs.clone(),
+    };
+
+    inner.store.put_commit(&persisted).await?;
+    inner.record_snapshot_tag(&commit_id).await?;
+
+    inner
+        .store
+        .compare_and_swap_branch(&request.branch, current_head.as_deref(), Some(&commit_id))
+        .await?;
+    inner
+        .branches
+        .entry(request.branch.clone())
+        .or_default()
+        .head = Some(commit_id.clone());
+
+    inner.commits.insert(commit_id.clone(), record);
+
+    Ok(commit_id)
+}
+
+/// Validate and persist `requests` in order as one store batch. A request without a parent
+/// builds on its branch's head, including commits earlier in the batch. Nothing is written
+/// unless every request is valid, and the store applies the batch atomically.
+pub(super) async fn commit_batch(
+    inner: &mut Inner,
+    requests: Vec<CommitChangesRequest>,
+) -> PraxisResult<Vec<String>> {
+    // Branch heads before the batch, and as moved by it so far.
+    let mut initial: BTreeMap<String, Option<String>> = BTreeMap::new();
+    let mut heads: BTreeMap<String, Option<String>> = BTreeMap::new();
+    let mut staged: Vec<CommitRecord> = Vec::with_capacity(requests.len());
+    let mut staged_index: HashMap<String, usize> = HashMap::new();
+
+    for request in &requests {
+        validate_branch_name(&request.branch)?;
+        let current_head = match heads.get(&request.branch) {
+            Some(head) => head.clone(),
+            None => {
+                let head = inner
+                    .branches
+                    .get(&request.branch)
+                    .and_then(|state| state.head.clone());
+                initial.insert(request.branch.clone(), head.clone());
+                head
+            }
+        };
+        let parent = expected_parent(request, current_head.as_ref())?;
+        let base = match parent.as_ref().and_then(|id| staged_index.get(id)) {
+            Some(&index) => (
+                Arc::clone(&staged[index].snapshot),
+                Arc::clone(&staged[index].registry),
+            ),
+            None => inner.base_for(parent.as_ref()).await?,
+        };
+        let record = stage_commit(inner, request, parent, base).await?;
+        let commit_id = record.summary.id.clone();
+        if staged_index
+            .insert(commit_id.clone(), staged.len())
+            .is_some()
+        {
+            return Err(PraxisError::IntegrityViolation {
+                message: format!("commit '{commit_id}' already exists"),
             });
         }
-        (Some(explicit), _) => Some(explicit.clone()),
-        (None, head) => head.clone(),
-    };
+        heads.insert(request.branch.clone(), Some(commit_id));
+        staged.push(record);
+    }
+    if staged.is_empty() {
+        return Ok(Vec::new());
+    }
 
-    let base_snapshot = match expected_parent.as_deref() {
-        Some(parent_id) => inner.snapshot_for(parent_id).await?,
-        None => Arc::new(GraphSnapshot::empty()),
+    let batch = CommitBatch {
+        commits: staged
+            .iter()
+            .map(|record| PersistedCommit {
+                summary: record.summary.clone(),
+                change_set: record.change_set.clone(),
+                blobs: record.blobs.clone(),
+            })
+            .collect(),
+        tags: staged
+            .iter()
+            .map(|record| (snapshot_tag(&record.summary.id), record.summary.id.clone()))
+            .collect(),
+        refs: heads
+            .iter()
+            .map(|(branch, next)| RefUpdate {
+                branch: branch.clone(),
+                expected: initial.get(branch).cloned().flatten(),
+                next: next.clone(),
+            })
+            .collect(),
     };
+    inner.store.put_commits(&batch).await?;
+
+    for (branch, head) in heads {
+        inner.branches.entry(branch).or_default().head = head;
+    }
+    let ids = staged
+        .iter()
+        .map(|record| record.summary.id.clone())
+        .collect();
+    for record in staged {
+        inner.commits.insert(record.summary.id.clone(), record);
+    }
+    Ok(ids)
+}
+
+/// Parent a commit request builds on: its explicit parent, which must match the branch head,
+/// or the head itself.
+fn expected_parent(
+    request: &CommitChangesRequest,
+    current_head: Option<&String>,
+) -> PraxisResult<Option<String>> {
+    match (&request.parent, current_head) {
+        (Some(explicit), Some(head)) if explicit != head => Err(PraxisError::ConcurrencyConflict {
+            branch: request.branch.clone(),
+            expected: Some(explicit.clone()),
+            actual: Some(head.clone()),
+        }),
+        (Some(explicit), _) => Ok(Some(explicit.clone())),
+        (None, head) => Ok(head.cloned()),
+    }
+}
+
+/// Run every meta-model rule over `request` against the snapshot it would commit on, without
+/// writing anything.
+pub(super) async fn validate_changes(
+    inner: &mut Inner,
+    request: &CommitChangesRequest,
+) -> PraxisResult<ValidationReport> {
+    let (_, _, _, _, report) = check_changes(inner, request).await?;
+    Ok(report)
+}
 
+/// Apply `request` to its branch head in memory and describe the outcome. Nothing is
+/// persisted and the branch head does not move.
+pub(super) async fn preview_commit(
+    inner: &mut Inner,
+    request: &CommitChangesRequest,
+) -> PraxisResult<CommitPreview> {
+    let (parent, base_snapshot, base_registry, next, report) =
+        check_changes(inner, request).await?;
+    let blobs = base_registry.blob_refs(&normalize_change_set(&request.changes));
+    ensure_blobs_exist(inner, &blobs).await?;
+    Ok(CommitPreview {
+        parent,
+        patch: base_snapshot.diff(&next),
+        stats: StatsDelta::between(base_snapshot.stats(), next.stats()),
+        report,
+    })
+}
+
+/// Plan moving `branch` to the `target` schema, checking its head against it.
+pub(super) async fn plan_schema_migration(
+    inner: &mut Inner,
+    branch: &str,
+    target: &MetaModelDocument,
+    hints: &MigrationHints,
+) -> PraxisResult<MigrationPlan> {
+    let head = inner
+        .branches
+        .get(branch)
+        .ok_or_else(|| PraxisError::UnknownBranch {
+            branch: branch.to_string(),
+        })?
+        .head
+        .clone();
+    let (snapshot, registry) = inner.base_for(head.as_ref()).await?;
+    plan_migration(&snapshot, &registry.document(), target, hints)
+}
+
+/// Apply `request` to the snapshot it would commit on, collecting every violation, including
+/// a meta-model the changes would leave invalid. Returns the parent, its snapshot and schema,
+/// the resulting snapshot and the report.
+async fn check_changes(
+    inner: &mut Inner,
+    request: &CommitChangesRequest,
+) -> PraxisResult<(
+    Option<String>,
+    Arc<GraphSnapshot>,
+    Arc<MetaModelRegistry>,
+    GraphSnapshot,
+    ValidationReport,
+)> {
+    validate_branch_name(&request.branch)?;
+    let current_head = inner
+        .branches
+        .get(&request.branch)
+        .and_then(|state| state.head.clone());
+    let parent = expected_parent(request, current_head.as_ref())?;
+    let (base_snapshot, base_registry) = inner.base_for(parent.as_ref()).await?;
+    let normalized_changes = normalize_change_set(&request.changes);
+    let (next, mut report) =
+        base_snapshot.apply_lenient(&normalized_changes, base_registry.as_ref())?;
+    if let Err(issue) = schema_after(&base_registry, &normalized_changes, &base_snapshot, &next) {
+        report.issues.push(issue);
+    }
+    Ok((parent, base_snapshot, base_registry, next, report))
+}
+
+/// Schema in effect after `changes`, reporting entries that no longer build one as an issue.
+fn schema_after(
+    registry: &Arc<MetaModelRegistry>,
+    changes: &ChangeSet,
+    before: &GraphSnapshot,
+    after: &GraphSnapshot,
+) -> Result<Arc<MetaModelRegistry>, ValidationIssue> {
+    registry
+        .after_change(changes, before, after)
+        .map_err(|err| {
+            let reason = match err {
+                PraxisError::IntegrityViolation { message } => message,
+                other => other.to_string(),
+            };
+            ValidationIssue::error(
+                "meta-model",
+                ValidationRule::MetaModel,
+                format!("meta-model entries are invalid: {reason}"),
+            )
+        })
+}
+
+/// Normalise and apply `request` on top of `base`, the parent's snapshot and schema,
+/// returning the commit it would persist with its resulting snapshot and schema. Nothing is
+/// written.
+async fn stage_commit(
+    inner: &Inner,
+    request: &CommitChangesRequest,
+    parent: Option<String>,
+    (base_snapshot, base_registry): (Arc<GraphSnapshot>, Arc<MetaModelRegistry>),
+) -> PraxisResult<CommitRecord> {
     let normalized_changes = normalize_change_set(&request.changes);
     if !inner.config.allow_empty_commits && normalized_changes.is_empty() {
         return Err(PraxisError::ValidationFailed {
             message: "empty commits are disabled".into(),
         });
     }
-
-    let snapshot = Arc::new(base_snapshot.apply(&normalized_changes, inner.registry.as_ref())?);
-
-    let parents: Vec<String> = expected_parent.into_iter().collect();
+    let normalized_changes = with_declared_defaults(normalized_changes, &base_registry);
+
+    let snapshot = match base_snapshot.apply(&normalized_changes, &base_registry) {
+        Ok(snapshot) => Arc::new(snapshot),
+        // Rerun in collect-all mode so the caller sees every violation, not just the first.
+        Err(err) if err.code() == PraxisErrorCode::ValidationFailed => {
+            let report = base_snapshot.validate_changes(&normalized_changes, &base_registry)?;
+            if report.is_valid() {
+                return Err(err);
+            }
+            return Err(PraxisError::InvalidChanges { report });
+        }
+        Err(err) => return Err(err),
+    };
+    let registry = schema_after(
+        &base_registry,
+        &normalized_changes,
+        &base_snapshot,
+        &snapshot,
+    )
+    .map_err(ValidationIssue::into_error)?;
+    let blobs = base_registry.blob_refs(&normalized_changes);
+    ensure_blobs_exist(inner, &blobs).await?;
+
+    let parents: Vec<String> = parent.into_iter().collect();
     let timestamp = request.time.clone().or_else(|| Some(current_timestamp()));
 
     let commit_id = derive_commit_id(
@@ -79,15 +339,9 @@ pub(super) async fn commit(
         &normalized_changes,
     );
 
-    if inner.store.get_commit(&commit_id).await?.is_some() {
-        return Err(PraxisError::IntegrityViolation {
-            message: format!("commit '{commit_id}' already exists"),
-        });
-    }
-
     let summary = CommitSummary {
-        id: commit_id.clone(),
-        parents: parents.clone(),
+        id: commit_id,
+        parents,
         branch: request.branch.clone(),
         author: request.author.clone(),
         time: timestamp,
@@ -95,35 +349,13 @@ pub(super) async fn commit(
         tags: request.tags.clone(),
         change_count: change_count(&normalized_changes),
     };
-
-    let persisted = PersistedCommit {
-        summary: summary.clone(),
-        change_set: normalized_changes.clone(),
-    };
-
-    inner.store.put_commit(&persisted).await?;
-    inner.record_snapshot_tag(&commit_id).await?;
-
-    inner
-        .store
-        .compare_and_swap_branch(&request.branch, current_head.as_deref(), Some(&commit_id))
-        .await?;
-    inner
-        .branches
-        .entry(request.branch.clone())
-        .or_default()
-        .head = Some(commit_id.clone());
-
-    inner.commits.insert(
-        commit_id.clone(),
-        CommitRecord {
-            summary,
-            snapshot: Arc::clone(&snapshot),
-            change_set: normalized_changes,
-        },
-    );
-
-    Ok(commit_id)
+    Ok(CommitRecord {
+        summary,
+        snapshot,
+        registry,
+        change_set: normalized_changes,
+        blobs,
+    })
 }
 
 pub(super) async fn create_branch(
@@ -176,18 +408,76 @@ pub(super) async fn list_commits(
 }
 
 pub(super) async fn state_at(inner: &mut Inner, args: StateAtArgs) -> PraxisResult<StateAtResult> {
-    let (commit_id, snapshot, branch_name) =
-        resolve_snapshot(inner, &args.as_of, args.scenario.as_deref()).await?;
-    let stats = snapshot.stats();
+    let commit_id = resolve_commit_id(inner, &args.as_of, args.scenario.as_deref()).await?;
+    // Cached snapshots answer directly; otherwise the store counts without replaying history.
+    let (nodes, edges, branch_name) = match inner.commits.get(&commit_id) {
+        Some(record) => {
+            let stats = record.snapshot.stats();
+            (
+                stats.node_count as u64,
+                stats.edge_count as u64,
+                record.summary.branch.clone(),
+            )
+        }
+        None => {
+            let state = inner
+                .store
+                .graph_state(&commit_id, &GraphFilter::default())
+                .await?;
+            let summary = inner
+                .store
+                .get_commit(&commit_id)
+                .await?
+                .ok_or_else(|| PraxisError::UnknownCommit {
+                    commit: commit_id.clone(),
+                })?
+                .summary;
+            (
+                state.nodes.len() as u64,
+                state.edges.len() as u64,
+                summary.branch,
+            )
+        }
+    };
     Ok(StateAtResult::new(
         commit_id,
         Some(branch_name),
         args.confidence,
-        stats.node_count as u64,
-        stats.edge_count as u64,
+        nodes,
+        edges,
     ))
 }
 
+/// Reject `Blob` attribute values naming blobs the store does not hold.
+async fn ensure_blobs_exist(inner: &Inner, referenced: &BTreeSet<BlobRef>) -> PraxisResult<()> {
+    if referenced.is_empty() {
+        return Ok(());
+    }
+    let blobs = inner
+        .store
+        .blobs()
+        .ok_or_else(|| PraxisError::ValidationFailed {
+            message: "blob references require a store with blob storage".into(),
+        })?;
+    for blob in referenced {
+        if blobs.blob_info(blob).await?.is_none() {
+            return Err(PraxisError::ValidationFailed {
+                message: format!("unknown blob '{blob}'"),
+            });
+        }
+    }
+    Ok(())
+}
+
+pub(super) async fn search(inner: &mut Inner, args: SearchArgs) -> PraxisResult<SearchResult> {
+    let commit_id = resolve_commit_id(inner, &args.as_of, args.scenario.as_deref()).await?;
+    let mut query = args.query;
+    let registry = inner.registry_at(&commit_id).await?;
+    query.node_types = with_subtypes(&registry, &query.node_types);
+    let hits = inner.store.search_nodes(&commit_id, &query).await?;
+    Ok(SearchResult { commit_id, hits })
+}
+
 pub(super) async fn diff_summary(inner: &mut Inner, args: DiffArgs) -> PraxisResult<DiffSummary> {
     let (from_id, from_snapshot, _) = resolve_snapshot(inner, &args.from, None).await?;
     let (to_id, to_snapshot, _) = resolve_snapshot(inner, &args.to, None).await?;
@@ -204,6 +494,18 @@ pub(super) async fn diff_summary(inner: &mut Inner, args: DiffArgs) -> PraxisRes
     ))
 }
 
+/// Schema differences between the commits `args` resolves to; `scope` is ignored.
+pub(super) async fn meta_model_diff(
+    inner: &mut Inner,
+    args: DiffArgs,
+) -> PraxisResult<MetaModelDiff> {
+    let from_id = resolve_commit_id(inner, &args.from, None).await?;
+    let to_id = resolve_commit_id(inner, &args.to, None).await?;
+    let from = inner.registry_at(&from_id).await?;
+    let to = inner.registry_at(&to_id).await?;
+    Ok(MetaModelDiff::between(&from.document(), &to.document()))
+}
+
 pub(super) async fn topology_delta(
     inner: &mut Inner,
     args: TopologyDeltaArgs,
@@ -304,11 +606,16 @@ pub(super) async fn merge(inner: &mut Inner, request: MergeRequest) -> PraxisRes
         tags: tags.clone(),
         change_count: change_count(&normalized_changes),
     };
-    let snapshot = Arc::new(target_snapshot.apply(&normalized_changes, inner.registry.as_ref())?);
+    let target_registry = inner.registry_at(&target_head).await?;
+    let (snapshot, registry) =
+        target_snapshot.apply_merge(&normalized_changes, &target_registry)?;
+    let snapshot = Arc::new(snapshot);
+    let blobs = target_registry.blob_refs(&normalized_changes);
 
     let persisted = PersistedCommit {
         summary: summary.clone(),
         change_set: normalized_changes.clone(),
+        blobs: blobs.clone(),
     };
 
     inner.store.put_commit(&persisted).await?;
@@ -327,7 +634,9 @@ pub(super) async fn merge(inner: &mut Inner, request: MergeRequest) -> PraxisRes
         CommitRecord {
             summary,
             snapshot: Arc::clone(&snapshot),
+            registry,
             change_set: normalized_changes,
+            blobs,
         },
     );
 
@@ -528,6 +837,7 @@ mod tests {
                     label: None,
                     category: None,
                     extends: None,
+                    is_abstract: false,
                     attributes: vec![],
                     effect_types: vec![],
                 },
@@ -536,6 +846,7 @@ mod tests {
                     label: None,
                     category: None,
                     extends: None,
+                    is_abstract: false,
                     attributes: vec![],
                     effect_types: vec![],
                 },
@@ -553,6 +864,7 @@ mod tests {
                 attributes: None,
                 relationships: Some(rels),
             }),
+            remove: None,
         };
         MetaModelRegistry::from_document(doc).expect("registry")
     }
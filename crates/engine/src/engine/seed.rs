@@ -4,8 +4,8 @@ use crate::PraxisEngine;
 use crate::dataset::BaselineDataset;
 use crate::error::PraxisResult;
 use crate::meta_seed::meta_model_seed_change_set;
+use aideon_mneme::meta::MetaModelDocument;
 use aideon_mneme::temporal::CommitChangesRequest;
-use std::collections::BTreeMap;
 
 impl PraxisEngine {
     /// Ensure the commit log contains an initial design sample commit.
@@ -28,40 +28,29 @@ impl PraxisEngine {
         Ok(())
     }
 
+    /// Commit the meta-model seed and every dataset commit as a single batch.
     pub async fn bootstrap_with_dataset(&self, dataset: &BaselineDataset) -> PraxisResult<()> {
-        self.seed_meta_commit().await?;
-        self.apply_dataset_commits(dataset).await?;
+        let configured = self.lock().await.registry.document();
+        let mut requests = vec![meta_seed_request(&configured)];
+        requests.extend(
+            dataset
+                .commits()
+                .iter()
+                .map(|commit| commit.to_request(None)),
+        );
+        self.commit_batch(requests).await?;
         Ok(())
     }
+}
 
-    async fn seed_meta_commit(&self) -> PraxisResult<String> {
-        let meta_changes = meta_model_seed_change_set();
-        let request = CommitChangesRequest {
-            branch: "main".into(),
-            parent: None,
-            author: Some("bootstrap".into()),
-            time: None,
-            message: "seed: meta-model".into(),
-            tags: vec!["baseline".into(), "meta".into()],
-            changes: meta_changes,
-        };
-        self.commit(request).await
-    }
-
-    async fn apply_dataset_commits(&self, dataset: &BaselineDataset) -> PraxisResult<()> {
-        let mut branch_heads: BTreeMap<String, Option<String>> = self
-            .list_branches()
-            .await
-            .into_iter()
-            .map(|info| (info.name.clone(), info.head.clone()))
-            .collect();
-        for commit in dataset.commits() {
-            let branch = commit.branch.clone();
-            let parent = branch_heads.get(&branch).and_then(|head| head.clone());
-            let request = commit.to_request(parent);
-            let next_id = self.commit(request).await?;
-            branch_heads.insert(branch, Some(next_id));
-        }
-        Ok(())
+fn meta_seed_request(configured: &MetaModelDocument) -> CommitChangesRequest {
+    CommitChangesRequest {
+        branch: "main".into(),
+        parent: None,
+        author: Some("bootstrap".into()),
+        time: None,
+        message: "seed: meta-model".into(),
+        tags: vec!["baseline".into(), "meta".into()],
+        changes: meta_model_seed_change_set(configured),
     }
 }
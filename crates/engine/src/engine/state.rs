@@ -3,11 +3,12 @@
 use crate::engine::config::PraxisEngineConfig;
 use crate::error::PraxisResult;
 use crate::graph::GraphSnapshot;
-use crate::meta::MetaModelRegistry;
+use crate::meta::{META_MODEL_ENTRY_TYPE, MetaModelRegistry};
+use aideon_mneme::blob::BlobRef;
 use aideon_mneme::temporal::ChangeSet;
-use aideon_mneme::{CommitSummary, Store};
+use aideon_mneme::{CommitSummary, GraphFilter, Store};
 use async_recursion::async_recursion;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 
 pub(super) struct Inner {
@@ -15,6 +16,7 @@ pub(super) struct Inner {
     pub(super) branches: BTreeMap<String, BranchState>,
     pub(super) config: PraxisEngineConfig,
     pub(super) store: Arc<dyn Store>,
+    /// Configured schema, in effect until a commit records meta-model entries.
     pub(super) registry: Arc<MetaModelRegistry>,
 }
 
@@ -22,8 +24,12 @@ pub(super) struct Inner {
 pub(super) struct CommitRecord {
     pub(super) summary: CommitSummary,
     pub(super) snapshot: Arc<GraphSnapshot>,
+    /// Schema in effect at this commit, used to validate its children.
+    pub(super) registry: Arc<MetaModelRegistry>,
     #[allow(dead_code)]
     pub(super) change_set: ChangeSet,
+    /// Blobs the change set's `Blob` attributes reference.
+    pub(super) blobs: BTreeSet<BlobRef>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -73,26 +79,85 @@ impl Inner {
                 commit: commit_id.into(),
             }
         })?;
-        let base_snapshot = match persisted.summary.parents.first() {
-            Some(parent_id) => self.snapshot_for(parent_id).await?,
-            None => Arc::new(GraphSnapshot::empty()),
+        let (base_snapshot, base_registry) =
+            self.base_for(persisted.summary.parents.first()).await?;
+        let replay_failed = |err| crate::error::PraxisError::IntegrityViolation {
+            message: format!("replay commit '{commit_id}' failed: {err}"),
         };
-        let snapshot = Arc::new(
+        let change_set = &persisted.change_set;
+        let (snapshot, registry) = if persisted.summary.parents.len() > 1 {
             base_snapshot
-                .apply(&persisted.change_set, self.registry.as_ref())
-                .map_err(|err| crate::error::PraxisError::IntegrityViolation {
-                    message: format!("replay commit '{commit_id}' failed: {err}"),
-                })?,
-        );
+                .apply_merge(change_set, &base_registry)
+                .map_err(replay_failed)?
+        } else {
+            let snapshot = base_snapshot
+                .apply(change_set, base_registry.as_ref())
+                .map_err(replay_failed)?;
+            let registry = base_registry
+                .after_change(change_set, &base_snapshot, &snapshot)
+                .map_err(replay_failed)?;
+            (snapshot, registry)
+        };
+        let snapshot = Arc::new(snapshot);
         let record = CommitRecord {
             summary: persisted.summary.clone(),
             snapshot: Arc::clone(&snapshot),
+            registry,
             change_set: persisted.change_set.clone(),
+            blobs: persisted.blobs.clone(),
         };
         self.commits.insert(commit_id.into(), record.clone());
         Ok(record)
     }
 
+    /// Snapshot and schema a child of `parent` builds on; the configured schema and an empty
+    /// graph for a root commit.
+    pub(super) async fn base_for(
+        &mut self,
+        parent: Option<&String>,
+    ) -> PraxisResult<(Arc<GraphSnapshot>, Arc<MetaModelRegistry>)> {
+        match parent {
+            Some(parent_id) => {
+                let record = self.record_for(parent_id).await?;
+                Ok((record.snapshot, record.registry))
+            }
+            None => Ok((Arc::new(GraphSnapshot::empty()), Arc::clone(&self.registry))),
+        }
+    }
+
+    /// Schema in effect at `commit_id`. Uncached commits read their meta-model entries from the
+    /// store rather than replaying the snapshot.
+    pub(super) async fn registry_at(
+        &mut self,
+        commit_id: &str,
+    ) -> PraxisResult<Arc<MetaModelRegistry>> {
+        if let Some(record) = self.commits.get(commit_id) {
+            return Ok(Arc::clone(&record.registry));
+        }
+        let filter = GraphFilter {
+            node_types: vec![META_MODEL_ENTRY_TYPE.into()],
+            ..GraphFilter::default()
+        };
+        let state = self.store.graph_state(commit_id, &filter).await?;
+        Ok(Arc::new(self.registry.with_entries(&state.nodes)?))
+    }
+
+    /// Schema in effect at the head of `branch`, or the configured schema before its first
+    /// commit.
+    pub(super) async fn registry_for_branch(
+        &mut self,
+        branch: &str,
+    ) -> PraxisResult<Arc<MetaModelRegistry>> {
+        let head = self
+            .branches
+            .get(branch)
+            .and_then(|state| state.head.clone());
+        match head {
+            Some(head) => self.registry_at(&head).await,
+            None => Ok(Arc::clone(&self.registry)),
+        }
+    }
+
     #[async_recursion]
     pub(super) async fn snapshot_for(
         &mut self,
@@ -3,6 +3,7 @@
 use crate::engine::state::Inner;
 use crate::error::{PraxisError, PraxisResult};
 use crate::graph::GraphSnapshot;
+use crate::meta::MetaModelRegistry;
 use aideon_mneme::temporal::{ChangeSet, CommitRef, EdgeTombstone, EdgeVersion};
 use blake3::Hasher;
 use serde::Serialize;
@@ -21,6 +22,19 @@ pub(super) fn change_count(set: &ChangeSet) -> u64 {
         + set.edge_deletes.len()) as u64
 }
 
+/// `types` followed by their subtypes, so type filters also match specialised nodes.
+pub(super) fn with_subtypes(registry: &MetaModelRegistry, types: &[String]) -> Vec<String> {
+    let mut expanded = types.to_vec();
+    for ty in types {
+        for subtype in registry.subtypes_of(ty) {
+            if !expanded.contains(&subtype) {
+                expanded.push(subtype);
+            }
+        }
+    }
+    expanded
+}
+
 pub(super) fn normalize_change_set(input: &ChangeSet) -> ChangeSet {
     let mut normalized = input.clone();
     normalized.node_creates.sort_by_key(|node| node.id.clone());
@@ -32,6 +46,27 @@ pub(super) fn normalize_change_set(input: &ChangeSet) -> ChangeSet {
     normalized
 }
 
+/// `changes` with the declared defaults filled into created nodes and edges, so the persisted
+/// change set matches the snapshot it produces.
+pub(super) fn with_declared_defaults(
+    changes: ChangeSet,
+    registry: &MetaModelRegistry,
+) -> ChangeSet {
+    ChangeSet {
+        node_creates: changes
+            .node_creates
+            .iter()
+            .map(|node| registry.node_with_defaults(node))
+            .collect(),
+        edge_creates: changes
+            .edge_creates
+            .iter()
+            .map(|edge| registry.edge_with_defaults(edge))
+            .collect(),
+        ..changes
+    }
+}
+
 fn edge_sort_key(edge: &EdgeVersion) -> (String, String, String) {
     (
         edge.id.clone().unwrap_or_default(),
@@ -139,7 +174,7 @@ pub(super) async fn resolve_commit_id(
 ) -> PraxisResult<String> {
     match reference {
         CommitRef::Id(value) => {
-            if inner.record_for(value).await.is_ok() {
+            if commit_exists(inner, value).await? {
                 Ok(value.clone())
             } else if let Some(branch_state) = inner.branches.get(value.as_str()) {
                 branch_state
@@ -170,7 +205,9 @@ pub(super) async fn resolve_commit_id(
         }
         CommitRef::Branch { branch, at } => {
             if let Some(at) = at {
-                inner.record_for(at).await?;
+                if !commit_exists(inner, at).await? {
+                    return Err(PraxisError::UnknownCommit { commit: at.clone() });
+                }
                 return Ok(at.clone());
             }
             let target_branch =
@@ -190,6 +227,14 @@ pub(super) async fn resolve_commit_id(
     }
 }
 
+/// Check for a commit without replaying its snapshot.
+async fn commit_exists(inner: &Inner, commit_id: &str) -> PraxisResult<bool> {
+    if inner.commits.contains_key(commit_id) {
+        return Ok(true);
+    }
+    Ok(inner.store.get_commit(commit_id).await?.is_some())
+}
+
 pub(super) async fn find_common_ancestor(
     inner: &mut Inner,
     a: &str,
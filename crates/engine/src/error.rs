@@ -1,6 +1,8 @@
 use aideon_mneme::MnemeError;
 use thiserror::Error;
 
+use crate::report::ValidationReport;
+
 /// Structured error codes emitted by the Praxis engine.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PraxisErrorCode {
@@ -36,6 +38,10 @@ pub enum PraxisError {
     #[error("validation failed: {message}")]
     ValidationFailed { message: String },
 
+    /// A change set broke meta-model rules; the report lists every violation, not just the first.
+    #[error("validation failed: {}", report.summary())]
+    InvalidChanges { report: ValidationReport },
+
     #[error("integrity violation: {message}")]
     IntegrityViolation { message: String },
 
@@ -44,13 +50,23 @@ pub enum PraxisError {
 }
 
 impl PraxisError {
+    /// Structured validation report carried by the error, if any.
+    pub fn report(&self) -> Option<&ValidationReport> {
+        match self {
+            Self::InvalidChanges { report } => Some(report),
+            _ => None,
+        }
+    }
+
     /// Machine-friendly error code for host serialization.
     pub fn code(&self) -> PraxisErrorCode {
         match self {
             Self::UnknownBranch { .. } => PraxisErrorCode::UnknownBranch,
             Self::UnknownCommit { .. } => PraxisErrorCode::UnknownCommit,
             Self::ConcurrencyConflict { .. } => PraxisErrorCode::ConcurrencyConflict,
-            Self::ValidationFailed { .. } => PraxisErrorCode::ValidationFailed,
+            Self::ValidationFailed { .. } | Self::InvalidChanges { .. } => {
+                PraxisErrorCode::ValidationFailed
+            }
             Self::IntegrityViolation { .. } => PraxisErrorCode::IntegrityViolation,
             Self::MergeConflict { .. } => PraxisErrorCode::MergeConflict,
         }
@@ -70,6 +86,12 @@ impl From<MnemeError> for PraxisError {
                 actual,
             },
             MnemeError::Storage { message } => PraxisError::IntegrityViolation { message },
+            err @ (MnemeError::KeyRequired { .. }
+            | MnemeError::WrongKey { .. }
+            | MnemeError::SchemaTooNew { .. }
+            | MnemeError::Locked { .. }) => PraxisError::IntegrityViolation {
+                message: err.to_string(),
+            },
         }
     }
 }
@@ -77,6 +99,7 @@ impl From<MnemeError> for PraxisError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::report::{ValidationIssue, ValidationRule};
 
     #[test]
     fn codes_are_stable() {
@@ -110,6 +133,13 @@ mod tests {
             .code(),
             PraxisErrorCode::ValidationFailed
         );
+        assert_eq!(
+            PraxisError::InvalidChanges {
+                report: ValidationReport::default()
+            }
+            .code(),
+            PraxisErrorCode::ValidationFailed
+        );
         assert_eq!(
             PraxisError::IntegrityViolation {
                 message: "x".into()
@@ -126,6 +156,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn invalid_changes_summarise_the_report() {
+        let issue = |id: &str| {
+            ValidationIssue::error(id, ValidationRule::Required, format!("node '{id}' invalid"))
+        };
+        let err = PraxisError::InvalidChanges {
+            report: ValidationReport {
+                issues: vec![issue("a1"), issue("a2"), issue("a3")],
+            },
+        };
+        assert_eq!(
+            err.to_string(),
+            "validation failed: node 'a1' invalid (+2 more)"
+        );
+        assert_eq!(err.report().map(|report| report.issues.len()), Some(3));
+        assert!(
+            PraxisError::ValidationFailed {
+                message: "x".into()
+            }
+            .report()
+            .is_none()
+        );
+    }
+
     #[test]
     fn mneme_errors_map_to_praxis_errors() {
         let err: PraxisError = MnemeError::Storage {
@@ -141,5 +195,13 @@ mod tests {
         }
         .into();
         assert!(matches!(err, PraxisError::ConcurrencyConflict { .. }));
+
+        let err: PraxisError = MnemeError::WrongKey {
+            message: "authentication failed".into(),
+        }
+        .into();
+        assert!(
+            matches!(err, PraxisError::IntegrityViolation { ref message } if message.contains("key"))
+        );
     }
 }
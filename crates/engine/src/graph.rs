@@ -1,12 +1,15 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
 
 use aideon_mneme::temporal::{
     ChangeSet, DiffPatch, EdgeTombstone, EdgeVersion, NodeTombstone, NodeVersion,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::error::{PraxisError, PraxisResult};
-use crate::meta::MetaModelRegistry;
+use crate::error::PraxisResult;
+use crate::meta::{MetaModelRegistry, MultiplicityViolation, edge_label, split_entry_changes};
+use crate::report::{ValidationIssue, ValidationReport, ValidationRule};
+use aideon_mneme::meta::MetaRuleSeverity;
 
 /// Deterministic key for edges stored inside a graph snapshot.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -56,18 +59,220 @@ impl GraphSnapshot {
         }
     }
 
+    /// Apply `change`, failing on the first rule violation.
     pub fn apply(
         &self,
         change: &ChangeSet,
         registry: &MetaModelRegistry,
+    ) -> PraxisResult<GraphSnapshot> {
+        let mut issues = Issues::fail_fast();
+        self.apply_checked(change, registry, &mut issues)
+    }
+
+    /// Run every node and edge rule over `change` and report all violations, warnings
+    /// included, instead of stopping at the first.
+    pub fn validate_changes(
+        &self,
+        change: &ChangeSet,
+        registry: &MetaModelRegistry,
+    ) -> PraxisResult<ValidationReport> {
+        Ok(self.apply_lenient(change, registry)?.1)
+    }
+
+    /// Apply `change` without failing on rule violations, returning the snapshot together with
+    /// every issue. Elements that break meta-model rules are kept so later checks see them;
+    /// creates of existing elements and changes to missing ones are skipped.
+    pub fn apply_lenient(
+        &self,
+        change: &ChangeSet,
+        registry: &MetaModelRegistry,
+    ) -> PraxisResult<(GraphSnapshot, ValidationReport)> {
+        let mut issues = Issues::collect_all();
+        let next = self.apply_checked(change, registry, &mut issues)?;
+        Ok((next, issues.report))
+    }
+
+    fn apply_checked(
+        &self,
+        change: &ChangeSet,
+        registry: &MetaModelRegistry,
+        issues: &mut Issues,
     ) -> PraxisResult<GraphSnapshot> {
         let mut next = self.clone();
-        apply_node_changes(&mut next, change, registry)?;
-        apply_edge_changes(&mut next, change, registry)?;
-        next.validate()?;
+        apply_node_changes(&mut next, change, registry, issues)?;
+        apply_edge_changes(&mut next, change, registry, issues)?;
+        next.validate(issues)?;
+        let touched = change.node_creates.iter().chain(&change.node_updates);
+        next.enforce_uniqueness(touched.map(|node| node.id.as_str()), registry, issues)?;
+        next.enforce_multiplicity(change, registry, issues)?;
         Ok(next)
     }
 
+    /// Apply a merge's `change`. Its meta-model entry changes go first, under `registry`, the
+    /// target's schema; the rest is then validated against the merged schema, so merged data
+    /// may use types the source branch added. Returns the snapshot and the schema after it.
+    pub fn apply_merge(
+        &self,
+        change: &ChangeSet,
+        registry: &Arc<MetaModelRegistry>,
+    ) -> PraxisResult<(GraphSnapshot, Arc<MetaModelRegistry>)> {
+        let (entries, rest) = split_entry_changes(change, self);
+        let with_schema = self.apply(&entries, registry)?;
+        let merged = registry.after_change(&entries, self, &with_schema)?;
+        let next = with_schema.apply(&rest, &merged)?;
+        Ok((next, merged))
+    }
+
+    /// Every multiplicity violation in the snapshot, warnings included.
+    pub fn multiplicity_violations(
+        &self,
+        registry: &MetaModelRegistry,
+    ) -> Vec<MultiplicityViolation> {
+        registry.multiplicity_violations(self.nodes.values(), self.edges.values())
+    }
+
+    /// Check every element already in the snapshot against `registry`, as a commit under that
+    /// schema would check the elements it touches. Used to find data a schema change breaks.
+    pub fn check_against(&self, registry: &MetaModelRegistry) -> PraxisResult<ValidationReport> {
+        let mut issues = Issues::collect_all();
+        for node in self.nodes.values() {
+            issues.extend(registry.check_node(node))?;
+        }
+        let mut pairs: BTreeMap<(&str, &str, &str), usize> = BTreeMap::new();
+        for edge in self.edges.values() {
+            let from_type = self
+                .nodes
+                .get(&edge.from)
+                .map(|node| node.r#type.as_deref());
+            let to_type = self.nodes.get(&edge.to).map(|node| node.r#type.as_deref());
+            // Dangling edges are reported by `validate`; untyped endpoints by their node check.
+            let (Some(Some(from_type)), Some(Some(to_type))) = (from_type, to_type) else {
+                continue;
+            };
+            issues.extend(registry.check_edge(edge, from_type, to_type))?;
+            if let Some(rel_type) = edge.r#type.as_deref() {
+                *pairs.entry((rel_type, &edge.from, &edge.to)).or_default() += 1;
+            }
+        }
+        for ((rel_type, from, to), count) in pairs {
+            if count > 1 && !registry.allows_duplicate(rel_type) {
+                issues.push(ValidationIssue::error(
+                    format!("{from}->{to}"),
+                    ValidationRule::DuplicateRelationship,
+                    format!("relationship '{rel_type}' already exists between '{from}' and '{to}'"),
+                ))?;
+            }
+        }
+        self.validate(&mut issues)?;
+        self.enforce_uniqueness(self.nodes.keys().map(String::as_str), registry, &mut issues)?;
+        issues.extend(
+            self.multiplicity_violations(registry)
+                .into_iter()
+                .map(ValidationIssue::from)
+                .collect(),
+        )?;
+        Ok(issues.report)
+    }
+
+    /// Report nodes among `touched` that repeat a `unique` attribute value held by another
+    /// node anywhere in the snapshot whose type shares the attribute's uniqueness scope: the
+    /// topmost supertype declaring it unique.
+    fn enforce_uniqueness<'a>(
+        &self,
+        touched: impl Iterator<Item = &'a str>,
+        registry: &MetaModelRegistry,
+        issues: &mut Issues,
+    ) -> PraxisResult<()> {
+        // Value -> holders for each (scope type, attribute), indexed on first use.
+        let mut indexes: HashMap<(&str, &str), HashMap<String, Vec<&str>>> = HashMap::new();
+        // Each clashing value is reported once, not again for every node holding it.
+        let mut reported: HashSet<(&str, &str, String)> = HashSet::new();
+        for node in touched.filter_map(|id| self.nodes.get(id)) {
+            let Some(node_type) = node.r#type.as_deref() else {
+                continue;
+            };
+            for (attr, scope) in registry.unique_attributes(node_type) {
+                let Some(value) = node.props.as_ref().and_then(|props| props.get(attr)) else {
+                    continue;
+                };
+                let index = indexes
+                    .entry((scope, attr))
+                    .or_insert_with(|| self.value_index(scope, attr, registry));
+                let key = value.to_string();
+                let holders = index.get(&key).into_iter().flatten();
+                if let Some(other) = holders.copied().find(|id| *id != node.id)
+                    && reported.insert((scope, attr, key.clone()))
+                {
+                    issues.push(
+                        ValidationIssue::error(
+                            &node.id,
+                            ValidationRule::Unique,
+                            format!(
+                                "node '{}' attribute '{attr}': value {value} is already used by node '{other}'",
+                                node.id
+                            ),
+                        )
+                        .on_attribute(attr),
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn value_index(
+        &self,
+        scope: &str,
+        attr: &str,
+        registry: &MetaModelRegistry,
+    ) -> HashMap<String, Vec<&str>> {
+        let mut index: HashMap<String, Vec<&str>> = HashMap::new();
+        for node in self.nodes.values() {
+            let Some(node_type) = node.r#type.as_deref() else {
+                continue;
+            };
+            if registry.unique_scope(node_type, attr) != Some(scope) {
+                continue;
+            }
+            if let Some(value) = node.props.as_ref().and_then(|props| props.get(attr)) {
+                index.entry(value.to_string()).or_default().push(&node.id);
+            }
+        }
+        index
+    }
+
+    /// Report multiplicity violations on nodes the change set touched; only errors abort.
+    fn enforce_multiplicity(
+        &self,
+        change: &ChangeSet,
+        registry: &MetaModelRegistry,
+        issues: &mut Issues,
+    ) -> PraxisResult<()> {
+        let edge_ends = change
+            .edge_creates
+            .iter()
+            .chain(&change.edge_updates)
+            .flat_map(|edge| [&edge.from, &edge.to])
+            .chain(
+                change
+                    .edge_deletes
+                    .iter()
+                    .flat_map(|tombstone| [&tombstone.from, &tombstone.to]),
+            );
+        let touched: BTreeSet<&String> = change
+            .node_creates
+            .iter()
+            .chain(&change.node_updates)
+            .map(|node| &node.id)
+            .chain(edge_ends)
+            .collect();
+        let nodes = touched.into_iter().filter_map(|id| self.nodes.get(id));
+        for violation in registry.multiplicity_violations(nodes, self.edges.values()) {
+            issues.push(violation.into())?;
+        }
+        Ok(())
+    }
+
     pub fn diff(&self, other: &GraphSnapshot) -> DiffPatch {
         let mut patch = DiffPatch::default();
 
@@ -103,17 +308,16 @@ impl GraphSnapshot {
         patch
     }
 
-    fn validate(&self) -> PraxisResult<()> {
+    fn validate(&self, issues: &mut Issues) -> PraxisResult<()> {
         for edge in self.edges.values() {
             if !self.nodes.contains_key(&edge.from) || !self.nodes.contains_key(&edge.to) {
-                return Err(PraxisError::IntegrityViolation {
-                    message: format!(
-                        "edge '{}' references missing endpoint(s)",
-                        edge.id
-                            .clone()
-                            .unwrap_or_else(|| format!("{}->{}", edge.from, edge.to))
-                    ),
-                });
+                let label = edge_label(edge);
+                let message = format!("edge '{label}' references missing endpoint(s)");
+                issues.push(ValidationIssue::error(
+                    label,
+                    ValidationRule::DanglingEdge,
+                    message,
+                ))?;
             }
         }
         Ok(())
@@ -123,6 +327,23 @@ impl GraphSnapshot {
         self.nodes.get(id)
     }
 
+    pub(crate) fn nodes(&self) -> impl Iterator<Item = &NodeVersion> {
+        self.nodes.values()
+    }
+
+    pub(crate) fn edges(&self) -> impl Iterator<Item = &EdgeVersion> {
+        self.edges.values()
+    }
+
+    pub(crate) fn nodes_of_type<'a>(
+        &'a self,
+        node_type: &'a str,
+    ) -> impl Iterator<Item = &'a NodeVersion> + 'a {
+        self.nodes
+            .values()
+            .filter(move |node| node.r#type.as_deref() == Some(node_type))
+    }
+
     pub(crate) fn has_node(&self, id: &str) -> bool {
         self.nodes.contains_key(id)
     }
@@ -157,37 +378,84 @@ fn sanitize_node(node: &NodeVersion) -> NodeVersion {
     copy
 }
 
+/// Where rule violations go while a change set is applied. Fail-fast mode aborts with the
+/// first error; collect-all mode keeps every issue for the report.
+struct Issues {
+    fail_fast: bool,
+    report: ValidationReport,
+}
+
+impl Issues {
+    fn fail_fast() -> Self {
+        Self {
+            fail_fast: true,
+            report: ValidationReport::default(),
+        }
+    }
+
+    fn collect_all() -> Self {
+        Self {
+            fail_fast: false,
+            report: ValidationReport::default(),
+        }
+    }
+
+    fn push(&mut self, issue: ValidationIssue) -> PraxisResult<()> {
+        if self.fail_fast && issue.severity == MetaRuleSeverity::Error {
+            return Err(issue.into_error());
+        }
+        self.report.issues.push(issue);
+        Ok(())
+    }
+
+    fn extend(&mut self, issues: Vec<ValidationIssue>) -> PraxisResult<()> {
+        issues.into_iter().try_for_each(|issue| self.push(issue))
+    }
+}
+
+type IssueResult<T> = Result<T, ValidationIssue>;
+
 fn apply_node_changes(
     snapshot: &mut GraphSnapshot,
     change: &ChangeSet,
     registry: &MetaModelRegistry,
+    issues: &mut Issues,
 ) -> PraxisResult<()> {
     // Node deletes first — we will validate edges afterwards to forbid dangling refs.
     for tombstone in &change.node_deletes {
         if snapshot.nodes.remove(&tombstone.id).is_none() {
-            return Err(PraxisError::ValidationFailed {
-                message: format!("node '{}' does not exist for delete", tombstone.id),
-            });
+            issues.push(ValidationIssue::error(
+                &tombstone.id,
+                ValidationRule::Missing,
+                format!("node '{}' does not exist for delete", tombstone.id),
+            ))?;
         }
     }
 
     for node in &change.node_creates {
-        registry.validate_node(node)?;
+        let node = &registry.node_with_defaults(node);
+        issues.extend(registry.check_node(node))?;
         if snapshot.nodes.contains_key(&node.id) {
-            return Err(PraxisError::ValidationFailed {
-                message: format!("node '{}' already exists", node.id),
-            });
+            issues.push(ValidationIssue::error(
+                &node.id,
+                ValidationRule::AlreadyExists,
+                format!("node '{}' already exists", node.id),
+            ))?;
+            continue;
         }
         snapshot.nodes.insert(node.id.clone(), sanitize_node(node));
     }
 
     // Node updates are replace-by-id for now (TODO: support partial updates with schema merge).
     for node in &change.node_updates {
-        registry.validate_node(node)?;
+        issues.extend(registry.check_node(node))?;
         if !snapshot.nodes.contains_key(&node.id) {
-            return Err(PraxisError::ValidationFailed {
-                message: format!("node '{}' missing for update", node.id),
-            });
+            issues.push(ValidationIssue::error(
+                &node.id,
+                ValidationRule::Missing,
+                format!("node '{}' missing for update", node.id),
+            ))?;
+            continue;
         }
         snapshot.nodes.insert(node.id.clone(), sanitize_node(node));
     }
@@ -198,50 +466,95 @@ fn apply_edge_changes(
     snapshot: &mut GraphSnapshot,
     change: &ChangeSet,
     registry: &MetaModelRegistry,
+    issues: &mut Issues,
 ) -> PraxisResult<()> {
     // Edge deletes
     for tombstone in &change.edge_deletes {
-        remove_edges_matching(&mut snapshot.edges, tombstone)?;
+        if let Err(issue) = remove_edges_matching(&mut snapshot.edges, tombstone) {
+            issues.push(issue)?;
+        }
     }
 
     for edge in &change.edge_creates {
-        ensure_endpoints_exist(&snapshot.nodes, edge)?;
-        let from_type = node_type(&snapshot.nodes, &edge.from)?;
-        let to_type = node_type(&snapshot.nodes, &edge.to)?;
-        registry.validate_edge(edge, &from_type, &to_type)?;
+        let edge = &registry.edge_with_defaults(edge);
+        let storable = check_edge_in(snapshot, edge, registry, issues)?;
         let key = EdgeKey::new(edge);
         if snapshot.edges.contains_key(&key) {
-            return Err(PraxisError::ValidationFailed {
-                message: format!(
-                    "edge '{}' already exists",
-                    edge.id
-                        .clone()
-                        .unwrap_or_else(|| format!("{}->{}", edge.from, edge.to))
-                ),
-            });
+            issues.push(ValidationIssue::error(
+                edge_label(edge),
+                ValidationRule::AlreadyExists,
+                format!("edge '{}' already exists", edge_label(edge)),
+            ))?;
+            continue;
         }
-        let rel_type = relationship_type(edge)?;
-        if !registry.allows_duplicate(rel_type) {
-            assert_no_duplicate_edge(&snapshot.edges, edge, rel_type)?;
+        if storable {
+            check_duplicate_edge(snapshot, edge, registry, issues)?;
+            snapshot.edges.insert(key, sanitize_edge(edge));
         }
-        snapshot.edges.insert(key, sanitize_edge(edge));
     }
 
     // Edge updates — replace existing entry by id when present, otherwise resolve by endpoints.
     for edge in &change.edge_updates {
-        ensure_endpoints_exist(&snapshot.nodes, edge)?;
-        let key = resolve_edge_key(&snapshot.edges, edge)?;
-        snapshot.edges.remove(&key);
-        let from_type = node_type(&snapshot.nodes, &edge.from)?;
-        let to_type = node_type(&snapshot.nodes, &edge.to)?;
-        registry.validate_edge(edge, &from_type, &to_type)?;
-        let rel_type = relationship_type(edge)?;
-        if !registry.allows_duplicate(rel_type) {
-            assert_no_duplicate_edge(&snapshot.edges, edge, rel_type)?;
+        if let Err(issue) = ensure_endpoints_exist(&snapshot.nodes, edge) {
+            issues.push(issue)?;
+            continue;
+        }
+        match resolve_edge_key(&snapshot.edges, edge) {
+            Ok(key) => snapshot.edges.remove(&key),
+            Err(issue) => {
+                issues.push(issue)?;
+                continue;
+            }
+        };
+        if check_edge_in(snapshot, edge, registry, issues)? {
+            check_duplicate_edge(snapshot, edge, registry, issues)?;
+            snapshot
+                .edges
+                .insert(EdgeKey::new(edge), sanitize_edge(edge));
         }
-        snapshot
-            .edges
-            .insert(EdgeKey::new(edge), sanitize_edge(edge));
+    }
+    Ok(())
+}
+
+/// Check `edge` against its endpoints and the meta-model. Returns whether it can be stored:
+/// issues that leave it unusable (missing endpoints or type) skip the insert.
+fn check_edge_in(
+    snapshot: &GraphSnapshot,
+    edge: &EdgeVersion,
+    registry: &MetaModelRegistry,
+    issues: &mut Issues,
+) -> PraxisResult<bool> {
+    let endpoint_types = ensure_endpoints_exist(&snapshot.nodes, edge).and_then(|()| {
+        Ok((
+            node_type(&snapshot.nodes, edge, &edge.from)?,
+            node_type(&snapshot.nodes, edge, &edge.to)?,
+        ))
+    });
+    let (from_type, to_type) = match endpoint_types {
+        Ok(types) => types,
+        Err(issue) => {
+            issues.push(issue)?;
+            return Ok(false);
+        }
+    };
+    issues.extend(registry.check_edge(edge, &from_type, &to_type))?;
+    // A missing relationship type was reported by the meta-model check.
+    Ok(edge.r#type.is_some())
+}
+
+fn check_duplicate_edge(
+    snapshot: &GraphSnapshot,
+    edge: &EdgeVersion,
+    registry: &MetaModelRegistry,
+    issues: &mut Issues,
+) -> PraxisResult<()> {
+    let Some(rel_type) = edge.r#type.as_deref() else {
+        return Ok(());
+    };
+    if !registry.allows_duplicate(rel_type)
+        && let Err(issue) = assert_no_duplicate_edge(&snapshot.edges, edge, rel_type)
+    {
+        issues.push(issue)?;
     }
     Ok(())
 }
@@ -249,15 +562,15 @@ fn apply_edge_changes(
 fn resolve_edge_key(
     edges: &BTreeMap<EdgeKey, EdgeVersion>,
     edge: &EdgeVersion,
-) -> PraxisResult<EdgeKey> {
+) -> IssueResult<EdgeKey> {
+    let missing =
+        |message| ValidationIssue::error(edge_label(edge), ValidationRule::Missing, message);
     if let Some(id) = &edge.id {
         return edges
             .keys()
             .find(|k| k.id.as_deref() == Some(id.as_str()))
             .cloned()
-            .ok_or_else(|| PraxisError::ValidationFailed {
-                message: format!("edge '{}' missing for update", id),
-            });
+            .ok_or_else(|| missing(format!("edge '{}' missing for update", id)));
     }
 
     // Resolve by endpoints; require a single match to maintain determinism.
@@ -267,19 +580,22 @@ fn resolve_edge_key(
         .cloned()
         .collect();
     if matches.is_empty() {
-        return Err(PraxisError::ValidationFailed {
-            message: format!("edge '{}->{}' missing for update", edge.from, edge.to),
-        });
+        return Err(missing(format!(
+            "edge '{}->{}' missing for update",
+            edge.from, edge.to
+        )));
     }
     if matches.len() > 1 {
-        return Err(PraxisError::ValidationFailed {
-            message: format!(
+        return Err(ValidationIssue::error(
+            edge_label(edge),
+            ValidationRule::Ambiguous,
+            format!(
                 "edge '{}->{}' update is ambiguous ({} matches)",
                 edge.from,
                 edge.to,
                 matches.len()
             ),
-        });
+        ));
     }
     Ok(matches.pop().expect("single match"))
 }
@@ -301,44 +617,42 @@ fn sanitize_edge(edge: &EdgeVersion) -> EdgeVersion {
     copy
 }
 
-fn node_type(nodes: &BTreeMap<String, NodeVersion>, node_id: &str) -> PraxisResult<String> {
+fn node_type(
+    nodes: &BTreeMap<String, NodeVersion>,
+    edge: &EdgeVersion,
+    node_id: &str,
+) -> IssueResult<String> {
+    let issue = |rule, message| ValidationIssue::error(edge_label(edge), rule, message);
     let node = nodes
         .get(node_id)
-        .ok_or_else(|| PraxisError::ValidationFailed {
-            message: format!("node '{node_id}' missing"),
-        })?;
-    node.r#type
-        .clone()
-        .ok_or_else(|| PraxisError::ValidationFailed {
-            message: format!("node '{node_id}' missing type"),
-        })
-}
-
-fn relationship_type(edge: &EdgeVersion) -> PraxisResult<&str> {
-    edge.r#type
-        .as_deref()
-        .ok_or_else(|| PraxisError::ValidationFailed {
-            message: format!("edge '{}->{}' missing type", edge.from, edge.to),
-        })
+        .ok_or_else(|| issue(ValidationRule::Missing, format!("node '{node_id}' missing")))?;
+    node.r#type.clone().ok_or_else(|| {
+        issue(
+            ValidationRule::MissingType,
+            format!("node '{node_id}' missing type"),
+        )
+    })
 }
 
 fn assert_no_duplicate_edge(
     edges: &BTreeMap<EdgeKey, EdgeVersion>,
     candidate: &EdgeVersion,
     rel_type: &str,
-) -> PraxisResult<()> {
+) -> IssueResult<()> {
     let exists = edges.values().any(|edge| {
         edge.from == candidate.from
             && edge.to == candidate.to
             && edge.r#type.as_deref() == Some(rel_type)
     });
     if exists {
-        return Err(PraxisError::ValidationFailed {
-            message: format!(
+        return Err(ValidationIssue::error(
+            edge_label(candidate),
+            ValidationRule::DuplicateRelationship,
+            format!(
                 "relationship '{rel_type}' already exists between '{}' and '{}'",
                 candidate.from, candidate.to
             ),
-        });
+        ));
     }
     Ok(())
 }
@@ -346,16 +660,13 @@ fn assert_no_duplicate_edge(
 fn ensure_endpoints_exist(
     nodes: &BTreeMap<String, NodeVersion>,
     edge: &EdgeVersion,
-) -> PraxisResult<()> {
+) -> IssueResult<()> {
     if !nodes.contains_key(&edge.from) || !nodes.contains_key(&edge.to) {
-        return Err(PraxisError::ValidationFailed {
-            message: format!(
-                "edge '{}' references missing node(s)",
-                edge.id
-                    .clone()
-                    .unwrap_or_else(|| format!("{}->{}", edge.from, edge.to))
-            ),
-        });
+        return Err(ValidationIssue::error(
+            edge_label(edge),
+            ValidationRule::Missing,
+            format!("edge '{}' references missing node(s)", edge_label(edge)),
+        ));
     }
     Ok(())
 }
@@ -363,19 +674,20 @@ fn ensure_endpoints_exist(
 fn remove_edges_matching(
     edges: &mut BTreeMap<EdgeKey, EdgeVersion>,
     tombstone: &EdgeTombstone,
-) -> PraxisResult<()> {
+) -> IssueResult<()> {
     let keys: Vec<EdgeKey> = edges
         .keys()
         .filter(|k| k.matches_tombstone(tombstone))
         .cloned()
         .collect();
     if keys.is_empty() {
-        return Err(PraxisError::ValidationFailed {
-            message: format!(
-                "edge '{}->{}' does not exist for delete",
-                tombstone.from, tombstone.to
-            ),
-        });
+        let label = format!("{}->{}", tombstone.from, tombstone.to);
+        let message = format!("edge '{label}' does not exist for delete");
+        return Err(ValidationIssue::error(
+            label,
+            ValidationRule::Missing,
+            message,
+        ));
     }
     for key in keys {
         edges.remove(&key);
@@ -386,14 +698,23 @@ fn remove_edges_matching(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::PraxisError;
     use aideon_mneme::meta::{
-        MetaModelDocument, MetaRelationship, MetaRelationshipValidation, MetaType,
+        MetaAttribute, MetaAttributeConstraints, MetaAttributeKind, MetaModelDocument,
+        MetaMultiplicity, MetaRelationship, MetaRelationshipValidation, MetaType,
         MetaValidationRules,
     };
     use serde_json::Value;
     use std::collections::BTreeMap as StdBTreeMap;
 
     fn registry(allow_duplicate: Option<bool>) -> MetaModelRegistry {
+        registry_with(allow_duplicate, None)
+    }
+
+    fn registry_with(
+        allow_duplicate: Option<bool>,
+        multiplicity: Option<MetaMultiplicity>,
+    ) -> MetaModelRegistry {
         let mut rel_rules = StdBTreeMap::new();
         rel_rules.insert(
             "rel".to_string(),
@@ -411,6 +732,7 @@ mod tests {
                     label: None,
                     category: None,
                     extends: None,
+                    is_abstract: false,
                     attributes: vec![],
                     effect_types: vec![],
                 },
@@ -419,6 +741,7 @@ mod tests {
                     label: None,
                     category: None,
                     extends: None,
+                    is_abstract: false,
                     attributes: vec![],
                     effect_types: vec![],
                 },
@@ -429,13 +752,14 @@ mod tests {
                 from: vec!["A".into()],
                 to: vec!["B".into()],
                 directed: Some(true),
-                multiplicity: None,
+                multiplicity,
                 attributes: vec![],
             }],
             validation: Some(MetaValidationRules {
                 attributes: None,
                 relationships: Some(rel_rules),
             }),
+            remove: None,
         };
         MetaModelRegistry::from_document(doc).expect("registry")
     }
@@ -714,4 +1038,360 @@ mod tests {
         let patch = modified.diff(&deleted);
         assert_eq!(patch.node_dels.len(), 1);
     }
+
+    #[test]
+    fn apply_enforces_minimum_multiplicity_by_severity() {
+        let hosted = |severity| MetaMultiplicity {
+            from: Some("1..*".into()),
+            to: Some("many".into()),
+            severity,
+        };
+        let node = |id: &str, ty: &str| NodeVersion {
+            id: id.into(),
+            r#type: Some(ty.into()),
+            props: None,
+        };
+        let orphan = ChangeSet {
+            node_creates: vec![node("b", "B")],
+            ..ChangeSet::default()
+        };
+
+        let strict = registry_with(None, Some(hosted(None)));
+        let err = GraphSnapshot::empty().apply(&orphan, &strict).unwrap_err();
+        assert!(matches!(err, PraxisError::ValidationFailed { .. }));
+        assert!(
+            err.to_string()
+                .contains("node 'b' has 0 incoming 'rel' relationship(s); expected 1..*"),
+            "{err}"
+        );
+
+        let linked = GraphSnapshot::empty()
+            .apply(
+                &ChangeSet {
+                    node_creates: vec![node("a", "A"), node("b", "B")],
+                    edge_creates: vec![EdgeVersion {
+                        id: None,
+                        from: "a".into(),
+                        to: "b".into(),
+                        r#type: Some("rel".into()),
+                        directed: None,
+                        props: None,
+                    }],
+                    ..ChangeSet::default()
+                },
+                &strict,
+            )
+            .expect("hosted node accepted");
+        let unlink = ChangeSet {
+            edge_deletes: vec![EdgeTombstone {
+                from: "a".into(),
+                to: "b".into(),
+            }],
+            ..ChangeSet::default()
+        };
+        assert!(linked.apply(&unlink, &strict).is_err());
+
+        let lenient = registry_with(None, Some(hosted(Some(MetaRuleSeverity::Warning))));
+        let snapshot = GraphSnapshot::empty()
+            .apply(&orphan, &lenient)
+            .expect("warnings do not block commits");
+        let violations = snapshot.multiplicity_violations(&lenient);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].node_id, "b");
+        assert_eq!(violations[0].severity, MetaRuleSeverity::Warning);
+    }
+
+    #[test]
+    fn apply_accepts_subtypes_and_rejects_abstract_types() {
+        let meta_type = |id: &str, extends: Option<&str>, is_abstract: bool| MetaType {
+            id: id.into(),
+            label: None,
+            category: None,
+            extends: extends.map(Into::into),
+            is_abstract,
+            attributes: vec![],
+            effect_types: vec![],
+        };
+        let registry = MetaModelRegistry::from_document(MetaModelDocument {
+            version: "v1".into(),
+            description: None,
+            types: vec![
+                meta_type("Component", None, true),
+                meta_type("App", Some("Component"), false),
+                meta_type("Service", Some("App"), false),
+                meta_type("B", None, false),
+            ],
+            relationships: vec![MetaRelationship {
+                id: "rel".into(),
+                label: None,
+                from: vec!["Component".into()],
+                to: vec!["B".into()],
+                directed: Some(true),
+                multiplicity: None,
+                attributes: vec![],
+            }],
+            validation: None,
+            remove: None,
+        })
+        .expect("registry");
+        assert!(registry.is_a("Service", "Component"));
+        assert!(!registry.is_a("B", "Component"));
+        assert_eq!(registry.subtypes_of("Component"), vec!["App", "Service"]);
+        assert!(registry.is_abstract("Component"));
+
+        let node = |id: &str, ty: &str| NodeVersion {
+            id: id.into(),
+            r#type: Some(ty.into()),
+            props: None,
+        };
+        GraphSnapshot::empty()
+            .apply(
+                &ChangeSet {
+                    node_creates: vec![node("s", "Service"), node("b", "B")],
+                    edge_creates: vec![EdgeVersion {
+                        id: None,
+                        from: "s".into(),
+                        to: "b".into(),
+                        r#type: Some("rel".into()),
+                        directed: None,
+                        props: None,
+                    }],
+                    ..ChangeSet::default()
+                },
+                &registry,
+            )
+            .expect("subtype allowed as source");
+
+        let err = GraphSnapshot::empty()
+            .apply(
+                &ChangeSet {
+                    node_creates: vec![node("c", "Component")],
+                    ..ChangeSet::default()
+                },
+                &registry,
+            )
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("abstract type 'Component'"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn apply_fills_defaults_and_enforces_unique_attributes() {
+        let attribute = |name: &str, default: Option<Value>, unique: bool| MetaAttribute {
+            name: name.into(),
+            value_type: MetaAttributeKind::String,
+            required: default.is_none(),
+            enum_values: vec![],
+            default,
+            constraints: MetaAttributeConstraints {
+                unique,
+                ..Default::default()
+            },
+        };
+        let registry = MetaModelRegistry::from_document(MetaModelDocument {
+            version: "v1".into(),
+            description: None,
+            types: vec![MetaType {
+                id: "App".into(),
+                label: None,
+                category: None,
+                extends: None,
+                is_abstract: false,
+                attributes: vec![
+                    attribute("name", None, true),
+                    attribute("status", Some(serde_json::json!("active")), false),
+                ],
+                effect_types: vec![],
+            }],
+            relationships: vec![],
+            validation: None,
+            remove: None,
+        })
+        .expect("registry");
+        let app = |id: &str, name: &str| NodeVersion {
+            id: id.into(),
+            r#type: Some("App".into()),
+            props: Some(serde_json::json!({ "name": name })),
+        };
+        let create = |nodes| ChangeSet {
+            node_creates: nodes,
+            ..ChangeSet::default()
+        };
+
+        let snapshot = GraphSnapshot::empty()
+            .apply(&create(vec![app("a1", "CRM"), app("a2", "ERP")]), &registry)
+            .expect("distinct names");
+        assert_eq!(
+            snapshot.node("a1").and_then(|node| node.props.clone()),
+            Some(serde_json::json!({ "name": "CRM", "status": "active" }))
+        );
+
+        let err = snapshot
+            .apply(&create(vec![app("a3", "CRM")]), &registry)
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("node 'a3' attribute 'name': value \"CRM\" is already used by node 'a1'"),
+            "{err}"
+        );
+        let rename = |name| ChangeSet {
+            node_updates: vec![app("a2", name)],
+            ..ChangeSet::default()
+        };
+        assert!(snapshot.apply(&rename("CRM"), &registry).is_err());
+        snapshot
+            .apply(&rename("ERP"), &registry)
+            .expect("a node keeps its own value");
+    }
+
+    #[test]
+    fn unique_attributes_span_subtypes() {
+        let ty = |id: &str, extends: Option<&str>, attributes| MetaType {
+            id: id.into(),
+            label: None,
+            category: None,
+            extends: extends.map(Into::into),
+            is_abstract: false,
+            attributes,
+            effect_types: vec![],
+        };
+        let registry = MetaModelRegistry::from_document(MetaModelDocument {
+            version: "v1".into(),
+            description: None,
+            types: vec![
+                ty(
+                    "App",
+                    None,
+                    vec![MetaAttribute {
+                        name: "name".into(),
+                        value_type: MetaAttributeKind::String,
+                        required: true,
+                        enum_values: vec![],
+                        default: None,
+                        constraints: MetaAttributeConstraints {
+                            unique: true,
+                            ..Default::default()
+                        },
+                    }],
+                ),
+                ty("SaaSApp", Some("App"), vec![]),
+                ty("Service", None, vec![]),
+            ],
+            relationships: vec![],
+            validation: None,
+            remove: None,
+        })
+        .expect("registry");
+        let node = |id: &str, ty: &str| NodeVersion {
+            id: id.into(),
+            r#type: Some(ty.into()),
+            props: Some(serde_json::json!({ "name": "CRM" })),
+        };
+        let create = |node| ChangeSet {
+            node_creates: vec![node],
+            ..ChangeSet::default()
+        };
+
+        let snapshot = GraphSnapshot::empty()
+            .apply(&create(node("a1", "App")), &registry)
+            .expect("first app");
+        let err = snapshot
+            .apply(&create(node("s1", "SaaSApp")), &registry)
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("already used by node 'a1'"),
+            "{err}"
+        );
+        snapshot
+            .apply(&create(node("x1", "Service")), &registry)
+            .expect("unrelated types may share the value");
+    }
+
+    #[test]
+    fn validate_changes_reports_every_issue() {
+        let registry = MetaModelRegistry::from_document(MetaModelDocument {
+            version: "v1".into(),
+            description: None,
+            types: vec![MetaType {
+                id: "App".into(),
+                label: None,
+                category: None,
+                extends: None,
+                is_abstract: false,
+                attributes: vec![MetaAttribute {
+                    name: "name".into(),
+                    value_type: MetaAttributeKind::String,
+                    required: true,
+                    enum_values: vec![],
+                    default: None,
+                    constraints: MetaAttributeConstraints {
+                        unique: true,
+                        ..Default::default()
+                    },
+                }],
+                effect_types: vec![],
+            }],
+            relationships: vec![],
+            validation: None,
+            remove: None,
+        })
+        .expect("registry");
+        let app = |id: &str, props: Value| NodeVersion {
+            id: id.into(),
+            r#type: Some("App".into()),
+            props: Some(props),
+        };
+        let change = ChangeSet {
+            node_creates: vec![
+                app("a1", serde_json::json!({ "name": "CRM" })),
+                app("a2", serde_json::json!({})),
+                app("a3", serde_json::json!({ "name": "CRM" })),
+            ],
+            node_deletes: vec![NodeTombstone { id: "ghost".into() }],
+            edge_creates: vec![EdgeVersion {
+                id: None,
+                from: "a1".into(),
+                to: "missing".into(),
+                r#type: Some("uses".into()),
+                directed: Some(true),
+                props: None,
+            }],
+            ..ChangeSet::default()
+        };
+
+        let report = GraphSnapshot::empty()
+            .validate_changes(&change, &registry)
+            .expect("report");
+        let found: Vec<(&str, Option<&str>, ValidationRule)> = report
+            .issues
+            .iter()
+            .map(|issue| {
+                (
+                    issue.element_id.as_str(),
+                    issue.attribute.as_deref(),
+                    issue.rule,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("ghost", None, ValidationRule::Missing),
+                ("a2", Some("name"), ValidationRule::Required),
+                ("a1->missing", None, ValidationRule::Missing),
+                ("a1", Some("name"), ValidationRule::Unique),
+            ]
+        );
+        assert!(!report.is_valid());
+
+        let err = GraphSnapshot::empty()
+            .apply(&change, &registry)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "validation failed: node 'ghost' does not exist for delete"
+        );
+    }
 }
@@ -12,9 +12,11 @@ mod error;
 mod graph;
 pub mod meta;
 mod meta_seed;
+mod report;
 
 pub use dataset::{BaselineDataset, DatasetCommit};
 pub use engine::{PraxisEngine, PraxisEngineConfig};
 pub use error::{PraxisError, PraxisErrorCode, PraxisResult};
 pub use graph::{GraphSnapshot, SnapshotStats};
-pub use meta::{MetaModelConfig, MetaModelRegistry};
+pub use meta::{META_MODEL_ENTRY_TYPE, MetaModelConfig, MetaModelRegistry};
+pub use report::{CommitPreview, StatsDelta, ValidationIssue, ValidationReport, ValidationRule};
@@ -13,7 +13,7 @@ pub enum MetaModelSource {
     /// Inline JSON string supplied programmatically.
     Inline(String),
     /// Already parsed document (used by tests).
-    Document(MetaModelDocument),
+    Document(Box<MetaModelDocument>),
 }
 
 /// Configuration describing how the registry should be initialised.
@@ -0,0 +1,267 @@
+//! Meta-model diffs for review tooling, and checks on configured overlays.
+
+use aideon_mneme::meta::MetaModelDocument;
+use serde::{Deserialize, Serialize};
+
+use crate::error::PraxisResult;
+use crate::meta::config::{MetaModelConfig, MetaModelSource};
+use crate::meta::evolution::{
+    AttributeOwner, Compatibility, MigrationHints, SchemaChange, compare,
+};
+use crate::meta::loader::{load_document, merge_documents};
+
+/// Every difference between two meta-model documents, with their overall compatibility.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetaModelDiff {
+    pub from_version: String,
+    pub to_version: String,
+    /// Breaking when any change is.
+    pub compatibility: Compatibility,
+    pub changes: Vec<SchemaChange>,
+}
+
+impl MetaModelDiff {
+    pub fn between(old: &MetaModelDocument, new: &MetaModelDocument) -> Self {
+        let changes = compare(old, new, &MigrationHints::default());
+        let compatibility = if changes
+            .iter()
+            .any(|change| change.compatibility() == Compatibility::Breaking)
+        {
+            Compatibility::Breaking
+        } else {
+            Compatibility::Compatible
+        };
+        Self {
+            from_version: old.version.clone(),
+            to_version: new.version.clone(),
+            compatibility,
+            changes,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn breaking(&self) -> impl Iterator<Item = &SchemaChange> {
+        self.changes
+            .iter()
+            .filter(|change| change.compatibility() == Compatibility::Breaking)
+    }
+}
+
+/// The configured layer that last defined an attribute of the merged document.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeProvenance {
+    pub owner: AttributeOwner,
+    pub attribute: String,
+    /// 0 for the base document, `n` for the n-th entry of `MetaModelConfig::overrides`.
+    pub layer: usize,
+    /// Where the layer was loaded from.
+    pub source: String,
+}
+
+/// A `remove` directive an overlay applied.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayRemoval {
+    /// `n` for the n-th entry of `MetaModelConfig::overrides`.
+    pub layer: usize,
+    /// Where the layer was loaded from.
+    pub source: String,
+    /// What was removed: a type, relationship, attribute, enum value or endpoint.
+    pub removal: SchemaChange,
+}
+
+/// What the configured overlays do to the base document.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayReport {
+    /// From the base document to the merged one.
+    pub diff: MetaModelDiff,
+    /// One entry per attribute of the merged document, ordered by owner.
+    pub provenance: Vec<AttributeProvenance>,
+    /// Everything the overlays removed, in the order they applied it.
+    pub removals: Vec<OverlayRemoval>,
+}
+
+/// Diff the base document of `config` against the result of merging its overlays, and record
+/// which layer defined each merged attribute and which layer removed what.
+pub fn check_overlays(config: &MetaModelConfig) -> PraxisResult<OverlayReport> {
+    let base = load_document(&config.base)?;
+    let overlays = config
+        .overrides
+        .iter()
+        .map(load_document)
+        .collect::<PraxisResult<Vec<_>>>()?;
+    let merged = merge_documents(base.clone(), &overlays)?;
+    let source = |layer: usize| match layer.checked_sub(1) {
+        Some(overlay) => describe(&config.overrides[overlay]),
+        None => describe(&config.base),
+    };
+    let provenance = merged
+        .provenance
+        .into_iter()
+        .map(|((owner, attribute), layer)| AttributeProvenance {
+            owner,
+            attribute,
+            layer,
+            source: source(layer),
+        })
+        .collect();
+    let removals = merged
+        .removals
+        .into_iter()
+        .map(|(layer, removal)| OverlayRemoval {
+            layer,
+            source: source(layer),
+            removal,
+        })
+        .collect();
+    Ok(OverlayReport {
+        diff: MetaModelDiff::between(&base, &merged.document),
+        provenance,
+        removals,
+    })
+}
+
+fn describe(source: &MetaModelSource) -> String {
+    match source {
+        MetaModelSource::EmbeddedCore => "embedded core".into(),
+        MetaModelSource::File(path) => path.display().to_string(),
+        MetaModelSource::Inline(_) => "inline".into(),
+        MetaModelSource::Document(_) => "document".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::RelationshipEnd;
+    use serde_json::json;
+
+    fn overlay(value: serde_json::Value) -> MetaModelSource {
+        MetaModelSource::Inline(value.to_string())
+    }
+
+    #[test]
+    fn overlays_extend_base_definitions_and_record_provenance() {
+        let config = MetaModelConfig::default()
+            .add_override(overlay(json!({
+                "version": "1.0.0",
+                "types": [{
+                    "id": "Application",
+                    "attributes": [{ "name": "owner", "type": "string" }]
+                }],
+                "remove": { "attributes": [{ "type": "Application", "name": "vendor" }] }
+            })))
+            .add_override(overlay(json!({
+                "version": "1.0.0",
+                "relationships": [{ "id": "hosts", "to": ["DataEntity"] }]
+            })));
+
+        let report = check_overlays(&config).expect("report");
+        let application: Vec<(&str, usize, &str)> = report
+            .provenance
+            .iter()
+            .filter(|entry| entry.owner == AttributeOwner::Type("Application".into()))
+            .map(|entry| (entry.attribute.as_str(), entry.layer, entry.source.as_str()))
+            .collect();
+        assert_eq!(
+            application,
+            vec![
+                ("disposition", 0, "embedded core"),
+                ("lifecycle", 0, "embedded core"),
+                ("name", 0, "embedded core"),
+                ("owner", 1, "inline"),
+            ]
+        );
+        assert_eq!(report.diff.compatibility, Compatibility::Compatible);
+        assert!(report.diff.changes.contains(&SchemaChange::EndpointAdded {
+            relationship: "hosts".into(),
+            end: RelationshipEnd::Target,
+            type_id: "DataEntity".into(),
+        }));
+        assert!(
+            report
+                .diff
+                .changes
+                .contains(&SchemaChange::AttributeRemoved {
+                    owner: AttributeOwner::Type("Application".into()),
+                    attribute: "vendor".into(),
+                })
+        );
+        assert_eq!(
+            report.removals,
+            vec![OverlayRemoval {
+                layer: 1,
+                source: "inline".into(),
+                removal: SchemaChange::AttributeRemoved {
+                    owner: AttributeOwner::Type("Application".into()),
+                    attribute: "vendor".into(),
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn diff_serialises_for_review_tooling() {
+        let base = load_document(&MetaModelSource::EmbeddedCore).expect("core");
+        let mut tightened = base.clone();
+        tightened.relationships.retain(|rel| rel.id != "hosts");
+
+        let diff = MetaModelDiff::between(&base, &tightened);
+        assert_eq!(diff.compatibility, Compatibility::Breaking);
+        assert_eq!(diff.breaking().count(), 1);
+        assert_eq!(
+            serde_json::to_value(&diff).expect("json"),
+            json!({
+                "fromVersion": base.version,
+                "toVersion": base.version,
+                "compatibility": "breaking",
+                "changes": [{ "change": "relationshipRemoved", "relationship": "hosts" }]
+            })
+        );
+        assert!(MetaModelDiff::between(&base, &base).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_undeclared_enum_renames_as_removed_and_added() {
+        let base = load_document(&MetaModelSource::EmbeddedCore).expect("core");
+        let mut renamed = base.clone();
+        let disposition = renamed
+            .types
+            .iter_mut()
+            .find(|ty| ty.id == "Application")
+            .and_then(|ty| {
+                ty.attributes
+                    .iter_mut()
+                    .find(|attr| attr.name == "disposition")
+            })
+            .expect("disposition");
+        for value in &mut disposition.enum_values {
+            if value == "Tolerate" {
+                *value = "Maintain".into();
+            }
+        }
+
+        let owner = AttributeOwner::Type("Application".into());
+        assert_eq!(
+            MetaModelDiff::between(&base, &renamed).changes,
+            vec![
+                SchemaChange::EnumValueRemoved {
+                    owner: owner.clone(),
+                    attribute: "disposition".into(),
+                    value: "Tolerate".into(),
+                },
+                SchemaChange::EnumValueAdded {
+                    owner,
+                    attribute: "disposition".into(),
+                    value: "Maintain".into(),
+                },
+            ]
+        );
+    }
+}
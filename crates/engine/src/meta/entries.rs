@@ -0,0 +1,297 @@
+//! Meta-model entries: the schema stored as `MetaModelEntry` nodes in the graph.
+//!
+//! Each type and relationship is one node (`meta:type:<id>`, `meta:relationship:<id>`) and
+//! validation rules live in `meta:validation`, so schema changes are committed, branched, diffed
+//! and merged like any other data.
+
+use crate::error::{PraxisError, PraxisResult};
+use crate::graph::GraphSnapshot;
+use aideon_mneme::meta::{
+    MetaAttribute, MetaModelDocument, MetaMultiplicity, MetaRelationship, MetaType,
+    MetaValidationRules,
+};
+use aideon_mneme::temporal::{ChangeSet, NodeVersion};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, json};
+
+/// Node type holding one meta-model entry.
+pub const META_MODEL_ENTRY_TYPE: &str = "MetaModelEntry";
+
+const TYPE_PREFIX: &str = "meta:type:";
+const RELATIONSHIP_PREFIX: &str = "meta:relationship:";
+const VALIDATION_ID: &str = "meta:validation";
+
+/// Entry nodes recording every type, relationship and validation rule of `doc`.
+pub(crate) fn entry_nodes(doc: &MetaModelDocument) -> Vec<NodeVersion> {
+    let types = doc.types.iter().map(type_entry);
+    let relationships = doc.relationships.iter().map(relationship_entry);
+    types
+        .chain(relationships)
+        .chain(doc.validation.as_ref().map(validation_entry))
+        .collect()
+}
+
+fn type_entry(ty: &MetaType) -> NodeVersion {
+    let label = ty.label.clone().unwrap_or_else(|| ty.id.clone());
+    let mut props = Map::new();
+    props.insert("kind".into(), json!("type"));
+    props.insert("name".into(), json!(label));
+    props.insert(
+        "description".into(),
+        json!(format!("Definition of {label}")),
+    );
+    let payload = json!({
+        "id": ty.id,
+        "attributes": ty.attributes.iter().map(|attr| &attr.name).collect::<Vec<_>>(),
+    });
+    props.insert("payload".into(), json!(payload.to_string()));
+    props.insert("label".into(), json!(label));
+    if let Some(category) = &ty.category {
+        props.insert("category".into(), json!(category));
+    }
+    if let Some(parent) = &ty.extends {
+        props.insert("extends".into(), json!(parent));
+    }
+    if ty.is_abstract {
+        props.insert("abstract".into(), Value::Bool(true));
+    }
+    if !ty.attributes.is_empty() {
+        props.insert("attributes".into(), json!(ty.attributes));
+    }
+    if !ty.effect_types.is_empty() {
+        props.insert("effectTypes".into(), json!(ty.effect_types));
+    }
+    entry(format!("{TYPE_PREFIX}{}", ty.id), props)
+}
+
+fn relationship_entry(rel: &MetaRelationship) -> NodeVersion {
+    let label = rel.label.clone().unwrap_or_else(|| rel.id.clone());
+    let mut props = Map::new();
+    props.insert("kind".into(), json!("relationship"));
+    props.insert("label".into(), json!(label));
+    props.insert("type".into(), json!(rel.id));
+    props.insert("from".into(), json!(rel.from));
+    props.insert("to".into(), json!(rel.to));
+    let mut details = Map::new();
+    if let Some(directed) = rel.directed {
+        details.insert("directed".into(), Value::Bool(directed));
+    }
+    if let Some(multiplicity) = &rel.multiplicity {
+        details.insert("multiplicity".into(), json!(multiplicity));
+    }
+    if !rel.attributes.is_empty() {
+        details.insert("attributes".into(), json!(rel.attributes));
+    }
+    props.insert("details".into(), Value::Object(details));
+    props.insert("name".into(), json!(format!("{label} relationship")));
+    props.insert(
+        "description".into(),
+        json!(format!(
+            "Allows {} -> {}",
+            rel.from.join(", "),
+            rel.to.join(", ")
+        )),
+    );
+    let payload = json!({ "id": rel.id, "from": rel.from, "to": rel.to });
+    props.insert("payload".into(), json!(payload.to_string()));
+    entry(format!("{RELATIONSHIP_PREFIX}{}", rel.id), props)
+}
+
+fn validation_entry(rules: &MetaValidationRules) -> NodeVersion {
+    let mut props = Map::new();
+    props.insert("kind".into(), json!("validation"));
+    props.insert("name".into(), json!("Validation rules"));
+    props.insert(
+        "description".into(),
+        json!("Attribute and relationship validation rules"),
+    );
+    props.insert(
+        "payload".into(),
+        json!(json!({ "id": "validation" }).to_string()),
+    );
+    props.insert("rules".into(), json!(rules));
+    entry(VALIDATION_ID.into(), props)
+}
+
+fn entry(id: String, props: Map<String, Value>) -> NodeVersion {
+    NodeVersion {
+        id,
+        r#type: Some(META_MODEL_ENTRY_TYPE.into()),
+        props: Some(Value::Object(props)),
+    }
+}
+
+pub(crate) fn is_entry(node: &NodeVersion) -> bool {
+    node.r#type.as_deref() == Some(META_MODEL_ENTRY_TYPE)
+}
+
+/// True when `change` creates, updates or deletes an entry of `before`.
+pub(crate) fn touches_entries(change: &ChangeSet, before: &GraphSnapshot) -> bool {
+    let was_entry = |id: &str| before.node(id).is_some_and(is_entry);
+    change
+        .node_creates
+        .iter()
+        .chain(&change.node_updates)
+        .any(|node| is_entry(node) || was_entry(&node.id))
+        || change.node_deletes.iter().any(|node| was_entry(&node.id))
+}
+
+/// Split `change` into its meta-model entry changes and everything else.
+pub(crate) fn split_entry_changes(
+    change: &ChangeSet,
+    before: &GraphSnapshot,
+) -> (ChangeSet, ChangeSet) {
+    let was_entry = |id: &str| before.node(id).is_some_and(is_entry);
+    let touches = |node: &NodeVersion| is_entry(node) || was_entry(&node.id);
+    let (entry_creates, node_creates) = change.node_creates.iter().cloned().partition(touches);
+    let (entry_updates, node_updates) = change.node_updates.iter().cloned().partition(touches);
+    let (entry_deletes, node_deletes) = change
+        .node_deletes
+        .iter()
+        .cloned()
+        .partition(|node| was_entry(&node.id));
+    let entries = ChangeSet {
+        node_creates: entry_creates,
+        node_updates: entry_updates,
+        node_deletes: entry_deletes,
+        ..ChangeSet::default()
+    };
+    let rest = ChangeSet {
+        node_creates,
+        node_updates,
+        node_deletes,
+        ..change.clone()
+    };
+    (entries, rest)
+}
+
+/// The schema recorded by `entries`. Version and description come from `base`, which also
+/// supplies the `MetaModelEntry` type and validation rules when no entry records them, so
+/// schema edits cannot lock themselves out.
+pub(super) fn document_from_entries<'a>(
+    base: &MetaModelDocument,
+    entries: impl IntoIterator<Item = &'a NodeVersion>,
+) -> PraxisResult<MetaModelDocument> {
+    let mut doc = MetaModelDocument {
+        version: base.version.clone(),
+        description: base.description.clone(),
+        types: Vec::new(),
+        relationships: Vec::new(),
+        validation: None,
+        remove: None,
+    };
+    for node in entries {
+        let props = node
+            .props
+            .as_ref()
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        if let Some(id) = node.id.strip_prefix(TYPE_PREFIX) {
+            doc.types.push(read_type(&node.id, id, &props)?);
+        } else if let Some(id) = node.id.strip_prefix(RELATIONSHIP_PREFIX) {
+            doc.relationships
+                .push(read_relationship(&node.id, id, &props)?);
+        } else if node.id == VALIDATION_ID {
+            doc.validation = Some(field(&node.id, &props, "rules")?.unwrap_or_default());
+        }
+    }
+    if !doc.types.iter().any(|ty| ty.id == META_MODEL_ENTRY_TYPE)
+        && let Some(entry_type) = base.types.iter().find(|ty| ty.id == META_MODEL_ENTRY_TYPE)
+    {
+        doc.types.push(entry_type.clone());
+    }
+    if doc.validation.is_none() {
+        doc.validation = base.validation.clone();
+    }
+    Ok(doc)
+}
+
+fn read_type(node_id: &str, id: &str, props: &Map<String, Value>) -> PraxisResult<MetaType> {
+    Ok(MetaType {
+        id: id.into(),
+        label: field(node_id, props, "label")?,
+        category: field(node_id, props, "category")?,
+        extends: field(node_id, props, "extends")?,
+        is_abstract: field(node_id, props, "abstract")?.unwrap_or_default(),
+        attributes: field::<Vec<MetaAttribute>>(node_id, props, "attributes")?.unwrap_or_default(),
+        effect_types: field(node_id, props, "effectTypes")?.unwrap_or_default(),
+    })
+}
+
+fn read_relationship(
+    node_id: &str,
+    id: &str,
+    props: &Map<String, Value>,
+) -> PraxisResult<MetaRelationship> {
+    let details: Map<String, Value> = field(node_id, props, "details")?.unwrap_or_default();
+    Ok(MetaRelationship {
+        id: id.into(),
+        label: field(node_id, props, "label")?,
+        from: field(node_id, props, "from")?.unwrap_or_default(),
+        to: field(node_id, props, "to")?.unwrap_or_default(),
+        directed: field(node_id, &details, "directed")?,
+        multiplicity: field::<MetaMultiplicity>(node_id, &details, "multiplicity")?,
+        attributes: field(node_id, &details, "attributes")?.unwrap_or_default(),
+    })
+}
+
+fn field<T: DeserializeOwned>(
+    node_id: &str,
+    props: &Map<String, Value>,
+    name: &str,
+) -> PraxisResult<Option<T>> {
+    match props.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|err| PraxisError::IntegrityViolation {
+                message: format!("meta-model entry '{node_id}' has invalid '{name}': {err}"),
+            }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::MetaModelRegistry;
+
+    #[test]
+    fn entries_round_trip_the_document() {
+        let doc = MetaModelRegistry::embedded().expect("registry").document();
+        let entries = entry_nodes(&doc);
+        let mut rebuilt = document_from_entries(&doc, &entries).expect("document");
+
+        let mut expected = doc.clone();
+        expected.types.sort_by(|a, b| a.id.cmp(&b.id));
+        expected.relationships.sort_by(|a, b| a.id.cmp(&b.id));
+        rebuilt.types.sort_by(|a, b| a.id.cmp(&b.id));
+        rebuilt.relationships.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(rebuilt, expected);
+    }
+
+    #[test]
+    fn base_supplies_what_entries_leave_out() {
+        let base = MetaModelRegistry::embedded().expect("registry").document();
+        let capability = entry_nodes(&base)
+            .into_iter()
+            .find(|node| node.id == "meta:type:Capability")
+            .expect("capability entry");
+
+        let doc = document_from_entries(&base, [&capability]).expect("document");
+        let ids: Vec<&str> = doc.types.iter().map(|ty| ty.id.as_str()).collect();
+        assert_eq!(ids, vec!["Capability", META_MODEL_ENTRY_TYPE]);
+        assert!(doc.relationships.is_empty());
+        assert_eq!(doc.validation, base.validation);
+
+        let broken = NodeVersion {
+            props: Some(json!({ "attributes": "name" })),
+            ..capability
+        };
+        let err = document_from_entries(&base, [&broken]).unwrap_err();
+        assert!(
+            err.to_string().contains("has invalid 'attributes'"),
+            "{err}"
+        );
+    }
+}
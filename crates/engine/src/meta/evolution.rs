@@ -0,0 +1,1124 @@
+//! Schema evolution: classify what changed between two meta-model documents and plan the data
+//! changes that keep a branch valid under the newer one.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+use aideon_mneme::meta::{
+    MetaAttribute, MetaAttributeConstraints, MetaAttributeKind, MetaModelDocument,
+    MetaRelationship, MetaRelationshipValidation, MetaRuleSeverity, MetaType, MetaValidationRules,
+};
+use aideon_mneme::temporal::{ChangeSet, EdgeTombstone, EdgeVersion, NodeTombstone, NodeVersion};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::PraxisResult;
+use crate::graph::GraphSnapshot;
+use crate::meta::entries::{META_MODEL_ENTRY_TYPE, entry_nodes, is_entry};
+use crate::meta::validation::parse_bound;
+use crate::meta::{MetaModelRegistry, RelationshipEnd};
+use crate::report::{ValidationReport, ValidationRule};
+
+/// Whether data valid under the old schema stays valid under the new one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Compatibility {
+    Compatible,
+    /// Existing elements may fail validation.
+    Breaking,
+}
+
+/// The type or relationship an attribute is declared on.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AttributeOwner {
+    Type(String),
+    Relationship(String),
+}
+
+/// One difference between two meta-model documents. Attribute changes concern declared
+/// attributes; a change on a supertype affects its subtypes as well.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "change",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum SchemaChange {
+    TypeAdded {
+        type_id: String,
+    },
+    TypeRemoved {
+        type_id: String,
+    },
+    /// The type's `extends` parent changed, and with it the attributes and endpoints it inherits.
+    ParentChanged {
+        type_id: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    AbstractChanged {
+        type_id: String,
+        is_abstract: bool,
+    },
+    /// Presentation fields changed: `label`, `category` or `effectTypes`.
+    TypeDetailsChanged {
+        type_id: String,
+        fields: Vec<String>,
+    },
+    AttributeAdded {
+        owner: AttributeOwner,
+        attribute: String,
+        required: bool,
+        /// Whether a default can fill the attribute on existing elements.
+        has_default: bool,
+    },
+    /// Removing an attribute is compatible: values already stored are kept and ignored.
+    AttributeRemoved {
+        owner: AttributeOwner,
+        attribute: String,
+    },
+    KindChanged {
+        owner: AttributeOwner,
+        attribute: String,
+        from: MetaAttributeKind,
+        to: MetaAttributeKind,
+    },
+    RequiredChanged {
+        owner: AttributeOwner,
+        attribute: String,
+        required: bool,
+    },
+    /// Only created elements receive defaults, so this never affects stored ones.
+    DefaultChanged {
+        owner: AttributeOwner,
+        attribute: String,
+    },
+    /// `pattern`, bounds or `unique` changed; `tightened` when some old value may now fail.
+    ConstraintsChanged {
+        owner: AttributeOwner,
+        attribute: String,
+        tightened: bool,
+    },
+    EnumValueAdded {
+        owner: AttributeOwner,
+        attribute: String,
+        value: String,
+    },
+    EnumValueRemoved {
+        owner: AttributeOwner,
+        attribute: String,
+        value: String,
+    },
+    EnumValueRenamed {
+        owner: AttributeOwner,
+        attribute: String,
+        from: String,
+        to: String,
+    },
+    RelationshipAdded {
+        relationship: String,
+    },
+    RelationshipRemoved {
+        relationship: String,
+    },
+    EndpointAdded {
+        relationship: String,
+        end: RelationshipEnd,
+        type_id: String,
+    },
+    EndpointRemoved {
+        relationship: String,
+        end: RelationshipEnd,
+        type_id: String,
+    },
+    MultiplicityChanged {
+        relationship: String,
+        tightened: bool,
+    },
+    /// `label` or `directed` changed.
+    RelationshipDetailsChanged {
+        relationship: String,
+        fields: Vec<String>,
+    },
+    /// A document-level validation rule changed, named by its path such as
+    /// `attributes.string.maxLength` or `relationships.hosts.allowSelf`.
+    ValidationRuleChanged {
+        rule: String,
+        tightened: bool,
+    },
+}
+
+impl SchemaChange {
+    pub fn compatibility(&self) -> Compatibility {
+        let breaking = match self {
+            Self::TypeAdded { .. }
+            | Self::TypeDetailsChanged { .. }
+            | Self::AttributeRemoved { .. }
+            | Self::DefaultChanged { .. }
+            | Self::RelationshipDetailsChanged { .. }
+            | Self::EnumValueAdded { .. }
+            | Self::RelationshipAdded { .. }
+            | Self::EndpointAdded { .. } => false,
+            Self::AbstractChanged { is_abstract, .. } => *is_abstract,
+            Self::AttributeAdded { required, .. } | Self::RequiredChanged { required, .. } => {
+                *required
+            }
+            Self::ConstraintsChanged { tightened, .. }
+            | Self::MultiplicityChanged { tightened, .. }
+            | Self::ValidationRuleChanged { tightened, .. } => *tightened,
+            Self::TypeRemoved { .. }
+            | Self::ParentChanged { .. }
+            | Self::KindChanged { .. }
+            | Self::EnumValueRemoved { .. }
+            | Self::EnumValueRenamed { .. }
+            | Self::RelationshipRemoved { .. }
+            | Self::EndpointRemoved { .. } => true,
+        };
+        if breaking {
+            Compatibility::Breaking
+        } else {
+            Compatibility::Compatible
+        }
+    }
+}
+
+/// Guidance `compare` cannot infer from the documents alone.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationHints {
+    /// Enum values renamed rather than removed. When planning a migration, an attribute that
+    /// drops exactly one value and adds exactly one is treated as a rename without a hint.
+    #[serde(default)]
+    pub enum_renames: Vec<EnumRename>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnumRename {
+    pub owner: AttributeOwner,
+    pub attribute: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// What moving a branch to a new schema involves, computed at its head.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationPlan {
+    pub changes: Vec<SchemaChange>,
+    /// Existing elements that fail the new schema.
+    pub report: ValidationReport,
+    /// Meta-model entry changes that record the new schema on the branch.
+    pub schema_update: ChangeSet,
+    /// Proposed data fix-ups, to commit after `schema_update`: declared defaults for absent
+    /// attributes, renamed enum values, and removal of edges the new schema no longer allows.
+    pub fixes: ChangeSet,
+    /// Issues left once `fixes` are applied, which need a manual decision.
+    pub unresolved: ValidationReport,
+}
+
+impl MigrationPlan {
+    pub fn is_breaking(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.compatibility() == Compatibility::Breaking)
+    }
+}
+
+/// Every difference from `old` to `new`, sorted by type, then relationship, then rule. Only
+/// hinted enum values count as renamed; others are reported as removed and added.
+pub fn compare(
+    old: &MetaModelDocument,
+    new: &MetaModelDocument,
+    hints: &MigrationHints,
+) -> Vec<SchemaChange> {
+    let mut changes = Vec::new();
+    let old_types: BTreeMap<&str, &MetaType> =
+        old.types.iter().map(|ty| (ty.id.as_str(), ty)).collect();
+    let new_types: BTreeMap<&str, &MetaType> =
+        new.types.iter().map(|ty| (ty.id.as_str(), ty)).collect();
+    for (id, before) in &old_types {
+        match new_types.get(id) {
+            Some(after) => compare_type(before, after, hints, &mut changes),
+            None => changes.push(SchemaChange::TypeRemoved {
+                type_id: id.to_string(),
+            }),
+        }
+    }
+    for id in new_types.keys().filter(|id| !old_types.contains_key(*id)) {
+        changes.push(SchemaChange::TypeAdded {
+            type_id: id.to_string(),
+        });
+    }
+
+    let old_rels: BTreeMap<&str, &MetaRelationship> = old
+        .relationships
+        .iter()
+        .map(|rel| (rel.id.as_str(), rel))
+        .collect();
+    let new_rels: BTreeMap<&str, &MetaRelationship> = new
+        .relationships
+        .iter()
+        .map(|rel| (rel.id.as_str(), rel))
+        .collect();
+    for (id, before) in &old_rels {
+        match new_rels.get(id) {
+            Some(after) => compare_relationship(before, after, hints, &mut changes),
+            None => changes.push(SchemaChange::RelationshipRemoved {
+                relationship: id.to_string(),
+            }),
+        }
+    }
+    for id in new_rels.keys().filter(|id| !old_rels.contains_key(*id)) {
+        changes.push(SchemaChange::RelationshipAdded {
+            relationship: id.to_string(),
+        });
+    }
+
+    compare_validation(
+        old.validation.as_ref(),
+        new.validation.as_ref(),
+        &mut changes,
+    );
+    changes
+}
+
+/// Compare `snapshot`, valid under `current`, with the `target` schema: classify the schema
+/// changes, report the elements that would break, and propose the change sets that move the
+/// branch over.
+pub fn plan_migration(
+    snapshot: &GraphSnapshot,
+    current: &MetaModelDocument,
+    target: &MetaModelDocument,
+    hints: &MigrationHints,
+) -> PraxisResult<MigrationPlan> {
+    let changes = infer_enum_renames(compare(current, target, hints));
+    let registry = MetaModelRegistry::from_document(target.clone())?;
+    let report = snapshot.check_against(&registry)?;
+    let renames: Vec<EnumRename> = changes
+        .iter()
+        .filter_map(|change| match change {
+            SchemaChange::EnumValueRenamed {
+                owner,
+                attribute,
+                from,
+                to,
+            } => Some(EnumRename {
+                owner: owner.clone(),
+                attribute: attribute.clone(),
+                from: from.clone(),
+                to: to.clone(),
+            }),
+            _ => None,
+        })
+        .collect();
+    let fixes = fix_ups(snapshot, &registry, &renames);
+    let (fixed, _) = snapshot.apply_lenient(&fixes, &registry)?;
+    Ok(MigrationPlan {
+        changes,
+        report,
+        schema_update: schema_update(snapshot, &registry),
+        fixes,
+        unresolved: fixed.check_against(&registry)?,
+    })
+}
+
+fn compare_type(
+    before: &MetaType,
+    after: &MetaType,
+    hints: &MigrationHints,
+    changes: &mut Vec<SchemaChange>,
+) {
+    if before.extends != after.extends {
+        changes.push(SchemaChange::ParentChanged {
+            type_id: after.id.clone(),
+            from: before.extends.clone(),
+            to: after.extends.clone(),
+        });
+    }
+    if before.is_abstract != after.is_abstract {
+        changes.push(SchemaChange::AbstractChanged {
+            type_id: after.id.clone(),
+            is_abstract: after.is_abstract,
+        });
+    }
+    let fields = changed_fields([
+        ("label", before.label != after.label),
+        ("category", before.category != after.category),
+        ("effectTypes", before.effect_types != after.effect_types),
+    ]);
+    if !fields.is_empty() {
+        changes.push(SchemaChange::TypeDetailsChanged {
+            type_id: after.id.clone(),
+            fields,
+        });
+    }
+    let owner = AttributeOwner::Type(after.id.clone());
+    compare_attributes(
+        &owner,
+        &before.attributes,
+        &after.attributes,
+        hints,
+        changes,
+    );
+}
+
+fn compare_relationship(
+    before: &MetaRelationship,
+    after: &MetaRelationship,
+    hints: &MigrationHints,
+    changes: &mut Vec<SchemaChange>,
+) {
+    let ends = [
+        (RelationshipEnd::Source, &before.from, &after.from),
+        (RelationshipEnd::Target, &before.to, &after.to),
+    ];
+    for (end, old_types, new_types) in ends {
+        for type_id in old_types.iter().filter(|ty| !new_types.contains(ty)) {
+            changes.push(SchemaChange::EndpointRemoved {
+                relationship: after.id.clone(),
+                end,
+                type_id: type_id.clone(),
+            });
+        }
+        for type_id in new_types.iter().filter(|ty| !old_types.contains(ty)) {
+            changes.push(SchemaChange::EndpointAdded {
+                relationship: after.id.clone(),
+                end,
+                type_id: type_id.clone(),
+            });
+        }
+    }
+    let fields = changed_fields([
+        ("label", before.label != after.label),
+        ("directed", before.directed != after.directed),
+    ]);
+    if !fields.is_empty() {
+        changes.push(SchemaChange::RelationshipDetailsChanged {
+            relationship: after.id.clone(),
+            fields,
+        });
+    }
+    if before.multiplicity != after.multiplicity {
+        // Bounds are compared as a whole: any new error-severity bound may reject old data.
+        let enforced = after.multiplicity.as_ref().is_some_and(|rule| {
+            rule.severity.unwrap_or_default() == MetaRuleSeverity::Error
+                && (rule.from.is_some() || rule.to.is_some())
+        });
+        changes.push(SchemaChange::MultiplicityChanged {
+            relationship: after.id.clone(),
+            tightened: enforced,
+        });
+    }
+    let owner = AttributeOwner::Relationship(after.id.clone());
+    compare_attributes(
+        &owner,
+        &before.attributes,
+        &after.attributes,
+        hints,
+        changes,
+    );
+}
+
+fn changed_fields<const N: usize>(fields: [(&str, bool); N]) -> Vec<String> {
+    fields
+        .into_iter()
+        .filter(|(_, changed)| *changed)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+fn compare_attributes(
+    owner: &AttributeOwner,
+    before: &[MetaAttribute],
+    after: &[MetaAttribute],
+    hints: &MigrationHints,
+    changes: &mut Vec<SchemaChange>,
+) {
+    let old_attrs: BTreeMap<&str, &MetaAttribute> = before
+        .iter()
+        .map(|attr| (attr.name.as_str(), attr))
+        .collect();
+    let new_attrs: BTreeMap<&str, &MetaAttribute> = after
+        .iter()
+        .map(|attr| (attr.name.as_str(), attr))
+        .collect();
+    for (name, old_attr) in &old_attrs {
+        match new_attrs.get(name) {
+            Some(new_attr) => compare_attribute(owner, old_attr, new_attr, hints, changes),
+            None => changes.push(SchemaChange::AttributeRemoved {
+                owner: owner.clone(),
+                attribute: name.to_string(),
+            }),
+        }
+    }
+    for (name, attr) in new_attrs
+        .iter()
+        .filter(|(name, _)| !old_attrs.contains_key(*name))
+    {
+        changes.push(SchemaChange::AttributeAdded {
+            owner: owner.clone(),
+            attribute: name.to_string(),
+            required: attr.required,
+            has_default: attr.default.is_some(),
+        });
+    }
+}
+
+fn compare_attribute(
+    owner: &AttributeOwner,
+    before: &MetaAttribute,
+    after: &MetaAttribute,
+    hints: &MigrationHints,
+    changes: &mut Vec<SchemaChange>,
+) {
+    if before.value_type != after.value_type {
+        changes.push(SchemaChange::KindChanged {
+            owner: owner.clone(),
+            attribute: after.name.clone(),
+            from: before.value_type.clone(),
+            to: after.value_type.clone(),
+        });
+        return;
+    }
+    if before.required != after.required {
+        changes.push(SchemaChange::RequiredChanged {
+            owner: owner.clone(),
+            attribute: after.name.clone(),
+            required: after.required,
+        });
+    }
+    if before.default != after.default {
+        changes.push(SchemaChange::DefaultChanged {
+            owner: owner.clone(),
+            attribute: after.name.clone(),
+        });
+    }
+    if before.constraints != after.constraints {
+        changes.push(SchemaChange::ConstraintsChanged {
+            owner: owner.clone(),
+            attribute: after.name.clone(),
+            tightened: constraints_tightened(&before.constraints, &after.constraints),
+        });
+    }
+    if after.value_type == MetaAttributeKind::Enum {
+        compare_enum_values(owner, before, after, hints, changes);
+    }
+}
+
+fn compare_enum_values(
+    owner: &AttributeOwner,
+    before: &MetaAttribute,
+    after: &MetaAttribute,
+    hints: &MigrationHints,
+    changes: &mut Vec<SchemaChange>,
+) {
+    let mut removed: Vec<&String> = before
+        .enum_values
+        .iter()
+        .filter(|value| !after.enum_values.contains(value))
+        .collect();
+    let mut added: Vec<&String> = after
+        .enum_values
+        .iter()
+        .filter(|value| !before.enum_values.contains(value))
+        .collect();
+    let mut renames: Vec<(&String, &String)> = Vec::new();
+    let hinted = hints
+        .enum_renames
+        .iter()
+        .filter(|rename| &rename.owner == owner && rename.attribute == after.name);
+    for rename in hinted {
+        if removed.contains(&&rename.from) && added.contains(&&rename.to) {
+            removed.retain(|value| **value != rename.from);
+            added.retain(|value| **value != rename.to);
+            renames.push((&rename.from, &rename.to));
+        }
+    }
+
+    let change = |value: &String| (owner.clone(), after.name.clone(), value.clone());
+    for (from, to) in renames {
+        changes.push(SchemaChange::EnumValueRenamed {
+            owner: owner.clone(),
+            attribute: after.name.clone(),
+            from: from.clone(),
+            to: to.clone(),
+        });
+    }
+    for (owner, attribute, value) in removed.into_iter().map(change) {
+        changes.push(SchemaChange::EnumValueRemoved {
+            owner,
+            attribute,
+            value,
+        });
+    }
+    for (owner, attribute, value) in added.into_iter().map(change) {
+        changes.push(SchemaChange::EnumValueAdded {
+            owner,
+            attribute,
+            value,
+        });
+    }
+}
+
+/// Treat each attribute left with exactly one removed and one added enum value as renaming it,
+/// the guess migration planning makes for unhinted changes.
+fn infer_enum_renames(changes: Vec<SchemaChange>) -> Vec<SchemaChange> {
+    // Values each enum attribute lost and gained.
+    let mut values: BTreeMap<(AttributeOwner, String), (Vec<String>, Vec<String>)> =
+        BTreeMap::new();
+    for change in &changes {
+        match change {
+            SchemaChange::EnumValueRemoved {
+                owner,
+                attribute,
+                value,
+            } => values
+                .entry((owner.clone(), attribute.clone()))
+                .or_default()
+                .0
+                .push(value.clone()),
+            SchemaChange::EnumValueAdded {
+                owner,
+                attribute,
+                value,
+            } => values
+                .entry((owner.clone(), attribute.clone()))
+                .or_default()
+                .1
+                .push(value.clone()),
+            _ => {}
+        }
+    }
+    let renamed_to = |owner: &AttributeOwner, attribute: &str| -> Option<String> {
+        match values.get(&(owner.clone(), attribute.to_string())) {
+            Some((removed, added)) if removed.len() == 1 && added.len() == 1 => {
+                Some(added[0].clone())
+            }
+            _ => None,
+        }
+    };
+    changes
+        .into_iter()
+        .filter_map(|change| match change {
+            SchemaChange::EnumValueRemoved {
+                owner,
+                attribute,
+                value,
+            } => Some(match renamed_to(&owner, &attribute) {
+                Some(to) => SchemaChange::EnumValueRenamed {
+                    owner,
+                    attribute,
+                    from: value,
+                    to,
+                },
+                None => SchemaChange::EnumValueRemoved {
+                    owner,
+                    attribute,
+                    value,
+                },
+            }),
+            SchemaChange::EnumValueAdded {
+                ref owner,
+                ref attribute,
+                ..
+            } if renamed_to(owner, attribute).is_some() => None,
+            change => Some(change),
+        })
+        .collect()
+}
+
+/// True when some value `before` accepted may fail `after`.
+fn constraints_tightened(
+    before: &MetaAttributeConstraints,
+    after: &MetaAttributeConstraints,
+) -> bool {
+    let date = |bound: &Option<String>| bound.as_deref().and_then(parse_bound);
+    (after.pattern.is_some() && after.pattern != before.pattern)
+        || bound_tightened(before.min, after.min, Ordering::Greater)
+        || bound_tightened(before.max, after.max, Ordering::Less)
+        || bound_tightened(
+            date(&before.earliest),
+            date(&after.earliest),
+            Ordering::Greater,
+        )
+        || bound_tightened(date(&before.latest), date(&after.latest), Ordering::Less)
+        || (after.unique && !before.unique)
+}
+
+/// True when bound `after` excludes values `before` allowed; `stricter` is the direction a
+/// tighter bound moves in.
+fn bound_tightened<T: PartialOrd>(before: Option<T>, after: Option<T>, stricter: Ordering) -> bool {
+    match (before, after) {
+        (_, None) => false,
+        (None, Some(_)) => true,
+        (Some(before), Some(after)) => after.partial_cmp(&before) == Some(stricter),
+    }
+}
+
+fn compare_validation(
+    before: Option<&MetaValidationRules>,
+    after: Option<&MetaValidationRules>,
+    changes: &mut Vec<SchemaChange>,
+) {
+    let max_length = |rules: Option<&MetaValidationRules>, text: bool| {
+        let attributes = rules.and_then(|rules| rules.attributes.as_ref());
+        let rule = attributes.and_then(|attrs| {
+            if text {
+                attrs.text.as_ref()
+            } else {
+                attrs.string.as_ref()
+            }
+        });
+        rule.and_then(|rule| rule.max_length)
+    };
+    for (rule, text) in [
+        ("attributes.string.maxLength", false),
+        ("attributes.text.maxLength", true),
+    ] {
+        let (old, new) = (max_length(before, text), max_length(after, text));
+        if old != new {
+            changes.push(SchemaChange::ValidationRuleChanged {
+                rule: rule.into(),
+                tightened: bound_tightened(old, new, Ordering::Less),
+            });
+        }
+    }
+
+    let case_sensitive = |rules: Option<&MetaValidationRules>| {
+        rules
+            .and_then(|rules| rules.attributes.as_ref())
+            .and_then(|attrs| attrs.enum_rule.as_ref())
+            .and_then(|rule| rule.case_sensitive)
+            .unwrap_or(false)
+    };
+    if case_sensitive(before) != case_sensitive(after) {
+        changes.push(SchemaChange::ValidationRuleChanged {
+            rule: "attributes.enum.caseSensitive".into(),
+            tightened: case_sensitive(after),
+        });
+    }
+
+    let relationship_rules = |rules: Option<&MetaValidationRules>| {
+        rules
+            .and_then(|rules| rules.relationships.clone())
+            .unwrap_or_default()
+    };
+    let (old_rules, new_rules) = (relationship_rules(before), relationship_rules(after));
+    let ids: BTreeSet<&String> = old_rules.keys().chain(new_rules.keys()).collect();
+    for id in ids {
+        let (old, new) = (old_rules.get(id), new_rules.get(id));
+        // Self-links are allowed unless forbidden; duplicates unless disallowed.
+        let allows_self = |rule: Option<&MetaRelationshipValidation>| {
+            rule.and_then(|rule| rule.allow_self) != Some(false)
+        };
+        let allows_duplicate = |rule: Option<&MetaRelationshipValidation>| {
+            rule.and_then(|rule| rule.allow_duplicate).unwrap_or(true)
+        };
+        let flags = [
+            ("allowSelf", allows_self(old), allows_self(new)),
+            (
+                "allowDuplicate",
+                allows_duplicate(old),
+                allows_duplicate(new),
+            ),
+        ];
+        for (flag, was_allowed, allowed) in flags {
+            if was_allowed != allowed {
+                changes.push(SchemaChange::ValidationRuleChanged {
+                    rule: format!("relationships.{id}.{flag}"),
+                    tightened: !allowed,
+                });
+            }
+        }
+    }
+}
+
+/// Entry creates, updates and deletes that make the snapshot's meta-model entries record the
+/// schema of `target`.
+fn schema_update(snapshot: &GraphSnapshot, target: &MetaModelRegistry) -> ChangeSet {
+    let wanted: Vec<NodeVersion> = entry_nodes(&target.document())
+        .iter()
+        .map(|node| target.node_with_defaults(node))
+        .collect();
+    let ids: BTreeSet<&str> = wanted.iter().map(|node| node.id.as_str()).collect();
+    let mut update = ChangeSet::default();
+    for entry in snapshot.nodes_of_type(META_MODEL_ENTRY_TYPE) {
+        if !ids.contains(entry.id.as_str()) {
+            update.node_deletes.push(NodeTombstone {
+                id: entry.id.clone(),
+            });
+        }
+    }
+    for node in wanted {
+        match snapshot.node(&node.id) {
+            None => update.node_creates.push(node),
+            Some(current) if *current != node => update.node_updates.push(node),
+            Some(_) => {}
+        }
+    }
+    update
+}
+
+/// Data changes that repair what the fix-ups can repair mechanically under `registry`.
+fn fix_ups(
+    snapshot: &GraphSnapshot,
+    registry: &MetaModelRegistry,
+    renames: &[EnumRename],
+) -> ChangeSet {
+    let mut fixes = ChangeSet::default();
+    for node in snapshot.nodes().filter(|node| !is_entry(node)) {
+        let node_type = node.r#type.as_deref().unwrap_or_default();
+        let renamed = NodeVersion {
+            props: rename_values(
+                &node.props,
+                renames,
+                |owner| matches!(owner, AttributeOwner::Type(ty) if registry.is_a(node_type, ty)),
+            ),
+            ..node.clone()
+        };
+        let fixed = registry.node_with_defaults(&renamed);
+        if fixed != *node {
+            fixes.node_updates.push(fixed);
+        }
+    }
+
+    // A tombstone removes every edge between its endpoints, so a pair with any edge to drop or
+    // repair is cleared and its other edges are created again.
+    let mut pairs: BTreeMap<(&str, &str), Vec<&EdgeVersion>> = BTreeMap::new();
+    for edge in snapshot.edges() {
+        pairs
+            .entry((edge.from.as_str(), edge.to.as_str()))
+            .or_default()
+            .push(edge);
+    }
+    for ((from, to), edges) in pairs {
+        let mut changed = false;
+        let mut kept = Vec::new();
+        for edge in edges {
+            if disallowed(snapshot, registry, edge) {
+                changed = true;
+                continue;
+            }
+            let renamed = EdgeVersion {
+                props: rename_values(
+                    &edge.props,
+                    renames,
+                    |owner| matches!(owner, AttributeOwner::Relationship(rel) if edge.r#type.as_deref() == Some(rel)),
+                ),
+                ..edge.clone()
+            };
+            let fixed = registry.edge_with_defaults(&renamed);
+            changed |= fixed != *edge;
+            kept.push(fixed);
+        }
+        if changed {
+            fixes.edge_deletes.push(EdgeTombstone {
+                from: from.into(),
+                to: to.into(),
+            });
+            fixes.edge_creates.extend(kept);
+        }
+    }
+    fixes
+}
+
+/// True when `registry` no longer has `edge`'s relationship or no longer allows it between its
+/// endpoints' types.
+fn disallowed(snapshot: &GraphSnapshot, registry: &MetaModelRegistry, edge: &EdgeVersion) -> bool {
+    let endpoint_type = |id: &str| snapshot.node(id).and_then(|node| node.r#type.as_deref());
+    let (Some(from_type), Some(to_type)) = (endpoint_type(&edge.from), endpoint_type(&edge.to))
+    else {
+        return false;
+    };
+    registry
+        .check_edge(edge, from_type, to_type)
+        .iter()
+        .any(|issue| {
+            matches!(
+                issue.rule,
+                ValidationRule::UnknownType | ValidationRule::Endpoint
+            )
+        })
+}
+
+fn rename_values(
+    props: &Option<Value>,
+    renames: &[EnumRename],
+    owns: impl Fn(&AttributeOwner) -> bool,
+) -> Option<Value> {
+    let mut props = props.clone();
+    if let Some(Value::Object(map)) = props.as_mut() {
+        for rename in renames.iter().filter(|rename| owns(&rename.owner)) {
+            if let Some(value) = map.get_mut(&rename.attribute)
+                && value
+                    .as_str()
+                    .is_some_and(|text| text.eq_ignore_ascii_case(&rename.from))
+            {
+                *value = Value::String(rename.to.clone());
+            }
+        }
+    }
+    props
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aideon_mneme::meta::MetaStringRule;
+
+    fn doc(types: Vec<MetaType>) -> MetaModelDocument {
+        MetaModelDocument {
+            version: "v1".into(),
+            description: None,
+            types,
+            relationships: vec![],
+            validation: None,
+            remove: None,
+        }
+    }
+
+    fn ty(id: &str, attributes: Vec<MetaAttribute>) -> MetaType {
+        MetaType {
+            id: id.into(),
+            label: None,
+            category: None,
+            extends: None,
+            is_abstract: false,
+            attributes,
+            effect_types: vec![],
+        }
+    }
+
+    fn attr(name: &str, kind: MetaAttributeKind) -> MetaAttribute {
+        MetaAttribute {
+            name: name.into(),
+            value_type: kind,
+            required: false,
+            enum_values: vec![],
+            default: None,
+            constraints: MetaAttributeConstraints::default(),
+        }
+    }
+
+    fn status(values: &[&str]) -> MetaAttribute {
+        MetaAttribute {
+            enum_values: values.iter().map(|value| value.to_string()).collect(),
+            ..attr("status", MetaAttributeKind::Enum)
+        }
+    }
+
+    #[test]
+    fn compare_classifies_attribute_changes() {
+        let old = doc(vec![
+            ty(
+                "Risk",
+                vec![
+                    attr("name", MetaAttributeKind::String),
+                    MetaAttribute {
+                        constraints: MetaAttributeConstraints {
+                            min: Some(0.0),
+                            max: Some(1.0),
+                            ..Default::default()
+                        },
+                        ..attr("score", MetaAttributeKind::Number)
+                    },
+                    attr("notes", MetaAttributeKind::Text),
+                    status(&["open", "closed"]),
+                ],
+            ),
+            ty("Issue", vec![]),
+        ]);
+        let mut new = doc(vec![
+            ty(
+                "Risk",
+                vec![
+                    MetaAttribute {
+                        required: true,
+                        ..attr("name", MetaAttributeKind::String)
+                    },
+                    MetaAttribute {
+                        constraints: MetaAttributeConstraints {
+                            min: Some(-1.0),
+                            max: Some(1.0),
+                            ..Default::default()
+                        },
+                        ..attr("score", MetaAttributeKind::Number)
+                    },
+                    status(&["active", "closed"]),
+                    attr("owner", MetaAttributeKind::String),
+                ],
+            ),
+            ty("Control", vec![]),
+        ]);
+        new.validation = Some(MetaValidationRules {
+            attributes: Some(aideon_mneme::meta::MetaAttributeValidation {
+                string: Some(MetaStringRule {
+                    max_length: Some(80),
+                }),
+                ..Default::default()
+            }),
+            relationships: None,
+        });
+
+        let owner = || AttributeOwner::Type("Risk".into());
+        let changes = compare(&old, &new, &MigrationHints::default());
+        let expected = vec![
+            SchemaChange::TypeRemoved {
+                type_id: "Issue".into(),
+            },
+            SchemaChange::RequiredChanged {
+                owner: owner(),
+                attribute: "name".into(),
+                required: true,
+            },
+            SchemaChange::AttributeRemoved {
+                owner: owner(),
+                attribute: "notes".into(),
+            },
+            SchemaChange::ConstraintsChanged {
+                owner: owner(),
+                attribute: "score".into(),
+                tightened: false,
+            },
+            SchemaChange::EnumValueRemoved {
+                owner: owner(),
+                attribute: "status".into(),
+                value: "open".into(),
+            },
+            SchemaChange::EnumValueAdded {
+                owner: owner(),
+                attribute: "status".into(),
+                value: "active".into(),
+            },
+            SchemaChange::AttributeAdded {
+                owner: owner(),
+                attribute: "owner".into(),
+                required: false,
+                has_default: false,
+            },
+            SchemaChange::TypeAdded {
+                type_id: "Control".into(),
+            },
+            SchemaChange::ValidationRuleChanged {
+                rule: "attributes.string.maxLength".into(),
+                tightened: true,
+            },
+        ];
+        assert_eq!(changes, expected);
+        let breaking: Vec<bool> = changes
+            .iter()
+            .map(|change| change.compatibility() == Compatibility::Breaking)
+            .collect();
+        assert_eq!(
+            breaking,
+            vec![true, true, false, false, true, false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn migration_planning_infers_single_enum_renames() {
+        let old = doc(vec![
+            ty("Risk", vec![status(&["open", "closed"])]),
+            ty("Issue", vec![status(&["new", "stale", "done"])]),
+        ]);
+        let new = doc(vec![
+            ty("Risk", vec![status(&["active", "closed"])]),
+            ty("Issue", vec![status(&["triaged", "fixed", "done"])]),
+        ]);
+        let risk = AttributeOwner::Type("Risk".into());
+        let issue = AttributeOwner::Type("Issue".into());
+        let removed = |owner: &AttributeOwner, value: &str| SchemaChange::EnumValueRemoved {
+            owner: owner.clone(),
+            attribute: "status".into(),
+            value: value.into(),
+        };
+        let added = |owner: &AttributeOwner, value: &str| SchemaChange::EnumValueAdded {
+            owner: owner.clone(),
+            attribute: "status".into(),
+            value: value.into(),
+        };
+
+        let changes = compare(&old, &new, &MigrationHints::default());
+        assert_eq!(
+            changes,
+            vec![
+                removed(&issue, "new"),
+                removed(&issue, "stale"),
+                added(&issue, "triaged"),
+                added(&issue, "fixed"),
+                removed(&risk, "open"),
+                added(&risk, "active"),
+            ]
+        );
+        assert_eq!(
+            infer_enum_renames(changes),
+            vec![
+                removed(&issue, "new"),
+                removed(&issue, "stale"),
+                added(&issue, "triaged"),
+                added(&issue, "fixed"),
+                SchemaChange::EnumValueRenamed {
+                    owner: risk,
+                    attribute: "status".into(),
+                    from: "open".into(),
+                    to: "active".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn hints_pick_renames_among_several_enum_changes() {
+        let old = doc(vec![ty("Risk", vec![status(&["open", "stale", "closed"])])]);
+        let new = doc(vec![ty("Risk", vec![status(&["active", "done"])])]);
+        let owner = AttributeOwner::Type("Risk".into());
+
+        let unhinted = compare(&old, &new, &MigrationHints::default());
+        assert!(
+            unhinted
+                .iter()
+                .all(|change| !matches!(change, SchemaChange::EnumValueRenamed { .. }))
+        );
+
+        let hints = MigrationHints {
+            enum_renames: vec![EnumRename {
+                owner: owner.clone(),
+                attribute: "status".into(),
+                from: "closed".into(),
+                to: "done".into(),
+            }],
+        };
+        let changes = compare(&old, &new, &hints);
+        assert_eq!(
+            changes,
+            vec![
+                SchemaChange::EnumValueRenamed {
+                    owner: owner.clone(),
+                    attribute: "status".into(),
+                    from: "closed".into(),
+                    to: "done".into(),
+                },
+                SchemaChange::EnumValueRemoved {
+                    owner: owner.clone(),
+                    attribute: "status".into(),
+                    value: "open".into(),
+                },
+                SchemaChange::EnumValueRemoved {
+                    owner: owner.clone(),
+                    attribute: "status".into(),
+                    value: "stale".into(),
+                },
+                SchemaChange::EnumValueAdded {
+                    owner,
+                    attribute: "status".into(),
+                    value: "active".into(),
+                },
+            ]
+        );
+        let json = serde_json::to_value(&changes[0]).expect("json");
+        assert_eq!(json["change"], "enumValueRenamed");
+        assert_eq!(json["owner"]["type"], "Risk");
+    }
+}
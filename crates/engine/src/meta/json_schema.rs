@@ -0,0 +1,330 @@
+//! JSON Schema (draft 2020-12) generated from the registry, so renderer forms and external
+//! import tooling check attributes with the same rules as `validation.rs`.
+//!
+//! Keywords JSON Schema lacks are emitted as `x-aideon-*` annotations, which validators ignore.
+
+use aideon_mneme::blob::BLOB_REF_PREFIX;
+use aideon_mneme::meta::{MetaAttribute, MetaAttributeKind};
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+
+use crate::meta::model::{AttributeRuleSet, RelationshipDescriptor, TypeDescriptor};
+
+pub(super) const DRAFT_2020_12: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// `$defs` key of the props schema for a node type.
+pub(super) fn type_key(type_id: &str) -> String {
+    format!("type.{type_id}")
+}
+
+/// `$defs` key of the props schema for a relationship.
+pub(super) fn relationship_key(rel_id: &str) -> String {
+    format!("relationship.{rel_id}")
+}
+
+/// Schema for the props of a node of this type. Inherited attributes are included, so the
+/// schema stands alone; the supertypes are recorded in `x-aideon-extends`.
+pub(super) fn type_schema(
+    title: &str,
+    descriptor: &TypeDescriptor,
+    rules: &AttributeRuleSet,
+) -> Map<String, Value> {
+    let mut schema = props_schema(title, &descriptor.attributes, rules);
+    if !descriptor.ancestors.is_empty() {
+        schema.insert("x-aideon-extends".into(), json!(descriptor.ancestors));
+    }
+    if descriptor.is_abstract {
+        schema.insert("x-aideon-abstract".into(), Value::Bool(true));
+    }
+    schema
+}
+
+/// Schema for the props of an edge of this relationship; allowed endpoint types are recorded
+/// in `x-aideon-from` and `x-aideon-to`.
+pub(super) fn relationship_schema(
+    title: &str,
+    descriptor: &RelationshipDescriptor,
+    rules: &AttributeRuleSet,
+) -> Map<String, Value> {
+    let mut schema = props_schema(title, &descriptor.attributes, rules);
+    schema.insert("x-aideon-from".into(), json!(descriptor.from));
+    schema.insert("x-aideon-to".into(), json!(descriptor.to));
+    schema
+}
+
+fn props_schema(
+    title: &str,
+    attributes: &BTreeMap<String, MetaAttribute>,
+    rules: &AttributeRuleSet,
+) -> Map<String, Value> {
+    let properties: Map<String, Value> = attributes
+        .values()
+        .map(|attr| (attr.name.clone(), attribute_schema(attr, rules)))
+        .collect();
+    let required: Vec<&str> = attributes
+        .values()
+        // A defaulted attribute is filled in on create, so imports may leave it out.
+        .filter(|attr| attr.required && attr.default.is_none())
+        .map(|attr| attr.name.as_str())
+        .collect();
+    let mut schema = Map::new();
+    schema.insert("title".into(), json!(title));
+    schema.insert("type".into(), json!("object"));
+    schema.insert("properties".into(), Value::Object(properties));
+    if !required.is_empty() {
+        schema.insert("required".into(), json!(required));
+    }
+    schema
+}
+
+fn attribute_schema(attr: &MetaAttribute, rules: &AttributeRuleSet) -> Value {
+    let constraints = &attr.constraints;
+    let mut schema = Map::new();
+    let mut set = |key: &str, value: Value| {
+        schema.insert(key.into(), value);
+    };
+    match attr.value_type {
+        MetaAttributeKind::String | MetaAttributeKind::Text => {
+            set("type", json!("string"));
+            let max = if attr.value_type == MetaAttributeKind::Text {
+                rules.text_max
+            } else {
+                rules.string_max
+            };
+            if let Some(max) = max {
+                set("maxLength", json!(max));
+            }
+            if let Some(pattern) = &constraints.pattern {
+                set("pattern", json!(pattern));
+            }
+        }
+        MetaAttributeKind::Number => {
+            set("type", json!("number"));
+            if let Some(min) = constraints.min {
+                set("minimum", json!(min));
+            }
+            if let Some(max) = constraints.max {
+                set("maximum", json!(max));
+            }
+        }
+        MetaAttributeKind::Boolean => set("type", json!("boolean")),
+        MetaAttributeKind::Datetime => {
+            set("type", json!("string"));
+            set("format", json!("date-time"));
+            // `formatMinimum` / `formatMaximum` are the ajv-formats range keywords.
+            if let Some(earliest) = &constraints.earliest {
+                set("formatMinimum", json!(earliest));
+            }
+            if let Some(latest) = &constraints.latest {
+                set("formatMaximum", json!(latest));
+            }
+        }
+        MetaAttributeKind::Enum => {
+            set("type", json!("string"));
+            set("enum", json!(attr.enum_values));
+            // The engine also accepts other casings unless enums are case-sensitive; the
+            // schema only lists the canonical spelling.
+            if !rules.enum_case_sensitive {
+                set("x-aideon-caseInsensitive", Value::Bool(true));
+            }
+        }
+        MetaAttributeKind::Blob => {
+            set("type", json!("string"));
+            set(
+                "pattern",
+                json!(format!("^{BLOB_REF_PREFIX}[0-9a-f]{{64}}$")),
+            );
+        }
+    }
+    if let Some(default) = &attr.default {
+        set("default", default.clone());
+    }
+    if constraints.unique {
+        set("x-aideon-unique", Value::Bool(true));
+    }
+    Value::Object(schema)
+}
+
+/// Schema for one node or edge entry of an import file, choosing the props schema by `type`.
+/// `kinds` pairs each allowed type id with its `$defs` key and whether it has required
+/// attributes.
+pub(super) fn element_schema(
+    mut properties: Map<String, Value>,
+    required: &[&str],
+    kinds: &[(String, String, bool)],
+) -> Value {
+    let ids: Vec<&str> = kinds.iter().map(|(id, _, _)| id.as_str()).collect();
+    properties.insert("type".into(), json!({ "enum": ids }));
+    properties.insert("props".into(), json!({ "type": "object" }));
+    let dispatch: Vec<Value> = kinds
+        .iter()
+        .map(|(id, key, has_required)| {
+            let mut then = json!({
+                "properties": { "props": { "$ref": format!("#/$defs/{key}") } }
+            });
+            if *has_required {
+                then["required"] = json!(["props"]);
+            }
+            json!({
+                "if": { "properties": { "type": { "const": id } }, "required": ["type"] },
+                "then": then,
+            })
+        })
+        .collect();
+    json!({
+        "type": "object",
+        "required": required,
+        "properties": properties,
+        "allOf": dispatch,
+    })
+}
+
+/// Root schema of a dataset import file (the format `BaselineDataset` reads), whose commits
+/// list `nodes` and `edges`.
+pub(super) fn dataset_schema(version: &str, defs: Map<String, Value>) -> Value {
+    json!({
+        "$schema": DRAFT_2020_12,
+        "$id": format!("urn:aideon:meta-model:{version}"),
+        "title": format!("Aideon Praxis dataset (meta-model {version})"),
+        "type": "object",
+        "required": ["version", "commits"],
+        "properties": {
+            "version": { "type": "string" },
+            "description": { "type": "string" },
+            "defaults": { "type": "object" },
+            "commits": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["key", "message"],
+                    "properties": {
+                        "key": { "type": "string" },
+                        "message": { "type": "string" },
+                        "branch": { "type": "string" },
+                        "nodes": { "type": "array", "items": { "$ref": "#/$defs/node" } },
+                        "edges": { "type": "array", "items": { "$ref": "#/$defs/edge" } }
+                    }
+                }
+            }
+        },
+        "$defs": defs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::BASELINE_YAML;
+    use crate::meta::{MetaModelConfig, MetaModelRegistry, MetaModelSource};
+    use serde_json::{Value, json};
+
+    fn registry() -> MetaModelRegistry {
+        let overlay = json!({
+            "version": "1.0.0",
+            "types": [{
+                "id": "SaaSApplication",
+                "extends": "Application",
+                "attributes": [
+                    { "name": "tenantUrl", "type": "string", "pattern": "^https://" },
+                    {
+                        "name": "seats",
+                        "type": "number",
+                        "required": true,
+                        "min": 1,
+                        "default": 10
+                    }
+                ]
+            }]
+        });
+        let config =
+            MetaModelConfig::default().add_override(MetaModelSource::Inline(overlay.to_string()));
+        MetaModelRegistry::load(&config).expect("registry")
+    }
+
+    #[test]
+    fn type_schema_flattens_inherited_attributes() {
+        let registry = registry();
+        let schema = registry
+            .type_json_schema("SaaSApplication")
+            .expect("schema");
+
+        assert_eq!(schema["$schema"], json!(super::DRAFT_2020_12));
+        assert_eq!(schema["x-aideon-extends"], json!(["Application"]));
+        assert_eq!(schema["required"], json!(["name"]));
+        let props = &schema["properties"];
+        assert_eq!(
+            props["name"],
+            json!({ "type": "string", "maxLength": 256, "x-aideon-unique": true })
+        );
+        assert_eq!(
+            props["disposition"]["enum"],
+            json!(["Invest", "Tolerate", "Migrate", "Eliminate"])
+        );
+        assert_eq!(
+            props["disposition"]["x-aideon-caseInsensitive"],
+            json!(true)
+        );
+        assert_eq!(props["tenantUrl"]["pattern"], json!("^https://"));
+        assert_eq!(
+            props["seats"],
+            json!({ "type": "number", "minimum": 1.0, "default": 10 })
+        );
+        assert!(registry.type_json_schema("Unknown").is_none());
+
+        let accesses = registry
+            .relationship_json_schema("accesses")
+            .expect("relationship");
+        assert_eq!(accesses["required"], json!(["mode"]));
+        assert_eq!(accesses["x-aideon-to"], json!(["DataEntity"]));
+    }
+
+    #[test]
+    fn bundle_dispatches_import_elements_by_type() {
+        let schema = registry().json_schema();
+
+        assert_eq!(schema["$id"], json!("urn:aideon:meta-model:1.0.0"));
+        let defs = &schema["$defs"];
+        assert!(defs["type.Application"].is_object());
+        assert!(defs["relationship.accesses"].is_object());
+        let node_types = defs["node"]["properties"]["type"]["enum"]
+            .as_array()
+            .expect("type enum");
+        assert!(node_types.contains(&json!("SaaSApplication")));
+        let application = defs["node"]["allOf"]
+            .as_array()
+            .expect("dispatch")
+            .iter()
+            .find(|branch| branch["if"]["properties"]["type"]["const"] == "Application")
+            .expect("Application branch");
+        assert_eq!(
+            application["then"],
+            json!({
+                "properties": { "props": { "$ref": "#/$defs/type.Application" } },
+                "required": ["props"]
+            })
+        );
+        assert_eq!(defs["edge"]["required"], json!(["from", "to", "type"]));
+        assert_eq!(
+            schema["properties"]["commits"]["items"]["properties"]["nodes"]["items"],
+            json!({ "$ref": "#/$defs/node" })
+        );
+    }
+
+    #[test]
+    fn embedded_baseline_dataset_validates_against_the_bundle() {
+        let schema = MetaModelRegistry::load(&MetaModelConfig::default())
+            .expect("registry")
+            .json_schema();
+        let validator = jsonschema::validator_for(&schema).expect("valid JSON Schema");
+        let dataset: Value = serde_yaml::from_str(BASELINE_YAML).expect("dataset");
+
+        let errors: Vec<String> = validator
+            .iter_errors(&dataset)
+            .map(|err| format!("{}: {err}", err.instance_path))
+            .collect();
+        assert!(errors.is_empty(), "{errors:#?}");
+
+        let mut invalid = dataset.clone();
+        invalid["commits"][0]["nodes"][0]["props"] = json!({ "purpose": "no name" });
+        assert!(!validator.is_valid(&invalid));
+    }
+}
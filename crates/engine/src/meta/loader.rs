@@ -1,9 +1,14 @@
 //! Praxis meta-model document loading and merging.
 
 use crate::error::{PraxisError, PraxisResult};
+use crate::meta::RelationshipEnd;
 use crate::meta::config::MetaModelSource;
-use aideon_mneme::meta::{MetaModelDocument, MetaRelationship, MetaType};
-use std::collections::HashMap;
+use crate::meta::evolution::{AttributeOwner, SchemaChange};
+use aideon_mneme::meta::{
+    MetaAttribute, MetaAttributeKind, MetaAttributeRemoval, MetaModelDocument, MetaRelationship,
+    MetaRemovals, MetaType, MetaValidationRules,
+};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 
 const CORE_META_JSON: &str = include_str!("../../../../docs/data/meta/core-v1.json");
@@ -19,7 +24,7 @@ pub(super) fn load_document(source: &MetaModelSource) -> PraxisResult<MetaModelD
             parse_document(&contents)
         }
         MetaModelSource::Inline(raw) => parse_document(raw),
-        MetaModelSource::Document(doc) => Ok(doc.clone()),
+        MetaModelSource::Document(doc) => Ok((**doc).clone()),
     }
 }
 
@@ -29,11 +34,35 @@ fn parse_document(raw: &str) -> PraxisResult<MetaModelDocument> {
     })
 }
 
+/// A merged document, the layer that last defined each attribute (0 for the base document, `n`
+/// for the n-th overlay) and what each overlay removed, in order.
+#[derive(Debug)]
+pub(super) struct MergedDocument {
+    pub document: MetaModelDocument,
+    pub provenance: BTreeMap<(AttributeOwner, String), usize>,
+    pub removals: Vec<(usize, SchemaChange)>,
+}
+
+/// Layer `overlays` onto `base`. Each overlay first applies its `remove` directives, then adds
+/// or overrides individual attributes, appends enum values and relationship endpoints, and
+/// merges validation rules entry by entry. The base document cannot remove anything.
 pub(super) fn merge_documents(
-    mut base: MetaModelDocument,
+    base: MetaModelDocument,
     overlays: &[MetaModelDocument],
-) -> PraxisResult<MetaModelDocument> {
-    for layer in overlays {
+) -> PraxisResult<MergedDocument> {
+    if base.remove.is_some() {
+        return Err(PraxisError::IntegrityViolation {
+            message: "meta-model base document cannot contain remove directives".into(),
+        });
+    }
+    let mut merged = MergedDocument {
+        document: base,
+        provenance: BTreeMap::new(),
+        removals: Vec::new(),
+    };
+    record_attributes(&mut merged.provenance, &merged.document, 0);
+    for (idx, layer) in overlays.iter().enumerate() {
+        let base = &merged.document;
         if layer.version != base.version {
             return Err(PraxisError::IntegrityViolation {
                 message: format!(
@@ -42,46 +71,329 @@ pub(super) fn merge_documents(
                 ),
             });
         }
+        if let Some(removals) = &layer.remove {
+            apply_removals(&mut merged, removals, idx + 1)?;
+        }
+        let base = &mut merged.document;
         merge_types(&mut base.types, &layer.types);
         merge_relationships(&mut base.relationships, &layer.relationships);
-        if layer.validation.is_some() {
-            base.validation = layer.validation.clone();
+        if let Some(validation) = &layer.validation {
+            merge_validation(
+                base.validation.get_or_insert_with(Default::default),
+                validation,
+            );
         }
         if layer.description.is_some() {
             base.description = layer.description.clone();
         }
+        record_attributes(&mut merged.provenance, layer, idx + 1);
     }
-    Ok(base)
+    check_references(&merged)?;
+    Ok(merged)
 }
 
-fn merge_types(base: &mut Vec<MetaType>, overrides: &[MetaType]) {
-    let mut index: HashMap<String, usize> = base
+/// Reject relationships left without source or target types, and references to a type an
+/// overlay removed without defining it again.
+fn check_references(merged: &MergedDocument) -> PraxisResult<()> {
+    let doc = &merged.document;
+    if let Some(rel) = doc
+        .relationships
+        .iter()
+        .find(|rel| rel.from.is_empty() || rel.to.is_empty())
+    {
+        return Err(PraxisError::IntegrityViolation {
+            message: format!(
+                "meta-model relationship '{}' needs at least one source and target type",
+                rel.id
+            ),
+        });
+    }
+    let removed: BTreeSet<&str> = merged
+        .removals
         .iter()
-        .enumerate()
-        .map(|(idx, ty)| (ty.id.clone(), idx))
+        .filter_map(|(_, change)| match change {
+            SchemaChange::TypeRemoved { type_id } => Some(type_id.as_str()),
+            _ => None,
+        })
+        .filter(|id| doc.types.iter().all(|ty| ty.id != *id))
         .collect();
+    let dangling = |id: &str, user: String| PraxisError::IntegrityViolation {
+        message: format!("meta-model overlay removes type '{id}', but {user} still references it"),
+    };
+    for ty in &doc.types {
+        if let Some(parent) = ty.extends.as_deref().filter(|id| removed.contains(id)) {
+            return Err(dangling(parent, format!("type '{}'", ty.id)));
+        }
+    }
+    for rel in &doc.relationships {
+        if let Some(end) = rel
+            .from
+            .iter()
+            .chain(&rel.to)
+            .find(|id| removed.contains(id.as_str()))
+        {
+            return Err(dangling(end, format!("relationship '{}'", rel.id)));
+        }
+    }
+    Ok(())
+}
+
+fn record_attributes(
+    provenance: &mut BTreeMap<(AttributeOwner, String), usize>,
+    layer: &MetaModelDocument,
+    index: usize,
+) {
+    for ty in &layer.types {
+        for attr in &ty.attributes {
+            provenance.insert(
+                (AttributeOwner::Type(ty.id.clone()), attr.name.clone()),
+                index,
+            );
+        }
+    }
+    for rel in &layer.relationships {
+        for attr in &rel.attributes {
+            let owner = AttributeOwner::Relationship(rel.id.clone());
+            provenance.insert((owner, attr.name.clone()), index);
+        }
+    }
+}
+
+/// Apply one overlay's `remove` directives, recording each as a removal by `layer`.
+fn apply_removals(
+    merged: &mut MergedDocument,
+    removals: &MetaRemovals,
+    layer: usize,
+) -> PraxisResult<()> {
+    let MergedDocument {
+        document: doc,
+        provenance,
+        removals: log,
+    } = merged;
+    let mut record = |change| log.push((layer, change));
+    for id in &removals.types {
+        let before = doc.types.len();
+        doc.types.retain(|ty| &ty.id != id);
+        if doc.types.len() == before {
+            return Err(unknown_removal(format!("type '{id}'")));
+        }
+        provenance.retain(|(owner, _), _| *owner != AttributeOwner::Type(id.clone()));
+        record(SchemaChange::TypeRemoved {
+            type_id: id.clone(),
+        });
+    }
+    for id in &removals.relationships {
+        let before = doc.relationships.len();
+        doc.relationships.retain(|rel| &rel.id != id);
+        if doc.relationships.len() == before {
+            return Err(unknown_removal(format!("relationship '{id}'")));
+        }
+        provenance.retain(|(owner, _), _| *owner != AttributeOwner::Relationship(id.clone()));
+        if let Some(rules) = doc
+            .validation
+            .as_mut()
+            .and_then(|rules| rules.relationships.as_mut())
+        {
+            rules.remove(id);
+        }
+        record(SchemaChange::RelationshipRemoved {
+            relationship: id.clone(),
+        });
+    }
+    for removal in &removals.attributes {
+        let (owner, attributes) = owner_attributes(doc, removal)?;
+        let label = format!("attribute '{}'", removal.name);
+        let Some(pos) = attributes.iter().position(|attr| attr.name == removal.name) else {
+            return Err(unknown_removal(label));
+        };
+        if removal.enum_values.is_empty() {
+            attributes.remove(pos);
+            provenance.remove(&(owner.clone(), removal.name.clone()));
+            record(SchemaChange::AttributeRemoved {
+                owner,
+                attribute: removal.name.clone(),
+            });
+            continue;
+        }
+        let values = &mut attributes[pos].enum_values;
+        for value in &removal.enum_values {
+            let Some(at) = values.iter().position(|existing| existing == value) else {
+                return Err(unknown_removal(format!("enum value '{value}' of {label}")));
+            };
+            values.remove(at);
+            record(SchemaChange::EnumValueRemoved {
+                owner: owner.clone(),
+                attribute: removal.name.clone(),
+                value: value.clone(),
+            });
+        }
+    }
+    for removal in &removals.endpoints {
+        let Some(rel) = doc
+            .relationships
+            .iter_mut()
+            .find(|rel| rel.id == removal.relationship)
+        else {
+            return Err(unknown_removal(format!(
+                "relationship '{}'",
+                removal.relationship
+            )));
+        };
+        for (end, ends, removed) in [
+            (RelationshipEnd::Source, &mut rel.from, &removal.from),
+            (RelationshipEnd::Target, &mut rel.to, &removal.to),
+        ] {
+            for type_id in removed {
+                let Some(at) = ends.iter().position(|existing| existing == type_id) else {
+                    return Err(unknown_removal(format!(
+                        "endpoint '{type_id}' of relationship '{}'",
+                        rel.id
+                    )));
+                };
+                ends.remove(at);
+                record(SchemaChange::EndpointRemoved {
+                    relationship: rel.id.clone(),
+                    end,
+                    type_id: type_id.clone(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn owner_attributes<'a>(
+    doc: &'a mut MetaModelDocument,
+    removal: &MetaAttributeRemoval,
+) -> PraxisResult<(AttributeOwner, &'a mut Vec<MetaAttribute>)> {
+    match (&removal.type_id, &removal.relationship) {
+        (Some(id), None) => doc
+            .types
+            .iter_mut()
+            .find(|ty| &ty.id == id)
+            .map(|ty| (AttributeOwner::Type(id.clone()), &mut ty.attributes))
+            .ok_or_else(|| unknown_removal(format!("type '{id}'"))),
+        (None, Some(id)) => doc
+            .relationships
+            .iter_mut()
+            .find(|rel| &rel.id == id)
+            .map(|rel| {
+                (
+                    AttributeOwner::Relationship(id.clone()),
+                    &mut rel.attributes,
+                )
+            })
+            .ok_or_else(|| unknown_removal(format!("relationship '{id}'"))),
+        _ => Err(PraxisError::IntegrityViolation {
+            message: format!(
+                "meta-model overlay must remove attribute '{}' from exactly one type or relationship",
+                removal.name
+            ),
+        }),
+    }
+}
+
+fn unknown_removal(target: String) -> PraxisError {
+    PraxisError::IntegrityViolation {
+        message: format!("meta-model overlay removes unknown {target}"),
+    }
+}
+
+fn merge_types(base: &mut Vec<MetaType>, overrides: &[MetaType]) {
     for ty in overrides {
-        if let Some(pos) = index.get(&ty.id).copied() {
-            base[pos] = ty.clone();
-        } else {
-            index.insert(ty.id.clone(), base.len());
+        let Some(existing) = base.iter_mut().find(|existing| existing.id == ty.id) else {
             base.push(ty.clone());
-        }
+            continue;
+        };
+        override_with(&mut existing.label, &ty.label);
+        override_with(&mut existing.category, &ty.category);
+        override_with(&mut existing.extends, &ty.extends);
+        // `abstract` is a plain flag, so an overlay can make a type abstract but never concrete
+        // again; to do that, remove the type and define it anew in the same overlay.
+        existing.is_abstract |= ty.is_abstract;
+        merge_attributes(&mut existing.attributes, &ty.attributes);
+        append_missing(&mut existing.effect_types, &ty.effect_types);
     }
 }
 
 fn merge_relationships(base: &mut Vec<MetaRelationship>, overrides: &[MetaRelationship]) {
-    let mut index: HashMap<String, usize> = base
-        .iter()
-        .enumerate()
-        .map(|(idx, rel)| (rel.id.clone(), idx))
-        .collect();
     for rel in overrides {
-        if let Some(pos) = index.get(&rel.id).copied() {
-            base[pos] = rel.clone();
-        } else {
-            index.insert(rel.id.clone(), base.len());
+        let Some(existing) = base.iter_mut().find(|existing| existing.id == rel.id) else {
             base.push(rel.clone());
+            continue;
+        };
+        override_with(&mut existing.label, &rel.label);
+        append_missing(&mut existing.from, &rel.from);
+        append_missing(&mut existing.to, &rel.to);
+        override_with(&mut existing.directed, &rel.directed);
+        match (&mut existing.multiplicity, &rel.multiplicity) {
+            (Some(multiplicity), Some(overlay)) => {
+                override_with(&mut multiplicity.from, &overlay.from);
+                override_with(&mut multiplicity.to, &overlay.to);
+                override_with(&mut multiplicity.severity, &overlay.severity);
+            }
+            (multiplicity, overlay) => override_with(multiplicity, overlay),
+        }
+        merge_attributes(&mut existing.attributes, &rel.attributes);
+    }
+}
+
+/// An overlay attribute replaces the definition of the same name, except that enum values are
+/// appended to the existing ones.
+fn merge_attributes(base: &mut Vec<MetaAttribute>, overrides: &[MetaAttribute]) {
+    for attr in overrides {
+        let Some(existing) = base.iter_mut().find(|existing| existing.name == attr.name) else {
+            base.push(attr.clone());
+            continue;
+        };
+        let mut merged = attr.clone();
+        if existing.value_type == MetaAttributeKind::Enum
+            && attr.value_type == MetaAttributeKind::Enum
+        {
+            merged.enum_values = existing.enum_values.clone();
+            append_missing(&mut merged.enum_values, &attr.enum_values);
+        }
+        *existing = merged;
+    }
+}
+
+fn merge_validation(base: &mut MetaValidationRules, overlay: &MetaValidationRules) {
+    if let Some(attributes) = &overlay.attributes {
+        let merged = base.attributes.get_or_insert_with(Default::default);
+        for (rule, overlay) in [
+            (&mut merged.string, &attributes.string),
+            (&mut merged.text, &attributes.text),
+        ] {
+            if let Some(overlay) = overlay {
+                let rule = rule.get_or_insert_with(Default::default);
+                override_with(&mut rule.max_length, &overlay.max_length);
+            }
+        }
+        if let Some(overlay) = &attributes.enum_rule {
+            let rule = merged.enum_rule.get_or_insert_with(Default::default);
+            override_with(&mut rule.case_sensitive, &overlay.case_sensitive);
+        }
+    }
+    if let Some(relationships) = &overlay.relationships {
+        let merged = base.relationships.get_or_insert_with(Default::default);
+        for (id, overlay) in relationships {
+            let rule = merged.entry(id.clone()).or_default();
+            override_with(&mut rule.allow_self, &overlay.allow_self);
+            override_with(&mut rule.allow_duplicate, &overlay.allow_duplicate);
+        }
+    }
+}
+
+fn override_with<T: Clone>(base: &mut Option<T>, overlay: &Option<T>) {
+    if overlay.is_some() {
+        base.clone_from(overlay);
+    }
+}
+
+fn append_missing(base: &mut Vec<String>, values: &[String]) {
+    for value in values {
+        if !base.contains(value) {
+            base.push(value.clone());
         }
     }
 }
@@ -90,7 +402,8 @@ fn merge_relationships(base: &mut Vec<MetaRelationship>, overrides: &[MetaRelati
 mod tests {
     use super::*;
     use crate::meta::config::MetaModelSource;
-    use aideon_mneme::meta::{MetaAttribute, MetaAttributeKind, MetaModelDocument};
+    use aideon_mneme::meta::MetaRuleSeverity;
+    use serde_json::json;
 
     fn doc(
         version: &str,
@@ -103,6 +416,7 @@ mod tests {
             types,
             relationships,
             validation: None,
+            remove: None,
         }
     }
 
@@ -130,11 +444,14 @@ mod tests {
             label: Some("Capability".into()),
             category: None,
             extends: None,
+            is_abstract: false,
             attributes: vec![MetaAttribute {
                 name: "name".into(),
                 value_type: MetaAttributeKind::String,
                 required: true,
                 enum_values: vec![],
+                default: None,
+                constraints: Default::default(),
             }],
             effect_types: vec![],
         };
@@ -143,11 +460,14 @@ mod tests {
             label: Some("Capability (override)".into()),
             category: None,
             extends: None,
+            is_abstract: false,
             attributes: vec![MetaAttribute {
                 name: "owner".into(),
                 value_type: MetaAttributeKind::String,
                 required: false,
                 enum_values: vec![],
+                default: None,
+                constraints: Default::default(),
             }],
             effect_types: vec![],
         };
@@ -172,7 +492,7 @@ mod tests {
 
         let base = doc("v1", vec![base_type], vec![base_rel]);
         let overlay = doc("v1", vec![overlay_type], vec![overlay_rel]);
-        let merged = merge_documents(base, &[overlay]).unwrap();
+        let merged = merge_documents(base, &[overlay]).unwrap().document;
         assert_eq!(merged.types.len(), 1);
         assert_eq!(
             merged.types[0].label.as_deref(),
@@ -181,4 +501,233 @@ mod tests {
         assert_eq!(merged.relationships.len(), 1);
         assert_eq!(merged.relationships[0].directed, Some(false));
     }
+
+    fn inline(value: serde_json::Value) -> MetaModelDocument {
+        load_document(&MetaModelSource::Inline(value.to_string())).expect("document")
+    }
+
+    fn core() -> MetaModelDocument {
+        load_document(&MetaModelSource::EmbeddedCore).expect("core")
+    }
+
+    #[test]
+    fn merge_documents_adds_attributes_and_appends_enum_values() {
+        let overlay = inline(json!({
+            "version": "1.0.0",
+            "types": [{
+                "id": "Application",
+                "attributes": [
+                    { "name": "owner", "type": "string" },
+                    { "name": "disposition", "type": "enum", "enum": ["Retain", "Invest"] }
+                ]
+            }],
+            "relationships": [{
+                "id": "hosts",
+                "to": ["DataEntity"],
+                "multiplicity": { "severity": "warning" }
+            }],
+            "validation": {
+                "attributes": { "string": { "maxLength": 128 } },
+                "relationships": { "hosts": { "allowSelf": true } }
+            }
+        }));
+        let merged = merge_documents(core(), &[overlay]).unwrap();
+        let doc = &merged.document;
+
+        let application = doc.types.iter().find(|ty| ty.id == "Application").unwrap();
+        assert_eq!(application.label.as_deref(), Some("Application"));
+        let names: Vec<&str> = application
+            .attributes
+            .iter()
+            .map(|attr| attr.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["name", "vendor", "disposition", "lifecycle", "owner"]
+        );
+        assert_eq!(
+            application.attributes[2].enum_values,
+            vec!["Invest", "Tolerate", "Migrate", "Eliminate", "Retain"]
+        );
+
+        let hosts = doc
+            .relationships
+            .iter()
+            .find(|rel| rel.id == "hosts")
+            .unwrap();
+        assert_eq!(hosts.from, vec!["TechnologyComponent"]);
+        assert_eq!(hosts.to, vec!["Application", "DataEntity"]);
+        let multiplicity = hosts.multiplicity.as_ref().unwrap();
+        assert_eq!(multiplicity.from.as_deref(), Some("many"));
+        assert_eq!(multiplicity.severity, Some(MetaRuleSeverity::Warning));
+
+        let validation = doc.validation.as_ref().unwrap();
+        let attributes = validation.attributes.as_ref().unwrap();
+        assert_eq!(attributes.string.as_ref().unwrap().max_length, Some(128));
+        assert_eq!(attributes.text.as_ref().unwrap().max_length, Some(4096));
+        let hosts_rule = &validation.relationships.as_ref().unwrap()["hosts"];
+        assert_eq!(hosts_rule.allow_self, Some(true));
+
+        let owner = AttributeOwner::Type("Application".into());
+        assert_eq!(merged.provenance[&(owner.clone(), "name".into())], 0);
+        assert_eq!(merged.provenance[&(owner.clone(), "owner".into())], 1);
+        assert_eq!(merged.provenance[&(owner, "disposition".into())], 1);
+    }
+
+    #[test]
+    fn merge_documents_applies_remove_directives_first() {
+        let overlay = inline(json!({
+            "version": "1.0.0",
+            "types": [{
+                "id": "Application",
+                "attributes": [{ "name": "vendor", "type": "text" }]
+            }],
+            "remove": {
+                "types": ["PlanEvent"],
+                "relationships": ["plan_effect"],
+                "attributes": [
+                    { "type": "Application", "name": "vendor" },
+                    { "type": "Application", "name": "lifecycle", "enum": ["Retire"] },
+                    { "relationship": "accesses", "name": "mode" }
+                ],
+                "endpoints": [{ "relationship": "accesses", "from": ["BusinessProcess"] }]
+            }
+        }));
+        let merged = merge_documents(core(), &[overlay]).unwrap();
+        let doc = &merged.document;
+
+        assert!(doc.types.iter().all(|ty| ty.id != "PlanEvent"));
+        assert!(doc.relationships.iter().all(|rel| rel.id != "plan_effect"));
+        assert!(doc.remove.is_none());
+        let application = doc.types.iter().find(|ty| ty.id == "Application").unwrap();
+        let vendor = application
+            .attributes
+            .iter()
+            .find(|attr| attr.name == "vendor")
+            .unwrap();
+        assert_eq!(vendor.value_type, MetaAttributeKind::Text);
+        let lifecycle = application
+            .attributes
+            .iter()
+            .find(|attr| attr.name == "lifecycle")
+            .unwrap();
+        assert_eq!(lifecycle.enum_values, vec!["Plan", "Build", "Run"]);
+        let accesses = doc
+            .relationships
+            .iter()
+            .find(|rel| rel.id == "accesses")
+            .unwrap();
+        assert!(accesses.attributes.is_empty());
+        assert_eq!(accesses.from, vec!["Application"]);
+
+        let vendor_key = (AttributeOwner::Type("Application".into()), "vendor".into());
+        assert_eq!(merged.provenance[&vendor_key], 1);
+        let mode_key = (
+            AttributeOwner::Relationship("accesses".into()),
+            "mode".into(),
+        );
+        assert!(!merged.provenance.contains_key(&mode_key));
+        assert!(
+            merged
+                .provenance
+                .keys()
+                .all(|(owner, _)| *owner != AttributeOwner::Type("PlanEvent".into()))
+        );
+    }
+
+    #[test]
+    fn merge_documents_rejects_invalid_remove_directives() {
+        let cases = [
+            (
+                json!({ "attributes": [{ "type": "Application", "name": "missing" }] }),
+                "removes unknown attribute 'missing'",
+            ),
+            (
+                json!({ "attributes": [{ "type": "Application", "name": "disposition", "enum": ["Keep"] }] }),
+                "removes unknown enum value 'Keep'",
+            ),
+            (
+                json!({ "attributes": [{ "name": "name" }] }),
+                "exactly one type or relationship",
+            ),
+            (
+                json!({ "types": ["Missing"] }),
+                "removes unknown type 'Missing'",
+            ),
+            (
+                json!({ "endpoints": [{ "relationship": "hosts", "from": ["TechnologyComponent"] }] }),
+                "needs at least one source and target type",
+            ),
+            (
+                json!({ "types": ["DataEntity"] }),
+                "removes type 'DataEntity', but relationship 'accesses' still references it",
+            ),
+        ];
+        for (remove, expected) in cases {
+            let overlay = inline(json!({ "version": "1.0.0", "remove": remove }));
+            let err = merge_documents(core(), &[overlay]).unwrap_err();
+            assert!(err.to_string().contains(expected), "{err}");
+        }
+
+        let subtype = inline(json!({
+            "version": "1.0.0",
+            "types": [{ "id": "SaaSApplication", "extends": "Application" }]
+        }));
+        let removal = inline(json!({
+            "version": "1.0.0",
+            "remove": {
+                "types": ["Application"],
+                "relationships": ["hosts"],
+                "endpoints": [
+                    { "relationship": "realises", "from": ["Application"] },
+                    { "relationship": "accesses", "from": ["Application"] },
+                    { "relationship": "plan_effect", "to": ["Application"] }
+                ]
+            }
+        }));
+        let err = merge_documents(core(), &[subtype, removal]).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("but type 'SaaSApplication' still references it"),
+            "{err}"
+        );
+
+        let mut base = core();
+        base.remove = Some(MetaRemovals::default());
+        let err = merge_documents(base, &[]).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("base document cannot contain remove")
+        );
+    }
+
+    #[test]
+    fn merge_documents_redefines_a_removed_type() {
+        let overlay = inline(json!({
+            "version": "1.0.0",
+            "types": [{
+                "id": "PlanEvent",
+                "attributes": [{ "name": "name", "type": "string", "required": true }]
+            }],
+            "remove": { "types": ["PlanEvent"] }
+        }));
+        let merged = merge_documents(core(), &[overlay]).unwrap();
+
+        let plan_event = merged
+            .document
+            .types
+            .iter()
+            .find(|ty| ty.id == "PlanEvent")
+            .unwrap();
+        assert_eq!(plan_event.attributes.len(), 1);
+        assert_eq!(
+            merged.removals,
+            vec![(
+                1,
+                SchemaChange::TypeRemoved {
+                    type_id: "PlanEvent".into()
+                }
+            )]
+        );
+    }
 }
@@ -1,10 +1,24 @@
 //! Meta-model definition, loading, and validation.
 
 mod config;
+mod diff;
+mod entries;
+mod evolution;
+mod json_schema;
 mod loader;
 mod model;
+mod multiplicity;
 mod registry;
 mod validation;
 
 pub use config::{MetaModelConfig, MetaModelSource};
+pub use diff::{AttributeProvenance, MetaModelDiff, OverlayRemoval, OverlayReport, check_overlays};
+pub use entries::META_MODEL_ENTRY_TYPE;
+pub(crate) use entries::{entry_nodes, split_entry_changes};
+pub use evolution::{
+    AttributeOwner, Compatibility, EnumRename, MigrationHints, MigrationPlan, SchemaChange,
+    compare, plan_migration,
+};
+pub use multiplicity::{MultiplicityViolation, RelationshipEnd};
 pub use registry::MetaModelRegistry;
+pub(crate) use validation::edge_label;
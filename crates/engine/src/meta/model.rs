@@ -4,11 +4,27 @@ use crate::error::{PraxisError, PraxisResult};
 use aideon_mneme::meta::{
     MetaAttribute, MetaEnumRule, MetaRelationship, MetaStringRule, MetaType, MetaValidationRules,
 };
+use regex::Regex;
 use std::collections::{BTreeMap, HashMap};
 
 #[derive(Clone, Debug)]
 pub(super) struct TypeDescriptor {
     pub(super) attributes: BTreeMap<String, MetaAttribute>,
+    /// Supertypes from the nearest parent up, including a declared parent the document omits.
+    pub(super) ancestors: Vec<String>,
+    pub(super) is_abstract: bool,
+}
+
+/// True when `type_id` is `ancestor` or inherits from it.
+pub(super) fn is_a(
+    types: &BTreeMap<String, TypeDescriptor>,
+    type_id: &str,
+    ancestor: &str,
+) -> bool {
+    type_id == ancestor
+        || types
+            .get(type_id)
+            .is_some_and(|desc| desc.ancestors.iter().any(|parent| parent == ancestor))
 }
 
 #[derive(Clone, Debug)]
@@ -23,6 +39,8 @@ pub(super) struct AttributeRuleSet {
     pub(super) string_max: Option<usize>,
     pub(super) text_max: Option<usize>,
     pub(super) enum_case_sensitive: bool,
+    /// Compiled attribute `pattern` constraints, keyed by their source text.
+    pub(super) patterns: HashMap<String, Regex>,
 }
 
 impl AttributeRuleSet {
@@ -42,8 +60,29 @@ impl AttributeRuleSet {
             string_max,
             text_max,
             enum_case_sensitive,
+            patterns: HashMap::new(),
         }
     }
+
+    /// Compile the `pattern` constraints of `attributes`, rejecting invalid expressions.
+    pub(super) fn compile_patterns<'a>(
+        &mut self,
+        attributes: impl Iterator<Item = &'a MetaAttribute>,
+    ) -> PraxisResult<()> {
+        for attr in attributes {
+            let Some(pattern) = &attr.constraints.pattern else {
+                continue;
+            };
+            if self.patterns.contains_key(pattern) {
+                continue;
+            }
+            let regex = Regex::new(pattern).map_err(|err| PraxisError::IntegrityViolation {
+                message: format!("attribute '{}' has invalid pattern: {err}", attr.name),
+            })?;
+            self.patterns.insert(pattern.clone(), regex);
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -88,17 +127,24 @@ fn resolve_type_descriptor(
         })?;
     stack.push(id.clone());
     let mut attributes: BTreeMap<String, MetaAttribute> = BTreeMap::new();
-    if let Some(parent_id) = &ty.extends
-        && types.contains_key(parent_id)
-    {
-        let parent_desc = resolve_type_descriptor(parent_id.clone(), types, cache, stack)?;
-        attributes.extend(parent_desc.attributes);
+    let mut ancestors = Vec::new();
+    if let Some(parent_id) = &ty.extends {
+        ancestors.push(parent_id.clone());
+        if types.contains_key(parent_id) {
+            let parent_desc = resolve_type_descriptor(parent_id.clone(), types, cache, stack)?;
+            attributes.extend(parent_desc.attributes);
+            ancestors.extend(parent_desc.ancestors);
+        }
     }
     stack.pop();
     for attribute in &ty.attributes {
         attributes.insert(attribute.name.clone(), attribute.clone());
     }
-    let descriptor = TypeDescriptor { attributes };
+    let descriptor = TypeDescriptor {
+        attributes,
+        ancestors,
+        is_abstract: ty.is_abstract,
+    };
     cache.insert(id.clone(), descriptor.clone());
     Ok(descriptor)
 }
@@ -158,11 +204,14 @@ mod tests {
             label: None,
             category: None,
             extends: None,
+            is_abstract: false,
             attributes: vec![MetaAttribute {
                 name: "name".into(),
                 value_type: MetaAttributeKind::String,
                 required: true,
                 enum_values: vec![],
+                default: None,
+                constraints: Default::default(),
             }],
             effect_types: vec![],
         };
@@ -171,11 +220,14 @@ mod tests {
             label: None,
             category: None,
             extends: Some("Parent".into()),
+            is_abstract: false,
             attributes: vec![MetaAttribute {
                 name: "owner".into(),
                 value_type: MetaAttributeKind::String,
                 required: false,
                 enum_values: vec![],
+                default: None,
+                constraints: Default::default(),
             }],
             effect_types: vec![],
         };
@@ -183,6 +235,9 @@ mod tests {
         let child_desc = descriptors.get("Child").expect("child desc");
         assert!(child_desc.attributes.contains_key("name"));
         assert!(child_desc.attributes.contains_key("owner"));
+        assert!(is_a(&descriptors, "Child", "Parent"));
+        assert!(is_a(&descriptors, "Child", "Child"));
+        assert!(!is_a(&descriptors, "Parent", "Child"));
     }
 
     #[test]
@@ -192,6 +247,7 @@ mod tests {
             label: None,
             category: None,
             extends: Some("B".into()),
+            is_abstract: false,
             attributes: vec![],
             effect_types: vec![],
         };
@@ -200,6 +256,7 @@ mod tests {
             label: None,
             category: None,
             extends: Some("A".into()),
+            is_abstract: false,
             attributes: vec![],
             effect_types: vec![],
         };
@@ -0,0 +1,314 @@
+//! Relationship multiplicity rules and their evaluation over a graph.
+
+use crate::error::{PraxisError, PraxisResult};
+use crate::report::{ValidationIssue, ValidationRule};
+use aideon_mneme::meta::{MetaRelationship, MetaRuleSeverity};
+use aideon_mneme::temporal::{EdgeVersion, NodeVersion};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Allowed number of relationships per node: `min..=max`, unbounded when `max` is `None`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct Cardinality {
+    min: usize,
+    max: Option<usize>,
+}
+
+impl Cardinality {
+    const ANY: Self = Self { min: 0, max: None };
+
+    fn parse(raw: &str) -> Option<Self> {
+        let text = raw.trim().to_ascii_lowercase();
+        match text.as_str() {
+            "one" => return Some(Self::exactly(1)),
+            "many" | "*" => return Some(Self::ANY),
+            _ => {}
+        }
+        let (min, max) = match text.split_once("..") {
+            Some((min, "*")) => (min.trim().parse().ok()?, None),
+            Some((min, max)) => (min.trim().parse().ok()?, Some(max.trim().parse().ok()?)),
+            None => {
+                let exact = text.parse().ok()?;
+                (exact, Some(exact))
+            }
+        };
+        match max {
+            Some(max) if max < min => None,
+            _ => Some(Self { min, max }),
+        }
+    }
+
+    fn exactly(count: usize) -> Self {
+        Self {
+            min: count,
+            max: Some(count),
+        }
+    }
+
+    fn allows(&self, count: usize) -> bool {
+        count >= self.min && self.max.is_none_or(|max| count <= max)
+    }
+}
+
+impl fmt::Display for Cardinality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "{max}"),
+            Some(max) => write!(f, "{}..{max}", self.min),
+            None => write!(f, "{}..*", self.min),
+        }
+    }
+}
+
+/// Multiplicity declared for one relationship, with the endpoint types it applies to.
+#[derive(Clone, Debug)]
+pub(super) struct MultiplicityRule {
+    relationship: String,
+    source_types: Vec<String>,
+    target_types: Vec<String>,
+    /// Sources per target node.
+    sources: Cardinality,
+    /// Targets per source node.
+    targets: Cardinality,
+    severity: MetaRuleSeverity,
+}
+
+/// Rules for relationships whose multiplicity constrains anything; `many`/`many` is dropped.
+pub(super) fn multiplicity_rules(
+    relationships: &[MetaRelationship],
+) -> PraxisResult<Vec<MultiplicityRule>> {
+    let mut rules = Vec::new();
+    for rel in relationships {
+        let Some(multiplicity) = &rel.multiplicity else {
+            continue;
+        };
+        let bound = |raw: &Option<String>| match raw {
+            None => Ok(Cardinality::ANY),
+            Some(raw) => Cardinality::parse(raw).ok_or_else(|| PraxisError::IntegrityViolation {
+                message: format!("relationship '{}' has invalid multiplicity '{raw}'", rel.id),
+            }),
+        };
+        let rule = MultiplicityRule {
+            relationship: rel.id.clone(),
+            source_types: rel.from.clone(),
+            target_types: rel.to.clone(),
+            sources: bound(&multiplicity.from)?,
+            targets: bound(&multiplicity.to)?,
+            severity: multiplicity.severity.unwrap_or_default(),
+        };
+        if rule.sources != Cardinality::ANY || rule.targets != Cardinality::ANY {
+            rules.push(rule);
+        }
+    }
+    Ok(rules)
+}
+
+/// Which side of a relationship a node's count was taken on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RelationshipEnd {
+    /// The node is the source; the count is its outgoing relationships.
+    Source,
+    /// The node is the target; the count is its incoming relationships.
+    Target,
+}
+
+/// A node with more or fewer relationships of one type than the meta-model allows.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiplicityViolation {
+    pub relationship: String,
+    pub node_id: String,
+    pub end: RelationshipEnd,
+    pub count: usize,
+    /// Allowed range, e.g. `1..*`.
+    pub expected: String,
+    pub severity: MetaRuleSeverity,
+}
+
+impl fmt::Display for MultiplicityViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = match self.end {
+            RelationshipEnd::Source => "outgoing",
+            RelationshipEnd::Target => "incoming",
+        };
+        write!(
+            f,
+            "node '{}' has {} {direction} '{}' relationship(s); expected {}",
+            self.node_id, self.count, self.relationship, self.expected
+        )
+    }
+}
+
+impl From<MultiplicityViolation> for ValidationIssue {
+    fn from(violation: MultiplicityViolation) -> Self {
+        ValidationIssue {
+            element_id: violation.node_id.clone(),
+            attribute: None,
+            rule: ValidationRule::Multiplicity,
+            severity: violation.severity,
+            message: violation.to_string(),
+        }
+    }
+}
+
+/// Check `nodes` against `rules`, counting relationships across all of `edges`. A rule applies
+/// to a node whose type `is_a` one of the rule's endpoint types.
+pub(super) fn check<'a>(
+    rules: &[MultiplicityRule],
+    nodes: impl Iterator<Item = &'a NodeVersion>,
+    edges: impl Iterator<Item = &'a EdgeVersion>,
+    is_a: impl Fn(&str, &str) -> bool,
+) -> Vec<MultiplicityViolation> {
+    if rules.is_empty() {
+        return Vec::new();
+    }
+    let mut counts: HashMap<(&str, &str, RelationshipEnd), usize> = HashMap::new();
+    for edge in edges {
+        let Some(rel) = edge.r#type.as_deref() else {
+            continue;
+        };
+        *counts
+            .entry((rel, edge.from.as_str(), RelationshipEnd::Source))
+            .or_default() += 1;
+        *counts
+            .entry((rel, edge.to.as_str(), RelationshipEnd::Target))
+            .or_default() += 1;
+    }
+
+    // Sorted by node id so reports are deterministic.
+    let nodes: BTreeMap<&str, &str> = nodes
+        .filter_map(|node| Some((node.id.as_str(), node.r#type.as_deref()?)))
+        .collect();
+    let mut violations = Vec::new();
+    for (node_id, node_type) in nodes {
+        for rule in rules {
+            let ends = [
+                (RelationshipEnd::Source, &rule.source_types, rule.targets),
+                (RelationshipEnd::Target, &rule.target_types, rule.sources),
+            ];
+            for (end, types, allowed) in ends {
+                if !types.iter().any(|ty| is_a(node_type, ty)) {
+                    continue;
+                }
+                let count = counts
+                    .get(&(rule.relationship.as_str(), node_id, end))
+                    .copied()
+                    .unwrap_or(0);
+                if !allowed.allows(count) {
+                    violations.push(MultiplicityViolation {
+                        relationship: rule.relationship.clone(),
+                        node_id: node_id.to_string(),
+                        end,
+                        count,
+                        expected: allowed.to_string(),
+                        severity: rule.severity,
+                    });
+                }
+            }
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aideon_mneme::meta::MetaMultiplicity;
+
+    fn node(id: &str, ty: &str) -> NodeVersion {
+        NodeVersion {
+            id: id.into(),
+            r#type: Some(ty.into()),
+            props: None,
+        }
+    }
+
+    fn edge(from: &str, to: &str) -> EdgeVersion {
+        EdgeVersion {
+            id: None,
+            from: from.into(),
+            to: to.into(),
+            r#type: Some("hosts".into()),
+            directed: Some(true),
+            props: None,
+        }
+    }
+
+    fn hosts(from: &str, to: &str, severity: Option<MetaRuleSeverity>) -> MetaRelationship {
+        MetaRelationship {
+            id: "hosts".into(),
+            label: None,
+            from: vec!["Tech".into()],
+            to: vec!["App".into()],
+            directed: Some(true),
+            multiplicity: Some(MetaMultiplicity {
+                from: Some(from.into()),
+                to: Some(to.into()),
+                severity,
+            }),
+            attributes: vec![],
+        }
+    }
+
+    #[test]
+    fn parses_cardinality_notations() {
+        assert_eq!(Cardinality::parse("one"), Some(Cardinality::exactly(1)));
+        assert_eq!(Cardinality::parse("Many"), Some(Cardinality::ANY));
+        assert_eq!(
+            Cardinality::parse("0..1"),
+            Some(Cardinality {
+                min: 0,
+                max: Some(1)
+            })
+        );
+        assert_eq!(
+            Cardinality::parse("1..*"),
+            Some(Cardinality { min: 1, max: None })
+        );
+        assert_eq!(Cardinality::parse("2"), Some(Cardinality::exactly(2)));
+        assert_eq!(Cardinality::parse("3..1"), None);
+        assert_eq!(Cardinality::parse("some"), None);
+        assert!(multiplicity_rules(&[hosts("1..x", "many", None)]).is_err());
+        assert!(
+            multiplicity_rules(&[hosts("many", "*", None)])
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn reports_minimum_and_maximum_violations() {
+        let rules =
+            multiplicity_rules(&[hosts("1..*", "0..1", Some(MetaRuleSeverity::Warning))]).unwrap();
+        let nodes = [
+            node("t1", "Tech"),
+            node("a1", "App"),
+            node("a2", "App"),
+            node("a3", "App"),
+        ];
+        let edges = [edge("t1", "a1"), edge("t1", "a2")];
+        let violations = check(&rules, nodes.iter(), edges.iter(), |ty, other| ty == other);
+        let found: Vec<_> = violations
+            .iter()
+            .map(|v| (v.node_id.as_str(), v.end, v.count, v.expected.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("a3", RelationshipEnd::Target, 0, "1..*"),
+                ("t1", RelationshipEnd::Source, 2, "0..1"),
+            ]
+        );
+        assert!(
+            violations
+                .iter()
+                .all(|v| v.severity == MetaRuleSeverity::Warning)
+        );
+        assert_eq!(
+            violations[0].to_string(),
+            "node 'a3' has 0 incoming 'hosts' relationship(s); expected 1..*"
+        );
+    }
+}
@@ -1,25 +1,51 @@
 //! Praxis meta-model schema registry.
 
 use crate::error::PraxisResult;
+use crate::graph::GraphSnapshot;
 use crate::meta::config::MetaModelConfig;
+use crate::meta::entries::{
+    META_MODEL_ENTRY_TYPE, document_from_entries, is_entry, touches_entries,
+};
+use crate::meta::json_schema::{
+    DRAFT_2020_12, dataset_schema, element_schema, relationship_key, relationship_schema, type_key,
+    type_schema,
+};
 use crate::meta::loader::{load_document, merge_documents};
 use crate::meta::model::{
     AttributeRuleSet, RelationshipDescriptor, RelationshipRule, TypeDescriptor,
-    build_relationship_descriptors, build_type_descriptors, relationship_rules,
+    build_relationship_descriptors, build_type_descriptors, is_a, relationship_rules,
 };
-use crate::meta::validation::{validate_edge, validate_node};
-use aideon_mneme::meta::MetaModelDocument;
-use aideon_mneme::temporal::{EdgeVersion, NodeVersion};
-use std::collections::{BTreeMap, HashMap};
+use crate::meta::multiplicity::{self, MultiplicityRule, MultiplicityViolation};
+use crate::meta::validation::{
+    check_attribute_definitions, check_edge, check_node, validate_edge, validate_node,
+    with_defaults,
+};
+use crate::report::ValidationIssue;
+use aideon_mneme::blob::BlobRef;
+use aideon_mneme::meta::{MetaAttribute, MetaAttributeKind, MetaModelDocument};
+use aideon_mneme::temporal::{ChangeSet, EdgeVersion, NodeVersion};
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
 
 /// Materialised schema registry used by the Praxis engine for validation.
 pub struct MetaModelRegistry {
     document: Arc<MetaModelDocument>,
+    /// Document from configuration; meta-model entries in the graph are layered onto it.
+    configured: Arc<MetaModelDocument>,
     types: BTreeMap<String, TypeDescriptor>,
     relationships: BTreeMap<String, RelationshipDescriptor>,
     attr_rules: AttributeRuleSet,
     relationship_rules: HashMap<String, RelationshipRule>,
+    multiplicity: Vec<MultiplicityRule>,
+}
+
+impl std::fmt::Debug for MetaModelRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetaModelRegistry")
+            .field("version", &self.document.version)
+            .finish_non_exhaustive()
+    }
 }
 
 impl MetaModelRegistry {
@@ -34,7 +60,7 @@ impl MetaModelRegistry {
                     message: "meta-model config missing base document".into(),
                 })?;
         let merged = merge_documents(base.clone(), overlays)?;
-        Self::from_document(merged)
+        Self::from_document(merged.document)
     }
 
     pub fn embedded() -> PraxisResult<Self> {
@@ -42,16 +68,26 @@ impl MetaModelRegistry {
     }
 
     pub fn from_document(doc: MetaModelDocument) -> PraxisResult<Self> {
-        let attr_rules = AttributeRuleSet::from_validation(doc.validation.as_ref());
+        let declared = || {
+            let type_attrs = doc.types.iter().flat_map(|ty| &ty.attributes);
+            type_attrs.chain(doc.relationships.iter().flat_map(|rel| &rel.attributes))
+        };
+        let mut attr_rules = AttributeRuleSet::from_validation(doc.validation.as_ref());
+        attr_rules.compile_patterns(declared())?;
+        check_attribute_definitions(declared(), &attr_rules)?;
         let relationship_rules = relationship_rules(doc.validation.as_ref());
         let type_descriptors = build_type_descriptors(&doc.types)?;
         let relationship_descriptors = build_relationship_descriptors(&doc.relationships);
+        let multiplicity = multiplicity::multiplicity_rules(&doc.relationships)?;
+        let document = Arc::new(doc);
         Ok(Self {
-            document: Arc::new(doc),
+            configured: Arc::clone(&document),
+            document,
             types: type_descriptors,
             relationships: relationship_descriptors,
             attr_rules,
             relationship_rules,
+            multiplicity,
         })
     }
 
@@ -59,6 +95,38 @@ impl MetaModelRegistry {
         (*self.document).clone()
     }
 
+    /// Registry for the schema recorded by the `MetaModelEntry` nodes among `nodes`, layered on
+    /// this registry's configured document. Without entries, that is the configured schema.
+    pub fn with_entries<'a>(
+        &self,
+        nodes: impl IntoIterator<Item = &'a NodeVersion>,
+    ) -> PraxisResult<Self> {
+        let entries: Vec<&NodeVersion> = nodes.into_iter().filter(|node| is_entry(node)).collect();
+        let doc = if entries.is_empty() {
+            (*self.configured).clone()
+        } else {
+            document_from_entries(&self.configured, entries)?
+        };
+        let mut registry = Self::from_document(doc)?;
+        registry.configured = Arc::clone(&self.configured);
+        Ok(registry)
+    }
+
+    /// Schema in effect once `change` has turned `before` into `after`, given that `self` was in
+    /// effect at `before`. Change sets that leave the meta-model entries alone keep `self`.
+    pub fn after_change(
+        self: &Arc<Self>,
+        change: &ChangeSet,
+        before: &GraphSnapshot,
+        after: &GraphSnapshot,
+    ) -> PraxisResult<Arc<Self>> {
+        if !touches_entries(change, before) {
+            return Ok(Arc::clone(self));
+        }
+        let entries = after.nodes_of_type(META_MODEL_ENTRY_TYPE);
+        Ok(Arc::new(self.with_entries(entries)?))
+    }
+
     pub fn validate_node(&self, node: &NodeVersion) -> PraxisResult<()> {
         validate_node(node, &self.types, &self.attr_rules)
     }
@@ -73,16 +141,261 @@ impl MetaModelRegistry {
             edge,
             from_type,
             to_type,
+            &self.types,
             &self.relationships,
             &self.relationship_rules,
             &self.attr_rules,
         )
     }
 
+    /// Every rule `node` violates, rather than only the first.
+    pub(crate) fn check_node(&self, node: &NodeVersion) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        check_node(node, &self.types, &self.attr_rules, &mut issues);
+        issues
+    }
+
+    /// Every rule `edge` violates between endpoints of the given types.
+    pub(crate) fn check_edge(
+        &self,
+        edge: &EdgeVersion,
+        from_type: &str,
+        to_type: &str,
+    ) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        check_edge(
+            edge,
+            (from_type, to_type),
+            &self.types,
+            &self.relationships,
+            &self.relationship_rules,
+            &self.attr_rules,
+            &mut issues,
+        );
+        issues
+    }
+
+    /// `node` with the declared defaults of its type's absent attributes filled in.
+    pub fn node_with_defaults(&self, node: &NodeVersion) -> NodeVersion {
+        let descriptor = node.r#type.as_ref().and_then(|ty| self.types.get(ty));
+        NodeVersion {
+            props: match descriptor {
+                Some(desc) => with_defaults(&desc.attributes, &node.props),
+                None => node.props.clone(),
+            },
+            ..node.clone()
+        }
+    }
+
+    /// `edge` with the declared defaults of its relationship's absent attributes filled in.
+    pub fn edge_with_defaults(&self, edge: &EdgeVersion) -> EdgeVersion {
+        let descriptor = edge
+            .r#type
+            .as_ref()
+            .and_then(|ty| self.relationships.get(ty));
+        EdgeVersion {
+            props: match descriptor {
+                Some(desc) => with_defaults(&desc.attributes, &edge.props),
+                None => edge.props.clone(),
+            },
+            ..edge.clone()
+        }
+    }
+
+    /// Attributes declared `unique` for nodes of `type_id`, each with the type whose nodes its
+    /// values must be distinct across: the topmost supertype that declares it unique, so an
+    /// inherited attribute stays unique among all subtypes.
+    pub(crate) fn unique_attributes(&self, type_id: &str) -> Vec<(&str, &str)> {
+        let Some(desc) = self.types.get(type_id) else {
+            return Vec::new();
+        };
+        desc.attributes
+            .values()
+            .filter(|attr| attr.constraints.unique)
+            .filter_map(|attr| {
+                let scope = self.unique_scope(type_id, &attr.name)?;
+                Some((attr.name.as_str(), scope))
+            })
+            .collect()
+    }
+
+    /// The type a `unique` attribute of `type_id` is unique across, if it is unique at all.
+    pub(crate) fn unique_scope(&self, type_id: &str, attr: &str) -> Option<&str> {
+        let (type_id, desc) = self.types.get_key_value(type_id)?;
+        std::iter::once(type_id.as_str())
+            .chain(desc.ancestors.iter().map(String::as_str))
+            .take_while(|ty| {
+                self.types
+                    .get(*ty)
+                    .and_then(|desc| desc.attributes.get(attr))
+                    .is_some_and(|attr| attr.constraints.unique)
+            })
+            .last()
+    }
+
+    /// Blobs referenced by the `Blob` attributes `changes` sets. Values of other attributes are
+    /// plain data, even when shaped like a blob reference.
+    pub fn blob_refs(&self, changes: &ChangeSet) -> BTreeSet<BlobRef> {
+        let nodes = changes
+            .node_creates
+            .iter()
+            .chain(&changes.node_updates)
+            .filter_map(|node| {
+                let desc = self.types.get(node.r#type.as_deref()?)?;
+                Some((&desc.attributes, node.props.as_ref()?))
+            });
+        let edges = changes
+            .edge_creates
+            .iter()
+            .chain(&changes.edge_updates)
+            .filter_map(|edge| {
+                let desc = self.relationships.get(edge.r#type.as_deref()?)?;
+                Some((&desc.attributes, edge.props.as_ref()?))
+            });
+        nodes
+            .chain(edges)
+            .flat_map(|(attributes, props)| {
+                attributes
+                    .values()
+                    .filter(|attr| matches!(attr.value_type, MetaAttributeKind::Blob))
+                    .filter_map(|attr| props.get(&attr.name)?.as_str().and_then(BlobRef::parse))
+            })
+            .collect()
+    }
+
     pub fn allows_duplicate(&self, rel_type: &str) -> bool {
         self.relationship_rules
             .get(rel_type)
             .and_then(|rule| rule.allow_duplicate)
             .unwrap_or(true)
     }
+
+    /// Multiplicity violations of `nodes`, counting their relationships across all of `edges`.
+    pub fn multiplicity_violations<'a>(
+        &self,
+        nodes: impl Iterator<Item = &'a NodeVersion>,
+        edges: impl Iterator<Item = &'a EdgeVersion>,
+    ) -> Vec<MultiplicityViolation> {
+        multiplicity::check(&self.multiplicity, nodes, edges, |ty, ancestor| {
+            self.is_a(ty, ancestor)
+        })
+    }
+
+    /// True when `type_id` is `ancestor` or inherits from it through `extends`.
+    pub fn is_a(&self, type_id: &str, ancestor: &str) -> bool {
+        is_a(&self.types, type_id, ancestor)
+    }
+
+    /// Every type inheriting from `type_id`, directly or transitively, sorted by id.
+    pub fn subtypes_of(&self, type_id: &str) -> Vec<String> {
+        self.types
+            .iter()
+            .filter(|(id, desc)| {
+                id.as_str() != type_id && desc.ancestors.iter().any(|parent| parent == type_id)
+            })
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// JSON Schema (draft 2020-12) for the props of nodes of `type_id`, inherited attributes
+    /// included; `None` for an unknown type.
+    pub fn type_json_schema(&self, type_id: &str) -> Option<Value> {
+        let descriptor = self.types.get(type_id)?;
+        let mut schema = type_schema(&self.type_title(type_id), descriptor, &self.attr_rules);
+        schema.insert("$schema".into(), json!(DRAFT_2020_12));
+        Some(Value::Object(schema))
+    }
+
+    /// JSON Schema (draft 2020-12) for the props of edges of `rel_id`; `None` for an unknown
+    /// relationship.
+    pub fn relationship_json_schema(&self, rel_id: &str) -> Option<Value> {
+        let descriptor = self.relationships.get(rel_id)?;
+        let mut schema = relationship_schema(
+            &self.relationship_title(rel_id),
+            descriptor,
+            &self.attr_rules,
+        );
+        schema.insert("$schema".into(), json!(DRAFT_2020_12));
+        Some(Value::Object(schema))
+    }
+
+    /// The whole meta-model as one JSON Schema document. `$defs` holds a props schema per node
+    /// type (`type.<id>`) and relationship (`relationship.<id>`), plus `node` and `edge`
+    /// schemas that pick one by `type`; the root validates dataset import files.
+    pub fn json_schema(&self) -> Value {
+        let mut defs = Map::new();
+        for (id, descriptor) in &self.types {
+            let schema = type_schema(&self.type_title(id), descriptor, &self.attr_rules);
+            defs.insert(type_key(id), Value::Object(schema));
+        }
+        for (id, descriptor) in &self.relationships {
+            let schema =
+                relationship_schema(&self.relationship_title(id), descriptor, &self.attr_rules);
+            defs.insert(relationship_key(id), Value::Object(schema));
+        }
+        let has_required = |attributes: &BTreeMap<String, MetaAttribute>| {
+            attributes.values().any(|attr| attr.required)
+        };
+        let node_kinds: Vec<(String, String, bool)> = self
+            .types
+            .iter()
+            .filter(|(_, descriptor)| !descriptor.is_abstract)
+            .map(|(id, descriptor)| {
+                (
+                    id.clone(),
+                    type_key(id),
+                    has_required(&descriptor.attributes),
+                )
+            })
+            .collect();
+        let edge_kinds: Vec<(String, String, bool)> = self
+            .relationships
+            .iter()
+            .map(|(id, descriptor)| {
+                (
+                    id.clone(),
+                    relationship_key(id),
+                    has_required(&descriptor.attributes),
+                )
+            })
+            .collect();
+        let id = json!({ "type": "string", "minLength": 1 });
+        let node_fields = Map::from_iter([("id".to_string(), id.clone())]);
+        let edge_fields = Map::from_iter([
+            ("id".to_string(), id.clone()),
+            ("from".to_string(), id.clone()),
+            ("to".to_string(), id),
+            ("directed".to_string(), json!({ "type": "boolean" })),
+        ]);
+        defs.insert(
+            "node".into(),
+            element_schema(node_fields, &["id", "type"], &node_kinds),
+        );
+        defs.insert(
+            "edge".into(),
+            element_schema(edge_fields, &["from", "to", "type"], &edge_kinds),
+        );
+        dataset_schema(&self.document.version, defs)
+    }
+
+    fn type_title(&self, type_id: &str) -> String {
+        let ty = self.document.types.iter().find(|ty| ty.id == type_id);
+        ty.and_then(|ty| ty.label.clone())
+            .unwrap_or_else(|| type_id.to_string())
+    }
+
+    fn relationship_title(&self, rel_id: &str) -> String {
+        let rel = self
+            .document
+            .relationships
+            .iter()
+            .find(|rel| rel.id == rel_id);
+        rel.and_then(|rel| rel.label.clone())
+            .unwrap_or_else(|| rel_id.to_string())
+    }
+
+    /// True for types declared `abstract`, which nodes cannot use directly.
+    pub fn is_abstract(&self, type_id: &str) -> bool {
+        self.types.get(type_id).is_some_and(|desc| desc.is_abstract)
+    }
 }
@@ -1,10 +1,15 @@
 //! Praxis meta-model validation logic.
+//!
+//! Checks record every violation as a [`ValidationIssue`]; the `validate_*` wrappers fail on the
+//! first one.
 
 use crate::error::{PraxisError, PraxisResult};
-use crate::meta::model::{AttributeRuleSet, RelationshipRule, TypeDescriptor};
+use crate::meta::model::{AttributeRuleSet, RelationshipRule, TypeDescriptor, is_a};
+use crate::report::{ValidationIssue, ValidationRule};
+use aideon_mneme::blob::{BLOB_REF_PREFIX, BlobRef};
 use aideon_mneme::meta::{MetaAttribute, MetaAttributeKind};
 use aideon_mneme::temporal::{EdgeVersion, NodeVersion};
-use serde_json::Value;
+use serde_json::{Map, Value};
 use std::collections::{BTreeMap, HashMap};
 use time::OffsetDateTime;
 use time::format_description::well_known::Rfc3339;
@@ -16,155 +21,246 @@ pub(super) fn validate_node(
     types: &BTreeMap<String, TypeDescriptor>,
     attr_rules: &AttributeRuleSet,
 ) -> PraxisResult<()> {
-    let type_id = node
-        .r#type
-        .as_ref()
-        .ok_or_else(|| PraxisError::ValidationFailed {
-            message: format!("node '{}' missing type", node.id),
-        })?;
-    let descriptor = types
-        .get(type_id)
-        .ok_or_else(|| PraxisError::ValidationFailed {
-            message: format!("node '{}' references unknown type '{type_id}'", node.id),
-        })?;
-    if let Some(props) = node.props.as_ref() {
-        let map = props
-            .as_object()
-            .ok_or_else(|| PraxisError::ValidationFailed {
-                message: format!("node '{}' props must be an object", node.id),
-            })?;
-        validate_attributes(&descriptor.attributes, map, attr_rules, |attr, reason| {
-            format!("node '{}' attribute '{}': {}", node.id, attr.name, reason)
-        })?;
-    } else if descriptor.attributes.values().any(|attr| attr.required) {
-        return Err(PraxisError::ValidationFailed {
-            message: format!(
+    let mut issues = Vec::new();
+    check_node(node, types, attr_rules, &mut issues);
+    first_error(issues)
+}
+
+pub(super) fn check_node(
+    node: &NodeVersion,
+    types: &BTreeMap<String, TypeDescriptor>,
+    attr_rules: &AttributeRuleSet,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let issue = |rule, message| ValidationIssue::error(&node.id, rule, message);
+    let Some(type_id) = node.r#type.as_ref() else {
+        issues.push(issue(
+            ValidationRule::MissingType,
+            format!("node '{}' missing type", node.id),
+        ));
+        return;
+    };
+    let Some(descriptor) = types.get(type_id) else {
+        issues.push(issue(
+            ValidationRule::UnknownType,
+            format!("node '{}' references unknown type '{type_id}'", node.id),
+        ));
+        return;
+    };
+    if descriptor.is_abstract {
+        issues.push(issue(
+            ValidationRule::AbstractType,
+            format!(
+                "node '{}' cannot instantiate abstract type '{type_id}'",
+                node.id
+            ),
+        ));
+    }
+    match node.props.as_ref() {
+        Some(props) => match props.as_object() {
+            Some(map) => check_attributes(
+                &node.id,
+                &descriptor.attributes,
+                map,
+                attr_rules,
+                |attr, reason| format!("node '{}' attribute '{}': {}", node.id, attr.name, reason),
+                issues,
+            ),
+            None => issues.push(issue(
+                ValidationRule::PropsShape,
+                format!("node '{}' props must be an object", node.id),
+            )),
+        },
+        None if descriptor.attributes.values().any(|attr| attr.required) => issues.push(issue(
+            ValidationRule::Required,
+            format!(
                 "node '{}' missing required attributes for type '{}'",
                 node.id, type_id
             ),
-        });
+        )),
+        None => {}
     }
-
-    Ok(())
 }
 
 pub(super) fn validate_edge(
     edge: &EdgeVersion,
     from_type: &str,
     to_type: &str,
+    types: &BTreeMap<String, TypeDescriptor>,
     relationships: &BTreeMap<String, RelationshipDescriptor>,
     relationship_rules: &HashMap<String, RelationshipRule>,
     attr_rules: &AttributeRuleSet,
 ) -> PraxisResult<()> {
-    let rel_type = edge
-        .r#type
-        .as_ref()
-        .ok_or_else(|| PraxisError::ValidationFailed {
-            message: format!(
+    let mut issues = Vec::new();
+    check_edge(
+        edge,
+        (from_type, to_type),
+        types,
+        relationships,
+        relationship_rules,
+        attr_rules,
+        &mut issues,
+    );
+    first_error(issues)
+}
+
+pub(super) fn check_edge(
+    edge: &EdgeVersion,
+    (from_type, to_type): (&str, &str),
+    types: &BTreeMap<String, TypeDescriptor>,
+    relationships: &BTreeMap<String, RelationshipDescriptor>,
+    relationship_rules: &HashMap<String, RelationshipRule>,
+    attr_rules: &AttributeRuleSet,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let element_id = edge_label(edge);
+    let issue = |rule, message| ValidationIssue::error(&element_id, rule, message);
+    let Some(rel_type) = edge.r#type.as_ref() else {
+        issues.push(issue(
+            ValidationRule::MissingType,
+            format!(
                 "edge '{}->{}' missing relationship type",
                 edge.from, edge.to
             ),
-        })?;
-    let descriptor = relationships
-        .get(rel_type)
-        .ok_or_else(|| PraxisError::ValidationFailed {
-            message: format!("edge uses unknown relationship '{rel_type}'"),
-        })?;
+        ));
+        return;
+    };
+    let Some(descriptor) = relationships.get(rel_type) else {
+        issues.push(issue(
+            ValidationRule::UnknownType,
+            format!("edge uses unknown relationship '{rel_type}'"),
+        ));
+        return;
+    };
 
-    if !descriptor.from.iter().any(|ty| ty == from_type) {
-        return Err(PraxisError::ValidationFailed {
-            message: format!("edge type '{rel_type}' cannot originate from '{from_type}'"),
-        });
+    if !descriptor.from.iter().any(|ty| is_a(types, from_type, ty)) {
+        issues.push(issue(
+            ValidationRule::Endpoint,
+            format!("edge type '{rel_type}' cannot originate from '{from_type}'"),
+        ));
     }
-    if !descriptor.to.iter().any(|ty| ty == to_type) {
-        return Err(PraxisError::ValidationFailed {
-            message: format!("edge type '{rel_type}' cannot target '{to_type}'"),
-        });
+    if !descriptor.to.iter().any(|ty| is_a(types, to_type, ty)) {
+        issues.push(issue(
+            ValidationRule::Endpoint,
+            format!("edge type '{rel_type}' cannot target '{to_type}'"),
+        ));
     }
 
     if let Some(rule) = relationship_rules.get(rel_type)
         && rule.allow_self == Some(false)
         && edge.from == edge.to
     {
-        return Err(PraxisError::ValidationFailed {
-            message: format!("relationship '{rel_type}' forbids self-links"),
-        });
-    }
-
-    if let Some(props) = edge.props.as_ref() {
-        let map = props
-            .as_object()
-            .ok_or_else(|| PraxisError::ValidationFailed {
-                message: format!("edge '{}->{}' props must be an object", edge.from, edge.to),
-            })?;
-        validate_attributes(&descriptor.attributes, map, attr_rules, |attr, reason| {
-            format!(
-                "edge '{}->{}' attribute '{}': {}",
-                edge.from, edge.to, attr.name, reason
-            )
-        })?;
-    } else if descriptor.attributes.values().any(|attr| attr.required) {
-        return Err(PraxisError::ValidationFailed {
-            message: format!("edge type '{rel_type}' missing required attributes"),
-        });
+        issues.push(issue(
+            ValidationRule::SelfLink,
+            format!("relationship '{rel_type}' forbids self-links"),
+        ));
     }
 
-    Ok(())
+    match edge.props.as_ref() {
+        Some(props) => match props.as_object() {
+            Some(map) => check_attributes(
+                &element_id,
+                &descriptor.attributes,
+                map,
+                attr_rules,
+                |attr, reason| {
+                    format!(
+                        "edge '{}->{}' attribute '{}': {}",
+                        edge.from, edge.to, attr.name, reason
+                    )
+                },
+                issues,
+            ),
+            None => issues.push(issue(
+                ValidationRule::PropsShape,
+                format!("edge '{}->{}' props must be an object", edge.from, edge.to),
+            )),
+        },
+        None if descriptor.attributes.values().any(|attr| attr.required) => issues.push(issue(
+            ValidationRule::Required,
+            format!("edge type '{rel_type}' missing required attributes"),
+        )),
+        None => {}
+    }
 }
 
-fn validate_attributes(
+/// How issues name an edge: its id, or `from->to` when it has none.
+pub(crate) fn edge_label(edge: &EdgeVersion) -> String {
+    edge.id
+        .clone()
+        .unwrap_or_else(|| format!("{}->{}", edge.from, edge.to))
+}
+
+fn first_error(issues: Vec<ValidationIssue>) -> PraxisResult<()> {
+    match issues.into_iter().next() {
+        Some(issue) => Err(issue.into_error()),
+        None => Ok(()),
+    }
+}
+
+fn check_attributes(
+    element_id: &str,
     expected: &BTreeMap<String, MetaAttribute>,
     provided: &serde_json::Map<String, Value>,
     rules: &AttributeRuleSet,
     context: impl Fn(&MetaAttribute, &str) -> String,
-) -> PraxisResult<()> {
+    issues: &mut Vec<ValidationIssue>,
+) {
     for attr in expected.values() {
-        if let Some(value) = provided.get(&attr.name) {
-            if value.is_null() {
-                return Err(PraxisError::ValidationFailed {
-                    message: context(attr, "cannot be null"),
-                });
-            }
-            attribute_value_ok(attr, value, rules, |reason| context(attr, &reason))?;
-        } else if attr.required {
-            return Err(PraxisError::ValidationFailed {
-                message: context(attr, "is required"),
-            });
+        let fault = match provided.get(&attr.name) {
+            Some(value) if value.is_null() => Some(AttributeFault::new(
+                ValidationRule::NotNull,
+                "cannot be null".into(),
+            )),
+            Some(value) => attribute_value_ok(attr, value, rules).err(),
+            None if attr.required => Some(AttributeFault::new(
+                ValidationRule::Required,
+                "is required".into(),
+            )),
+            None => None,
+        };
+        if let Some(fault) = fault {
+            issues.push(
+                ValidationIssue::error(element_id, fault.rule, context(attr, &fault.reason))
+                    .on_attribute(&attr.name),
+            );
         }
     }
-    Ok(())
 }
 
+/// Why a single attribute value was rejected.
+struct AttributeFault {
+    rule: ValidationRule,
+    reason: String,
+}
+
+impl AttributeFault {
+    fn new(rule: ValidationRule, reason: String) -> Self {
+        Self { rule, reason }
+    }
+}
+
+type AttributeResult = Result<(), AttributeFault>;
+
 fn attribute_value_ok(
     attr: &MetaAttribute,
     value: &Value,
     rules: &AttributeRuleSet,
-    format_error: impl Fn(String) -> String,
-) -> PraxisResult<()> {
+) -> AttributeResult {
     match attr.value_type {
-        MetaAttributeKind::String | MetaAttributeKind::Text => {
-            validate_text(attr, value, rules, format_error)?
-        }
-        MetaAttributeKind::Number => validate_number(value, &format_error)?,
-        MetaAttributeKind::Boolean => validate_boolean(value, &format_error)?,
-        MetaAttributeKind::Enum => validate_enum(attr, value, rules, format_error)?,
-        MetaAttributeKind::Datetime => validate_datetime(value, format_error)?,
-        MetaAttributeKind::Blob => validate_blob(value, format_error)?,
+        MetaAttributeKind::String | MetaAttributeKind::Text => validate_text(attr, value, rules),
+        MetaAttributeKind::Number => validate_number(attr, value),
+        MetaAttributeKind::Boolean => validate_boolean(value),
+        MetaAttributeKind::Enum => validate_enum(attr, value, rules),
+        MetaAttributeKind::Datetime => validate_datetime(attr, value),
+        MetaAttributeKind::Blob => validate_blob(value),
     }
-    Ok(())
 }
 
-fn validate_text(
-    attr: &MetaAttribute,
-    value: &Value,
-    rules: &AttributeRuleSet,
-    format_error: impl Fn(String) -> String,
-) -> PraxisResult<()> {
+fn validate_text(attr: &MetaAttribute, value: &Value, rules: &AttributeRuleSet) -> AttributeResult {
     let text = value
         .as_str()
-        .ok_or_else(|| PraxisError::ValidationFailed {
-            message: format_error("expected string".into()),
-        })?;
+        .ok_or_else(|| AttributeFault::new(ValidationRule::ValueType, "expected string".into()))?;
     let max = if attr.value_type == MetaAttributeKind::Text {
         rules.text_max
     } else {
@@ -173,48 +269,68 @@ fn validate_text(
     if let Some(limit) = max
         && text.chars().count() > limit
     {
-        return Err(PraxisError::ValidationFailed {
-            message: format_error(format!(
+        return Err(AttributeFault::new(
+            ValidationRule::MaxLength,
+            format!(
                 "exceeds max length {} ({} chars)",
                 limit,
                 text.chars().count()
-            )),
-        });
+            ),
+        ));
+    }
+    if let Some(pattern) = &attr.constraints.pattern
+        && let Some(regex) = rules.patterns.get(pattern)
+        && !regex.is_match(text)
+    {
+        return Err(AttributeFault::new(
+            ValidationRule::Pattern,
+            format!("does not match pattern '{pattern}'"),
+        ));
     }
     Ok(())
 }
 
-fn validate_number(value: &Value, format_error: &impl Fn(String) -> String) -> PraxisResult<()> {
-    if value.is_number() {
-        Ok(())
-    } else {
-        Err(PraxisError::ValidationFailed {
-            message: format_error("expected number".into()),
-        })
+fn validate_number(attr: &MetaAttribute, value: &Value) -> AttributeResult {
+    let number = value
+        .as_f64()
+        .ok_or_else(|| AttributeFault::new(ValidationRule::ValueType, "expected number".into()))?;
+    if let Some(min) = attr.constraints.min
+        && number < min
+    {
+        return Err(AttributeFault::new(
+            ValidationRule::Range,
+            format!("value {number} is below minimum {min}"),
+        ));
     }
+    if let Some(max) = attr.constraints.max
+        && number > max
+    {
+        return Err(AttributeFault::new(
+            ValidationRule::Range,
+            format!("value {number} is above maximum {max}"),
+        ));
+    }
+    Ok(())
 }
 
-fn validate_boolean(value: &Value, format_error: &impl Fn(String) -> String) -> PraxisResult<()> {
+fn validate_boolean(value: &Value) -> AttributeResult {
     if value.is_boolean() {
         Ok(())
     } else {
-        Err(PraxisError::ValidationFailed {
-            message: format_error("expected boolean".into()),
-        })
+        Err(AttributeFault::new(
+            ValidationRule::ValueType,
+            "expected boolean".into(),
+        ))
     }
 }
 
-fn validate_enum(
-    attr: &MetaAttribute,
-    value: &Value,
-    rules: &AttributeRuleSet,
-    format_error: impl Fn(String) -> String,
-) -> PraxisResult<()> {
-    let text = value
-        .as_str()
-        .ok_or_else(|| PraxisError::ValidationFailed {
-            message: format_error("expected string value for enum".into()),
-        })?;
+fn validate_enum(attr: &MetaAttribute, value: &Value, rules: &AttributeRuleSet) -> AttributeResult {
+    let text = value.as_str().ok_or_else(|| {
+        AttributeFault::new(
+            ValidationRule::ValueType,
+            "expected string value for enum".into(),
+        )
+    })?;
     let case_sensitive = rules.enum_case_sensitive;
     let matches = attr.enum_values.iter().any(|variant| {
         if case_sensitive {
@@ -226,44 +342,129 @@ fn validate_enum(
     if matches {
         Ok(())
     } else {
-        Err(PraxisError::ValidationFailed {
-            message: format_error(format!(
-                "value '{}' not in [{}]",
-                text,
-                attr.enum_values.join(", ")
-            )),
-        })
+        Err(AttributeFault::new(
+            ValidationRule::Enum,
+            format!("value '{}' not in [{}]", text, attr.enum_values.join(", ")),
+        ))
     }
 }
 
-fn validate_datetime(value: &Value, format_error: impl Fn(String) -> String) -> PraxisResult<()> {
-    let text = value
-        .as_str()
-        .ok_or_else(|| PraxisError::ValidationFailed {
-            message: format_error("expected ISO-8601 string".into()),
-        })?;
-    if OffsetDateTime::parse(text, &Rfc3339).is_err() {
-        return Err(PraxisError::ValidationFailed {
-            message: format_error("invalid RFC3339 timestamp".into()),
-        });
+fn validate_datetime(attr: &MetaAttribute, value: &Value) -> AttributeResult {
+    let text = value.as_str().ok_or_else(|| {
+        AttributeFault::new(ValidationRule::ValueType, "expected ISO-8601 string".into())
+    })?;
+    let Ok(at) = OffsetDateTime::parse(text, &Rfc3339) else {
+        return Err(AttributeFault::new(
+            ValidationRule::ValueType,
+            "invalid RFC3339 timestamp".into(),
+        ));
+    };
+    if let Some(earliest) = attr.constraints.earliest.as_deref()
+        && parse_bound(earliest).is_some_and(|bound| at < bound)
+    {
+        return Err(AttributeFault::new(
+            ValidationRule::Range,
+            format!("'{text}' is before earliest '{earliest}'"),
+        ));
+    }
+    if let Some(latest) = attr.constraints.latest.as_deref()
+        && parse_bound(latest).is_some_and(|bound| at > bound)
+    {
+        return Err(AttributeFault::new(
+            ValidationRule::Range,
+            format!("'{text}' is after latest '{latest}'"),
+        ));
     }
     Ok(())
 }
 
-fn validate_blob(value: &Value, format_error: impl Fn(String) -> String) -> PraxisResult<()> {
-    if value.is_string() || value.is_object() || value.is_array() {
+pub(super) fn parse_bound(text: &str) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(text, &Rfc3339).ok()
+}
+
+/// Reject attribute declarations whose constraints contradict themselves or whose default would
+/// fail validation. `rules` must already hold the compiled patterns.
+pub(super) fn check_attribute_definitions<'a>(
+    attributes: impl Iterator<Item = &'a MetaAttribute>,
+    rules: &AttributeRuleSet,
+) -> PraxisResult<()> {
+    for attr in attributes {
+        let invalid = |reason: String| PraxisError::IntegrityViolation {
+            message: format!("attribute '{}' {reason}", attr.name),
+        };
+        let constraints = &attr.constraints;
+        if let (Some(min), Some(max)) = (constraints.min, constraints.max)
+            && min > max
+        {
+            return Err(invalid(format!("has min {min} above max {max}")));
+        }
+        let mut bounds = Vec::new();
+        for (label, bound) in [
+            ("earliest", &constraints.earliest),
+            ("latest", &constraints.latest),
+        ] {
+            if let Some(text) = bound {
+                let parsed = parse_bound(text)
+                    .ok_or_else(|| invalid(format!("has invalid {label} bound '{text}'")))?;
+                bounds.push(parsed);
+            }
+        }
+        if let [earliest, latest] = bounds[..]
+            && earliest > latest
+        {
+            return Err(invalid("has earliest bound after latest".into()));
+        }
+        if let Some(default) = &attr.default {
+            attribute_value_ok(attr, default, rules)
+                .map_err(|fault| invalid(format!("has invalid default: {}", fault.reason)))?;
+        }
+    }
+    Ok(())
+}
+
+/// `props` with the declared defaults of absent attributes filled in. Non-object props are
+/// returned unchanged for validation to reject.
+pub(super) fn with_defaults(
+    attributes: &BTreeMap<String, MetaAttribute>,
+    props: &Option<Value>,
+) -> Option<Value> {
+    let mut map = match props {
+        None | Some(Value::Null) => Map::new(),
+        Some(Value::Object(map)) => map.clone(),
+        Some(_) => return props.clone(),
+    };
+    let mut filled = false;
+    for attr in attributes.values() {
+        if let Some(default) = &attr.default
+            && !map.contains_key(&attr.name)
+        {
+            map.insert(attr.name.clone(), default.clone());
+            filled = true;
+        }
+    }
+    if filled {
+        Some(Value::Object(map))
+    } else {
+        props.clone()
+    }
+}
+
+/// Blob attributes hold only a content reference; the engine checks the blob exists at commit.
+fn validate_blob(value: &Value) -> AttributeResult {
+    if value.as_str().and_then(BlobRef::parse).is_some() {
         Ok(())
     } else {
-        Err(PraxisError::ValidationFailed {
-            message: format_error("expected string/structured blob".into()),
-        })
+        Err(AttributeFault::new(
+            ValidationRule::ValueType,
+            format!("expected blob reference '{BLOB_REF_PREFIX}<hash>'"),
+        ))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use aideon_mneme::meta::{MetaAttribute, MetaAttributeKind};
+    use aideon_mneme::meta::{MetaAttribute, MetaAttributeConstraints, MetaAttributeKind};
     use serde_json::json;
     use std::collections::BTreeMap;
 
@@ -272,6 +473,7 @@ mod tests {
             string_max: Some(5),
             text_max: Some(10),
             enum_case_sensitive: false,
+            patterns: HashMap::new(),
         }
     }
 
@@ -281,6 +483,8 @@ mod tests {
             value_type: MetaAttributeKind::String,
             required,
             enum_values: vec![],
+            default: None,
+            constraints: Default::default(),
         }
     }
 
@@ -290,6 +494,8 @@ mod tests {
             value_type: MetaAttributeKind::Enum,
             required: true,
             enum_values: variants.iter().map(|v| (*v).to_string()).collect(),
+            default: None,
+            constraints: Default::default(),
         }
     }
 
@@ -311,7 +517,14 @@ mod tests {
         let mut types = BTreeMap::<String, TypeDescriptor>::new();
         let mut attrs = BTreeMap::new();
         attrs.insert("name".into(), string_attr("name", true));
-        types.insert("Capability".into(), TypeDescriptor { attributes: attrs });
+        types.insert(
+            "Capability".into(),
+            TypeDescriptor {
+                attributes: attrs,
+                ancestors: vec![],
+                is_abstract: false,
+            },
+        );
 
         let node = NodeVersion {
             id: "n1".into(),
@@ -334,7 +547,14 @@ mod tests {
         let mut types = BTreeMap::<String, TypeDescriptor>::new();
         let mut attrs = BTreeMap::new();
         attrs.insert("name".into(), string_attr("name", true));
-        types.insert("Capability".into(), TypeDescriptor { attributes: attrs });
+        types.insert(
+            "Capability".into(),
+            TypeDescriptor {
+                attributes: attrs,
+                ancestors: vec![],
+                is_abstract: false,
+            },
+        );
 
         let node = NodeVersion {
             id: "n1".into(),
@@ -375,6 +595,7 @@ mod tests {
             },
         );
         let ruleset = rules();
+        let types = BTreeMap::<String, TypeDescriptor>::new();
         let mut rel_rules = HashMap::<String, RelationshipRule>::new();
         rel_rules.insert(
             "rel_aa".into(),
@@ -399,8 +620,16 @@ mod tests {
             directed: None,
             props: None,
         };
-        let err =
-            validate_edge(&edge_self, "A", "A", &relationships, &rel_rules, &ruleset).unwrap_err();
+        let err = validate_edge(
+            &edge_self,
+            "A",
+            "A",
+            &types,
+            &relationships,
+            &rel_rules,
+            &ruleset,
+        )
+        .unwrap_err();
         assert!(err.to_string().contains("forbids self-links"));
 
         let edge_enum_ok = EdgeVersion {
@@ -415,6 +644,7 @@ mod tests {
             &edge_enum_ok,
             "A",
             "B",
+            &types,
             &relationships,
             &rel_rules,
             &ruleset,
@@ -429,6 +659,7 @@ mod tests {
             &edge_enum_bad,
             "A",
             "B",
+            &types,
             &relationships,
             &rel_rules,
             &ruleset,
@@ -436,4 +667,157 @@ mod tests {
         .unwrap_err();
         assert!(err.to_string().contains("not in"));
     }
+
+    #[test]
+    fn validate_node_requires_blob_references() {
+        let mut types = BTreeMap::<String, TypeDescriptor>::new();
+        let mut attrs = BTreeMap::new();
+        attrs.insert(
+            "content".into(),
+            MetaAttribute {
+                name: "content".into(),
+                value_type: MetaAttributeKind::Blob,
+                required: true,
+                enum_values: vec![],
+                default: None,
+                constraints: Default::default(),
+            },
+        );
+        types.insert(
+            "Document".into(),
+            TypeDescriptor {
+                attributes: attrs,
+                ancestors: vec![],
+                is_abstract: false,
+            },
+        );
+        let node = |content: Value| NodeVersion {
+            id: "d1".into(),
+            r#type: Some("Document".into()),
+            props: Some(json!({ "content": content })),
+        };
+
+        let blob = BlobRef::for_bytes(b"pdf");
+        validate_node(&node(json!(blob.to_string())), &types, &rules()).unwrap();
+        for inline in [
+            json!("raw bytes"),
+            json!({ "data": "inline" }),
+            json!(blob.hash()),
+        ] {
+            let err = validate_node(&node(inline), &types, &rules()).unwrap_err();
+            assert!(err.to_string().contains("expected blob reference"));
+        }
+    }
+
+    #[test]
+    fn attribute_constraints_bound_values() {
+        let constrained = |value_type, constraints| MetaAttribute {
+            name: "field".into(),
+            value_type,
+            required: false,
+            enum_values: vec![],
+            default: None,
+            constraints,
+        };
+        let code = constrained(
+            MetaAttributeKind::String,
+            MetaAttributeConstraints {
+                pattern: Some("^[A-Z]{3}$".into()),
+                ..Default::default()
+            },
+        );
+        let confidence = constrained(
+            MetaAttributeKind::Number,
+            MetaAttributeConstraints {
+                min: Some(0.0),
+                max: Some(1.0),
+                ..Default::default()
+            },
+        );
+        let effective = constrained(
+            MetaAttributeKind::Datetime,
+            MetaAttributeConstraints {
+                earliest: Some("2025-01-01T00:00:00Z".into()),
+                latest: Some("2030-12-31T23:59:59Z".into()),
+                ..Default::default()
+            },
+        );
+        let mut rules = rules();
+        rules.compile_patterns([&code].into_iter()).unwrap();
+        let check = |attr: &MetaAttribute, value: Value| {
+            attribute_value_ok(attr, &value, &rules).map_err(|fault| fault.reason)
+        };
+
+        check(&code, json!("ABC")).unwrap();
+        assert!(check(&code, json!("AB1")).unwrap_err().contains("pattern"));
+        check(&confidence, json!(0.5)).unwrap();
+        assert!(
+            check(&confidence, json!(1.2))
+                .unwrap_err()
+                .contains("above maximum 1")
+        );
+        assert!(
+            check(&confidence, json!(-1))
+                .unwrap_err()
+                .contains("below minimum 0")
+        );
+        check(&effective, json!("2026-06-01T00:00:00Z")).unwrap();
+        assert!(
+            check(&effective, json!("2024-06-01T00:00:00Z"))
+                .unwrap_err()
+                .contains("before earliest")
+        );
+        assert!(
+            check(&effective, json!("2031-01-01T00:00:00Z"))
+                .unwrap_err()
+                .contains("after latest")
+        );
+    }
+
+    #[test]
+    fn attribute_definitions_reject_contradictions_and_bad_defaults() {
+        let attr = |default: Option<Value>, constraints| MetaAttribute {
+            name: "confidence".into(),
+            value_type: MetaAttributeKind::Number,
+            required: false,
+            enum_values: vec![],
+            default,
+            constraints,
+        };
+        let bounded = MetaAttributeConstraints {
+            min: Some(0.0),
+            max: Some(1.0),
+            ..Default::default()
+        };
+        check_attribute_definitions(
+            [&attr(Some(json!(0.5)), bounded.clone())].into_iter(),
+            &rules(),
+        )
+        .unwrap();
+        let err = check_attribute_definitions(
+            [&attr(Some(json!(2)), bounded.clone())].into_iter(),
+            &rules(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid default"), "{err}");
+        let inverted = MetaAttributeConstraints {
+            min: Some(1.0),
+            max: Some(0.0),
+            ..Default::default()
+        };
+        assert!(
+            check_attribute_definitions([&attr(None, inverted)].into_iter(), &rules()).is_err()
+        );
+
+        let mut attributes = BTreeMap::new();
+        attributes.insert("confidence".into(), attr(Some(json!(0.5)), bounded));
+        assert_eq!(
+            with_defaults(&attributes, &None),
+            Some(json!({ "confidence": 0.5 }))
+        );
+        assert_eq!(
+            with_defaults(&attributes, &Some(json!({ "confidence": 0.9 }))),
+            Some(json!({ "confidence": 0.9 }))
+        );
+    }
 }
@@ -0,0 +1,162 @@
+//! Structured reports for change sets: validation issues and commit previews.
+
+use aideon_mneme::meta::MetaRuleSeverity;
+use aideon_mneme::temporal::DiffPatch;
+use serde::{Deserialize, Serialize};
+
+use crate::error::PraxisError;
+use crate::graph::SnapshotStats;
+
+/// Rule a validation issue was raised by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationRule {
+    /// The node has no type.
+    MissingType,
+    /// The node or edge names a type the meta-model does not define.
+    UnknownType,
+    /// The node uses an abstract type.
+    AbstractType,
+    /// `props` is not a JSON object.
+    PropsShape,
+    /// A required attribute is absent.
+    Required,
+    /// An attribute is present but null.
+    NotNull,
+    /// The value does not have the attribute's kind.
+    ValueType,
+    MaxLength,
+    Pattern,
+    Range,
+    Enum,
+    Unique,
+    /// The edge's endpoint types are not allowed by its relationship.
+    Endpoint,
+    SelfLink,
+    DuplicateRelationship,
+    Multiplicity,
+    /// Created element already exists.
+    AlreadyExists,
+    /// Updated or deleted element does not exist, or an edge names a missing node.
+    Missing,
+    /// An edge update matches more than one edge.
+    Ambiguous,
+    /// A stored edge points at a node that no longer exists.
+    DanglingEdge,
+    /// The change set leaves the `MetaModelEntry` nodes describing an invalid schema.
+    MetaModel,
+}
+
+/// One rule violation, located by element and (where relevant) attribute.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    /// Node id, or the edge id (`from->to` for edges without one).
+    pub element_id: String,
+    /// Attribute the issue concerns; absent for whole-element rules.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribute: Option<String>,
+    pub rule: ValidationRule,
+    pub severity: MetaRuleSeverity,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub(crate) fn error(
+        element_id: impl Into<String>,
+        rule: ValidationRule,
+        message: String,
+    ) -> Self {
+        Self {
+            element_id: element_id.into(),
+            attribute: None,
+            rule,
+            severity: MetaRuleSeverity::Error,
+            message,
+        }
+    }
+
+    pub(crate) fn on_attribute(mut self, attribute: impl Into<String>) -> Self {
+        self.attribute = Some(attribute.into());
+        self
+    }
+
+    pub(crate) fn into_error(self) -> PraxisError {
+        match self.rule {
+            ValidationRule::DanglingEdge => PraxisError::IntegrityViolation {
+                message: self.message,
+            },
+            _ => PraxisError::ValidationFailed {
+                message: self.message,
+            },
+        }
+    }
+}
+
+/// Every issue found while validating a change set, in the order the rules ran.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// True when no issue has error severity; warnings alone do not block a commit.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// First error message, with a count of the remaining errors.
+    pub fn summary(&self) -> String {
+        let mut errors = self.errors();
+        match errors.next() {
+            Some(first) => match errors.count() {
+                0 => first.message.clone(),
+                more => format!("{} (+{more} more)", first.message),
+            },
+            None => "no errors".into(),
+        }
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == MetaRuleSeverity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == MetaRuleSeverity::Warning)
+    }
+}
+
+/// Signed change in node and edge counts between two snapshots.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsDelta {
+    pub nodes: i64,
+    pub edges: i64,
+}
+
+impl StatsDelta {
+    pub fn between(before: SnapshotStats, after: SnapshotStats) -> Self {
+        Self {
+            nodes: after.node_count as i64 - before.node_count as i64,
+            edges: after.edge_count as i64 - before.edge_count as i64,
+        }
+    }
+}
+
+/// What a commit would do to its branch, computed without persisting anything.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitPreview {
+    /// Commit the changes would build on; `None` for a branch without commits.
+    pub parent: Option<String>,
+    /// Elements added, modified and removed relative to the parent, including elements the
+    /// report flags.
+    pub patch: DiffPatch,
+    pub stats: StatsDelta,
+    pub report: ValidationReport,
+}
@@ -0,0 +1,377 @@
+use aideon_engine::meta::{Compatibility, MigrationHints, SchemaChange};
+use aideon_engine::{META_MODEL_ENTRY_TYPE, PraxisEngine, PraxisEngineConfig, ValidationRule};
+use aideon_mneme::meta::{MetaAttribute, MetaAttributeKind};
+use aideon_mneme::temporal::{
+    ChangeSet, CommitChangesRequest, CommitRef, DiffArgs, EdgeTombstone, EdgeVersion, MergeRequest,
+    NodeTombstone, NodeVersion,
+};
+use serde_json::json;
+use tempfile::tempdir;
+
+fn request(branch: &str, changes: ChangeSet) -> CommitChangesRequest {
+    CommitChangesRequest {
+        branch: branch.into(),
+        parent: None,
+        author: Some("meta-test".into()),
+        time: Some("2025-12-01T00:00:00Z".into()),
+        message: "meta-model change".into(),
+        tags: vec![],
+        changes,
+    }
+}
+
+fn risk_type() -> NodeVersion {
+    NodeVersion {
+        id: "meta:type:Risk".into(),
+        r#type: Some(META_MODEL_ENTRY_TYPE.into()),
+        props: Some(json!({
+            "kind": "type",
+            "name": "Risk",
+            "payload": "{\"id\":\"Risk\"}",
+            "label": "Risk",
+            "attributes": [{ "name": "name", "type": "string", "required": true }],
+        })),
+    }
+}
+
+fn risk(id: &str) -> ChangeSet {
+    ChangeSet {
+        node_creates: vec![NodeVersion {
+            id: id.into(),
+            r#type: Some("Risk".into()),
+            props: Some(json!({ "name": id })),
+        }],
+        ..ChangeSet::default()
+    }
+}
+
+#[tokio::test]
+async fn schema_changes_are_committed_per_branch() {
+    let engine = PraxisEngine::new().await.expect("engine init");
+    let main_head = engine
+        .list_commits("main".into())
+        .await
+        .expect("commits")
+        .last()
+        .map(|commit| commit.id.clone())
+        .expect("head");
+    engine
+        .create_branch("schema".into(), Some(CommitRef::Id(main_head.clone())))
+        .await
+        .expect("branch");
+
+    // A commit is validated against its parent's schema, so it cannot use a type it adds.
+    let mut both = risk("risk-1");
+    both.node_creates.push(risk_type());
+    let err = engine.commit(request("schema", both)).await.unwrap_err();
+    assert!(err.to_string().contains("unknown type 'Risk'"), "{err}");
+
+    let schema_commit = engine
+        .commit(request(
+            "schema",
+            ChangeSet {
+                node_creates: vec![risk_type()],
+                ..ChangeSet::default()
+            },
+        ))
+        .await
+        .expect("schema commit");
+    engine
+        .commit(request("schema", risk("risk-1")))
+        .await
+        .expect("risk on schema branch");
+    assert!(
+        engine
+            .commit(request("main", risk("risk-2")))
+            .await
+            .is_err()
+    );
+
+    let at_schema = engine.meta_model_at(&schema_commit).await.expect("schema");
+    assert!(at_schema.types.iter().any(|ty| ty.id == "Risk"));
+    let at_main = engine.meta_model_at(&main_head).await.expect("main");
+    assert!(!at_main.types.iter().any(|ty| ty.id == "Risk"));
+    assert!(
+        !engine
+            .meta_model()
+            .await
+            .expect("meta-model")
+            .types
+            .iter()
+            .any(|ty| ty.id == "Risk")
+    );
+
+    engine
+        .merge(MergeRequest {
+            source: "schema".into(),
+            target: "main".into(),
+            strategy: None,
+        })
+        .await
+        .expect("merge");
+    assert!(
+        engine
+            .meta_model()
+            .await
+            .expect("meta-model")
+            .types
+            .iter()
+            .any(|ty| ty.id == "Risk")
+    );
+    engine
+        .commit(request("main", risk("risk-2")))
+        .await
+        .expect("risk on main after merge");
+}
+
+#[tokio::test]
+async fn removing_a_type_entry_rejects_later_nodes() {
+    let engine = PraxisEngine::new().await.expect("engine init");
+    engine
+        .commit(request(
+            "main",
+            ChangeSet {
+                node_deletes: vec![NodeTombstone {
+                    id: "meta:type:DataEntity".into(),
+                }],
+                ..ChangeSet::default()
+            },
+        ))
+        .await
+        .expect("drop DataEntity");
+    assert!(
+        !engine
+            .meta_model()
+            .await
+            .expect("meta-model")
+            .types
+            .iter()
+            .any(|ty| ty.id == "DataEntity")
+    );
+
+    let err = engine
+        .commit(request(
+            "main",
+            ChangeSet {
+                node_creates: vec![NodeVersion {
+                    id: "data-x".into(),
+                    r#type: Some("DataEntity".into()),
+                    props: Some(json!({ "name": "X" })),
+                }],
+                ..ChangeSet::default()
+            },
+        ))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("unknown type 'DataEntity'"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn committed_schema_survives_a_restart() {
+    let dir = tempdir().expect("tempdir");
+    let db_path = dir.path().join("meta.sqlite");
+    let engine = PraxisEngine::with_sqlite(&db_path).await.expect("engine");
+    engine
+        .commit(request(
+            "main",
+            ChangeSet {
+                node_creates: vec![risk_type()],
+                ..ChangeSet::default()
+            },
+        ))
+        .await
+        .expect("schema commit");
+    drop(engine);
+
+    let reopened = PraxisEngine::with_sqlite_unseeded(&db_path, PraxisEngineConfig::default())
+        .await
+        .expect("reopen");
+    let schema = reopened.meta_model().await.expect("meta-model");
+    assert!(schema.types.iter().any(|ty| ty.id == "Risk"));
+    let head = reopened
+        .commit(request("main", risk("risk-1")))
+        .await
+        .expect("risk after restart");
+    assert!(reopened.snapshot_for_commit(&head).await.is_ok());
+}
+
+#[tokio::test]
+async fn schema_migration_plans_fix_ups_for_the_branch_head() {
+    let engine = PraxisEngine::new().await.expect("engine init");
+    let node = |id: &str, ty: &str, props| NodeVersion {
+        id: id.into(),
+        r#type: Some(ty.into()),
+        props: Some(props),
+    };
+    let edge = |from: &str, to: &str, ty: &str, props| EdgeVersion {
+        id: None,
+        from: from.into(),
+        to: to.into(),
+        r#type: Some(ty.into()),
+        directed: Some(true),
+        props,
+    };
+    engine
+        .commit(request(
+            "main",
+            ChangeSet {
+                node_creates: vec![
+                    node(
+                        "app-1",
+                        "Application",
+                        json!({ "name": "CRM", "disposition": "Tolerate" }),
+                    ),
+                    node("data-1", "DataEntity", json!({ "name": "Customer" })),
+                    node("cap-1", "Capability", json!({ "name": "Sell" })),
+                ],
+                edge_creates: vec![
+                    edge(
+                        "app-1",
+                        "data-1",
+                        "accesses",
+                        Some(json!({ "mode": "read" })),
+                    ),
+                    edge("app-1", "cap-1", "realises", None),
+                ],
+                ..ChangeSet::default()
+            },
+        ))
+        .await
+        .expect("data commit");
+
+    let mut target = engine.meta_model().await.expect("meta-model");
+    let application = target
+        .types
+        .iter_mut()
+        .find(|ty| ty.id == "Application")
+        .expect("application");
+    let disposition = application
+        .attributes
+        .iter_mut()
+        .find(|attr| attr.name == "disposition")
+        .expect("disposition");
+    disposition.enum_values = vec![
+        "Invest".into(),
+        "Maintain".into(),
+        "Migrate".into(),
+        "Eliminate".into(),
+    ];
+    application.attributes.push(MetaAttribute {
+        name: "owner".into(),
+        value_type: MetaAttributeKind::String,
+        required: true,
+        enum_values: vec![],
+        default: Some(json!("unassigned")),
+        constraints: Default::default(),
+    });
+    let accesses = target
+        .relationships
+        .iter_mut()
+        .find(|rel| rel.id == "accesses")
+        .expect("accesses");
+    accesses.from.retain(|ty| ty != "Application");
+
+    let plan = engine
+        .plan_schema_migration("main", &target, &MigrationHints::default())
+        .await
+        .expect("plan");
+    assert!(plan.is_breaking());
+    assert!(plan.changes.iter().any(|change| matches!(
+        change,
+        SchemaChange::EnumValueRenamed { from, to, .. } if from == "Tolerate" && to == "Maintain"
+    )));
+    let rules: Vec<ValidationRule> = plan
+        .report
+        .issues
+        .iter()
+        .filter(|issue| issue.element_id.starts_with("app-1"))
+        .map(|issue| issue.rule)
+        .collect();
+    assert_eq!(
+        rules,
+        vec![
+            ValidationRule::Enum,
+            ValidationRule::Required,
+            ValidationRule::Endpoint
+        ]
+    );
+    let app = plan
+        .fixes
+        .node_updates
+        .iter()
+        .find(|node| node.id == "app-1")
+        .expect("app fix");
+    assert_eq!(
+        app.props.as_ref().unwrap()["disposition"],
+        json!("Maintain")
+    );
+    assert_eq!(app.props.as_ref().unwrap()["owner"], json!("unassigned"));
+    assert!(plan.fixes.edge_deletes.contains(&EdgeTombstone {
+        from: "app-1".into(),
+        to: "data-1".into(),
+    }));
+    assert!(plan.unresolved.is_valid(), "{:?}", plan.unresolved);
+
+    engine
+        .commit(request("main", plan.schema_update.clone()))
+        .await
+        .expect("schema update");
+    engine
+        .commit(request("main", plan.fixes.clone()))
+        .await
+        .expect("fix-ups");
+    let after = engine
+        .plan_schema_migration("main", &target, &MigrationHints::default())
+        .await
+        .expect("plan after migration");
+    assert!(after.changes.is_empty(), "{:?}", after.changes);
+    assert!(after.report.is_valid(), "{:?}", after.report);
+    assert_eq!(after.schema_update, ChangeSet::default());
+    assert_eq!(after.fixes, ChangeSet::default());
+}
+
+#[tokio::test]
+async fn meta_model_diff_compares_branch_schemas() {
+    let engine = PraxisEngine::new().await.expect("engine init");
+    engine
+        .create_branch("schema".into(), Some(CommitRef::Id("main".into())))
+        .await
+        .expect("branch");
+    engine
+        .commit(request(
+            "schema",
+            ChangeSet {
+                node_creates: vec![risk_type()],
+                node_deletes: vec![NodeTombstone {
+                    id: "meta:relationship:hosts".into(),
+                }],
+                ..ChangeSet::default()
+            },
+        ))
+        .await
+        .expect("schema commit");
+
+    let diff = engine
+        .meta_model_diff(DiffArgs {
+            from: CommitRef::Id("main".into()),
+            to: CommitRef::Id("schema".into()),
+            scope: None,
+        })
+        .await
+        .expect("diff");
+    assert_eq!(diff.compatibility, Compatibility::Breaking);
+    assert_eq!(
+        diff.changes,
+        vec![
+            SchemaChange::TypeAdded {
+                type_id: "Risk".into()
+            },
+            SchemaChange::RelationshipRemoved {
+                relationship: "hosts".into()
+            },
+        ]
+    );
+}
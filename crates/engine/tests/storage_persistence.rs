@@ -1,7 +1,10 @@
-use aideon_engine::{PraxisEngine, PraxisEngineConfig};
+use std::sync::Arc;
+
+use aideon_engine::meta::{MetaModelConfig, MetaModelSource};
+use aideon_engine::{GraphSnapshot, PraxisEngine, PraxisEngineConfig};
 use aideon_mneme::{
-    SqliteDb, Store,
-    temporal::{ChangeSet, CommitChangesRequest, NodeVersion},
+    BlobRef, ElementRef, FileStore, GraphFilter, SearchArgs, SearchQuery, SqliteDb, Store,
+    temporal::{ChangeSet, CommitChangesRequest, CommitRef, NodeVersion, StateAtArgs},
 };
 use serde_json::json;
 use tempfile::tempdir;
@@ -70,3 +73,379 @@ async fn sqlite_persists_commits_across_restarts() {
         .expect("tag present");
     assert_eq!(resolved, commit_id, "snapshot tag should point to commit");
 }
+
+fn capability_request(branch: &str, id: &str) -> CommitChangesRequest {
+    CommitChangesRequest {
+        branch: branch.into(),
+        parent: None,
+        author: Some("import".into()),
+        time: Some("2025-11-12T00:00:00Z".into()),
+        message: format!("import {id}"),
+        tags: vec![],
+        changes: ChangeSet {
+            node_creates: vec![NodeVersion {
+                id: id.into(),
+                r#type: Some("Capability".into()),
+                props: Some(json!({ "name": id })),
+            }],
+            ..ChangeSet::default()
+        },
+    }
+}
+
+#[tokio::test]
+async fn sqlite_imports_commit_batches_atomically() {
+    let dir = tempdir().expect("tempdir");
+    let db_path = dir.path().join("batch.sqlite");
+    let engine = PraxisEngine::with_sqlite_unseeded(&db_path, PraxisEngineConfig::default())
+        .await
+        .expect("engine init");
+
+    let mut requests: Vec<_> = (0..200)
+        .map(|index| capability_request("main", &format!("cap-{index}")))
+        .collect();
+    requests.push(capability_request("draft", "cap-draft"));
+    let ids = engine.commit_batch(requests).await.expect("batch commit");
+    assert_eq!(ids.len(), 201);
+    let main = engine.list_commits("main".into()).await.unwrap();
+    assert_eq!(main.len(), 200);
+    assert_eq!(main[1].parents, vec![main[0].id.clone()]);
+    let head = &ids[199];
+    assert_eq!(engine.stats_for_commit(head).await.unwrap().node_count, 200);
+
+    // The second request names a stale parent, so neither is committed.
+    let mut stale = capability_request("main", "cap-stale");
+    stale.parent = Some(ids[0].clone());
+    let err = engine
+        .commit_batch(vec![capability_request("main", "cap-ok"), stale])
+        .await
+        .expect_err("stale parent");
+    assert!(
+        matches!(err, aideon_engine::PraxisError::ConcurrencyConflict { .. }),
+        "{err:?}"
+    );
+    drop(engine);
+
+    let db = SqliteDb::open(&db_path).await.expect("reopen");
+    assert_eq!(
+        db.get_branch_head("main").await.unwrap().as_deref(),
+        Some(head.as_str())
+    );
+    assert_eq!(
+        db.get_branch_head("draft").await.unwrap().as_deref(),
+        Some(ids[200].as_str())
+    );
+    assert_eq!(
+        db.get_tag(&format!("snapshot/{head}"))
+            .await
+            .unwrap()
+            .as_deref(),
+        Some(head.as_str())
+    );
+}
+
+#[tokio::test]
+async fn file_store_persists_commits_across_restarts() {
+    let dir = tempdir().expect("tempdir");
+
+    let store = FileStore::open(dir.path()).expect("open file store");
+    let engine = PraxisEngine::with_stores(PraxisEngineConfig::default(), Arc::new(store))
+        .await
+        .expect("engine init");
+    let parent = engine
+        .list_commits("main".into())
+        .await
+        .expect("list commits")
+        .last()
+        .map(|commit| commit.id.clone());
+    let commit_id = engine
+        .commit(CommitChangesRequest {
+            branch: "main".into(),
+            parent,
+            author: Some("health-check".into()),
+            time: Some("2025-11-12T00:00:00Z".into()),
+            message: "add capability for file store test".into(),
+            tags: vec![],
+            changes: ChangeSet {
+                node_creates: vec![NodeVersion {
+                    id: "cap-file".into(),
+                    r#type: Some("Capability".into()),
+                    props: Some(json!({ "name": "File Store" })),
+                }],
+                ..ChangeSet::default()
+            },
+        })
+        .await
+        .expect("commit succeeds");
+    drop(engine);
+
+    let reopened = FileStore::open(dir.path()).expect("reopen file store");
+    assert_eq!(
+        reopened.get_branch_head("main").await.unwrap().as_deref(),
+        Some(commit_id.as_str())
+    );
+    let engine =
+        PraxisEngine::with_stores_unseeded(PraxisEngineConfig::default(), Arc::new(reopened))
+            .await
+            .expect("reopen engine");
+    engine
+        .stats_for_commit(&commit_id)
+        .await
+        .expect("snapshot available for persisted commit");
+}
+
+#[tokio::test]
+async fn sqlite_answers_state_at_from_version_tables() {
+    let dir = tempdir().expect("tempdir");
+    let db_path = dir.path().join("versions.sqlite");
+
+    let engine = PraxisEngine::with_sqlite(&db_path)
+        .await
+        .expect("engine init");
+    let parent = engine
+        .list_commits("main".into())
+        .await
+        .expect("list commits")
+        .last()
+        .map(|commit| commit.id.clone());
+    let commit_id = engine
+        .commit(CommitChangesRequest {
+            branch: "main".into(),
+            parent,
+            author: None,
+            time: None,
+            message: "add capability for version test".into(),
+            tags: vec![],
+            changes: ChangeSet {
+                node_creates: vec![NodeVersion {
+                    id: "cap-versions".into(),
+                    r#type: Some("Capability".into()),
+                    props: Some(json!({ "name": "Versions" })),
+                }],
+                ..ChangeSet::default()
+            },
+        })
+        .await
+        .expect("commit succeeds");
+    let replayed = engine
+        .state_at(StateAtArgs::new(commit_id.clone(), None, None))
+        .await
+        .expect("state_at from cached snapshot");
+    drop(engine);
+
+    // A fresh engine has no cached snapshots, so state_at is answered by the store.
+    let reopened = PraxisEngine::with_sqlite_unseeded(&db_path, PraxisEngineConfig::default())
+        .await
+        .expect("reopen engine");
+    let stored = reopened
+        .state_at(StateAtArgs::new(commit_id.clone(), None, None))
+        .await
+        .expect("state_at from store");
+    assert_eq!(stored, replayed);
+
+    let capabilities = reopened
+        .graph_state(
+            &commit_id,
+            &GraphFilter {
+                node_types: vec!["Capability".into()],
+                ..GraphFilter::default()
+            },
+        )
+        .await
+        .expect("graph_state");
+    assert!(
+        capabilities
+            .nodes
+            .iter()
+            .any(|node| node.id == "cap-versions")
+    );
+    let history = reopened
+        .element_history(
+            &commit_id,
+            &ElementRef::Node {
+                id: "cap-versions".into(),
+            },
+        )
+        .await
+        .expect("element_history");
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].valid_from, commit_id);
+
+    // Branch names resolve to their head, like state_at.
+    let found = reopened
+        .search(SearchArgs {
+            as_of: CommitRef::Id("main".into()),
+            scenario: None,
+            query: SearchQuery::new("version"),
+        })
+        .await
+        .expect("search");
+    assert_eq!(found.commit_id, commit_id);
+    assert!(found.hits.iter().any(|hit| hit.node_id == "cap-versions"));
+}
+
+#[tokio::test]
+async fn declared_defaults_are_persisted_with_the_commit() {
+    let dir = tempdir().expect("tempdir");
+    let overlay = json!({
+        "version": "1.0.0",
+        "types": [{
+            "id": "Capability",
+            "attributes": [{ "name": "tier", "type": "string", "default": "core" }]
+        }]
+    });
+    let config = PraxisEngineConfig {
+        meta_model: MetaModelConfig::default()
+            .add_override(MetaModelSource::Inline(overlay.to_string())),
+        ..PraxisEngineConfig::default()
+    };
+    let engine = PraxisEngine::with_sqlite_unseeded(dir.path().join("defaults.sqlite"), config)
+        .await
+        .expect("engine init");
+    let commit_id = engine
+        .commit(capability_request("main", "cap-defaults"))
+        .await
+        .expect("commit succeeds");
+
+    let snapshot = engine
+        .snapshot_for_commit(&commit_id)
+        .await
+        .expect("snapshot");
+    let in_memory = GraphSnapshot::empty().diff(&snapshot).node_adds;
+    let stored = engine
+        .graph_state(&commit_id, &GraphFilter::default())
+        .await
+        .expect("graph_state")
+        .nodes;
+    assert_eq!(stored, in_memory);
+    assert_eq!(stored[0].props.as_ref().unwrap()["tier"], json!("core"));
+}
+
+#[tokio::test]
+async fn blob_attributes_must_reference_stored_blobs() {
+    let dir = tempdir().expect("tempdir");
+    let overlay = json!({
+        "version": "1.0.0",
+        "types": [{
+            "id": "Document",
+            "attributes": [{ "name": "content", "type": "blob", "required": true }]
+        }]
+    });
+    let config = PraxisEngineConfig {
+        meta_model: MetaModelConfig::default()
+            .add_override(MetaModelSource::Inline(overlay.to_string())),
+        ..PraxisEngineConfig::default()
+    };
+    let engine = PraxisEngine::with_sqlite_unseeded(dir.path().join("blobs.sqlite"), config)
+        .await
+        .expect("engine init");
+    let request = |content: String| CommitChangesRequest {
+        branch: "main".into(),
+        parent: None,
+        author: None,
+        time: None,
+        message: "attach document".into(),
+        tags: vec![],
+        changes: ChangeSet {
+            node_creates: vec![NodeVersion {
+                id: "doc-1".into(),
+                r#type: Some("Document".into()),
+                props: Some(json!({ "content": content })),
+            }],
+            ..ChangeSet::default()
+        },
+    };
+
+    let bytes = b"%PDF-1.7 architecture decision record";
+    let err = engine
+        .commit(request(BlobRef::for_bytes(bytes).to_string()))
+        .await
+        .expect_err("unknown blob rejected");
+    assert!(err.to_string().contains("unknown blob"));
+
+    let info = engine.put_blob(bytes).await.expect("upload");
+    engine
+        .commit(request(info.blob.to_string()))
+        .await
+        .expect("commit with stored blob");
+    let info = engine
+        .blob_info(&info.blob)
+        .await
+        .expect("blob_info")
+        .expect("blob stored");
+    assert_eq!(info.ref_count, 1);
+}
+
+#[tokio::test]
+async fn only_blob_attributes_reference_blobs() {
+    let dir = tempdir().expect("tempdir");
+    let overlay = json!({
+        "version": "1.0.0",
+        "types": [{
+            "id": "Document",
+            "attributes": [
+                { "name": "content", "type": "blob" },
+                { "name": "checksum", "type": "string" }
+            ]
+        }]
+    });
+    let config = PraxisEngineConfig {
+        meta_model: MetaModelConfig::default()
+            .add_override(MetaModelSource::Inline(overlay.to_string())),
+        ..PraxisEngineConfig::default()
+    };
+    let engine = PraxisEngine::with_sqlite_unseeded(dir.path().join("checksums.sqlite"), config)
+        .await
+        .expect("engine init");
+    let request = |changes: ChangeSet| CommitChangesRequest {
+        branch: "main".into(),
+        parent: None,
+        author: None,
+        time: None,
+        message: "document".into(),
+        tags: vec![],
+        changes,
+    };
+
+    // A text attribute shaped like a blob reference is neither checked nor counted.
+    let info = engine.put_blob(b"release notes").await.expect("upload");
+    let unknown = BlobRef::for_bytes(b"never uploaded");
+    engine
+        .commit(request(ChangeSet {
+            node_creates: vec![NodeVersion {
+                id: "doc-1".into(),
+                r#type: Some("Document".into()),
+                props: Some(json!({ "checksum": unknown.to_string() })),
+            }],
+            ..ChangeSet::default()
+        }))
+        .await
+        .expect("checksum is plain text");
+    engine
+        .commit(request(ChangeSet {
+            node_updates: vec![NodeVersion {
+                id: "doc-1".into(),
+                r#type: Some("Document".into()),
+                props: Some(json!({ "checksum": info.blob.to_string() })),
+            }],
+            ..ChangeSet::default()
+        }))
+        .await
+        .expect("checksum update");
+    let stored = engine.blob_info(&info.blob).await.expect("blob_info");
+    assert_eq!(stored.expect("blob stored").ref_count, 0);
+
+    engine
+        .commit(request(ChangeSet {
+            node_updates: vec![NodeVersion {
+                id: "doc-1".into(),
+                r#type: Some("Document".into()),
+                props: Some(json!({ "content": info.blob.to_string() })),
+            }],
+            ..ChangeSet::default()
+        }))
+        .await
+        .expect("attach content");
+    let stored = engine.blob_info(&info.blob).await.expect("blob_info");
+    assert_eq!(stored.expect("blob stored").ref_count, 1);
+}
@@ -1,4 +1,4 @@
-use aideon_engine::PraxisEngine;
+use aideon_engine::{PraxisEngine, PraxisError, ValidationRule};
 use aideon_mneme::temporal::{ChangeSet, CommitChangesRequest, DiffArgs, NodeVersion, StateAtArgs};
 use serde_json::json;
 
@@ -68,3 +68,105 @@ async fn state_at_and_diff_cover_new_commit() {
     assert_eq!(diff.to, commit_id);
     assert_eq!(diff.node_adds, 1, "expected one node addition in diff");
 }
+
+#[tokio::test]
+async fn invalid_commit_reports_every_issue() {
+    let engine = PraxisEngine::new().await.expect("engine init");
+    let request = CommitChangesRequest {
+        branch: "main".into(),
+        parent: None,
+        author: None,
+        time: None,
+        message: "invalid nodes".into(),
+        tags: vec![],
+        changes: ChangeSet {
+            node_creates: vec![
+                NodeVersion {
+                    id: "untyped".into(),
+                    r#type: None,
+                    props: None,
+                },
+                NodeVersion {
+                    id: "unknown".into(),
+                    r#type: Some("NoSuchType".into()),
+                    props: None,
+                },
+            ],
+            ..ChangeSet::default()
+        },
+    };
+
+    let report = engine
+        .validate_changes(request.clone())
+        .await
+        .expect("report");
+    let rules: Vec<ValidationRule> = report.issues.iter().map(|issue| issue.rule).collect();
+    assert_eq!(
+        rules,
+        vec![ValidationRule::UnknownType, ValidationRule::MissingType]
+    );
+
+    let err = engine.commit(request).await.unwrap_err();
+    assert!(err.to_string().ends_with("(+1 more)"), "{err}");
+    match err {
+        PraxisError::InvalidChanges { report: rejected } => assert_eq!(rejected, report),
+        other => panic!("expected a validation report, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn preview_commit_reports_changes_without_persisting() {
+    let engine = PraxisEngine::new().await.expect("engine init");
+    let commits_before = engine.list_commits("main".into()).await.expect("commits");
+    let head = commits_before.last().map(|c| c.id.clone());
+
+    let preview = engine
+        .preview_commit(CommitChangesRequest {
+            branch: "main".into(),
+            parent: None,
+            author: None,
+            time: None,
+            message: "preview".into(),
+            tags: vec![],
+            changes: ChangeSet {
+                node_creates: vec![
+                    NodeVersion {
+                        id: "cap-preview".into(),
+                        r#type: Some("Capability".into()),
+                        props: Some(json!({ "name": "Preview" })),
+                    },
+                    NodeVersion {
+                        id: "untyped".into(),
+                        r#type: None,
+                        props: None,
+                    },
+                ],
+                ..ChangeSet::default()
+            },
+        })
+        .await
+        .expect("preview");
+
+    assert_eq!(preview.parent, head);
+    let added: Vec<&str> = preview
+        .patch
+        .node_adds
+        .iter()
+        .map(|node| node.id.as_str())
+        .collect();
+    assert_eq!(added, vec!["cap-preview", "untyped"]);
+    assert_eq!(preview.stats.nodes, 2);
+    assert_eq!(preview.stats.edges, 0);
+    assert_eq!(preview.report.issues.len(), 1);
+    assert_eq!(preview.report.issues[0].element_id, "untyped");
+
+    let commits_after = engine.list_commits("main".into()).await.expect("commits");
+    assert_eq!(commits_after.len(), commits_before.len());
+    let branch_head = engine
+        .list_branches()
+        .await
+        .into_iter()
+        .find(|branch| branch.name == "main")
+        .and_then(|branch| branch.head);
+    assert_eq!(branch_head, head);
+}
@@ -0,0 +1,266 @@
+//! Consistent point-in-time backups of a datastore and restore into a new one.
+//!
+//! A backup is a directory:
+//!
+//! ```text
+//! manifest.json        BackupManifest, written last: a directory without it is incomplete
+//! datastore.sqlite     transactionally consistent copy of the database
+//! datastore.blobs/     blob files referenced by the copy (same layout as a live store)
+//! ```
+//!
+//! The database copy is taken online by the backend (`SqliteDb::backup_to`), so the app keeps
+//! running and the copy never contains a torn write-ahead log. Encrypted stores are copied
+//! sealed; restoring needs no key, opening the restored datastore does.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncReadExt;
+
+use crate::crypto::EncryptionMode;
+use crate::datastore::{DEFAULT_DB_NAME, create_datastore};
+use crate::lock::DatastoreLock;
+use crate::temporal::{BranchName, CommitId};
+use crate::{MnemeError, MnemeResult};
+
+/// Current backup layout version; readers reject anything newer.
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// Directory under a storage root holding rotating backups.
+pub const BACKUPS_DIR: &str = "backups";
+
+pub(crate) const MANIFEST_FILE: &str = "manifest.json";
+pub(crate) const DATABASE_FILE: &str = "datastore.sqlite";
+const BLOBS_DIR: &str = "datastore.blobs";
+const BACKUP_DIR_PREFIX: &str = "backup-";
+const HASH_CHUNK_BYTES: usize = 64 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub format_version: u32,
+    /// When the copy was taken; every branch head below is as of this instant.
+    pub created_at_ms: i64,
+    pub encryption: EncryptionMode,
+    pub heads: BTreeMap<BranchName, Option<CommitId>>,
+    pub commit_count: u64,
+    pub blob_count: u64,
+    pub database_bytes: u64,
+    /// BLAKE3 of `datastore.sqlite`, checked before a restore.
+    pub database_hash: String,
+}
+
+/// A complete backup found on disk.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackupInfo {
+    pub dir: PathBuf,
+    pub manifest: BackupManifest,
+}
+
+/// Schedule for rotating backups under a storage root.
+#[derive(Clone, Debug)]
+pub struct BackupPolicy {
+    /// Time between backups; a backup is taken at once when the newest one is older.
+    pub interval: Duration,
+    /// Complete backups kept; older ones are removed after each new backup.
+    pub keep: usize,
+}
+
+impl Default for BackupPolicy {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(24 * 60 * 60),
+            keep: 7,
+        }
+    }
+}
+
+/// Rotating backup directory for a storage root (the directory holding `datastore.json`).
+pub fn backups_dir(storage_root: &Path) -> PathBuf {
+    storage_root.join(BACKUPS_DIR)
+}
+
+/// Directory name for a backup taken at `created_at_ms`; names sort by age.
+pub(crate) fn backup_dir_name(created_at_ms: i64) -> String {
+    format!("{BACKUP_DIR_PREFIX}{created_at_ms:015}")
+}
+
+/// Complete backups under `root`, oldest first. Directories without a manifest are skipped.
+pub async fn list_backups(root: &Path) -> MnemeResult<Vec<BackupInfo>> {
+    let mut entries = match tokio::fs::read_dir(root).await {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(io_err("list backups", root, err)),
+    };
+    let mut backups = Vec::new();
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|err| io_err("list backups", root, err))?
+    {
+        let dir = entry.path();
+        if !dir.join(MANIFEST_FILE).exists() {
+            continue;
+        }
+        let manifest = read_manifest(&dir).await?;
+        backups.push(BackupInfo { dir, manifest });
+    }
+    backups.sort_by(|a, b| {
+        (a.manifest.created_at_ms, &a.dir).cmp(&(b.manifest.created_at_ms, &b.dir))
+    });
+    Ok(backups)
+}
+
+/// Remove the oldest complete backups under `root` so at most `keep` remain.
+pub async fn prune_backups(root: &Path, keep: usize) -> MnemeResult<Vec<PathBuf>> {
+    let backups = list_backups(root).await?;
+    let excess = backups.len().saturating_sub(keep);
+    let mut removed = Vec::with_capacity(excess);
+    for backup in backups.into_iter().take(excess) {
+        tokio::fs::remove_dir_all(&backup.dir)
+            .await
+            .map_err(|err| io_err("remove backup", &backup.dir, err))?;
+        removed.push(backup.dir);
+    }
+    Ok(removed)
+}
+
+/// Read and version-check a backup's manifest.
+pub async fn read_manifest(dir: &Path) -> MnemeResult<BackupManifest> {
+    let path = dir.join(MANIFEST_FILE);
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|err| io_err("read backup manifest", &path, err))?;
+    let manifest: BackupManifest = serde_json::from_slice(&bytes)
+        .map_err(|err| MnemeError::storage(format!("decode backup manifest: {err}")))?;
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(MnemeError::storage(format!(
+            "backup format {} is newer than supported version {BACKUP_FORMAT_VERSION}",
+            manifest.format_version
+        )));
+    }
+    Ok(manifest)
+}
+
+/// Restore the backup at `backup` into a new datastore under `base` (named `preferred_name`, or
+/// the default `praxis.sqlite`) and return its database path.
+///
+/// The copy is verified against the manifest first. `base` must not already hold a datastore
+/// with data; `datastore.json` is written with the backup's encryption mode.
+pub async fn restore_backup(
+    backup: &Path,
+    base: &Path,
+    preferred_name: Option<&str>,
+) -> MnemeResult<PathBuf> {
+    let manifest = read_manifest(backup).await?;
+    let source = backup.join(DATABASE_FILE);
+    let (bytes, hash) = hash_file(&source).await?;
+    if bytes != manifest.database_bytes || hash != manifest.database_hash {
+        return Err(MnemeError::storage(format!(
+            "backup '{}' is corrupt: database does not match its manifest",
+            backup.display()
+        )));
+    }
+
+    let name = preferred_name.unwrap_or(DEFAULT_DB_NAME);
+    let target = base.join(name);
+    if tokio::fs::metadata(&target)
+        .await
+        .is_ok_and(|meta| meta.len() > 0)
+    {
+        return Err(MnemeError::storage(format!(
+            "cannot restore into '{}': datastore already holds data",
+            target.display()
+        )));
+    }
+    if let Some(parent) = target.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|err| io_err("create datastore directory", parent, err))?;
+    }
+    let _lock = DatastoreLock::acquire(&target)?;
+    tokio::fs::copy(&source, &target)
+        .await
+        .map_err(|err| io_err("restore database", &target, err))?;
+    copy_tree(&backup_blob_dir(backup), &target.with_extension("blobs")).await?;
+
+    create_datastore(base, Some(name), Some(manifest.encryption))?;
+    Ok(target)
+}
+
+/// Blob directory inside a backup; mirrors the live store's layout.
+pub(crate) fn backup_blob_dir(backup: &Path) -> PathBuf {
+    backup.join(BLOBS_DIR)
+}
+
+pub(crate) async fn write_manifest(dir: &Path, manifest: &BackupManifest) -> MnemeResult<()> {
+    let path = dir.join(MANIFEST_FILE);
+    let json = serde_json::to_vec_pretty(manifest)
+        .map_err(|err| MnemeError::storage(format!("encode backup manifest: {err}")))?;
+    tokio::fs::write(&path, json)
+        .await
+        .map_err(|err| io_err("write backup manifest", &path, err))
+}
+
+/// Size and BLAKE3 hex digest of the file at `path`.
+pub(crate) async fn hash_file(path: &Path) -> MnemeResult<(u64, String)> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|err| io_err("open", path, err))?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = vec![0u8; HASH_CHUNK_BYTES];
+    let mut size = 0u64;
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|err| io_err("read", path, err))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        size += read as u64;
+    }
+    Ok((size, hasher.finalize().to_hex().to_string()))
+}
+
+/// Copy every file below `from` into `to`, keeping relative paths. A missing `from` is empty.
+pub(crate) async fn copy_tree(from: &Path, to: &Path) -> MnemeResult<()> {
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        let dir = from.join(&relative);
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(io_err("read backup blobs", &dir, err)),
+        };
+        tokio::fs::create_dir_all(to.join(&relative))
+            .await
+            .map_err(|err| io_err("create blob directory", &to.join(&relative), err))?;
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|err| io_err("read backup blobs", &dir, err))?
+        {
+            let child = relative.join(entry.file_name());
+            let kind = entry
+                .file_type()
+                .await
+                .map_err(|err| io_err("read backup blobs", &entry.path(), err))?;
+            if kind.is_dir() {
+                pending.push(child);
+            } else {
+                tokio::fs::copy(entry.path(), to.join(&child))
+                    .await
+                    .map_err(|err| io_err("restore blob", &to.join(&child), err))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn io_err(action: &str, path: &Path, err: std::io::Error) -> MnemeError {
+    MnemeError::storage(format!("{action} '{}': {err}", path.display()))
+}
@@ -0,0 +1,222 @@
+//! Content-addressed blob storage for `Blob` attributes.
+//!
+//! Blob bytes live outside commits and are addressed by their BLAKE3 hash. Attribute values hold
+//! only a [`BlobRef`] (`"blake3:<64 hex>"`), so a node touched by many commits never duplicates
+//! its payload. Only values of attributes declared `Blob` are references: the writer lists them
+//! in [`PersistedCommit::blobs`](crate::PersistedCommit::blobs), and a blob's reference count is
+//! the number of stored commits that list it.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::pin::Pin;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::temporal::ChangeSet;
+use crate::{MnemeError, MnemeResult};
+
+/// Prefix of a serialised [`BlobRef`].
+pub const BLOB_REF_PREFIX: &str = "blake3:";
+
+/// Bytes read per chunk when hashing an upload stream.
+pub(crate) const BLOB_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Reference to blob content, serialised as `blake3:<hex>`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct BlobRef {
+    hash: String,
+}
+
+impl BlobRef {
+    /// Reference for `bytes` without storing them.
+    pub fn for_bytes(bytes: &[u8]) -> Self {
+        Self::from_hash(blake3::hash(bytes))
+    }
+
+    pub(crate) fn from_hash(hash: blake3::Hash) -> Self {
+        Self {
+            hash: hash.to_hex().to_string(),
+        }
+    }
+
+    /// Parse `blake3:<64 lowercase hex>`; anything else is not a blob reference.
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::from_hex(value.strip_prefix(BLOB_REF_PREFIX)?)
+    }
+
+    /// Reference from a bare hex hash, as stored by backends.
+    pub(crate) fn from_hex(hash: &str) -> Option<Self> {
+        let valid = hash.len() == 64
+            && hash
+                .bytes()
+                .all(|byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte));
+        valid.then(|| Self { hash: hash.into() })
+    }
+
+    /// Hex-encoded BLAKE3 hash.
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+}
+
+impl fmt::Display for BlobRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{BLOB_REF_PREFIX}{}", self.hash)
+    }
+}
+
+impl TryFrom<String> for BlobRef {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value).ok_or_else(|| format!("invalid blob reference '{value}'"))
+    }
+}
+
+impl From<BlobRef> for String {
+    fn from(value: BlobRef) -> Self {
+        value.to_string()
+    }
+}
+
+/// Stored blob metadata.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobInfo {
+    pub blob: BlobRef,
+    pub size: u64,
+    /// Stored commits whose change sets reference the blob.
+    pub ref_count: u64,
+    pub created_at_ms: i64,
+}
+
+/// Streaming reader over blob content.
+pub type BlobReader = Pin<Box<dyn AsyncRead + Send>>;
+
+/// Upload, stream, and inspect content-addressed blobs. Storing the same bytes twice is a no-op.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Store everything `reader` yields, hashing it on the way in.
+    async fn put_blob_stream(
+        &self,
+        reader: &mut (dyn AsyncRead + Unpin + Send),
+    ) -> MnemeResult<BlobInfo>;
+    /// Open a stream over the blob's bytes, or `None` when it is not stored.
+    async fn open_blob(&self, blob: &BlobRef) -> MnemeResult<Option<BlobReader>>;
+    async fn blob_info(&self, blob: &BlobRef) -> MnemeResult<Option<BlobInfo>>;
+    /// Every stored blob, ordered by hash.
+    async fn list_blobs(&self) -> MnemeResult<Vec<BlobInfo>>;
+
+    async fn put_blob(&self, bytes: &[u8]) -> MnemeResult<BlobInfo> {
+        let mut reader = bytes;
+        self.put_blob_stream(&mut reader).await
+    }
+
+    async fn read_blob(&self, blob: &BlobRef) -> MnemeResult<Option<Vec<u8>>> {
+        let Some(mut reader) = self.open_blob(blob).await? else {
+            return Ok(None);
+        };
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|err| MnemeError::storage(format!("read blob '{blob}': {err}")))?;
+        Ok(Some(bytes))
+    }
+}
+
+/// The `blobs` a folded change set still holds: those some node or edge attribute is still set
+/// to. Used when commits are collapsed, so only the latest value of each attribute keeps a blob.
+pub(crate) fn retained_blob_refs<'a>(
+    blobs: impl IntoIterator<Item = &'a BlobRef>,
+    change_set: &ChangeSet,
+) -> BTreeSet<BlobRef> {
+    let values: BTreeSet<&str> = change_set
+        .node_creates
+        .iter()
+        .chain(change_set.node_updates.iter())
+        .filter_map(|node| node.props.as_ref())
+        .chain(
+            change_set
+                .edge_creates
+                .iter()
+                .chain(change_set.edge_updates.iter())
+                .filter_map(|edge| edge.props.as_ref()),
+        )
+        .filter_map(Value::as_object)
+        .flat_map(|props| props.values().filter_map(Value::as_str))
+        .collect();
+    blobs
+        .into_iter()
+        .filter(|blob| values.contains(blob.to_string().as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Copy `reader` into `writer` in chunks, hashing the bytes on the way through.
+pub(crate) async fn hash_stream(
+    reader: &mut (dyn AsyncRead + Unpin + Send),
+    writer: &mut (impl AsyncWrite + Unpin),
+) -> MnemeResult<(BlobRef, u64)> {
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = vec![0u8; BLOB_CHUNK_BYTES];
+    let mut size = 0u64;
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .await
+            .map_err(|err| MnemeError::storage(format!("read blob upload: {err}")))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        writer
+            .write_all(&buffer[..read])
+            .await
+            .map_err(|err| MnemeError::storage(format!("write blob: {err}")))?;
+        size += read as u64;
+    }
+    writer
+        .flush()
+        .await
+        .map_err(|err| MnemeError::storage(format!("write blob: {err}")))?;
+    Ok((BlobRef::from_hash(hasher.finalize()), size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temporal::NodeVersion;
+
+    #[test]
+    fn parses_only_well_formed_references() {
+        let blob = BlobRef::for_bytes(b"hello");
+        assert_eq!(BlobRef::parse(&blob.to_string()), Some(blob.clone()));
+        assert_eq!(blob.hash().len(), 64);
+        assert!(BlobRef::parse(blob.hash()).is_none());
+        assert!(BlobRef::parse(&format!("blake3:{}", blob.hash().to_uppercase())).is_none());
+        assert!(BlobRef::parse("blake3:abc").is_none());
+    }
+
+    #[test]
+    fn keeps_only_blobs_an_attribute_still_holds() {
+        let kept = BlobRef::for_bytes(b"one");
+        let replaced = BlobRef::for_bytes(b"two");
+        let change_set = ChangeSet {
+            node_creates: vec![NodeVersion {
+                id: "n1".into(),
+                r#type: None,
+                props: Some(serde_json::json!({ "diagram": kept.to_string() })),
+            }],
+            ..ChangeSet::default()
+        };
+        assert_eq!(
+            retained_blob_refs([&kept, &replaced], &change_set),
+            BTreeSet::from([kept])
+        );
+    }
+}
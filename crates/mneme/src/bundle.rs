@@ -0,0 +1,241 @@
+//! Portable bundles of commits together with the blobs they reference.
+//!
+//! A bundle is a directory:
+//!
+//! ```text
+//! manifest.json        BundleManifest
+//! commits.jsonl        one PersistedCommit per line, parents before children
+//! blobs/<hash>         raw blob content
+//! ```
+//!
+//! Importing verifies every blob against its hash before writing commits, skips commits the
+//! target store already holds, and leaves branch refs alone so the caller decides what to move.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::blob::{BlobRef, BlobStore};
+use crate::temporal::CommitId;
+use crate::{MnemeError, MnemeResult, PersistedCommit, Store};
+
+/// Current bundle layout version; readers reject anything newer.
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const COMMITS_FILE: &str = "commits.jsonl";
+const BLOBS_DIR: &str = "blobs";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleManifest {
+    pub format_version: u32,
+    /// Commits the bundle was exported from; everything they reach is included.
+    pub heads: Vec<CommitId>,
+    /// Every commit in the bundle, parents before children.
+    pub commits: Vec<CommitId>,
+    pub blobs: Vec<BundledBlob>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundledBlob {
+    pub blob: BlobRef,
+    pub size: u64,
+}
+
+/// Outcome of [`import_bundle`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImport {
+    pub imported_commits: Vec<CommitId>,
+    /// Commits already present in the target store.
+    pub skipped_commits: Vec<CommitId>,
+    pub imported_blobs: u64,
+}
+
+/// Write `heads`, every commit they reach, and every blob those commits reference to `dir`.
+pub async fn export_bundle(
+    store: &dyn Store,
+    heads: &[CommitId],
+    dir: &Path,
+) -> MnemeResult<BundleManifest> {
+    let commits = collect_history(store, heads).await?;
+    let referenced: BTreeSet<BlobRef> = commits
+        .iter()
+        .flat_map(|commit| commit.blobs.iter().cloned())
+        .collect();
+
+    let blob_dir = dir.join(BLOBS_DIR);
+    tokio::fs::create_dir_all(&blob_dir)
+        .await
+        .map_err(|err| io_err("create bundle directory", &blob_dir, err))?;
+    let mut blobs = Vec::with_capacity(referenced.len());
+    if !referenced.is_empty() {
+        let source = blob_store(store)?;
+        for blob in referenced {
+            let mut reader = source.open_blob(&blob).await?.ok_or_else(|| {
+                MnemeError::storage(format!("bundle references missing blob '{blob}'"))
+            })?;
+            let path = blob_dir.join(blob.hash());
+            let mut file = tokio::fs::File::create(&path)
+                .await
+                .map_err(|err| io_err("create bundle blob", &path, err))?;
+            let size = tokio::io::copy(&mut reader, &mut file)
+                .await
+                .map_err(|err| io_err("write bundle blob", &path, err))?;
+            file.flush()
+                .await
+                .map_err(|err| io_err("write bundle blob", &path, err))?;
+            blobs.push(BundledBlob { blob, size });
+        }
+    }
+
+    let mut lines = String::new();
+    for commit in &commits {
+        lines.push_str(&to_json(commit, "bundle commit")?);
+        lines.push('\n');
+    }
+    write_file(&dir.join(COMMITS_FILE), lines.as_bytes()).await?;
+    let manifest = BundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        heads: heads.to_vec(),
+        commits: commits
+            .iter()
+            .map(|commit| commit.summary.id.clone())
+            .collect(),
+        blobs,
+    };
+    write_file(
+        &dir.join(MANIFEST_FILE),
+        to_json(&manifest, "bundle manifest")?.as_bytes(),
+    )
+    .await?;
+    Ok(manifest)
+}
+
+/// Load the bundle at `dir` into `store`: blobs first, then commits in bundle order.
+pub async fn import_bundle(store: &dyn Store, dir: &Path) -> MnemeResult<BundleImport> {
+    let manifest = read_manifest(dir).await?;
+    let mut report = BundleImport::default();
+    if !manifest.blobs.is_empty() {
+        let target = blob_store(store)?;
+        for bundled in &manifest.blobs {
+            let path = dir.join(BLOBS_DIR).join(bundled.blob.hash());
+            let mut file = tokio::fs::File::open(&path)
+                .await
+                .map_err(|err| io_err("open bundle blob", &path, err))?;
+            let stored = target.put_blob_stream(&mut file).await?;
+            if stored.blob != bundled.blob {
+                return Err(MnemeError::storage(format!(
+                    "bundle blob '{}' is corrupt: content hashes to '{}'",
+                    bundled.blob, stored.blob
+                )));
+            }
+            report.imported_blobs += 1;
+        }
+    }
+
+    let path = dir.join(COMMITS_FILE);
+    let file = tokio::fs::File::open(&path)
+        .await
+        .map_err(|err| io_err("open bundle commits", &path, err))?;
+    let mut lines = BufReader::new(file).lines();
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .map_err(|err| io_err("read bundle commits", &path, err))?
+    {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let commit: PersistedCommit = serde_json::from_str(&line)
+            .map_err(|err| MnemeError::storage(format!("decode bundle commit: {err}")))?;
+        let id = commit.summary.id.clone();
+        if store.get_commit(&id).await?.is_some() {
+            report.skipped_commits.push(id);
+            continue;
+        }
+        store.put_commit(&commit).await?;
+        report.imported_commits.push(id);
+    }
+    Ok(report)
+}
+
+/// Read and version-check a bundle's manifest.
+pub async fn read_manifest(dir: &Path) -> MnemeResult<BundleManifest> {
+    let path = dir.join(MANIFEST_FILE);
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|err| io_err("read bundle manifest", &path, err))?;
+    let manifest: BundleManifest = serde_json::from_slice(&bytes)
+        .map_err(|err| MnemeError::storage(format!("decode bundle manifest: {err}")))?;
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(MnemeError::storage(format!(
+            "bundle format {} is newer than supported version {BUNDLE_FORMAT_VERSION}",
+            manifest.format_version
+        )));
+    }
+    Ok(manifest)
+}
+
+/// Every commit reachable from `heads`, parents before children.
+async fn collect_history(
+    store: &dyn Store,
+    heads: &[CommitId],
+) -> MnemeResult<Vec<PersistedCommit>> {
+    let mut ordered = Vec::new();
+    let mut done: HashSet<CommitId> = HashSet::new();
+    // Iterative post-order walk: a commit is emitted once all of its parents have been.
+    let mut stack: Vec<(CommitId, bool)> =
+        heads.iter().rev().map(|id| (id.clone(), false)).collect();
+    let mut loaded: HashMap<CommitId, PersistedCommit> = HashMap::new();
+    while let Some((id, expanded)) = stack.pop() {
+        if done.contains(&id) {
+            continue;
+        }
+        if expanded {
+            done.insert(id.clone());
+            ordered.push(loaded.remove(&id).expect("expanded commits are loaded"));
+            continue;
+        }
+        let commit = match loaded.get(&id) {
+            Some(commit) => commit.clone(),
+            None => store
+                .get_commit(&id)
+                .await?
+                .ok_or_else(|| MnemeError::storage(format!("unknown commit '{id}'")))?,
+        };
+        stack.push((id.clone(), true));
+        for parent in commit.summary.parents.iter().rev() {
+            if !done.contains(parent) {
+                stack.push((parent.clone(), false));
+            }
+        }
+        loaded.insert(id, commit);
+    }
+    Ok(ordered)
+}
+
+fn blob_store(store: &dyn Store) -> MnemeResult<&dyn BlobStore> {
+    store
+        .blobs()
+        .ok_or_else(|| MnemeError::storage("store has no blob storage"))
+}
+
+fn to_json<T: Serialize>(value: &T, label: &str) -> MnemeResult<String> {
+    serde_json::to_string(value)
+        .map_err(|err| MnemeError::storage(format!("encode {label}: {err}")))
+}
+
+async fn write_file(path: &Path, bytes: &[u8]) -> MnemeResult<()> {
+    tokio::fs::write(path, bytes)
+        .await
+        .map_err(|err| io_err("write", path, err))
+}
+
+fn io_err(action: &str, path: &Path, err: std::io::Error) -> MnemeError {
+    MnemeError::storage(format!("{action} '{}': {err}", path.display()))
+}
@@ -0,0 +1,902 @@
+//! Behavioural conformance suite for [`Store`] implementations.
+//!
+//! Every backend (and any third-party adapter) should pass [`run_all`] against a freshly opened,
+//! empty store. Checks panic with a descriptive message on the first divergence, so they can be
+//! called directly from `#[tokio::test]` functions:
+//!
+//! ```ignore
+//! #[tokio::test(flavor = "multi_thread")]
+//! async fn my_store_conforms() {
+//!     aideon_mneme::conformance::run_all(Arc::new(MyStore::new())).await;
+//! }
+//! ```
+//!
+//! Individual checks are public too, so adapters can opt out of one while a fix is pending.
+//!
+//! The suite panics by design, so it is only compiled with the `conformance` feature; enable it
+//! on the `aideon_mneme` dev-dependency.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::blob::BlobRef;
+use crate::search::{HIGHLIGHT_OPEN, SearchQuery};
+use crate::temporal::{
+    ChangeSet, CommitSummary, EdgeTombstone, EdgeVersion, NodeTombstone, NodeVersion,
+};
+use crate::versions::{ElementRef, ElementState, GraphFilter};
+use crate::{
+    ChangeEvent, ChangeSubscription, CommitBatch, MnemeError, PersistedCommit, RefUpdate, Store,
+};
+
+/// Number of tasks racing each other in the concurrency checks.
+const CONCURRENT_WRITERS: usize = 8;
+
+/// How long a change-feed check waits for an expected event.
+const FEED_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Run every conformance check against an empty store.
+pub async fn run_all(store: Arc<dyn Store>) {
+    commits_round_trip(store.as_ref()).await;
+    duplicate_commits_are_rejected(store.as_ref()).await;
+    ensure_branch_is_idempotent(store.as_ref()).await;
+    compare_and_swap_semantics(store.as_ref()).await;
+    branches_are_listed_in_name_order(store.as_ref()).await;
+    tags_upsert_and_list_in_order(store.as_ref()).await;
+    commit_batches_are_atomic(store.as_ref()).await;
+    change_feed_reports_writes(store.as_ref()).await;
+    graph_state_follows_lineage(store.as_ref()).await;
+    search_finds_nodes_by_props(store.as_ref()).await;
+    blobs_are_content_addressed(store.as_ref()).await;
+    concurrent_swaps_have_one_winner(store.clone()).await;
+    concurrent_commits_are_all_persisted(store).await;
+}
+
+/// Build a small commit with a single node create; ids are caller-supplied so checks never clash.
+pub fn sample_commit(id: &str, parents: &[&str]) -> PersistedCommit {
+    PersistedCommit {
+        summary: CommitSummary {
+            id: id.into(),
+            parents: parents.iter().map(|p| p.to_string()).collect(),
+            branch: "main".into(),
+            author: Some("conformance".into()),
+            time: Some("2025-01-01T00:00:00Z".into()),
+            message: format!("conformance commit {id}"),
+            tags: vec!["conformance".into()],
+            change_count: 1,
+        },
+        change_set: ChangeSet {
+            node_creates: vec![NodeVersion {
+                id: format!("node-{id}"),
+                r#type: Some("Capability".into()),
+                props: Some(serde_json::json!({ "name": id })),
+            }],
+            ..ChangeSet::default()
+        },
+        blobs: Default::default(),
+    }
+}
+
+/// Stored commits come back unchanged and unknown ids resolve to `None`.
+pub async fn commits_round_trip(store: &dyn Store) {
+    let commit = sample_commit("conf-roundtrip-1", &[]);
+    store.put_commit(&commit).await.expect("put_commit");
+    let loaded = store
+        .get_commit("conf-roundtrip-1")
+        .await
+        .expect("get_commit")
+        .expect("stored commit is returned");
+    assert_eq!(
+        serde_json::to_value(&loaded).unwrap(),
+        serde_json::to_value(&commit).unwrap(),
+        "commit must round-trip unchanged"
+    );
+    assert!(
+        store
+            .get_commit("conf-missing")
+            .await
+            .expect("get_commit")
+            .is_none(),
+        "unknown commit must resolve to None"
+    );
+}
+
+/// A second `put_commit` with the same id fails with `Storage("commit '<id>' already exists")`
+/// and leaves the original untouched.
+pub async fn duplicate_commits_are_rejected(store: &dyn Store) {
+    let original = sample_commit("conf-dup-1", &[]);
+    store.put_commit(&original).await.expect("first put_commit");
+    let mut duplicate = original.clone();
+    duplicate.summary.message = "overwrite attempt".into();
+    let err = store
+        .put_commit(&duplicate)
+        .await
+        .expect_err("duplicate commit must be rejected");
+    match &err {
+        MnemeError::Storage { message } => assert!(
+            message.contains("commit 'conf-dup-1' already exists"),
+            "unexpected duplicate message: {message}"
+        ),
+        other => panic!("duplicate commit should be a storage error, got {other:?}"),
+    }
+    let stored = store
+        .get_commit("conf-dup-1")
+        .await
+        .expect("get_commit")
+        .expect("original kept");
+    assert_eq!(stored.summary.message, original.summary.message);
+}
+
+/// `ensure_branch` creates an empty branch once and never resets an existing head.
+pub async fn ensure_branch_is_idempotent(store: &dyn Store) {
+    store.ensure_branch("conf-ensure").await.expect("ensure");
+    assert_eq!(store.get_branch_head("conf-ensure").await.unwrap(), None);
+    store
+        .compare_and_swap_branch("conf-ensure", None, Some("conf-roundtrip-1"))
+        .await
+        .expect("advance branch");
+    store.ensure_branch("conf-ensure").await.expect("re-ensure");
+    assert_eq!(
+        store
+            .get_branch_head("conf-ensure")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("conf-roundtrip-1"),
+        "ensure_branch must not reset an existing head"
+    );
+    assert_eq!(
+        store.get_branch_head("conf-never-created").await.unwrap(),
+        None
+    );
+}
+
+/// CAS succeeds only when `expected` matches the current head, reports the actual head on
+/// conflict, creates unknown branches when `expected` is `None`, and can reset a head to `None`.
+pub async fn compare_and_swap_semantics(store: &dyn Store) {
+    store
+        .compare_and_swap_branch("conf-cas", None, Some("c1"))
+        .await
+        .expect("CAS on unknown branch with expected None creates it");
+    assert_eq!(
+        store.get_branch_head("conf-cas").await.unwrap().as_deref(),
+        Some("c1")
+    );
+    assert!(
+        store
+            .list_branches()
+            .await
+            .unwrap()
+            .iter()
+            .any(|(name, _)| name == "conf-cas"),
+        "branch created by CAS must be listed"
+    );
+
+    let err = store
+        .compare_and_swap_branch("conf-cas", None, Some("c2"))
+        .await
+        .expect_err("stale expectation must conflict");
+    match err {
+        MnemeError::ConcurrencyConflict {
+            branch,
+            expected,
+            actual,
+        } => {
+            assert_eq!(branch, "conf-cas");
+            assert_eq!(expected, None);
+            assert_eq!(actual.as_deref(), Some("c1"));
+        }
+        other => panic!("expected ConcurrencyConflict, got {other:?}"),
+    }
+    assert_eq!(
+        store.get_branch_head("conf-cas").await.unwrap().as_deref(),
+        Some("c1"),
+        "failed CAS must not move the branch"
+    );
+
+    store
+        .compare_and_swap_branch("conf-cas", Some("c1"), Some("c2"))
+        .await
+        .expect("CAS with matching expectation");
+    store
+        .compare_and_swap_branch("conf-cas", Some("c2"), None)
+        .await
+        .expect("CAS back to empty");
+    assert_eq!(store.get_branch_head("conf-cas").await.unwrap(), None);
+    let err = store
+        .compare_and_swap_branch("conf-missing-branch", Some("c1"), Some("c2"))
+        .await
+        .expect_err("unknown branch has no head to match");
+    assert!(matches!(
+        err,
+        MnemeError::ConcurrencyConflict { actual: None, .. }
+    ));
+}
+
+/// `list_branches` is ordered by branch name regardless of creation order.
+pub async fn branches_are_listed_in_name_order(store: &dyn Store) {
+    for name in ["conf-order-c", "conf-order-a", "conf-order-b"] {
+        store.ensure_branch(name).await.expect("ensure");
+    }
+    let names: Vec<String> = store
+        .list_branches()
+        .await
+        .expect("list_branches")
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    let mut sorted = names.clone();
+    sorted.sort();
+    assert_eq!(names, sorted, "branches must be listed in name order");
+}
+
+/// Tags upsert (moving an existing tag), resolve missing tags to `None`, and list in tag order.
+pub async fn tags_upsert_and_list_in_order(store: &dyn Store) {
+    store.put_tag("conf-tag-b", "c1").await.expect("put_tag");
+    store.put_tag("conf-tag-a", "c1").await.expect("put_tag");
+    store.put_tag("conf-tag-b", "c2").await.expect("move tag");
+    assert_eq!(
+        store.get_tag("conf-tag-b").await.unwrap().as_deref(),
+        Some("c2")
+    );
+    assert_eq!(store.get_tag("conf-tag-missing").await.unwrap(), None);
+    let tags = store.list_tags().await.expect("list_tags");
+    let mut sorted = tags.clone();
+    sorted.sort();
+    assert_eq!(tags, sorted, "tags must be listed in tag order");
+    assert_eq!(
+        tags.iter().filter(|(tag, _)| tag == "conf-tag-b").count(),
+        1,
+        "moving a tag must not duplicate it"
+    );
+}
+
+/// `put_commits` writes commits, tags and branch moves together; a batch that fails on a
+/// duplicate commit or a stale branch expectation leaves no trace.
+pub async fn commit_batches_are_atomic(store: &dyn Store) {
+    let batch = CommitBatch {
+        commits: vec![
+            sample_commit("conf-batch-1", &[]),
+            sample_commit("conf-batch-2", &["conf-batch-1"]),
+        ],
+        tags: vec![("conf-batch-tag".into(), "conf-batch-2".into())],
+        refs: vec![RefUpdate {
+            branch: "conf-batch".into(),
+            expected: None,
+            next: Some("conf-batch-2".into()),
+        }],
+    };
+    store.put_commits(&batch).await.expect("put_commits");
+    assert!(store.get_commit("conf-batch-1").await.unwrap().is_some());
+    assert_eq!(
+        store.get_tag("conf-batch-tag").await.unwrap().as_deref(),
+        Some("conf-batch-2")
+    );
+    assert_eq!(
+        store
+            .get_branch_head("conf-batch")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("conf-batch-2")
+    );
+
+    let stale = CommitBatch {
+        commits: vec![sample_commit("conf-batch-3", &["conf-batch-2"])],
+        tags: vec![("conf-batch-tag".into(), "conf-batch-3".into())],
+        refs: vec![RefUpdate {
+            branch: "conf-batch".into(),
+            expected: Some("conf-batch-1".into()),
+            next: Some("conf-batch-3".into()),
+        }],
+    };
+    let err = store
+        .put_commits(&stale)
+        .await
+        .expect_err("stale expectation must fail the batch");
+    assert!(
+        matches!(err, MnemeError::ConcurrencyConflict { ref actual, .. } if actual.as_deref() == Some("conf-batch-2")),
+        "expected ConcurrencyConflict, got {err:?}"
+    );
+    let duplicate = CommitBatch {
+        commits: vec![
+            sample_commit("conf-batch-4", &["conf-batch-2"]),
+            sample_commit("conf-batch-1", &[]),
+        ],
+        ..CommitBatch::default()
+    };
+    store
+        .put_commits(&duplicate)
+        .await
+        .expect_err("duplicate commit must fail the batch");
+
+    for id in ["conf-batch-3", "conf-batch-4"] {
+        assert!(
+            store.get_commit(id).await.unwrap().is_none(),
+            "failed batch must not persist {id}"
+        );
+    }
+    assert_eq!(
+        store.get_tag("conf-batch-tag").await.unwrap().as_deref(),
+        Some("conf-batch-2"),
+        "failed batch must not move tags"
+    );
+    assert_eq!(
+        store
+            .get_branch_head("conf-batch")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("conf-batch-2"),
+        "failed batch must not move branches"
+    );
+}
+
+/// Writers racing to advance the same head: exactly one wins, the rest see a conflict.
+pub async fn concurrent_swaps_have_one_winner(store: Arc<dyn Store>) {
+    store
+        .ensure_branch("conf-race")
+        .await
+        .expect("ensure race branch");
+    let mut handles = Vec::new();
+    for idx in 0..CONCURRENT_WRITERS {
+        let store = store.clone();
+        handles.push(tokio::spawn(async move {
+            let next = format!("race-{idx}");
+            store
+                .compare_and_swap_branch("conf-race", None, Some(&next))
+                .await
+                .map(|_| next)
+        }));
+    }
+    let mut winners = Vec::new();
+    for handle in handles {
+        match handle.await.expect("writer task") {
+            Ok(next) => winners.push(next),
+            Err(MnemeError::ConcurrencyConflict { .. }) => {}
+            Err(other) => panic!("racing CAS must fail with a conflict, got {other:?}"),
+        }
+    }
+    assert_eq!(winners.len(), 1, "exactly one racing CAS may succeed");
+    assert_eq!(
+        store.get_branch_head("conf-race").await.unwrap(),
+        winners.pop(),
+        "branch must point at the winning writer"
+    );
+}
+
+/// Writers persisting distinct commits at the same time all succeed.
+pub async fn concurrent_commits_are_all_persisted(store: Arc<dyn Store>) {
+    let mut handles = Vec::new();
+    for idx in 0..CONCURRENT_WRITERS {
+        let store = store.clone();
+        handles.push(tokio::spawn(async move {
+            store
+                .put_commit(&sample_commit(&format!("conf-par-{idx}"), &[]))
+                .await
+        }));
+    }
+    for handle in handles {
+        handle
+            .await
+            .expect("writer task")
+            .expect("concurrent put_commit");
+    }
+    for idx in 0..CONCURRENT_WRITERS {
+        assert!(
+            store
+                .get_commit(&format!("conf-par-{idx}"))
+                .await
+                .unwrap()
+                .is_some(),
+            "commit conf-par-{idx} missing after concurrent writes"
+        );
+    }
+}
+
+/// Subscribers see commit, tag, and ref events with their commit summary, and a subscription
+/// resumed from the first event's cursor replays all three.
+pub async fn change_feed_reports_writes(store: &dyn Store) {
+    let mut live = store.subscribe(None).await.expect("subscribe");
+    store
+        .put_commit(&sample_commit("conf-feed-1", &[]))
+        .await
+        .expect("put_commit");
+    store
+        .put_tag("conf-feed-tag", "conf-feed-1")
+        .await
+        .expect("put_tag");
+    store
+        .compare_and_swap_branch("conf-feed", None, Some("conf-feed-1"))
+        .await
+        .expect("CAS");
+
+    let first = next_event(&mut live).await;
+    let cursor = first.at_ms();
+    assert!(
+        matches!(&first, ChangeEvent::CommitPersisted { commit, .. } if commit.id == "conf-feed-1"),
+        "expected commit event first, got {first:?}"
+    );
+    let tag = next_event(&mut live).await;
+    assert!(
+        matches!(&tag, ChangeEvent::TagChanged { tag, commit: Some(commit), .. }
+            if tag == "conf-feed-tag" && commit.id == "conf-feed-1"),
+        "expected tag event, got {tag:?}"
+    );
+    let moved = next_event(&mut live).await;
+    assert!(
+        matches!(&moved, ChangeEvent::RefMoved { branch, previous: None, head: Some(head), commit: Some(commit), .. }
+            if branch == "conf-feed" && head == "conf-feed-1" && commit.id == "conf-feed-1"),
+        "expected ref event, got {moved:?}"
+    );
+    assert!(
+        cursor <= tag.at_ms() && tag.at_ms() <= moved.at_ms(),
+        "event cursors must not go backwards"
+    );
+
+    let mut resumed = store.subscribe(Some(cursor)).await.expect("resubscribe");
+    let mut pending = vec![first, tag, moved];
+    while !pending.is_empty() {
+        let event = next_event(&mut resumed).await;
+        assert!(
+            event.at_ms() >= cursor,
+            "replayed event before cursor: {event:?}"
+        );
+        pending.retain(|expected| expected != &event);
+    }
+}
+
+/// Point-in-time state and element history follow the first-parent lineage, including forks off
+/// another branch and a second child of an older commit on the same branch.
+pub async fn graph_state_follows_lineage(store: &dyn Store) {
+    let node = |id: &str, ty: &str, rev: u64| NodeVersion {
+        id: format!("conf-ver-{id}"),
+        r#type: Some(ty.into()),
+        props: Some(serde_json::json!({ "rev": rev })),
+    };
+    let edge = |id: Option<&str>, from: &str, to: &str, rev: u64| EdgeVersion {
+        id: id.map(|id| format!("conf-ver-{id}")),
+        from: format!("conf-ver-{from}"),
+        to: format!("conf-ver-{to}"),
+        r#type: Some("serves".into()),
+        props: Some(serde_json::json!({ "rev": rev })),
+        ..EdgeVersion::default()
+    };
+    let commit = |id: &str, parent: Option<&str>, branch: &str, change_set: ChangeSet| {
+        let mut commit = sample_commit(id, parent.as_slice());
+        commit.summary.branch = branch.into();
+        commit.change_set = change_set;
+        commit
+    };
+    let writes = [
+        commit(
+            "conf-ver-1",
+            None,
+            "main",
+            ChangeSet {
+                node_creates: vec![node("a", "A", 1), node("b", "B", 1), node("c", "A", 1)],
+                edge_creates: vec![edge(Some("e1"), "a", "b", 1), edge(None, "b", "c", 1)],
+                ..ChangeSet::default()
+            },
+        ),
+        commit(
+            "conf-ver-2",
+            Some("conf-ver-1"),
+            "main",
+            ChangeSet {
+                node_updates: vec![node("a", "A", 2)],
+                node_deletes: vec![NodeTombstone {
+                    id: "conf-ver-c".into(),
+                }],
+                edge_deletes: vec![EdgeTombstone {
+                    from: "conf-ver-b".into(),
+                    to: "conf-ver-c".into(),
+                }],
+                ..ChangeSet::default()
+            },
+        ),
+        commit(
+            "conf-ver-fork",
+            Some("conf-ver-1"),
+            "conf-ver-branch",
+            ChangeSet {
+                node_creates: vec![node("d", "A", 1)],
+                edge_updates: vec![edge(Some("e1"), "a", "b", 2)],
+                ..ChangeSet::default()
+            },
+        ),
+        commit(
+            "conf-ver-3",
+            Some("conf-ver-2"),
+            "main",
+            ChangeSet {
+                node_deletes: vec![NodeTombstone {
+                    id: "conf-ver-b".into(),
+                }],
+                edge_deletes: vec![EdgeTombstone {
+                    from: "conf-ver-a".into(),
+                    to: "conf-ver-b".into(),
+                }],
+                ..ChangeSet::default()
+            },
+        ),
+        commit(
+            "conf-ver-2b",
+            Some("conf-ver-1"),
+            "main",
+            ChangeSet {
+                node_updates: vec![node("c", "A", 2)],
+                ..ChangeSet::default()
+            },
+        ),
+    ];
+    for write in &writes {
+        store.put_commit(write).await.expect("put_commit");
+    }
+
+    let all = GraphFilter::default();
+    let state = |commit_id: &'static str, filter: GraphFilter| async move {
+        let state = store
+            .graph_state(commit_id, &filter)
+            .await
+            .expect("graph_state");
+        assert_eq!(state.commit_id, commit_id);
+        let nodes: Vec<(String, u64)> = state
+            .nodes
+            .iter()
+            .map(|node| (node.id.replace("conf-ver-", ""), rev(&node.props)))
+            .collect();
+        let edges: Vec<(String, u64)> = state
+            .edges
+            .iter()
+            .map(|edge| {
+                (
+                    format!("{}->{}", edge.from, edge.to).replace("conf-ver-", ""),
+                    rev(&edge.props),
+                )
+            })
+            .collect();
+        (nodes, edges)
+    };
+    let pairs = |items: &[(&str, u64)]| -> Vec<(String, u64)> {
+        items
+            .iter()
+            .map(|(id, rev)| (id.to_string(), *rev))
+            .collect()
+    };
+
+    assert_eq!(
+        state("conf-ver-1", all.clone()).await,
+        (
+            pairs(&[("a", 1), ("b", 1), ("c", 1)]),
+            pairs(&[("b->c", 1), ("a->b", 1)])
+        ),
+        "state at the root commit"
+    );
+    assert_eq!(
+        state("conf-ver-2", all.clone()).await,
+        (pairs(&[("a", 2), ("b", 1)]), pairs(&[("a->b", 1)])),
+        "updates and deletes apply along the lineage"
+    );
+    assert_eq!(
+        state("conf-ver-3", all.clone()).await,
+        (pairs(&[("a", 2)]), pairs(&[])),
+        "edge tombstones remove edges by endpoints"
+    );
+    assert_eq!(
+        state("conf-ver-fork", all.clone()).await,
+        (
+            pairs(&[("a", 1), ("b", 1), ("c", 1), ("d", 1)]),
+            pairs(&[("b->c", 1), ("a->b", 2)])
+        ),
+        "a fork sees its base state and its own changes only"
+    );
+    assert_eq!(
+        state("conf-ver-2b", all.clone()).await,
+        (
+            pairs(&[("a", 1), ("b", 1), ("c", 2)]),
+            pairs(&[("b->c", 1), ("a->b", 1)])
+        ),
+        "a second child of an older commit does not see its sibling"
+    );
+    assert_eq!(
+        state(
+            "conf-ver-fork",
+            GraphFilter {
+                node_types: vec!["A".into()],
+                ..GraphFilter::default()
+            }
+        )
+        .await,
+        (pairs(&[("a", 1), ("c", 1), ("d", 1)]), pairs(&[])),
+        "type filters keep only edges between selected nodes"
+    );
+    assert_eq!(
+        state(
+            "conf-ver-1",
+            GraphFilter {
+                node_ids: vec!["conf-ver-a".into(), "conf-ver-b".into()],
+                ..GraphFilter::default()
+            }
+        )
+        .await,
+        (pairs(&[("a", 1), ("b", 1)]), pairs(&[("a->b", 1)])),
+        "id filters select a subgraph"
+    );
+
+    let history = |commit_id: &'static str, element: ElementRef| async move {
+        store
+            .element_history(commit_id, &element)
+            .await
+            .expect("element_history")
+            .into_iter()
+            .map(|version| {
+                let rev = match &version.element {
+                    ElementState::Node(node) => rev(&node.props),
+                    ElementState::Edge(edge) => rev(&edge.props),
+                };
+                (
+                    version.valid_from.replace("conf-ver-", ""),
+                    version.valid_to.map(|to| to.replace("conf-ver-", "")),
+                    rev,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let node_a = ElementRef::Node {
+        id: "conf-ver-a".into(),
+    };
+    assert_eq!(
+        history("conf-ver-3", node_a.clone()).await,
+        vec![
+            ("1".to_string(), Some("2".to_string()), 1),
+            ("2".to_string(), None, 2)
+        ],
+        "node history along main"
+    );
+    assert_eq!(
+        history("conf-ver-fork", node_a).await,
+        vec![("1".to_string(), None, 1)],
+        "node history ignores changes on other lineages"
+    );
+    let e1 = ElementRef::Edge {
+        id: "conf-ver-e1".into(),
+    };
+    assert_eq!(
+        history("conf-ver-fork", e1.clone()).await,
+        vec![
+            ("1".to_string(), Some("fork".to_string()), 1),
+            ("fork".to_string(), None, 2)
+        ],
+        "edge history across a fork"
+    );
+    assert_eq!(
+        history("conf-ver-3", e1).await,
+        vec![("1".to_string(), Some("3".to_string()), 1)],
+        "edge history ends at the tombstone"
+    );
+    assert_eq!(
+        history(
+            "conf-ver-2b",
+            ElementRef::EdgeBetween {
+                from: "conf-ver-b".into(),
+                to: "conf-ver-c".into(),
+            }
+        )
+        .await,
+        vec![("1".to_string(), None, 1)],
+        "endpoint history on a sibling lineage"
+    );
+    assert!(
+        store.graph_state("conf-ver-missing", &all).await.is_err(),
+        "unknown commits must be rejected"
+    );
+}
+
+/// Search matches every query term by prefix against node ids, types, and scalar props, as of a
+/// commit. Scores are backend-specific, so only the matched sets and limits are compared.
+pub async fn search_finds_nodes_by_props(store: &dyn Store) {
+    let node = |id: &str, ty: &str, props: serde_json::Value| NodeVersion {
+        id: format!("conf-search-{id}"),
+        r#type: Some(ty.into()),
+        props: Some(props),
+    };
+    let mut first = sample_commit("conf-search-1", &[]);
+    first.change_set = ChangeSet {
+        node_creates: vec![
+            node(
+                "crm",
+                "Application",
+                serde_json::json!({ "name": "Customer relationship management", "vendor": "Salesforce" }),
+            ),
+            node(
+                "erp",
+                "Application",
+                serde_json::json!({ "name": "Enterprise resource planning", "tags": ["finance"] }),
+            ),
+            node(
+                "sales",
+                "Capability",
+                serde_json::json!({ "name": "Sales management", "tier": 2 }),
+            ),
+        ],
+        ..ChangeSet::default()
+    };
+    let mut second = sample_commit("conf-search-2", &["conf-search-1"]);
+    second.change_set = ChangeSet {
+        node_updates: vec![node(
+            "erp",
+            "Application",
+            serde_json::json!({ "name": "Sales ledger", "tags": ["finance"] }),
+        )],
+        node_deletes: vec![NodeTombstone {
+            id: "conf-search-sales".into(),
+        }],
+        ..ChangeSet::default()
+    };
+    store.put_commit(&first).await.expect("put_commit");
+    store.put_commit(&second).await.expect("put_commit");
+
+    let search = |commit_id: &'static str, query: SearchQuery| async move {
+        let hits = store
+            .search_nodes(commit_id, &query)
+            .await
+            .expect("search_nodes");
+        for hit in &hits {
+            assert!(
+                hit.snippet.contains(HIGHLIGHT_OPEN),
+                "snippet highlights a match: {:?}",
+                hit.snippet
+            );
+        }
+        let mut ids: Vec<String> = hits
+            .into_iter()
+            .map(|hit| hit.node_id.replace("conf-search-", ""))
+            .collect();
+        ids.sort();
+        ids
+    };
+    assert_eq!(
+        search("conf-search-1", SearchQuery::new("sales")).await,
+        vec!["crm", "sales"],
+        "terms prefix-match property words"
+    );
+    assert_eq!(
+        search("conf-search-1", SearchQuery::new("MANAGE sales")).await,
+        vec!["crm", "sales"],
+        "every term must match, case-insensitively"
+    );
+    assert_eq!(
+        search("conf-search-1", SearchQuery::new("finance")).await,
+        vec!["erp"],
+        "nested array values are searchable"
+    );
+    assert_eq!(
+        search(
+            "conf-search-1",
+            SearchQuery {
+                node_types: vec!["Capability".into()],
+                ..SearchQuery::new("sales")
+            }
+        )
+        .await,
+        vec!["sales"],
+        "type filters restrict hits"
+    );
+    assert_eq!(
+        search("conf-search-2", SearchQuery::new("sales")).await,
+        vec!["crm", "erp"],
+        "search sees updates and deletes as of the commit"
+    );
+    assert!(
+        search("conf-search-1", SearchQuery::new("ledger"))
+            .await
+            .is_empty(),
+        "later versions are invisible to earlier commits"
+    );
+    assert_eq!(
+        search(
+            "conf-search-2",
+            SearchQuery {
+                limit: Some(1),
+                ..SearchQuery::new("sales")
+            }
+        )
+        .await
+        .len(),
+        1,
+        "limits cap the hit count"
+    );
+    assert!(
+        search("conf-search-2", SearchQuery::new("  "))
+            .await
+            .is_empty(),
+        "blank queries match nothing"
+    );
+    assert!(
+        store
+            .search_nodes("conf-search-missing", &SearchQuery::new("sales"))
+            .await
+            .is_err(),
+        "unknown commits must be rejected"
+    );
+}
+
+/// Blobs are addressed by content, stream back unchanged, and count the commits listing them.
+/// Stores without blob storage ([`Store::blobs`] returns `None`) pass trivially.
+pub async fn blobs_are_content_addressed(store: &dyn Store) {
+    let Some(blobs) = store.blobs() else {
+        return;
+    };
+    let payload = b"conformance blob payload".repeat(5_000);
+    let first = blobs.put_blob(&payload).await.expect("put_blob");
+    assert_eq!(first.blob, BlobRef::for_bytes(&payload));
+    assert_eq!(first.size, payload.len() as u64);
+    assert_eq!(first.ref_count, 0, "fresh uploads are unreferenced");
+    let mut reader = &payload[..];
+    let again = blobs
+        .put_blob_stream(&mut reader)
+        .await
+        .expect("put_blob_stream");
+    assert_eq!(again.blob, first.blob, "same content, same reference");
+    assert_eq!(
+        blobs.read_blob(&first.blob).await.expect("read_blob"),
+        Some(payload.clone()),
+        "content streams back unchanged"
+    );
+    let missing = BlobRef::for_bytes(b"conformance missing blob");
+    assert!(blobs.read_blob(&missing).await.expect("read").is_none());
+    assert!(blobs.blob_info(&missing).await.expect("info").is_none());
+
+    for (id, parents) in [
+        ("conf-blob-1", vec![]),
+        ("conf-blob-2", vec!["conf-blob-1"]),
+    ] {
+        let mut commit = sample_commit(id, &parents);
+        commit.change_set.node_creates[0].props =
+            Some(serde_json::json!({ "attachment": first.blob.to_string() }));
+        commit.blobs.insert(first.blob.clone());
+        store.put_commit(&commit).await.expect("put_commit");
+    }
+    // Only the commit's declared blobs count; a matching string elsewhere is just text.
+    let mut commit = sample_commit("conf-blob-3", &["conf-blob-2"]);
+    commit.change_set.node_creates[0].props =
+        Some(serde_json::json!({ "checksum": first.blob.to_string() }));
+    store.put_commit(&commit).await.expect("put_commit");
+    let info = blobs
+        .blob_info(&first.blob)
+        .await
+        .expect("blob_info")
+        .expect("blob stored");
+    assert_eq!(info.ref_count, 2, "every referencing commit counts");
+    let loaded = store
+        .get_commit("conf-blob-2")
+        .await
+        .expect("get_commit")
+        .expect("stored commit is returned");
+    assert_eq!(loaded.blobs, BTreeSet::from([first.blob.clone()]));
+    assert!(
+        blobs
+            .list_blobs()
+            .await
+            .expect("list_blobs")
+            .iter()
+            .any(|listed| listed == &info),
+        "listed blobs carry the same metadata"
+    );
+}
+
+fn rev(props: &Option<serde_json::Value>) -> u64 {
+    props
+        .as_ref()
+        .and_then(|props| props["rev"].as_u64())
+        .unwrap_or_default()
+}
+
+async fn next_event(subscription: &mut ChangeSubscription) -> ChangeEvent {
+    tokio::time::timeout(FEED_TIMEOUT, subscription.recv())
+        .await
+        .expect("timed out waiting for change event")
+        .expect("change feed error")
+}
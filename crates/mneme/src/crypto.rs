@@ -0,0 +1,507 @@
+//! Envelope encryption for data at rest.
+//!
+//! Payloads are sealed with XChaCha20-Poly1305 under a random 256-bit data key. Data keys are only
+//! ever stored wrapped by a key-encryption key derived from the user's secret: Argon2id over a
+//! passphrase, or BLAKE3 over the contents of a key file. Rotating the secret re-wraps every data
+//! key and starts a fresh one for new writes; earlier data keys stay in the keyring so existing
+//! ciphertext remains readable without rewriting the store.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use aideon_continuum::SnapshotStore;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+
+use crate::{MnemeError, MnemeResult};
+
+/// Smallest key file accepted, in bytes.
+pub const MIN_KEY_FILE_BYTES: usize = 32;
+
+/// Version byte leading every sealed payload.
+const SEALED_VERSION: u8 = 1;
+/// Prefix of sealed values stored in text columns.
+const SEALED_TEXT_PREFIX: &str = "sealed1:";
+const NONCE_BYTES: usize = 24;
+const HEADER_BYTES: usize = 1 + 4 + NONCE_BYTES;
+const SALT_BYTES: usize = 16;
+const KEY_FILE_CONTEXT: &str = "aideon-mneme key file v1";
+const INDEX_KEY_CONTEXT: &str = "aideon-mneme value index v1";
+
+/// How a datastore's data keys are protected; recorded in `datastore.json`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EncryptionMode {
+    #[default]
+    None,
+    Passphrase,
+    KeyFile,
+}
+
+impl EncryptionMode {
+    pub fn is_encrypted(self) -> bool {
+        self != Self::None
+    }
+}
+
+impl fmt::Display for EncryptionMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::None => "none",
+            Self::Passphrase => "passphrase",
+            Self::KeyFile => "key file",
+        })
+    }
+}
+
+/// Secret that unlocks an encrypted datastore.
+#[derive(Clone)]
+pub enum KeySource {
+    Passphrase(String),
+    /// File holding at least [`MIN_KEY_FILE_BYTES`] bytes of key material.
+    KeyFile(PathBuf),
+}
+
+impl KeySource {
+    pub fn mode(&self) -> EncryptionMode {
+        match self {
+            Self::Passphrase(_) => EncryptionMode::Passphrase,
+            Self::KeyFile(_) => EncryptionMode::KeyFile,
+        }
+    }
+}
+
+impl fmt::Debug for KeySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Passphrase(_) => f.write_str("Passphrase(<redacted>)"),
+            Self::KeyFile(path) => f.debug_tuple("KeyFile").field(path).finish(),
+        }
+    }
+}
+
+/// Argon2id cost settings used to stretch a passphrase.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Argon2Settings {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for Argon2Settings {
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// How the key-encryption key is derived from a [`KeySource`]; stored beside the wrapped keys.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyDerivation {
+    pub mode: EncryptionMode,
+    /// Base64 salt for passphrase stretching.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub argon2: Option<Argon2Settings>,
+}
+
+impl KeyDerivation {
+    /// Fresh derivation settings (with a new salt) for `source`.
+    pub fn for_source(source: &KeySource) -> Self {
+        match source {
+            KeySource::Passphrase(_) => {
+                let mut salt = [0u8; SALT_BYTES];
+                OsRng.fill_bytes(&mut salt);
+                Self {
+                    mode: EncryptionMode::Passphrase,
+                    salt: Some(BASE64.encode(salt)),
+                    argon2: Some(Argon2Settings::default()),
+                }
+            }
+            KeySource::KeyFile(_) => Self {
+                mode: EncryptionMode::KeyFile,
+                salt: None,
+                argon2: None,
+            },
+        }
+    }
+
+    fn derive(&self, source: &KeySource) -> MnemeResult<Key> {
+        if source.mode() != self.mode {
+            return Err(MnemeError::WrongKey {
+                message: format!("datastore expects a {}, got a {}", self.mode, source.mode()),
+            });
+        }
+        match source {
+            KeySource::Passphrase(passphrase) => {
+                let salt = self
+                    .salt
+                    .as_deref()
+                    .map(|salt| BASE64.decode(salt))
+                    .transpose()
+                    .map_err(|err| MnemeError::storage(format!("decode key salt: {err}")))?
+                    .ok_or_else(|| MnemeError::storage("passphrase derivation has no salt"))?;
+                let settings = self.argon2.clone().unwrap_or_default();
+                let params = Params::new(
+                    settings.memory_kib,
+                    settings.iterations,
+                    settings.parallelism,
+                    Some(32),
+                )
+                .map_err(|err| MnemeError::storage(format!("argon2 settings: {err}")))?;
+                let mut key = Key::default();
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+                    .map_err(|err| MnemeError::storage(format!("derive passphrase key: {err}")))?;
+                Ok(key)
+            }
+            KeySource::KeyFile(path) => {
+                let material = std::fs::read(path).map_err(|err| {
+                    MnemeError::storage(format!("read key file '{}': {err}", path.display()))
+                })?;
+                if material.len() < MIN_KEY_FILE_BYTES {
+                    return Err(MnemeError::storage(format!(
+                        "key file '{}' holds {} bytes; at least {MIN_KEY_FILE_BYTES} are required",
+                        path.display(),
+                        material.len()
+                    )));
+                }
+                Ok(blake3::derive_key(KEY_FILE_CONTEXT, &material).into())
+            }
+        }
+    }
+}
+
+/// A data key encrypted under the key-encryption key, as persisted by a backend.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WrappedKey {
+    pub key_id: u32,
+    /// Nonce followed by the sealed key.
+    pub wrapped: Vec<u8>,
+}
+
+/// Key material replacing the stored keyring after a rotation.
+pub(crate) struct Rotation {
+    pub derivation: KeyDerivation,
+    pub wrapped: Vec<WrappedKey>,
+    pub active: u32,
+    key: Key,
+}
+
+/// Unlocked data keys. Clones share state, so a rotation is seen by every handle.
+#[derive(Clone)]
+pub struct Keyring {
+    state: Arc<RwLock<KeyringState>>,
+}
+
+struct KeyringState {
+    active: u32,
+    keys: BTreeMap<u32, Key>,
+    /// Keys value hashes so equal plaintexts can be found without revealing them.
+    index_key: [u8; 32],
+}
+
+impl fmt::Debug for Keyring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keyring")
+            .field("active", &self.read().active)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Keyring {
+    /// Start a keyring with one fresh data key, wrapped under `source`.
+    pub(crate) fn create(
+        source: &KeySource,
+    ) -> MnemeResult<(Self, KeyDerivation, Vec<WrappedKey>)> {
+        let derivation = KeyDerivation::for_source(source);
+        let kek = derivation.derive(source)?;
+        let key = XChaCha20Poly1305::generate_key(&mut OsRng);
+        let wrapped = vec![wrap(&kek, 1, &key)?];
+        let keyring = Self::from_keys(1, BTreeMap::from([(1, key)]));
+        Ok((keyring, derivation, wrapped))
+    }
+
+    /// Unwrap stored data keys; fails with [`MnemeError::WrongKey`] when `source` does not match.
+    pub(crate) fn unlock(
+        source: &KeySource,
+        derivation: &KeyDerivation,
+        wrapped: &[WrappedKey],
+        active: u32,
+    ) -> MnemeResult<Self> {
+        let kek = derivation.derive(source)?;
+        let mut keys = BTreeMap::new();
+        for entry in wrapped {
+            keys.insert(entry.key_id, unwrap(&kek, entry)?);
+        }
+        if !keys.contains_key(&active) {
+            return Err(MnemeError::storage(format!(
+                "keyring has no active data key {active}"
+            )));
+        }
+        Ok(Self::from_keys(active, keys))
+    }
+
+    fn from_keys(active: u32, keys: BTreeMap<u32, Key>) -> Self {
+        let first = keys
+            .values()
+            .next()
+            .expect("keyring holds at least one key");
+        let index_key = blake3::derive_key(INDEX_KEY_CONTEXT, first);
+        Self {
+            state: Arc::new(RwLock::new(KeyringState {
+                active,
+                keys,
+                index_key,
+            })),
+        }
+    }
+
+    /// Re-wrap every data key under `source` and add a fresh active key for new writes.
+    pub(crate) fn prepare_rotation(&self, source: &KeySource) -> MnemeResult<Rotation> {
+        let derivation = KeyDerivation::for_source(source);
+        let kek = derivation.derive(source)?;
+        let state = self.read();
+        let active = state.keys.keys().next_back().copied().unwrap_or(0) + 1;
+        let key = XChaCha20Poly1305::generate_key(&mut OsRng);
+        let mut wrapped = Vec::with_capacity(state.keys.len() + 1);
+        for (key_id, existing) in state.keys.iter().chain([(&active, &key)]) {
+            wrapped.push(wrap(&kek, *key_id, existing)?);
+        }
+        Ok(Rotation {
+            derivation,
+            wrapped,
+            active,
+            key,
+        })
+    }
+
+    /// Switch to the rotation's new data key once it has been persisted.
+    pub(crate) fn finish_rotation(&self, rotation: Rotation) {
+        let mut state = self.state.write().unwrap_or_else(|err| err.into_inner());
+        state.keys.insert(rotation.active, rotation.key);
+        state.active = rotation.active;
+    }
+
+    /// Id of the data key used for new writes.
+    pub fn active_key_id(&self) -> u32 {
+        self.read().active
+    }
+
+    /// Encrypt `plaintext` under the active data key.
+    pub fn seal(&self, plaintext: &[u8]) -> MnemeResult<Vec<u8>> {
+        let state = self.read();
+        let cipher = XChaCha20Poly1305::new(&state.keys[&state.active]);
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let sealed = cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| MnemeError::storage("seal payload"))?;
+        let mut out = Vec::with_capacity(HEADER_BYTES + sealed.len());
+        out.push(SEALED_VERSION);
+        out.extend_from_slice(&state.active.to_be_bytes());
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&sealed);
+        Ok(out)
+    }
+
+    /// Decrypt a payload produced by [`Keyring::seal`] with any key in the ring.
+    pub fn open(&self, sealed: &[u8]) -> MnemeResult<Vec<u8>> {
+        if sealed.len() < HEADER_BYTES || sealed[0] != SEALED_VERSION {
+            return Err(MnemeError::storage("sealed payload has an unknown layout"));
+        }
+        let key_id = u32::from_be_bytes(sealed[1..5].try_into().expect("four bytes"));
+        let state = self.read();
+        let key = state.keys.get(&key_id).ok_or_else(|| {
+            MnemeError::storage(format!("sealed payload uses unknown data key {key_id}"))
+        })?;
+        XChaCha20Poly1305::new(key)
+            .decrypt(
+                XNonce::from_slice(&sealed[5..HEADER_BYTES]),
+                &sealed[HEADER_BYTES..],
+            )
+            .map_err(|_| MnemeError::storage("sealed payload failed authentication"))
+    }
+
+    /// [`Keyring::seal`] for text columns.
+    pub fn seal_text(&self, text: &str) -> MnemeResult<String> {
+        Ok(format!(
+            "{SEALED_TEXT_PREFIX}{}",
+            BASE64.encode(self.seal(text.as_bytes())?)
+        ))
+    }
+
+    /// [`Keyring::open`] for text written by [`Keyring::seal_text`].
+    pub fn open_text(&self, text: &str) -> MnemeResult<String> {
+        let encoded = text
+            .strip_prefix(SEALED_TEXT_PREFIX)
+            .ok_or_else(|| MnemeError::storage("expected sealed text"))?;
+        let sealed = BASE64
+            .decode(encoded)
+            .map_err(|err| MnemeError::storage(format!("decode sealed text: {err}")))?;
+        String::from_utf8(self.open(&sealed)?)
+            .map_err(|err| MnemeError::storage(format!("sealed text is not UTF-8: {err}")))
+    }
+
+    /// Keyed hash of `bytes`, stable across rotations, for content-addressed lookups.
+    pub fn index_hash(&self, bytes: &[u8]) -> String {
+        blake3::keyed_hash(&self.read().index_key, bytes)
+            .to_hex()
+            .to_string()
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, KeyringState> {
+        self.state.read().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+fn wrap(kek: &Key, key_id: u32, key: &Key) -> MnemeResult<WrappedKey> {
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let sealed = XChaCha20Poly1305::new(kek)
+        .encrypt(&nonce, key.as_slice())
+        .map_err(|_| MnemeError::storage("wrap data key"))?;
+    let mut wrapped = nonce.to_vec();
+    wrapped.extend_from_slice(&sealed);
+    Ok(WrappedKey { key_id, wrapped })
+}
+
+fn unwrap(kek: &Key, entry: &WrappedKey) -> MnemeResult<Key> {
+    if entry.wrapped.len() <= NONCE_BYTES {
+        return Err(MnemeError::storage(format!(
+            "wrapped data key {} is truncated",
+            entry.key_id
+        )));
+    }
+    let (nonce, sealed) = entry.wrapped.split_at(NONCE_BYTES);
+    let key = XChaCha20Poly1305::new(kek)
+        .decrypt(XNonce::from_slice(nonce), sealed)
+        .map_err(|_| MnemeError::WrongKey {
+            message: "the key does not unwrap the datastore's data keys".into(),
+        })?;
+    Key::from_exact_iter(key).ok_or_else(|| {
+        MnemeError::storage(format!(
+            "wrapped data key {} has the wrong size",
+            entry.key_id
+        ))
+    })
+}
+
+/// Snapshot store that seals layout snapshots before handing them to `inner`.
+pub struct SealedSnapshotStore<S> {
+    inner: S,
+    keyring: Keyring,
+}
+
+impl<S: SnapshotStore> SealedSnapshotStore<S> {
+    pub fn new(inner: S, keyring: Keyring) -> Self {
+        Self { inner, keyring }
+    }
+}
+
+impl<S: SnapshotStore> SnapshotStore for SealedSnapshotStore<S> {
+    fn put(&self, key: &str, bytes: &[u8]) -> Result<(), String> {
+        let sealed = self.keyring.seal(bytes).map_err(|err| err.to_string())?;
+        self.inner.put(key, &sealed)
+    }
+
+    fn get(&self, key: &str) -> Result<Vec<u8>, String> {
+        let sealed = self.inner.get(key)?;
+        self.keyring.open(&sealed).map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemorySnapshotStore;
+
+    fn fast_passphrase(passphrase: &str) -> (KeySource, KeyDerivation) {
+        let source = KeySource::Passphrase(passphrase.into());
+        let mut derivation = KeyDerivation::for_source(&source);
+        derivation.argon2 = Some(Argon2Settings {
+            memory_kib: 64,
+            iterations: 1,
+            parallelism: 1,
+        });
+        (source, derivation)
+    }
+
+    #[test]
+    fn sealed_payloads_round_trip_and_detect_tampering() {
+        let (source, derivation) = fast_passphrase("correct horse");
+        let kek = derivation.derive(&source).unwrap();
+        let wrapped = wrap(&kek, 1, &XChaCha20Poly1305::generate_key(&mut OsRng)).unwrap();
+        let keyring = Keyring::unlock(&source, &derivation, &[wrapped], 1).unwrap();
+
+        let sealed = keyring.seal(b"vendor contract").unwrap();
+        assert_ne!(&sealed[HEADER_BYTES..], b"vendor contract");
+        assert_eq!(keyring.open(&sealed).unwrap(), b"vendor contract");
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(keyring.open(&tampered).is_err());
+
+        let text = keyring.seal_text("Restricted").unwrap();
+        assert!(text.starts_with(SEALED_TEXT_PREFIX));
+        assert_eq!(keyring.open_text(&text).unwrap(), "Restricted");
+    }
+
+    #[test]
+    fn wrong_passphrase_is_a_typed_error() {
+        let (source, derivation) = fast_passphrase("correct horse");
+        let kek = derivation.derive(&source).unwrap();
+        let wrapped = wrap(&kek, 1, &XChaCha20Poly1305::generate_key(&mut OsRng)).unwrap();
+        let wrong = KeySource::Passphrase("battery staple".into());
+        let err = Keyring::unlock(&wrong, &derivation, &[wrapped], 1).unwrap_err();
+        assert!(matches!(err, MnemeError::WrongKey { .. }), "{err:?}");
+    }
+
+    #[test]
+    fn rotation_keeps_old_payloads_readable() {
+        let (source, derivation) = fast_passphrase("first");
+        let kek = derivation.derive(&source).unwrap();
+        let wrapped = wrap(&kek, 1, &XChaCha20Poly1305::generate_key(&mut OsRng)).unwrap();
+        let keyring = Keyring::unlock(&source, &derivation, &[wrapped], 1).unwrap();
+        let before = keyring.seal(b"before").unwrap();
+        let hash = keyring.index_hash(b"value");
+
+        let dir = tempfile::tempdir().unwrap();
+        let key_file = dir.path().join("praxis.key");
+        std::fs::write(&key_file, [7u8; MIN_KEY_FILE_BYTES]).unwrap();
+        let next = KeySource::KeyFile(key_file);
+        let rotation = keyring.prepare_rotation(&next).unwrap();
+        let (derivation, wrapped, active) = (
+            rotation.derivation.clone(),
+            rotation.wrapped.clone(),
+            rotation.active,
+        );
+        keyring.finish_rotation(rotation);
+        assert_eq!(keyring.active_key_id(), 2);
+
+        let reopened = Keyring::unlock(&next, &derivation, &wrapped, active).unwrap();
+        assert_eq!(reopened.open(&before).unwrap(), b"before");
+        let after = keyring.seal(b"after").unwrap();
+        assert_eq!(reopened.open(&after).unwrap(), b"after");
+        assert_eq!(reopened.index_hash(b"value"), hash);
+    }
+
+    #[test]
+    fn sealed_snapshot_store_hides_layout_bytes() {
+        let (keyring, _, _) = Keyring::create(&KeySource::Passphrase("layout".into())).unwrap();
+        let store = SealedSnapshotStore::new(MemorySnapshotStore::default(), keyring);
+        store.put("layout/main", b"{\"x\":1}").unwrap();
+        assert_ne!(store.inner.get("layout/main").unwrap(), b"{\"x\":1}");
+        assert_eq!(store.get("layout/main").unwrap(), b"{\"x\":1}");
+    }
+}
@@ -3,36 +3,76 @@ use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::crypto::EncryptionMode;
+use crate::lock::DatastoreLock;
 use crate::{MnemeError, MnemeResult};
 
-const DEFAULT_DB_NAME: &str = "praxis.sqlite";
+pub(crate) const DEFAULT_DB_NAME: &str = "praxis.sqlite";
 const STATE_FILE: &str = "datastore.json";
 
 #[derive(Serialize, Deserialize)]
 struct DatastoreState {
     name: String,
+    /// Absent in files written before encryption support, which are all plaintext.
+    #[serde(default)]
+    encryption: EncryptionMode,
 }
 
-pub fn create_datastore(base: &Path, preferred_name: Option<&str>) -> MnemeResult<PathBuf> {
+/// Provision the datastore file under `base` and record it in `datastore.json`.
+///
+/// `encryption` records how the store is keyed; `None` keeps the recorded mode (plaintext for a
+/// new datastore). An existing datastore can move between key kinds after a key rotation, but
+/// cannot switch between encrypted and plaintext. Fails with [`MnemeError::Locked`] while
+/// another process writes to the datastore.
+pub fn create_datastore(
+    base: &Path,
+    preferred_name: Option<&str>,
+    encryption: Option<EncryptionMode>,
+) -> MnemeResult<PathBuf> {
     fs::create_dir_all(base)
         .map_err(|err| MnemeError::storage(format!("create base dir: {err}")))?;
+    let recorded = read_state(base);
     let name = preferred_name
         .map(|value| value.to_string())
-        .or_else(|| read_state(base))
+        .or_else(|| recorded.as_ref().map(|state| state.name.clone()))
         .unwrap_or_else(|| DEFAULT_DB_NAME.to_string());
+    let recorded_mode = recorded
+        .filter(|state| state.name == name)
+        .map(|state| state.encryption);
     let path = base.join(&name);
+    let encryption = match (recorded_mode, encryption) {
+        (Some(current), Some(requested))
+            if current.is_encrypted() != requested.is_encrypted() && holds_data(&path) =>
+        {
+            return Err(MnemeError::storage(format!(
+                "datastore '{}' was created with encryption mode '{current}'; it cannot change \
+                 to '{requested}'",
+                path.display()
+            )));
+        }
+        (_, Some(requested)) => requested,
+        (current, None) => current.unwrap_or_default(),
+    };
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
             .map_err(|err| MnemeError::storage(format!("create parent: {err}")))?;
     }
+    let _lock = DatastoreLock::acquire(&path)?;
     if !path.exists() {
         fs::File::create(&path)
             .map_err(|err| MnemeError::storage(format!("create db file: {err}")))?;
     }
-    write_state(base, &name)?;
+    write_state(base, &name, encryption)?;
     Ok(path)
 }
 
+/// Encryption mode recorded for the datastore under `base`; plaintext when none is recorded.
+pub fn datastore_encryption(base: &Path) -> EncryptionMode {
+    read_state(base)
+        .map(|state| state.encryption)
+        .unwrap_or_default()
+}
+
 /// Resolve the datastore path without mutating the filesystem.
 ///
 /// This helper first consults the persisted `datastore.json` metadata written by
@@ -41,8 +81,8 @@ pub fn create_datastore(base: &Path, preferred_name: Option<&str>) -> MnemeResul
 /// the datastore to be provisioned ahead of time can rely on this function to
 /// fail fast instead of accidentally creating a new, empty database.
 pub fn datastore_path(base: &Path) -> MnemeResult<PathBuf> {
-    if let Some(name) = read_state(base) {
-        let candidate = base.join(&name);
+    if let Some(state) = read_state(base) {
+        let candidate = base.join(&state.name);
         if candidate.exists() {
             return Ok(candidate);
         }
@@ -59,16 +99,20 @@ pub fn datastore_path(base: &Path) -> MnemeResult<PathBuf> {
     )))
 }
 
-fn read_state(base: &Path) -> Option<String> {
+fn holds_data(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|meta| meta.len() > 0)
+}
+
+fn read_state(base: &Path) -> Option<DatastoreState> {
     let state_path = base.join(STATE_FILE);
     let data = fs::read_to_string(state_path).ok()?;
-    let state: DatastoreState = serde_json::from_str(&data).ok()?;
-    Some(state.name)
+    serde_json::from_str(&data).ok()
 }
 
-fn write_state(base: &Path, name: &str) -> MnemeResult<()> {
+fn write_state(base: &Path, name: &str, encryption: EncryptionMode) -> MnemeResult<()> {
     let state = DatastoreState {
         name: name.to_string(),
+        encryption,
     };
     let path = base.join(STATE_FILE);
     fs::write(
@@ -81,7 +125,10 @@ fn write_state(base: &Path, name: &str) -> MnemeResult<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::{DEFAULT_DB_NAME, create_datastore, datastore_path};
+    use super::{
+        DEFAULT_DB_NAME, STATE_FILE, create_datastore, datastore_encryption, datastore_path,
+    };
+    use crate::crypto::EncryptionMode;
     use std::fs;
     use tempfile::tempdir;
 
@@ -90,7 +137,7 @@ mod tests {
         let dir = tempdir().expect("tempdir");
         let base = dir.path();
         let custom = "custom.sqlite";
-        let path = create_datastore(base, Some(custom)).expect("create ok");
+        let path = create_datastore(base, Some(custom), None).expect("create ok");
         assert!(path.exists());
 
         let resolved = datastore_path(base).expect("resolve path");
@@ -109,6 +156,33 @@ mod tests {
         assert_eq!(resolved, default);
     }
 
+    #[test]
+    fn records_encryption_mode() {
+        let dir = tempdir().expect("tempdir");
+        let base = dir.path();
+        assert_eq!(datastore_encryption(base), EncryptionMode::None);
+        let path = create_datastore(base, None, Some(EncryptionMode::Passphrase)).expect("create");
+        assert_eq!(datastore_encryption(base), EncryptionMode::Passphrase);
+
+        create_datastore(base, None, None).expect("reopen keeps mode");
+        assert_eq!(datastore_encryption(base), EncryptionMode::Passphrase);
+
+        fs::write(&path, b"sqlite").expect("populate");
+        create_datastore(base, None, Some(EncryptionMode::KeyFile)).expect("rotated key kind");
+        assert_eq!(datastore_encryption(base), EncryptionMode::KeyFile);
+        let err = create_datastore(base, None, Some(EncryptionMode::None))
+            .expect_err("encryption is fixed once data exists");
+        assert!(err.to_string().contains("key file"));
+    }
+
+    #[test]
+    fn reads_state_files_without_encryption() {
+        let dir = tempdir().expect("tempdir");
+        let base = dir.path();
+        fs::write(base.join(STATE_FILE), r#"{ "name": "praxis.sqlite" }"#).expect("state");
+        assert_eq!(datastore_encryption(base), EncryptionMode::None);
+    }
+
     #[test]
     fn errors_when_no_datastore_present() {
         let dir = tempdir().expect("tempdir");
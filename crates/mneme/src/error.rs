@@ -1,5 +1,10 @@
 use thiserror::Error;
 
+use std::path::PathBuf;
+
+use crate::crypto::EncryptionMode;
+use crate::lock::LockHolder;
+
 #[derive(Debug, Error)]
 pub enum MnemeError {
     #[error("mneme storage error: {message}")]
@@ -10,6 +15,29 @@ pub enum MnemeError {
         expected: Option<String>,
         actual: Option<String>,
     },
+    /// The datastore is encrypted and was opened without a key.
+    #[error("mneme datastore is encrypted ({mode}); a key is required to open it")]
+    KeyRequired { mode: EncryptionMode },
+    /// The supplied key does not unlock the datastore.
+    #[error("mneme datastore key rejected: {message}")]
+    WrongKey { message: String },
+    /// The datastore records migrations this build does not know; it was written by a newer
+    /// release and opening it could corrupt data.
+    #[error(
+        "mneme datastore schema is newer than this build (unknown migrations: {}; latest \
+         supported is {latest})",
+        unknown.join(", ")
+    )]
+    SchemaTooNew {
+        unknown: Vec<String>,
+        latest: String,
+    },
+    /// Another live process holds the datastore's writer lock.
+    #[error(
+        "mneme datastore lock '{}' is held by {holder}; close it or open the datastore read-only",
+        path.display()
+    )]
+    Locked { path: PathBuf, holder: LockHolder },
 }
 
 impl MnemeError {
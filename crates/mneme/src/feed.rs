@@ -0,0 +1,148 @@
+//! Change-feed events published by Mneme stores.
+//!
+//! Every store owns a [`ChangeFeed`] and publishes an event once a commit is persisted, a branch
+//! ref moves, or a tag is written. Subscribers receive live events over a broadcast channel and can
+//! resume after a restart by passing the last `at_ms` they processed: events at or after that
+//! cursor are replayed first (at-least-once, so consumers should tolerate repeats). For SQLite the
+//! cursor is `metis_events.created_at_ms`; the in-process backends replay from a bounded journal.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::temporal::{CommitId, CommitSummary};
+use crate::{MnemeError, MnemeResult};
+
+/// Live events buffered per subscriber (and events kept for in-process replay).
+pub const CHANGE_FEED_CAPACITY: usize = 1024;
+
+/// Typed change notification emitted by a store.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ChangeEvent {
+    /// A commit (and its Metis projection) was persisted.
+    CommitPersisted { at_ms: i64, commit: CommitSummary },
+    /// A branch ref moved; `commit` is the summary of the new head when it exists.
+    RefMoved {
+        at_ms: i64,
+        branch: String,
+        previous: Option<CommitId>,
+        head: Option<CommitId>,
+        commit: Option<CommitSummary>,
+    },
+    /// A tag was created or moved.
+    TagChanged {
+        at_ms: i64,
+        tag: String,
+        commit_id: CommitId,
+        commit: Option<CommitSummary>,
+    },
+}
+
+impl ChangeEvent {
+    /// Cursor position of this event (milliseconds since the Unix epoch).
+    pub fn at_ms(&self) -> i64 {
+        match self {
+            Self::CommitPersisted { at_ms, .. }
+            | Self::RefMoved { at_ms, .. }
+            | Self::TagChanged { at_ms, .. } => *at_ms,
+        }
+    }
+}
+
+/// Broadcast hub shared by a store and its clones.
+#[derive(Clone)]
+pub struct ChangeFeed {
+    sender: broadcast::Sender<ChangeEvent>,
+    journal: Arc<Mutex<VecDeque<ChangeEvent>>>,
+}
+
+impl Default for ChangeFeed {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CHANGE_FEED_CAPACITY);
+        Self {
+            sender,
+            journal: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+}
+
+impl ChangeFeed {
+    /// Publish an event to live subscribers and the in-process journal.
+    pub fn publish(&self, event: ChangeEvent) {
+        let mut journal = self.journal.lock().expect("change feed poisoned");
+        if journal.len() == CHANGE_FEED_CAPACITY {
+            journal.pop_front();
+        }
+        journal.push_back(event.clone());
+        // No receivers is not an error: nobody is listening yet.
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribe to live events, replaying journalled events at or after `since_ms` first.
+    pub fn subscribe(&self, since_ms: Option<i64>) -> ChangeSubscription {
+        let journal = self.journal.lock().expect("change feed poisoned");
+        let backlog = match since_ms {
+            Some(since) => journal
+                .iter()
+                .filter(|event| event.at_ms() >= since)
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+        ChangeSubscription::new(backlog, self.sender.subscribe())
+    }
+
+    /// Raw live receiver for stores that replay their backlog from durable storage. Take it before
+    /// reading the backlog so no event falls between the two.
+    pub fn receiver(&self) -> broadcast::Receiver<ChangeEvent> {
+        self.sender.subscribe()
+    }
+}
+
+/// Replay-then-live stream of change events.
+pub struct ChangeSubscription {
+    backlog: VecDeque<ChangeEvent>,
+    replayed_up_to: Option<i64>,
+    live: broadcast::Receiver<ChangeEvent>,
+}
+
+impl ChangeSubscription {
+    pub fn new(backlog: Vec<ChangeEvent>, live: broadcast::Receiver<ChangeEvent>) -> Self {
+        let replayed_up_to = backlog.iter().map(ChangeEvent::at_ms).max();
+        Self {
+            backlog: backlog.into(),
+            replayed_up_to,
+            live,
+        }
+    }
+
+    /// Wait for the next event. Fails when the subscriber fell more than
+    /// [`CHANGE_FEED_CAPACITY`] events behind (resubscribe from the last cursor) or the store was
+    /// dropped.
+    pub async fn recv(&mut self) -> MnemeResult<ChangeEvent> {
+        if let Some(event) = self.backlog.pop_front() {
+            return Ok(event);
+        }
+        loop {
+            match self.live.recv().await {
+                Ok(event) if self.replayed_up_to.is_some_and(|at| event.at_ms() < at) => continue,
+                Ok(event) => return Ok(event),
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    return Err(MnemeError::storage(format!(
+                        "change feed subscriber lagged by {missed} events; resubscribe from the last cursor"
+                    )));
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    return Err(MnemeError::storage("change feed closed"));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,588 @@
+//! Append-only, file-segment implementation of the Mneme store.
+//!
+//! Layout under the store root:
+//!
+//! ```text
+//! segments/00000001.log   one commit per line: "<crc32 hex> <PersistedCommit JSON>\n"
+//! refs.json               branch heads and tags, replaced atomically via rename
+//! ```
+//!
+//! Segments are never rewritten; a new one is started once the active segment exceeds the size
+//! limit. Every line carries a CRC-32 of its JSON payload so the log can be audited with nothing
+//! more than a text editor and `crc32`.
+//!
+//! File I/O is blocking `std::fs`, so every [`Store`] call runs on Tokio's blocking pool while it
+//! holds the state lock.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::sqlite::current_time_ms;
+use crate::temporal::CommitSummary;
+use crate::{
+    ChangeEvent, ChangeFeed, ChangeSubscription, CommitBatch, MnemeError, MnemeResult,
+    PersistedCommit, Store,
+};
+
+const SEGMENTS_DIR: &str = "segments";
+const REFS_FILE: &str = "refs.json";
+const SEGMENT_EXTENSION: &str = "log";
+
+/// Default size after which a new segment is started (8 MiB).
+pub const DEFAULT_SEGMENT_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Store that keeps commits in checksummed, append-only log segments and refs in a small JSON file.
+#[derive(Clone)]
+pub struct FileStore {
+    inner: Arc<Mutex<FileState>>,
+    feed: ChangeFeed,
+}
+
+struct FileState {
+    root: PathBuf,
+    segment_bytes: u64,
+    active_segment: u32,
+    active_len: u64,
+    index: HashMap<String, RecordLocation>,
+    refs: RefsFile,
+}
+
+#[derive(Clone, Copy)]
+struct RecordLocation {
+    segment: u32,
+    offset: u64,
+    len: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct RefsFile {
+    #[serde(default)]
+    branches: BTreeMap<String, Option<String>>,
+    #[serde(default)]
+    tags: BTreeMap<String, String>,
+}
+
+impl FileStore {
+    /// Open (or create) a file store rooted at `root` and ensure the main branch exists.
+    pub fn open(root: impl AsRef<Path>) -> MnemeResult<Self> {
+        Self::open_with_segment_size(root, DEFAULT_SEGMENT_BYTES)
+    }
+
+    /// Open a file store that rolls over to a new segment once the active one reaches `bytes`.
+    pub fn open_with_segment_size(root: impl AsRef<Path>, bytes: u64) -> MnemeResult<Self> {
+        let root = root.as_ref().to_path_buf();
+        let segments_dir = root.join(SEGMENTS_DIR);
+        fs::create_dir_all(&segments_dir).map_err(|err| {
+            MnemeError::storage(format!(
+                "create segment directory '{}': {err}",
+                segments_dir.display()
+            ))
+        })?;
+
+        let mut state = FileState {
+            root,
+            segment_bytes: bytes.max(1),
+            active_segment: 1,
+            active_len: 0,
+            index: HashMap::new(),
+            refs: RefsFile::default(),
+        };
+        let segments = list_segments(&segments_dir)?;
+        let last = segments.last().copied();
+        for segment in segments {
+            let len = state.scan_segment(segment, Some(segment) == last)?;
+            state.active_segment = segment;
+            state.active_len = len;
+        }
+        state.refs = read_refs(&state.root.join(REFS_FILE))?;
+        if !state.refs.branches.contains_key("main") {
+            state.refs.branches.insert("main".into(), None);
+            state.write_refs()?;
+        }
+        Ok(Self {
+            inner: Arc::new(Mutex::new(state)),
+            feed: ChangeFeed::default(),
+        })
+    }
+}
+
+impl FileState {
+    fn segment_path(&self, segment: u32) -> PathBuf {
+        self.root
+            .join(SEGMENTS_DIR)
+            .join(format!("{segment:08}.{SEGMENT_EXTENSION}"))
+    }
+
+    /// Index every record in a segment, verifying checksums. A torn trailing line in the newest
+    /// segment (crash mid-append) is truncated; any other damage is reported as an error.
+    fn scan_segment(&mut self, segment: u32, is_last: bool) -> MnemeResult<u64> {
+        let path = self.segment_path(segment);
+        let file = File::open(&path).map_err(|err| io_err(&path, err))?;
+        let mut reader = BufReader::new(file);
+        let mut offset = 0u64;
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let read = reader
+                .read_until(b'\n', &mut line)
+                .map_err(|err| io_err(&path, err))? as u64;
+            if read == 0 {
+                break;
+            }
+            if line.last() != Some(&b'\n') {
+                if is_last {
+                    let file = OpenOptions::new()
+                        .write(true)
+                        .open(&path)
+                        .map_err(|err| io_err(&path, err))?;
+                    file.set_len(offset).map_err(|err| io_err(&path, err))?;
+                    file.sync_all().map_err(|err| io_err(&path, err))?;
+                    break;
+                }
+                return Err(MnemeError::storage(format!(
+                    "segment '{}' has a truncated record at offset {offset}",
+                    path.display()
+                )));
+            }
+            let commit = decode_record(&line, &path, offset)?;
+            self.index.insert(
+                commit.summary.id,
+                RecordLocation {
+                    segment,
+                    offset,
+                    len: read,
+                },
+            );
+            offset += read;
+        }
+        Ok(offset)
+    }
+
+    fn summary_of(&self, id: &str) -> Option<crate::temporal::CommitSummary> {
+        let location = self.index.get(id).copied()?;
+        self.read_commit(location).ok().map(|commit| commit.summary)
+    }
+
+    fn read_commit(&self, location: RecordLocation) -> MnemeResult<PersistedCommit> {
+        let path = self.segment_path(location.segment);
+        let mut file = File::open(&path).map_err(|err| io_err(&path, err))?;
+        file.seek(SeekFrom::Start(location.offset))
+            .map_err(|err| io_err(&path, err))?;
+        let mut line = vec![0u8; location.len as usize];
+        file.read_exact(&mut line)
+            .map_err(|err| io_err(&path, err))?;
+        decode_record(&line, &path, location.offset)
+    }
+
+    /// Append one record. A failed write is truncated away so the next append starts on a
+    /// record boundary; the in-memory position only moves once the record is durable.
+    fn append_commit(&mut self, commit: &PersistedCommit) -> MnemeResult<()> {
+        let payload = serde_json::to_string(commit)
+            .map_err(|err| MnemeError::storage(format!("serialise commit: {err}")))?;
+        let line = format!("{:08x} {payload}\n", crc32fast::hash(payload.as_bytes()));
+        let (segment, offset) =
+            if self.active_len > 0 && self.active_len + line.len() as u64 > self.segment_bytes {
+                (self.active_segment + 1, 0)
+            } else {
+                (self.active_segment, self.active_len)
+            };
+        let path = self.segment_path(segment);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|err| io_err(&path, err))?;
+        if let Err(err) = file
+            .write_all(line.as_bytes())
+            .and_then(|()| file.sync_data())
+        {
+            return Err(match file.set_len(offset) {
+                Ok(()) => io_err(&path, err),
+                Err(truncate) => MnemeError::storage(format!(
+                    "append to '{}': {err}; truncating the partial record also failed: {truncate}",
+                    path.display()
+                )),
+            });
+        }
+        self.index.insert(
+            commit.summary.id.clone(),
+            RecordLocation {
+                segment,
+                offset,
+                len: line.len() as u64,
+            },
+        );
+        self.active_segment = segment;
+        self.active_len = offset + line.len() as u64;
+        Ok(())
+    }
+
+    /// Drop records appended since `(segment, len)`: later segments are deleted, the first is
+    /// truncated and `commits` leave the index.
+    fn discard_from(
+        &mut self,
+        (segment, len): (u32, u64),
+        commits: &[PersistedCommit],
+    ) -> MnemeResult<()> {
+        for later in (segment + 1..=self.active_segment).rev() {
+            let path = self.segment_path(later);
+            match fs::remove_file(&path) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(io_err(&path, err));
+                }
+                _ => {}
+            }
+        }
+        let path = self.segment_path(segment);
+        if path.exists() {
+            let file = OpenOptions::new()
+                .write(true)
+                .open(&path)
+                .map_err(|err| io_err(&path, err))?;
+            file.set_len(len).map_err(|err| io_err(&path, err))?;
+            file.sync_all().map_err(|err| io_err(&path, err))?;
+        }
+        for commit in commits {
+            self.index.remove(&commit.summary.id);
+        }
+        self.active_segment = segment;
+        self.active_len = len;
+        Ok(())
+    }
+
+    /// Persist refs by writing a sibling temp file and renaming it over `refs.json`.
+    fn write_refs(&self) -> MnemeResult<()> {
+        let path = self.root.join(REFS_FILE);
+        let tmp = self.root.join(format!("{REFS_FILE}.tmp"));
+        let json = serde_json::to_vec_pretty(&self.refs)
+            .map_err(|err| MnemeError::storage(format!("serialise refs: {err}")))?;
+        let mut file = File::create(&tmp).map_err(|err| io_err(&tmp, err))?;
+        file.write_all(&json).map_err(|err| io_err(&tmp, err))?;
+        file.sync_all().map_err(|err| io_err(&tmp, err))?;
+        fs::rename(&tmp, &path).map_err(|err| io_err(&path, err))
+    }
+
+    /// Apply a refs mutation and persist it, restoring the previous refs if the write fails.
+    fn update_refs(&mut self, mutate: impl FnOnce(&mut RefsFile)) -> MnemeResult<()> {
+        let previous = RefsFile {
+            branches: self.refs.branches.clone(),
+            tags: self.refs.tags.clone(),
+        };
+        mutate(&mut self.refs);
+        if let Err(err) = self.write_refs() {
+            self.refs = previous;
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+impl FileStore {
+    /// Run `op` on the blocking pool with the state locked: segment and refs I/O, fsync
+    /// included, must not stall the async runtime's worker threads.
+    async fn with_state<T: Send + 'static>(
+        &self,
+        op: impl FnOnce(&mut FileState, &ChangeFeed) -> MnemeResult<T> + Send + 'static,
+    ) -> MnemeResult<T> {
+        let inner = Arc::clone(&self.inner);
+        let feed = self.feed.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut guard = inner.lock().expect("file store poisoned");
+            op(&mut guard, &feed)
+        })
+        .await
+        .map_err(|err| MnemeError::storage(format!("file store task failed: {err}")))?
+    }
+}
+
+#[async_trait]
+impl Store for FileStore {
+    async fn put_commit(&self, commit: &PersistedCommit) -> MnemeResult<()> {
+        let commit = commit.clone();
+        self.with_state(move |state, feed| {
+            if state.index.contains_key(&commit.summary.id) {
+                return Err(MnemeError::storage(format!(
+                    "commit '{}' already exists",
+                    commit.summary.id
+                )));
+            }
+            state.append_commit(&commit)?;
+            feed.publish(ChangeEvent::CommitPersisted {
+                at_ms: current_time_ms(),
+                commit: commit.summary,
+            });
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_commit(&self, id: &str) -> MnemeResult<Option<PersistedCommit>> {
+        let id = id.to_string();
+        self.with_state(move |state, _| {
+            state
+                .index
+                .get(&id)
+                .copied()
+                .map(|location| state.read_commit(location))
+                .transpose()
+        })
+        .await
+    }
+
+    async fn ensure_branch(&self, branch: &str) -> MnemeResult<()> {
+        let branch = branch.to_string();
+        self.with_state(move |state, _| {
+            if state.refs.branches.contains_key(&branch) {
+                return Ok(());
+            }
+            state.update_refs(|refs| {
+                refs.branches.insert(branch, None);
+            })
+        })
+        .await
+    }
+
+    async fn compare_and_swap_branch(
+        &self,
+        branch: &str,
+        expected: Option<&str>,
+        next: Option<&str>,
+    ) -> MnemeResult<()> {
+        let branch = branch.to_string();
+        let expected = expected.map(str::to_string);
+        let next = next.map(str::to_string);
+        self.with_state(move |state, feed| {
+            let current = state.refs.branches.get(&branch).cloned().unwrap_or(None);
+            if current != expected {
+                return Err(MnemeError::ConcurrencyConflict {
+                    branch,
+                    expected,
+                    actual: current,
+                });
+            }
+            state.update_refs(|refs| {
+                refs.branches.insert(branch.clone(), next.clone());
+            })?;
+            let commit = next.as_deref().and_then(|id| state.summary_of(id));
+            feed.publish(ChangeEvent::RefMoved {
+                at_ms: current_time_ms(),
+                branch,
+                previous: current,
+                head: next,
+                commit,
+            });
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_branch_head(&self, branch: &str) -> MnemeResult<Option<String>> {
+        let branch = branch.to_string();
+        self.with_state(move |state, _| Ok(state.refs.branches.get(&branch).cloned().flatten()))
+            .await
+    }
+
+    async fn list_branches(&self) -> MnemeResult<Vec<(String, Option<String>)>> {
+        self.with_state(|state, _| {
+            Ok(state
+                .refs
+                .branches
+                .iter()
+                .map(|(name, head)| (name.clone(), head.clone()))
+                .collect())
+        })
+        .await
+    }
+
+    async fn put_tag(&self, tag: &str, commit_id: &str) -> MnemeResult<()> {
+        let tag = tag.to_string();
+        let commit_id = commit_id.to_string();
+        self.with_state(move |state, feed| {
+            state.update_refs(|refs| {
+                refs.tags.insert(tag.clone(), commit_id.clone());
+            })?;
+            let commit = state.summary_of(&commit_id);
+            feed.publish(ChangeEvent::TagChanged {
+                at_ms: current_time_ms(),
+                tag,
+                commit_id,
+                commit,
+            });
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_tag(&self, tag: &str) -> MnemeResult<Option<String>> {
+        let tag = tag.to_string();
+        self.with_state(move |state, _| Ok(state.refs.tags.get(&tag).cloned()))
+            .await
+    }
+
+    async fn list_tags(&self) -> MnemeResult<Vec<(String, String)>> {
+        self.with_state(|state, _| {
+            Ok(state
+                .refs
+                .tags
+                .iter()
+                .map(|(tag, commit)| (tag.clone(), commit.clone()))
+                .collect())
+        })
+        .await
+    }
+
+    /// Checks every write first, appends the commits, then replaces `refs.json` once. If an append
+    /// or the refs write fails, the batch's records are truncated from the log again.
+    async fn put_commits(&self, batch: &CommitBatch) -> MnemeResult<()> {
+        let batch = batch.clone();
+        self.with_state(move |state, feed| state.put_commits(&batch, feed))
+            .await
+    }
+
+    async fn subscribe(&self, since_ms: Option<i64>) -> MnemeResult<ChangeSubscription> {
+        Ok(self.feed.subscribe(since_ms))
+    }
+}
+
+impl FileState {
+    fn put_commits(&mut self, batch: &CommitBatch, feed: &ChangeFeed) -> MnemeResult<()> {
+        let mut seen = HashSet::new();
+        for commit in &batch.commits {
+            let id = &commit.summary.id;
+            if self.index.contains_key(id) || !seen.insert(id) {
+                return Err(MnemeError::storage(format!("commit '{id}' already exists")));
+            }
+        }
+        let mut heads = BTreeMap::new();
+        for update in &batch.refs {
+            let current = heads
+                .get(&update.branch)
+                .cloned()
+                .unwrap_or_else(|| self.refs.branches.get(&update.branch).cloned().flatten());
+            if current != update.expected {
+                return Err(MnemeError::ConcurrencyConflict {
+                    branch: update.branch.clone(),
+                    expected: update.expected.clone(),
+                    actual: current,
+                });
+            }
+            heads.insert(update.branch.clone(), update.next.clone());
+        }
+
+        let start = (self.active_segment, self.active_len);
+        let written = batch
+            .commits
+            .iter()
+            .try_for_each(|commit| self.append_commit(commit))
+            .and_then(|()| {
+                self.update_refs(|refs| {
+                    for (tag, commit_id) in &batch.tags {
+                        refs.tags.insert(tag.clone(), commit_id.clone());
+                    }
+                    refs.branches.extend(heads);
+                })
+            });
+        if let Err(err) = written {
+            self.discard_from(start, &batch.commits)?;
+            return Err(err);
+        }
+
+        // Summaries of this batch come from memory rather than a segment read per event.
+        let batched: HashMap<&str, &CommitSummary> = batch
+            .commits
+            .iter()
+            .map(|commit| (commit.summary.id.as_str(), &commit.summary))
+            .collect();
+        let summary_of = |id: &str| {
+            batched
+                .get(id)
+                .map(|summary| (*summary).clone())
+                .or_else(|| self.summary_of(id))
+        };
+        let at_ms = current_time_ms();
+        for commit in &batch.commits {
+            feed.publish(ChangeEvent::CommitPersisted {
+                at_ms,
+                commit: commit.summary.clone(),
+            });
+        }
+        for (tag, commit_id) in &batch.tags {
+            feed.publish(ChangeEvent::TagChanged {
+                at_ms,
+                tag: tag.clone(),
+                commit_id: commit_id.clone(),
+                commit: summary_of(commit_id),
+            });
+        }
+        for update in &batch.refs {
+            feed.publish(ChangeEvent::RefMoved {
+                at_ms,
+                branch: update.branch.clone(),
+                previous: update.expected.clone(),
+                head: update.next.clone(),
+                commit: update.next.as_deref().and_then(summary_of),
+            });
+        }
+        Ok(())
+    }
+}
+
+fn list_segments(dir: &Path) -> MnemeResult<Vec<u32>> {
+    let mut segments = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|err| io_err(dir, err))? {
+        let path = entry.map_err(|err| io_err(dir, err))?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(SEGMENT_EXTENSION) {
+            continue;
+        }
+        if let Some(number) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<u32>().ok())
+        {
+            segments.push(number);
+        }
+    }
+    segments.sort_unstable();
+    Ok(segments)
+}
+
+fn read_refs(path: &Path) -> MnemeResult<RefsFile> {
+    match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map_err(|err| MnemeError::storage(format!("parse '{}': {err}", path.display()))),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(RefsFile::default()),
+        Err(err) => Err(io_err(path, err)),
+    }
+}
+
+fn decode_record(line: &[u8], path: &Path, offset: u64) -> MnemeResult<PersistedCommit> {
+    let corrupt = |detail: &str| {
+        MnemeError::storage(format!(
+            "segment '{}' record at offset {offset}: {detail}",
+            path.display()
+        ))
+    };
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    if line.len() < 9 || line[8] != b' ' {
+        return Err(corrupt("malformed record header"));
+    }
+    let expected = std::str::from_utf8(&line[..8])
+        .ok()
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| corrupt("malformed checksum"))?;
+    let payload = &line[9..];
+    if crc32fast::hash(payload) != expected {
+        return Err(corrupt("checksum mismatch"));
+    }
+    serde_json::from_slice(payload).map_err(|err| corrupt(&format!("decode commit: {err}")))
+}
+
+fn io_err(path: &Path, err: std::io::Error) -> MnemeError {
+    MnemeError::storage(format!("'{}': {err}", path.display()))
+}
@@ -0,0 +1,105 @@
+//! Schema-agnostic replay of change sets along a commit lineage.
+//!
+//! The engine owns validation; Mneme only needs to reproduce the resulting node/edge state when
+//! rewriting history (compaction) or answering storage-level queries. The ordering mirrors
+//! `GraphSnapshot::apply`: node deletes, creates, updates, then edge deletes, creates, updates.
+
+use std::collections::BTreeMap;
+
+use crate::temporal::{ChangeSet, EdgeVersion, NodeVersion};
+
+type EdgeKey = (Option<String>, String, String);
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LineageState {
+    pub(crate) nodes: BTreeMap<String, NodeVersion>,
+    pub(crate) edges: BTreeMap<EdgeKey, EdgeVersion>,
+}
+
+impl LineageState {
+    pub(crate) fn apply(&mut self, change: &ChangeSet) {
+        for tombstone in &change.node_deletes {
+            self.nodes.remove(&tombstone.id);
+        }
+        for node in change.node_creates.iter().chain(change.node_updates.iter()) {
+            self.nodes.insert(node.id.clone(), node.clone());
+        }
+        for tombstone in &change.edge_deletes {
+            self.edges
+                .retain(|(_, from, to), _| !(from == &tombstone.from && to == &tombstone.to));
+        }
+        for edge in &change.edge_creates {
+            self.edges.insert(edge_key(edge), edge.clone());
+        }
+        for edge in &change.edge_updates {
+            match &edge.id {
+                Some(id) => self
+                    .edges
+                    .retain(|(existing, _, _), _| existing.as_deref() != Some(id.as_str())),
+                None => self
+                    .edges
+                    .retain(|(_, from, to), _| !(from == &edge.from && to == &edge.to)),
+            }
+            self.edges.insert(edge_key(edge), edge.clone());
+        }
+    }
+
+    /// Express the accumulated state as a change set that recreates it from an empty graph.
+    pub(crate) fn into_change_set(self) -> ChangeSet {
+        ChangeSet {
+            node_creates: self.nodes.into_values().collect(),
+            edge_creates: self.edges.into_values().collect(),
+            ..ChangeSet::default()
+        }
+    }
+}
+
+fn edge_key(edge: &EdgeVersion) -> EdgeKey {
+    (edge.id.clone(), edge.from.clone(), edge.to.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temporal::{EdgeTombstone, NodeTombstone};
+
+    fn node(id: &str) -> NodeVersion {
+        NodeVersion {
+            id: id.into(),
+            r#type: Some("A".into()),
+            props: None,
+        }
+    }
+
+    #[test]
+    fn replays_creates_updates_and_deletes() {
+        let mut state = LineageState::default();
+        state.apply(&ChangeSet {
+            node_creates: vec![node("a"), node("b")],
+            edge_creates: vec![EdgeVersion {
+                id: Some("e1".into()),
+                from: "a".into(),
+                to: "b".into(),
+                ..EdgeVersion::default()
+            }],
+            ..ChangeSet::default()
+        });
+        state.apply(&ChangeSet {
+            node_deletes: vec![NodeTombstone { id: "b".into() }],
+            node_creates: vec![node("c")],
+            edge_deletes: vec![EdgeTombstone {
+                from: "a".into(),
+                to: "b".into(),
+            }],
+            ..ChangeSet::default()
+        });
+        let squashed = state.into_change_set();
+        let ids: Vec<_> = squashed
+            .node_creates
+            .iter()
+            .map(|n| n.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["a", "c"]);
+        assert!(squashed.edge_creates.is_empty());
+    }
+}
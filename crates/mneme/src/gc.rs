@@ -0,0 +1,100 @@
+//! Garbage collection and history compaction options shared by Mneme backends.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::temporal::CommitId;
+
+/// Tag prefix used by the engine to pin a snapshot to its commit. These tags are bookkeeping and
+/// never keep a commit alive on their own.
+pub const SNAPSHOT_TAG_PREFIX: &str = "snapshot/";
+
+/// Default grace period for unreachable commits and reflog entries (30 days).
+pub const DEFAULT_GC_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Options controlling a garbage-collection pass.
+#[derive(Clone, Debug)]
+pub struct GcOptions {
+    /// Unreachable commits persisted within this window are kept, reflog entries younger than the
+    /// window still count as reachability roots, and unreferenced blobs uploaded within it survive
+    /// so an upload is not collected before the commit that references it lands.
+    pub retention: Duration,
+    /// Optional compaction that collapses history older than a cutoff into one checkpoint commit.
+    pub compact: Option<CompactionOptions>,
+    /// Report what would be removed without touching the database.
+    pub dry_run: bool,
+}
+
+impl Default for GcOptions {
+    fn default() -> Self {
+        Self {
+            retention: DEFAULT_GC_RETENTION,
+            compact: None,
+            dry_run: false,
+        }
+    }
+}
+
+/// Collapse the first-parent lineage ending at `cutoff` into a single checkpoint commit.
+#[derive(Clone, Debug)]
+pub struct CompactionOptions {
+    /// Newest commit folded into the checkpoint; its descendants are re-parented onto it.
+    pub cutoff: CommitId,
+    /// Identifier for the checkpoint commit. Defaults to `checkpoint-<cutoff>`.
+    pub checkpoint_id: Option<CommitId>,
+}
+
+impl CompactionOptions {
+    pub fn new(cutoff: impl Into<CommitId>) -> Self {
+        Self {
+            cutoff: cutoff.into(),
+            checkpoint_id: None,
+        }
+    }
+
+    pub(crate) fn resolved_checkpoint_id(&self) -> CommitId {
+        self.checkpoint_id
+            .clone()
+            .unwrap_or_else(|| format!("checkpoint-{}", self.cutoff))
+    }
+}
+
+/// Outcome of a garbage-collection pass.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcReport {
+    pub dry_run: bool,
+    pub reachable_commits: u64,
+    /// Unreachable commits kept because they were persisted within the retention window.
+    pub retained_commits: u64,
+    pub removed_commits: Vec<CommitId>,
+    pub removed_events: u64,
+    pub removed_node_changes: u64,
+    pub removed_edge_changes: u64,
+    pub removed_tags: u64,
+    pub expired_reflog_entries: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<CommitId>,
+    /// Commits folded into the checkpoint commit.
+    pub compacted_commits: u64,
+    /// Blobs no stored commit references, uploaded before the retention window.
+    pub removed_blobs: u64,
+    pub removed_blob_bytes: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoint_id_defaults_to_cutoff() {
+        let options = CompactionOptions::new("c9");
+        assert_eq!(options.resolved_checkpoint_id(), "checkpoint-c9");
+        let options = CompactionOptions {
+            checkpoint_id: Some("cp".into()),
+            ..options
+        };
+        assert_eq!(options.resolved_checkpoint_id(), "cp");
+    }
+}
@@ -1,20 +1,51 @@
+pub mod backup;
+pub mod blob;
+pub mod bundle;
+#[cfg(feature = "conformance")]
+pub mod conformance;
+pub mod crypto;
 pub mod datastore;
 mod error;
+pub mod feed;
+mod file;
+mod fold;
+pub mod gc;
 pub mod health;
+pub mod lock;
 mod memory;
 pub mod meta;
+pub mod outbox;
+pub mod schema;
+pub mod search;
 mod sqlite;
 mod store;
 pub mod temporal;
 mod types;
+pub mod versions;
+pub mod workspace;
 
+pub use backup::{
+    BackupInfo, BackupManifest, BackupPolicy, backups_dir, list_backups, prune_backups,
+    restore_backup,
+};
+pub use blob::{BlobInfo, BlobRef, BlobStore};
+pub use crypto::{EncryptionMode, KeySource, Keyring, SealedSnapshotStore};
 pub use error::{MnemeError, MnemeResult};
+pub use feed::{ChangeEvent, ChangeFeed, ChangeSubscription};
+pub use gc::{CompactionOptions, GcOptions, GcReport};
 pub use health::WorkerHealth;
+pub use lock::{LockHolder, lock_holder, lock_path};
 pub use meta::*;
+pub use outbox::{DeadLetter, NackOutcome, OutboxCursor, OutboxEvent, RetryPolicy};
+pub use schema::{MigrationKind, MigrationStatus, PendingMigration, SchemaStatus};
+pub use search::{SearchArgs, SearchHit, SearchQuery, SearchResult};
 pub use store::Store;
 pub use temporal::*;
-pub use types::PersistedCommit;
+pub use types::{CommitBatch, PersistedCommit, RefUpdate};
+pub use versions::{ElementRef, ElementState, ElementVersion, GraphFilter, GraphState};
+pub use workspace::{Workspace, WorkspaceCatalogue};
 
-pub use datastore::{create_datastore, datastore_path};
+pub use datastore::{create_datastore, datastore_encryption, datastore_path};
+pub use file::{DEFAULT_SEGMENT_BYTES, FileStore};
 pub use memory::{MemorySnapshotStore, MemoryStore};
-pub use sqlite::SqliteDb;
+pub use sqlite::{OutboxConsumer, ReencodeReport, SqliteDb};
@@ -0,0 +1,294 @@
+//! Advisory single-writer lock for datastores shared between processes.
+//!
+//! A writer creates `<datastore>.lock` next to the SQLite file, recording its pid, host, start
+//! time and a heartbeat it refreshes while the store is open. The file is created complete and
+//! exclusively (written to a temporary file, then hard-linked into place), so a second writer
+//! either wins the whole lock or reads a complete holder record. Opens within one process share
+//! the lock; read-only opens never take it.
+//!
+//! A lock is stale when its heartbeat is older than [`LOCK_STALE_AFTER`], or when it names a pid
+//! on this host that is no longer running (checked where the platform exposes `/proc`). Stale
+//! locks are removed and taken over.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{MnemeError, MnemeResult};
+
+/// Heartbeat age after which a lock is considered abandoned.
+pub const LOCK_STALE_AFTER: Duration = Duration::from_secs(120);
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Process holding a datastore's writer lock, as recorded in its lock file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockHolder {
+    pub pid: u32,
+    pub host: String,
+    pub started_at_ms: i64,
+    pub heartbeat_at_ms: i64,
+    /// Distinguishes acquisitions by the same process, so a release never removes a successor.
+    pub token: String,
+}
+
+impl LockHolder {
+    /// True when the holder has stopped heartbeating or is a dead process on this host.
+    pub fn is_stale(&self) -> bool {
+        let silent_ms = now_ms().saturating_sub(self.heartbeat_at_ms);
+        if silent_ms > LOCK_STALE_AFTER.as_millis() as i64 {
+            return true;
+        }
+        self.host == host_name() && process_exited(self.pid)
+    }
+}
+
+impl fmt::Display for LockHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pid {} on {} (since {} ms)",
+            self.pid, self.host, self.started_at_ms
+        )
+    }
+}
+
+/// Lock file guarding the datastore at `db_path`.
+pub fn lock_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("lock")
+}
+
+/// Current holder of the writer lock on `db_path`, if any (stale holders included).
+pub fn lock_holder(db_path: &Path) -> MnemeResult<Option<LockHolder>> {
+    read_holder(&lock_path(db_path))
+}
+
+/// Held writer lock; the lock file is removed when the last clone drops.
+#[derive(Clone)]
+pub(crate) struct DatastoreLock {
+    held: Arc<Held>,
+}
+
+struct Held {
+    path: PathBuf,
+    holder: LockHolder,
+    heartbeat: AtomicBool,
+}
+
+impl DatastoreLock {
+    /// Take the writer lock on `db_path`, sharing it with other opens in this process and
+    /// recovering stale locks. Fails with [`MnemeError::Locked`] while another live process
+    /// holds it.
+    pub(crate) fn acquire(db_path: &Path) -> MnemeResult<Self> {
+        let path = lock_path(&canonical(db_path));
+        let mut held = registry().lock().unwrap_or_else(|err| err.into_inner());
+        if let Some(existing) = held.get(&path).and_then(Weak::upgrade) {
+            return Ok(Self { held: existing });
+        }
+        let lock = Self::create(&path)?;
+        held.insert(path, Arc::downgrade(&lock.held));
+        Ok(lock)
+    }
+
+    fn create(path: &Path) -> MnemeResult<Self> {
+        // Two attempts: the second follows the removal of a stale lock.
+        for _ in 0..2 {
+            let holder = new_holder();
+            if try_create(path, &holder)? {
+                return Ok(Self {
+                    held: Arc::new(Held {
+                        path: path.to_path_buf(),
+                        holder,
+                        heartbeat: AtomicBool::new(false),
+                    }),
+                });
+            }
+            match read_holder(path)? {
+                // Released between our attempt and the read.
+                None => continue,
+                // Left behind by this process (e.g. a leaked handle) or abandoned elsewhere.
+                Some(current) if is_own_orphan(&current) || current.is_stale() => {
+                    if read_holder(path)?.as_ref() == Some(&current) {
+                        remove(path)?;
+                    }
+                }
+                Some(current) => {
+                    return Err(MnemeError::Locked {
+                        path: path.to_path_buf(),
+                        holder: current,
+                    });
+                }
+            }
+        }
+        let holder = read_holder(path)?.unwrap_or_else(new_holder);
+        Err(MnemeError::Locked {
+            path: path.to_path_buf(),
+            holder,
+        })
+    }
+
+    /// Keep the heartbeat fresh while any clone is alive. Started once, on the current Tokio
+    /// runtime; without one the lock relies on the pid check alone.
+    pub(crate) fn spawn_heartbeat(&self) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        if self.held.heartbeat.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let weak = Arc::downgrade(&self.held);
+        runtime.spawn(async move {
+            loop {
+                tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+                let Some(held) = weak.upgrade() else { break };
+                if !held.refresh() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+impl Held {
+    /// Rewrite the heartbeat if the lock is still ours; false once it has been taken over.
+    fn refresh(&self) -> bool {
+        match read_holder(&self.path) {
+            Ok(Some(current)) if current.token == self.holder.token => {
+                let updated = LockHolder {
+                    heartbeat_at_ms: now_ms(),
+                    ..current
+                };
+                let staging = staging_path(&self.path);
+                write_holder(&staging, &updated)
+                    .and_then(|()| {
+                        fs::rename(&staging, &self.path).map_err(|err| io_err(&self.path, err))
+                    })
+                    .is_ok()
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Drop for Held {
+    fn drop(&mut self) {
+        if let Ok(Some(current)) = read_holder(&self.path)
+            && current.token == self.holder.token
+        {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn registry() -> &'static Mutex<HashMap<PathBuf, Weak<Held>>> {
+    static HELD: OnceLock<Mutex<HashMap<PathBuf, Weak<Held>>>> = OnceLock::new();
+    HELD.get_or_init(Default::default)
+}
+
+/// Create the lock file holding `holder` unless one exists. The record is written to a private
+/// file first and linked into place, so readers never see a partial lock.
+fn try_create(path: &Path, holder: &LockHolder) -> MnemeResult<bool> {
+    let staging = staging_path(path);
+    write_holder(&staging, holder)?;
+    let linked = fs::hard_link(&staging, path);
+    let _ = fs::remove_file(&staging);
+    match linked {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+        Err(err) => Err(io_err(path, err)),
+    }
+}
+
+fn read_holder(path: &Path) -> MnemeResult<Option<LockHolder>> {
+    match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|err| MnemeError::storage(format!("decode lock '{}': {err}", path.display()))),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(io_err(path, err)),
+    }
+}
+
+fn write_holder(path: &Path, holder: &LockHolder) -> MnemeResult<()> {
+    let json = serde_json::to_vec_pretty(holder)
+        .map_err(|err| MnemeError::storage(format!("encode lock: {err}")))?;
+    let mut file = fs::File::create(path).map_err(|err| io_err(path, err))?;
+    file.write_all(&json)
+        .and_then(|()| file.sync_all())
+        .map_err(|err| io_err(path, err))
+}
+
+fn remove(path: &Path) -> MnemeResult<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(io_err(path, err)),
+    }
+}
+
+fn new_holder() -> LockHolder {
+    static SEQ: AtomicU64 = AtomicU64::new(0);
+    let pid = std::process::id();
+    let now = now_ms();
+    let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+    LockHolder {
+        pid,
+        host: host_name(),
+        started_at_ms: now,
+        heartbeat_at_ms: now,
+        token: blake3::hash(format!("{pid}:{now}:{seq}").as_bytes()).to_hex()[..16].to_string(),
+    }
+}
+
+/// A lock naming this process that is not in the registry belongs to a handle that is gone.
+fn is_own_orphan(holder: &LockHolder) -> bool {
+    holder.pid == std::process::id() && holder.host == host_name()
+}
+
+fn staging_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}.tmp", std::process::id()));
+    PathBuf::from(name)
+}
+
+/// Registry key for `db_path`: the canonical parent directory joined with the file name, so
+/// different spellings of one datastore share a lock even before the file exists.
+fn canonical(db_path: &Path) -> PathBuf {
+    let parent = match db_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    match (fs::canonicalize(parent), db_path.file_name()) {
+        (Ok(parent), Some(name)) => parent.join(name),
+        _ => db_path.to_path_buf(),
+    }
+}
+
+fn host_name() -> String {
+    whoami::fallible::hostname().unwrap_or_else(|_| "unknown".into())
+}
+
+/// True only when the platform can tell that `pid` is not running.
+fn process_exited(pid: u32) -> bool {
+    let proc = Path::new("/proc");
+    proc.join("self").exists() && !proc.join(pid.to_string()).exists()
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+fn io_err(path: &Path, err: std::io::Error) -> MnemeError {
+    MnemeError::storage(format!("datastore lock '{}': {err}", path.display()))
+}
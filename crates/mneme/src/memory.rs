@@ -1,15 +1,22 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::{Arc, Mutex};
 
 use aideon_continuum::SnapshotStore as ContinuumSnapshotStore;
 
 use async_trait::async_trait;
+use tokio::io::AsyncRead;
 
-use crate::{MnemeError, MnemeResult, PersistedCommit, Store};
+use crate::blob::{BlobInfo, BlobReader, BlobRef, BlobStore, hash_stream};
+use crate::sqlite::current_time_ms;
+use crate::{
+    ChangeEvent, ChangeFeed, ChangeSubscription, CommitBatch, MnemeError, MnemeResult,
+    PersistedCommit, Store,
+};
 
 #[derive(Clone, Default)]
 pub struct MemoryStore {
     inner: Arc<Mutex<MemoryState>>,
+    feed: ChangeFeed,
 }
 
 #[derive(Default)]
@@ -17,6 +24,28 @@ struct MemoryState {
     commits: BTreeMap<String, PersistedCommit>,
     branches: BTreeMap<String, Option<String>>,
     tags: BTreeMap<String, String>,
+    blobs: BTreeMap<BlobRef, StoredBlob>,
+}
+
+struct StoredBlob {
+    bytes: Vec<u8>,
+    created_at_ms: i64,
+}
+
+impl MemoryState {
+    fn blob_info(&self, blob: &BlobRef, stored: &StoredBlob) -> BlobInfo {
+        let ref_count = self
+            .commits
+            .values()
+            .filter(|commit| commit.blobs.contains(blob))
+            .count();
+        BlobInfo {
+            blob: blob.clone(),
+            size: stored.bytes.len() as u64,
+            ref_count: ref_count as u64,
+            created_at_ms: stored.created_at_ms,
+        }
+    }
 }
 
 #[async_trait]
@@ -32,6 +61,10 @@ impl Store for MemoryStore {
         guard
             .commits
             .insert(commit.summary.id.clone(), commit.clone());
+        self.feed.publish(ChangeEvent::CommitPersisted {
+            at_ms: current_time_ms(),
+            commit: commit.summary.clone(),
+        });
         Ok(())
     }
 
@@ -53,15 +86,26 @@ impl Store for MemoryStore {
         next: Option<&str>,
     ) -> MnemeResult<()> {
         let mut guard = self.inner.lock().expect("memory store poisoned");
-        let current = guard.branches.entry(branch.into()).or_insert(None);
+        let current = guard.branches.get(branch).cloned().unwrap_or(None);
         if current.as_deref() != expected {
             return Err(MnemeError::ConcurrencyConflict {
                 branch: branch.into(),
                 expected: expected.map(|s| s.to_string()),
-                actual: current.clone(),
+                actual: current,
             });
         }
-        *current = next.map(|s| s.to_string());
+        guard
+            .branches
+            .insert(branch.into(), next.map(|s| s.to_string()));
+        self.feed.publish(ChangeEvent::RefMoved {
+            at_ms: current_time_ms(),
+            branch: branch.into(),
+            previous: current,
+            head: next.map(|s| s.to_string()),
+            commit: next
+                .and_then(|id| guard.commits.get(id))
+                .map(|commit| commit.summary.clone()),
+        });
         Ok(())
     }
 
@@ -84,6 +128,15 @@ impl Store for MemoryStore {
     async fn put_tag(&self, tag: &str, commit_id: &str) -> MnemeResult<()> {
         let mut guard = self.inner.lock().expect("memory store poisoned");
         guard.tags.insert(tag.into(), commit_id.into());
+        self.feed.publish(ChangeEvent::TagChanged {
+            at_ms: current_time_ms(),
+            tag: tag.into(),
+            commit_id: commit_id.into(),
+            commit: guard
+                .commits
+                .get(commit_id)
+                .map(|commit| commit.summary.clone()),
+        });
         Ok(())
     }
 
@@ -100,6 +153,124 @@ impl Store for MemoryStore {
             .map(|(tag, commit)| (tag.clone(), commit.clone()))
             .collect())
     }
+
+    /// Checks every write before applying any, so a failing batch leaves the store untouched.
+    async fn put_commits(&self, batch: &CommitBatch) -> MnemeResult<()> {
+        let mut guard = self.inner.lock().expect("memory store poisoned");
+        let mut seen = HashSet::new();
+        for commit in &batch.commits {
+            let id = &commit.summary.id;
+            if guard.commits.contains_key(id) || !seen.insert(id) {
+                return Err(MnemeError::storage(format!("commit '{id}' already exists")));
+            }
+        }
+        let mut heads = BTreeMap::new();
+        for update in &batch.refs {
+            let current = heads
+                .get(&update.branch)
+                .cloned()
+                .unwrap_or_else(|| guard.branches.get(&update.branch).cloned().flatten());
+            if current != update.expected {
+                return Err(MnemeError::ConcurrencyConflict {
+                    branch: update.branch.clone(),
+                    expected: update.expected.clone(),
+                    actual: current,
+                });
+            }
+            heads.insert(update.branch.clone(), update.next.clone());
+        }
+
+        let at_ms = current_time_ms();
+        for commit in &batch.commits {
+            guard
+                .commits
+                .insert(commit.summary.id.clone(), commit.clone());
+            self.feed.publish(ChangeEvent::CommitPersisted {
+                at_ms,
+                commit: commit.summary.clone(),
+            });
+        }
+        for (tag, commit_id) in &batch.tags {
+            guard.tags.insert(tag.clone(), commit_id.clone());
+            self.feed.publish(ChangeEvent::TagChanged {
+                at_ms,
+                tag: tag.clone(),
+                commit_id: commit_id.clone(),
+                commit: guard
+                    .commits
+                    .get(commit_id)
+                    .map(|commit| commit.summary.clone()),
+            });
+        }
+        for update in &batch.refs {
+            guard
+                .branches
+                .insert(update.branch.clone(), update.next.clone());
+            self.feed.publish(ChangeEvent::RefMoved {
+                at_ms,
+                branch: update.branch.clone(),
+                previous: update.expected.clone(),
+                head: update.next.clone(),
+                commit: update
+                    .next
+                    .as_ref()
+                    .and_then(|id| guard.commits.get(id))
+                    .map(|commit| commit.summary.clone()),
+            });
+        }
+        Ok(())
+    }
+
+    async fn subscribe(&self, since_ms: Option<i64>) -> MnemeResult<ChangeSubscription> {
+        Ok(self.feed.subscribe(since_ms))
+    }
+
+    fn blobs(&self) -> Option<&dyn BlobStore> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl BlobStore for MemoryStore {
+    async fn put_blob_stream(
+        &self,
+        reader: &mut (dyn AsyncRead + Unpin + Send),
+    ) -> MnemeResult<BlobInfo> {
+        let mut bytes = Vec::new();
+        let (blob, _) = hash_stream(reader, &mut bytes).await?;
+        let mut guard = self.inner.lock().expect("memory store poisoned");
+        guard.blobs.entry(blob.clone()).or_insert(StoredBlob {
+            bytes,
+            created_at_ms: current_time_ms(),
+        });
+        let stored = &guard.blobs[&blob];
+        Ok(guard.blob_info(&blob, stored))
+    }
+
+    async fn open_blob(&self, blob: &BlobRef) -> MnemeResult<Option<BlobReader>> {
+        let guard = self.inner.lock().expect("memory store poisoned");
+        Ok(guard
+            .blobs
+            .get(blob)
+            .map(|stored| Box::pin(std::io::Cursor::new(stored.bytes.clone())) as BlobReader))
+    }
+
+    async fn blob_info(&self, blob: &BlobRef) -> MnemeResult<Option<BlobInfo>> {
+        let guard = self.inner.lock().expect("memory store poisoned");
+        Ok(guard
+            .blobs
+            .get(blob)
+            .map(|stored| guard.blob_info(blob, stored)))
+    }
+
+    async fn list_blobs(&self) -> MnemeResult<Vec<BlobInfo>> {
+        let guard = self.inner.lock().expect("memory store poisoned");
+        Ok(guard
+            .blobs
+            .iter()
+            .map(|(blob, stored)| guard.blob_info(blob, stored))
+            .collect())
+    }
 }
 
 #[derive(Default)]
@@ -147,6 +318,7 @@ mod tests {
                 change_count: 0,
             },
             change_set: ChangeSet::default(),
+            blobs: Default::default(),
         }
     }
 
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// JSON document describing the Praxis meta-model schema.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -13,6 +14,50 @@ pub struct MetaModelDocument {
     pub relationships: Vec<MetaRelationship>,
     #[serde(default)]
     pub validation: Option<MetaValidationRules>,
+    /// Overlay directives removing definitions of earlier layers; applied before the overlay's
+    /// own definitions and never present in a merged document.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remove: Option<MetaRemovals>,
+}
+
+/// Definitions an overlay removes from the layers below it.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MetaRemovals {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<String>,
+    /// Removing a relationship also drops its validation entry.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relationships: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<MetaAttributeRemoval>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub endpoints: Vec<MetaEndpointRemoval>,
+}
+
+/// An attribute of a type or relationship (exactly one of the two), or only some of its enum
+/// values when `enum` is given.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MetaAttributeRemoval {
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relationship: Option<String>,
+    pub name: String,
+    #[serde(default, rename = "enum", skip_serializing_if = "Vec::is_empty")]
+    pub enum_values: Vec<String>,
+}
+
+/// Endpoint types a relationship no longer allows.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MetaEndpointRemoval {
+    pub relationship: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub from: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub to: Vec<String>,
 }
 
 /// Meta-model element type definition.
@@ -26,6 +71,13 @@ pub struct MetaType {
     pub category: Option<String>,
     #[serde(default)]
     pub extends: Option<String>,
+    /// Abstract types only group their subtypes; no node may use one directly.
+    #[serde(
+        default,
+        rename = "abstract",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub is_abstract: bool,
     #[serde(default)]
     pub attributes: Vec<MetaAttribute>,
     #[serde(default, rename = "effectTypes")]
@@ -43,6 +95,35 @@ pub struct MetaAttribute {
     pub required: bool,
     #[serde(default, rename = "enum")]
     pub enum_values: Vec<String>,
+    /// Value written into a created node or edge that omits the attribute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(flatten)]
+    pub constraints: MetaAttributeConstraints,
+}
+
+/// Per-attribute value constraints; each applies only to the matching attribute kind.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MetaAttributeConstraints {
+    /// Regular expression `string` and `text` values must match; anchor it to match whole values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Inclusive lower bound for `number` values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    /// Inclusive upper bound for `number` values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// Inclusive RFC 3339 lower bound for `datetime` values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub earliest: Option<String>,
+    /// Inclusive RFC 3339 upper bound for `datetime` values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest: Option<String>,
+    /// Node values must be distinct among nodes of the same type.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unique: bool,
 }
 
 /// Primitive kinds supported by schema attributes.
@@ -65,7 +146,10 @@ pub struct MetaRelationship {
     pub id: String,
     #[serde(default)]
     pub label: Option<String>,
+    /// Overlays may omit endpoints when extending an existing relationship.
+    #[serde(default)]
     pub from: Vec<String>,
+    #[serde(default)]
     pub to: Vec<String>,
     #[serde(default)]
     pub directed: Option<bool>,
@@ -76,11 +160,26 @@ pub struct MetaRelationship {
 }
 
 /// Optional multiplicity definition per relationship endpoint.
+///
+/// Each bound is `one`, `many`, `*`, `N`, `N..M` or `N..*`. `from` bounds how many sources each
+/// target node has; `to` bounds how many targets each source node has.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct MetaMultiplicity {
     pub from: Option<String>,
     pub to: Option<String>,
+    /// How violations are reported; errors reject the commit. Defaults to `error`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<MetaRuleSeverity>,
+}
+
+/// How a meta-model rule violation is reported.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum MetaRuleSeverity {
+    #[default]
+    Error,
+    Warning,
 }
 
 /// Optional validation directives declared inside the meta-model document.
@@ -0,0 +1,109 @@
+//! Outbox consumer DTOs shared by Mneme backends.
+//!
+//! Commit events written to `metis_events` are consumed by named groups with a persisted offset.
+//! Delivery is at-least-once: an event is handed out again on every poll until it is acked, or
+//! nacked into the retry schedule. Events that keep failing are dead-lettered.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::temporal::CommitId;
+
+/// Retry schedule for nacked events: exponential backoff capped at `max_backoff`, dead-lettered
+/// once `max_attempts` deliveries have failed.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the next delivery after `failed_attempts` failures (1-based).
+    pub fn backoff_for(&self, failed_attempts: u32) -> Duration {
+        let exponent = failed_attempts.saturating_sub(1).min(31);
+        self.initial_backoff
+            .saturating_mul(1u32 << exponent)
+            .min(self.max_backoff)
+    }
+}
+
+/// Position of a consumer group within `metis_events`: the last event the offset covers.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxCursor {
+    /// `metis_events.seq`, which orders the log by insertion.
+    pub seq: i64,
+    pub created_at_ms: i64,
+    pub event_id: String,
+}
+
+/// Commit event handed to a consumer.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxEvent {
+    pub event_id: String,
+    pub commit_id: CommitId,
+    pub created_at_ms: i64,
+    /// Delivery attempt, starting at 1.
+    pub attempt: u32,
+    /// Metis payload recorded alongside the commit (commit metadata plus change counts).
+    pub payload: serde_json::Value,
+}
+
+/// What happened to a nacked event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum NackOutcome {
+    /// The event will be redelivered once `next_attempt_at_ms` has passed.
+    Retry {
+        failed_attempts: u32,
+        next_attempt_at_ms: i64,
+    },
+    /// The event exhausted its retries and moved to the dead-letter list.
+    DeadLettered { failed_attempts: u32 },
+}
+
+/// Event that exhausted its retries for a consumer group.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetter {
+    pub event_id: String,
+    pub failed_attempts: u32,
+    pub last_error: Option<String>,
+    pub dead_at_ms: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+        };
+        assert_eq!(policy.backoff_for(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff_for(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff_for(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff_for(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff_for(64), Duration::from_millis(500));
+    }
+}
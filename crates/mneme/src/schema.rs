@@ -0,0 +1,84 @@
+//! Schema version introspection for persistent stores.
+//!
+//! Stores record applied migrations by id. A [`SchemaStatus`] compares that record with the
+//! migrations this build knows, so tooling can report pending work before opening a store and
+//! refuse databases written by a newer release.
+
+use serde::{Deserialize, Serialize};
+
+/// What a migration changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MigrationKind {
+    /// DDL: tables, indexes, virtual tables.
+    Schema,
+    /// Rewrites or seeds rows in existing tables.
+    Data,
+}
+
+/// One known migration and whether the store has applied it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationStatus {
+    pub id: String,
+    pub kind: MigrationKind,
+    pub applied_at_ms: Option<i64>,
+}
+
+impl MigrationStatus {
+    pub fn is_applied(&self) -> bool {
+        self.applied_at_ms.is_some()
+    }
+}
+
+/// Applied and pending migrations of one store, in application order.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaStatus {
+    pub migrations: Vec<MigrationStatus>,
+    /// Applied migrations this build does not know; the store was written by a newer release.
+    pub unknown: Vec<String>,
+}
+
+impl SchemaStatus {
+    /// Id of the newest applied migration this build knows.
+    pub fn version(&self) -> Option<&str> {
+        self.migrations
+            .iter()
+            .rev()
+            .find(|migration| migration.is_applied())
+            .map(|migration| migration.id.as_str())
+    }
+
+    /// Id of the newest migration this build knows.
+    pub fn latest(&self) -> Option<&str> {
+        self.migrations
+            .last()
+            .map(|migration| migration.id.as_str())
+    }
+
+    pub fn pending(&self) -> impl Iterator<Item = &MigrationStatus> {
+        self.migrations
+            .iter()
+            .filter(|migration| !migration.is_applied())
+    }
+
+    /// True when every known migration is applied and none are unknown.
+    pub fn is_current(&self) -> bool {
+        self.unknown.is_empty() && self.pending().next().is_none()
+    }
+
+    pub fn is_newer_than_supported(&self) -> bool {
+        !self.unknown.is_empty()
+    }
+}
+
+/// A migration that opening the store would apply, with the SQL it runs. Data migrations list a
+/// description of the rows they touch instead.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingMigration {
+    pub id: String,
+    pub kind: MigrationKind,
+    pub statements: Vec<String>,
+}
@@ -0,0 +1,223 @@
+//! Full-text search over node properties.
+//!
+//! Queries are split into word tokens and every token must prefix-match a word of the node's
+//! searchable text (its id, type, and scalar property values). Snippets wrap matched words in
+//! [`HIGHLIGHT_OPEN`] / [`HIGHLIGHT_CLOSE`]; the markers are control characters so renderers can
+//! split on them without escaping user content.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::temporal::{BranchName, CommitRef, NodeVersion};
+use crate::versions::{GraphFilter, replay_graph_state};
+use crate::{MnemeResult, Store};
+
+/// Marker placed before a highlighted word in [`SearchHit::snippet`].
+pub const HIGHLIGHT_OPEN: &str = "\u{2}";
+/// Marker placed after a highlighted word in [`SearchHit::snippet`].
+pub const HIGHLIGHT_CLOSE: &str = "\u{3}";
+/// Hits returned when [`SearchQuery::limit`] is unset.
+pub const DEFAULT_SEARCH_LIMIT: usize = 50;
+/// Words of context kept around the first match in a snippet.
+pub(crate) const SNIPPET_WORDS: usize = 12;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    pub text: String,
+    /// Restrict hits to these node types; empty matches every type.
+    #[serde(default)]
+    pub node_types: Vec<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl SearchQuery {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Lower-cased word tokens of the query; an empty list matches nothing.
+    pub fn terms(&self) -> Vec<String> {
+        words(&self.text).map(|word| word.to_lowercase()).collect()
+    }
+
+    pub fn effective_limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_SEARCH_LIMIT)
+    }
+}
+
+/// Ranked search result; higher scores are better.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub node_id: String,
+    pub node_type: Option<String>,
+    pub score: f64,
+    pub snippet: String,
+}
+
+/// Search request addressed by commit reference, as sent by the renderer.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchArgs {
+    pub as_of: CommitRef,
+    #[serde(default)]
+    pub scenario: Option<BranchName>,
+    #[serde(flatten)]
+    pub query: SearchQuery,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    /// Commit the reference resolved to.
+    pub commit_id: String,
+    pub hits: Vec<SearchHit>,
+}
+
+/// Searchable text of a node: id, type, then scalar property values in document order.
+pub fn node_text(node: &NodeVersion) -> String {
+    let mut parts = vec![node.id.clone()];
+    parts.extend(node.r#type.clone());
+    if let Some(props) = &node.props {
+        collect_scalars(props, &mut parts);
+    }
+    parts.join(" ")
+}
+
+/// Score `text` against `terms`: the number of words matched, or `None` when a term is missing.
+pub fn score_text(text: &str, terms: &[String]) -> Option<f64> {
+    if terms.is_empty() {
+        return None;
+    }
+    let lowered: Vec<String> = words(text).map(|word| word.to_lowercase()).collect();
+    let mut score = 0usize;
+    for term in terms {
+        let hits = lowered.iter().filter(|word| word.starts_with(term)).count();
+        if hits == 0 {
+            return None;
+        }
+        score += hits;
+    }
+    Some(score as f64)
+}
+
+/// Build a snippet around the first matched word, highlighting every matched word.
+pub(crate) fn snippet(text: &str, terms: &[String]) -> String {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let is_match = |token: &str| {
+        words(token).any(|word| {
+            let word = word.to_lowercase();
+            terms.iter().any(|term| word.starts_with(term))
+        })
+    };
+    let first = tokens.iter().position(|token| is_match(token)).unwrap_or(0);
+    let start = first.saturating_sub(SNIPPET_WORDS / 2);
+    let end = (start + SNIPPET_WORDS).min(tokens.len());
+    let mut out: Vec<String> = tokens[start..end]
+        .iter()
+        .map(|token| {
+            if is_match(token) {
+                format!("{HIGHLIGHT_OPEN}{token}{HIGHLIGHT_CLOSE}")
+            } else {
+                token.to_string()
+            }
+        })
+        .collect();
+    if start > 0 {
+        out.insert(0, "…".into());
+    }
+    if end < tokens.len() {
+        out.push("…".into());
+    }
+    out.join(" ")
+}
+
+/// Search the replayed state at `commit_id` (used by backends without a full-text index).
+pub(crate) async fn replay_search<S: Store + ?Sized>(
+    store: &S,
+    commit_id: &str,
+    query: &SearchQuery,
+) -> MnemeResult<Vec<SearchHit>> {
+    let terms = query.terms();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+    let filter = GraphFilter {
+        node_types: query.node_types.clone(),
+        ..GraphFilter::default()
+    };
+    let state = replay_graph_state(store, commit_id, &filter).await?;
+    Ok(rank_nodes(state.nodes, query))
+}
+
+/// Score `nodes` against `query` in memory, best matches first.
+pub(crate) fn rank_nodes(nodes: Vec<NodeVersion>, query: &SearchQuery) -> Vec<SearchHit> {
+    let terms = query.terms();
+    if terms.is_empty() {
+        return Vec::new();
+    }
+    let mut hits: Vec<SearchHit> = nodes
+        .into_iter()
+        .filter_map(|node| {
+            let text = node_text(&node);
+            score_text(&text, &terms).map(|score| SearchHit {
+                snippet: snippet(&text, &terms),
+                node_id: node.id,
+                node_type: node.r#type,
+                score,
+            })
+        })
+        .collect();
+    hits.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.node_id.cmp(&b.node_id))
+    });
+    hits.truncate(query.effective_limit());
+    hits
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+}
+
+fn collect_scalars(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(text) => out.push(text.clone()),
+        Value::Number(number) => out.push(number.to_string()),
+        Value::Array(items) => items.iter().for_each(|item| collect_scalars(item, out)),
+        Value::Object(map) => map.values().for_each(|item| collect_scalars(item, out)),
+        Value::Bool(_) | Value::Null => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_every_term_by_prefix() {
+        let terms = SearchQuery::new("sales CRM").terms();
+        assert_eq!(score_text("Salesforce CRM platform", &terms), Some(2.0));
+        assert_eq!(score_text("Salesforce platform", &terms), None);
+        assert_eq!(score_text("anything", &[]), None);
+    }
+
+    #[test]
+    fn snippets_highlight_matches() {
+        let terms = SearchQuery::new("sales").terms();
+        let text = "app-crm Application Customer relationship management on Salesforce.";
+        assert_eq!(
+            snippet(text, &terms),
+            format!(
+                "app-crm Application Customer relationship management on {HIGHLIGHT_OPEN}Salesforce.{HIGHLIGHT_CLOSE}"
+            )
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Online backups of the SQLite store.
+//!
+//! `VACUUM INTO` writes the database as seen by one read transaction into a fresh file, so the
+//! copy is consistent while writers keep going and never depends on the state of the live WAL.
+//! Branch heads and blob lists are read back from the copy, not the live store, so the manifest
+//! describes exactly the instant the copy was taken.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbBackend, QueryResult, Statement};
+
+use super::{SqliteDb, blobs, current_time_ms, keyring};
+use crate::backup::{
+    BACKUP_FORMAT_VERSION, BackupInfo, BackupManifest, BackupPolicy, DATABASE_FILE, MANIFEST_FILE,
+    backup_blob_dir, backup_dir_name, hash_file, io_err, list_backups, prune_backups,
+    write_manifest,
+};
+use crate::blob::BlobRef;
+use crate::{MnemeError, MnemeResult};
+
+impl SqliteDb {
+    /// Write a consistent copy of the live store, its blobs, and a manifest into `dir`.
+    pub async fn backup_to(&self, dir: &Path) -> MnemeResult<BackupManifest> {
+        if dir.join(MANIFEST_FILE).exists() {
+            return Err(MnemeError::storage(format!(
+                "'{}' already holds a backup",
+                dir.display()
+            )));
+        }
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|err| io_err("create backup directory", dir, err))?;
+        let copy = dir.join(DATABASE_FILE);
+        if copy.exists() {
+            // Left behind by an interrupted backup; it has no manifest, so nothing refers to it.
+            tokio::fs::remove_file(&copy)
+                .await
+                .map_err(|err| io_err("remove incomplete backup", &copy, err))?;
+        }
+        let created_at_ms = current_time_ms();
+        self.conn
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "VACUUM INTO ?",
+                vec![copy.to_string_lossy().into_owned().into()],
+            ))
+            .await
+            .map_err(db_err)?;
+
+        let snapshot = Database::connect(format!("sqlite://{}?mode=ro", copy.display()))
+            .await
+            .map_err(|err| {
+                MnemeError::storage(format!("open backup copy '{}': {err}", copy.display()))
+            })?;
+        let described = describe(&snapshot).await;
+        snapshot.close().await.map_err(db_err)?;
+        let (heads, commit_count, blob_hashes, encryption) = described?;
+
+        let target_blobs = backup_blob_dir(dir);
+        for hash in &blob_hashes {
+            let blob = BlobRef::from_hex(hash)
+                .ok_or_else(|| MnemeError::storage(format!("invalid blob hash '{hash}'")))?;
+            let source = blobs::blob_path(&self.blob_dir, &blob);
+            let target = blobs::blob_path(&target_blobs, &blob);
+            if let Some(parent) = target.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .map_err(|err| io_err("create backup blob directory", parent, err))?;
+            }
+            tokio::fs::copy(&source, &target)
+                .await
+                .map_err(|err| io_err("back up blob", &source, err))?;
+        }
+
+        let (database_bytes, database_hash) = hash_file(&copy).await?;
+        let manifest = BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            created_at_ms,
+            encryption,
+            heads,
+            commit_count,
+            blob_count: blob_hashes.len() as u64,
+            database_bytes,
+            database_hash,
+        };
+        write_manifest(dir, &manifest).await?;
+        Ok(manifest)
+    }
+
+    /// Take a backup into a new directory under `root`, then keep only the newest `keep`.
+    pub async fn backup_rotating(&self, root: &Path, keep: usize) -> MnemeResult<BackupInfo> {
+        let dir = root.join(backup_dir_name(current_time_ms()));
+        let manifest = self.backup_to(&dir).await?;
+        prune_backups(root, keep.max(1)).await?;
+        Ok(BackupInfo { dir, manifest })
+    }
+
+    /// Take rotating backups under `root` on `policy`'s schedule until the task is aborted.
+    /// The first backup is due one interval after the newest existing one, so restarts do not
+    /// postpone backups indefinitely. Each outcome is passed to `on_backup`.
+    pub fn spawn_backups<F>(
+        &self,
+        root: PathBuf,
+        policy: BackupPolicy,
+        on_backup: F,
+    ) -> tokio::task::JoinHandle<()>
+    where
+        F: Fn(MnemeResult<BackupInfo>) + Send + 'static,
+    {
+        let db = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let wait = match list_backups(&root).await {
+                    Ok(backups) => backups.last().map_or(Duration::ZERO, |latest| {
+                        let age = current_time_ms().saturating_sub(latest.manifest.created_at_ms);
+                        policy
+                            .interval
+                            .saturating_sub(Duration::from_millis(age.max(0) as u64))
+                    }),
+                    Err(err) => {
+                        on_backup(Err(err));
+                        policy.interval
+                    }
+                };
+                tokio::time::sleep(wait).await;
+                let outcome = db.backup_rotating(&root, policy.keep).await;
+                on_backup(outcome);
+            }
+        })
+    }
+}
+
+/// Consistent copy of the database file at `source` into `target` (which must not exist), read
+/// through any write-ahead log. Needs no key: pages are copied as stored.
+pub(crate) async fn copy_database(source: &Path, target: &Path) -> MnemeResult<()> {
+    let conn = Database::connect(format!("sqlite://{}?mode=ro", source.display()))
+        .await
+        .map_err(|err| {
+            MnemeError::storage(format!("open sqlite store '{}': {err}", source.display()))
+        })?;
+    let copied = conn
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "VACUUM INTO ?",
+            vec![target.to_string_lossy().into_owned().into()],
+        ))
+        .await
+        .map_err(db_err);
+    conn.close().await.map_err(db_err)?;
+    copied.map(|_| ())
+}
+
+type Description = (
+    BTreeMap<String, Option<String>>,
+    u64,
+    Vec<String>,
+    crate::crypto::EncryptionMode,
+);
+
+async fn describe(snapshot: &DatabaseConnection) -> MnemeResult<Description> {
+    let mut heads = BTreeMap::new();
+    for row in query(
+        snapshot,
+        "SELECT branch, commit_id FROM refs ORDER BY branch",
+    )
+    .await?
+    {
+        heads.insert(get(&row, "branch")?, get(&row, "commit_id")?);
+    }
+    let commit_count: i64 = get(
+        &query(snapshot, "SELECT COUNT(*) AS total FROM commits").await?[0],
+        "total",
+    )?;
+    let blob_hashes = query(snapshot, "SELECT hash FROM metis_blobs ORDER BY hash")
+        .await?
+        .iter()
+        .map(|row| get(row, "hash"))
+        .collect::<MnemeResult<Vec<String>>>()?;
+    let encryption = keyring::stored_mode(snapshot).await?;
+    Ok((heads, commit_count as u64, blob_hashes, encryption))
+}
+
+async fn query(conn: &DatabaseConnection, sql: &str) -> MnemeResult<Vec<QueryResult>> {
+    conn.query_all(Statement::from_string(DbBackend::Sqlite, sql))
+        .await
+        .map_err(db_err)
+}
+
+fn get<T: sea_orm::TryGetable>(row: &QueryResult, column: &str) -> MnemeResult<T> {
+    row.try_get("", column)
+        .map_err(|err| MnemeError::storage(format!("read backup column '{column}': {err}")))
+}
+
+fn db_err(err: sea_orm::DbErr) -> MnemeError {
+    MnemeError::storage(format!("SeaORM error: {err}"))
+}
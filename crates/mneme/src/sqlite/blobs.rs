@@ -0,0 +1,395 @@
+//! Content-addressed blob storage for the SQLite store.
+//!
+//! Blob bytes are files under the store's blob directory (`<db>.blobs/<aa>/<hash>`, next to the
+//! database file); `metis_blobs` records their size and upload time, and `metis_blob_refs` lists
+//! the blobs each commit's `Blob` attributes reference, as given by [`PersistedCommit::blobs`]. Uploads stream into a temporary file that
+//! is renamed into place once its hash is known, so a crashed upload never leaves a partial blob
+//! under a valid name.
+//!
+//! Encrypted stores seal blob files with the store's keyring. A sealed file starts with
+//! [`SEALED_BLOB_MAGIC`], followed by frames of a little-endian `u32` length and one chunk of the
+//! blob sealed on its own, so uploads and reads still stream. The hash, and so the reference,
+//! is over the plaintext. Files without the magic predate sealing and are read as they are.
+
+use std::collections::BTreeSet;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll, ready};
+
+use async_trait::async_trait;
+use sea_orm::{ConnectionTrait, DbBackend, QueryResult, Statement, Value};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+
+use super::{SqliteDb, current_time_ms};
+use crate::blob::{BLOB_CHUNK_BYTES, BlobInfo, BlobReader, BlobRef, BlobStore, hash_stream};
+use crate::crypto::Keyring;
+use crate::{MnemeError, MnemeResult, PersistedCommit};
+
+/// Distinguishes concurrent uploads within one process.
+static UPLOAD_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Leading bytes of a sealed blob file.
+const SEALED_BLOB_MAGIC: &[u8; 8] = b"MNBLOBS1";
+
+/// Length prefix of each sealed frame.
+const FRAME_LEN_BYTES: usize = 4;
+
+const INFO_SQL: &str = "SELECT b.hash, b.size_bytes, b.created_at_ms, \
+     (SELECT COUNT(*) FROM metis_blob_refs r WHERE r.hash = b.hash) AS ref_count \
+     FROM metis_blobs b";
+
+/// Directory holding the blobs of the database at `db_path`.
+pub(super) fn blob_dir(db_path: &Path) -> PathBuf {
+    db_path.with_extension("blobs")
+}
+
+pub(super) fn blob_path(dir: &Path, blob: &BlobRef) -> PathBuf {
+    dir.join(&blob.hash()[..2]).join(blob.hash())
+}
+
+/// Record the blobs referenced by `commit` using the caller's transaction.
+pub(super) async fn index_refs<C: ConnectionTrait>(
+    conn: &C,
+    commit: &PersistedCommit,
+) -> MnemeResult<()> {
+    for blob in &commit.blobs {
+        exec(
+            conn,
+            "INSERT INTO metis_blob_refs (commit_id, hash) VALUES (?, ?) \
+             ON CONFLICT(commit_id, hash) DO NOTHING",
+            vec![commit.summary.id.clone().into(), blob.hash().into()],
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Blobs recorded for `commit_id` by [`index_refs`].
+pub(super) async fn read_refs<C: ConnectionTrait>(
+    conn: &C,
+    commit_id: &str,
+) -> MnemeResult<BTreeSet<BlobRef>> {
+    let rows = conn
+        .query_all(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT hash FROM metis_blob_refs WHERE commit_id = ?",
+            vec![commit_id.into()],
+        ))
+        .await
+        .map_err(db_err)?;
+    let mut refs = BTreeSet::new();
+    for row in &rows {
+        let hash: String = get(row, "hash")?;
+        refs.insert(BlobRef::from_hex(&hash).ok_or_else(|| {
+            MnemeError::storage(format!(
+                "commit '{commit_id}' references malformed blob '{hash}'"
+            ))
+        })?);
+    }
+    Ok(refs)
+}
+
+/// Drop metadata for unreferenced blobs uploaded before `horizon_ms`; returns `(hash, size)` of
+/// each so the caller can count them and delete the files once the transaction commits.
+pub(super) async fn collect_unreferenced<C: ConnectionTrait>(
+    conn: &C,
+    horizon_ms: i64,
+) -> MnemeResult<Vec<(String, u64)>> {
+    let rows = conn
+        .query_all(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "SELECT hash, size_bytes FROM metis_blobs b WHERE b.created_at_ms < ? \
+             AND NOT EXISTS (SELECT 1 FROM metis_blob_refs r WHERE r.hash = b.hash)",
+            vec![horizon_ms.into()],
+        ))
+        .await
+        .map_err(db_err)?;
+    let mut doomed = Vec::with_capacity(rows.len());
+    for row in &rows {
+        let hash: String = get(row, "hash")?;
+        let size: i64 = get(row, "size_bytes")?;
+        exec(
+            conn,
+            "DELETE FROM metis_blobs WHERE hash = ?",
+            vec![hash.clone().into()],
+        )
+        .await?;
+        doomed.push((hash, size as u64));
+    }
+    Ok(doomed)
+}
+
+impl SqliteDb {
+    /// Delete blob files whose metadata was dropped by garbage collection.
+    pub(super) async fn remove_blob_files(&self, hashes: &[String]) -> MnemeResult<()> {
+        for hash in hashes {
+            let Some(blob) = BlobRef::from_hex(hash) else {
+                continue;
+            };
+            match tokio::fs::remove_file(blob_path(&self.blob_dir, &blob)).await {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => {
+                    return Err(MnemeError::storage(format!("remove blob '{blob}': {err}")));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BlobStore for SqliteDb {
+    async fn put_blob_stream(
+        &self,
+        reader: &mut (dyn AsyncRead + Unpin + Send),
+    ) -> MnemeResult<BlobInfo> {
+        tokio::fs::create_dir_all(&self.blob_dir)
+            .await
+            .map_err(|err| io_err("create blob directory", &self.blob_dir, err))?;
+        let temp = self.blob_dir.join(format!(
+            ".upload-{}-{}",
+            std::process::id(),
+            UPLOAD_SEQ.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut file = tokio::fs::File::create(&temp)
+            .await
+            .map_err(|err| io_err("create blob upload", &temp, err))?;
+        let hashed = match self.sealer.keyring() {
+            Some(keyring) => seal_stream(reader, &mut file, keyring).await,
+            None => hash_stream(reader, &mut file).await,
+        };
+        drop(file);
+        let (blob, size) = match hashed {
+            Ok(hashed) => hashed,
+            Err(err) => {
+                let _ = tokio::fs::remove_file(&temp).await;
+                return Err(err);
+            }
+        };
+        let path = blob_path(&self.blob_dir, &blob);
+        if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            let _ = tokio::fs::remove_file(&temp).await;
+        } else {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .map_err(|err| io_err("create blob directory", parent, err))?;
+            }
+            tokio::fs::rename(&temp, &path)
+                .await
+                .map_err(|err| io_err("store blob", &path, err))?;
+        }
+        exec(
+            &self.conn,
+            "INSERT INTO metis_blobs (hash, size_bytes, created_at_ms) VALUES (?, ?, ?) \
+             ON CONFLICT(hash) DO NOTHING",
+            vec![
+                blob.hash().into(),
+                (size as i64).into(),
+                current_time_ms().into(),
+            ],
+        )
+        .await?;
+        self.blob_info(&blob)
+            .await?
+            .ok_or_else(|| MnemeError::storage(format!("blob '{blob}' vanished after upload")))
+    }
+
+    async fn open_blob(&self, blob: &BlobRef) -> MnemeResult<Option<BlobReader>> {
+        if self.blob_info(blob).await?.is_none() {
+            return Ok(None);
+        }
+        let path = blob_path(&self.blob_dir, blob);
+        let mut file = tokio::fs::File::open(&path)
+            .await
+            .map_err(|err| io_err("open blob", &path, err))?;
+        let Some(keyring) = self.sealer.keyring() else {
+            return Ok(Some(Box::pin(file)));
+        };
+        let mut magic = Vec::with_capacity(SEALED_BLOB_MAGIC.len());
+        (&mut file)
+            .take(SEALED_BLOB_MAGIC.len() as u64)
+            .read_to_end(&mut magic)
+            .await
+            .map_err(|err| io_err("read blob", &path, err))?;
+        if magic != SEALED_BLOB_MAGIC {
+            file.seek(SeekFrom::Start(0))
+                .await
+                .map_err(|err| io_err("read blob", &path, err))?;
+            return Ok(Some(Box::pin(file)));
+        }
+        Ok(Some(Box::pin(SealedBlobReader::new(file, keyring.clone()))))
+    }
+
+    async fn blob_info(&self, blob: &BlobRef) -> MnemeResult<Option<BlobInfo>> {
+        let row = self
+            .conn
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                format!("{INFO_SQL} WHERE b.hash = ?"),
+                vec![Value::from(blob.hash())],
+            ))
+            .await
+            .map_err(db_err)?;
+        row.as_ref().map(decode_info).transpose()
+    }
+
+    async fn list_blobs(&self) -> MnemeResult<Vec<BlobInfo>> {
+        let rows = self
+            .conn
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                format!("{INFO_SQL} ORDER BY b.hash"),
+            ))
+            .await
+            .map_err(db_err)?;
+        rows.iter().map(decode_info).collect()
+    }
+}
+
+/// [`hash_stream`] for encrypted stores: hash the plaintext and write it as sealed frames.
+async fn seal_stream(
+    reader: &mut (dyn AsyncRead + Unpin + Send),
+    writer: &mut (impl AsyncWrite + Unpin),
+    keyring: &Keyring,
+) -> MnemeResult<(BlobRef, u64)> {
+    let write_err = |err: std::io::Error| MnemeError::storage(format!("write blob: {err}"));
+    writer
+        .write_all(SEALED_BLOB_MAGIC)
+        .await
+        .map_err(write_err)?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = vec![0u8; BLOB_CHUNK_BYTES];
+    let mut size = 0u64;
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .await
+            .map_err(|err| MnemeError::storage(format!("read blob upload: {err}")))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        let frame = keyring.seal(&buffer[..read])?;
+        writer
+            .write_all(&(frame.len() as u32).to_le_bytes())
+            .await
+            .map_err(write_err)?;
+        writer.write_all(&frame).await.map_err(write_err)?;
+        size += read as u64;
+    }
+    writer.flush().await.map_err(write_err)?;
+    Ok((BlobRef::from_hash(hasher.finalize()), size))
+}
+
+/// Streams the plaintext of a sealed blob file, opening one frame at a time.
+struct SealedBlobReader {
+    file: tokio::fs::File,
+    keyring: Keyring,
+    /// The frame being read: its length prefix, then its sealed chunk.
+    frame: Vec<u8>,
+    filled: usize,
+    in_body: bool,
+    /// The last opened chunk and how much of it has been returned.
+    plain: Vec<u8>,
+    offset: usize,
+}
+
+impl SealedBlobReader {
+    fn new(file: tokio::fs::File, keyring: Keyring) -> Self {
+        Self {
+            file,
+            keyring,
+            frame: vec![0; FRAME_LEN_BYTES],
+            filled: 0,
+            in_body: false,
+            plain: Vec::new(),
+            offset: 0,
+        }
+    }
+}
+
+impl AsyncRead for SealedBlobReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if this.offset < this.plain.len() {
+                let count = buf.remaining().min(this.plain.len() - this.offset);
+                buf.put_slice(&this.plain[this.offset..this.offset + count]);
+                this.offset += count;
+                return Poll::Ready(Ok(()));
+            }
+            while this.filled < this.frame.len() {
+                let mut read = ReadBuf::new(&mut this.frame[this.filled..]);
+                ready!(Pin::new(&mut this.file).poll_read(cx, &mut read))?;
+                let count = read.filled().len();
+                if count == 0 {
+                    if !this.in_body && this.filled == 0 {
+                        return Poll::Ready(Ok(()));
+                    }
+                    return Poll::Ready(Err(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "sealed blob ends mid-frame",
+                    )));
+                }
+                this.filled += count;
+            }
+            this.filled = 0;
+            if this.in_body {
+                this.plain = this
+                    .keyring
+                    .open(&this.frame)
+                    .map_err(std::io::Error::other)?;
+                this.offset = 0;
+                this.frame = vec![0; FRAME_LEN_BYTES];
+            } else {
+                let len = u32::from_le_bytes(this.frame[..].try_into().expect("four bytes"));
+                this.frame = vec![0; len as usize];
+            }
+            this.in_body = !this.in_body;
+        }
+    }
+}
+
+fn decode_info(row: &QueryResult) -> MnemeResult<BlobInfo> {
+    let hash: String = get(row, "hash")?;
+    let blob = BlobRef::from_hex(&hash)
+        .ok_or_else(|| MnemeError::storage(format!("invalid blob hash '{hash}'")))?;
+    Ok(BlobInfo {
+        blob,
+        size: get::<i64>(row, "size_bytes")? as u64,
+        ref_count: get::<i64>(row, "ref_count")? as u64,
+        created_at_ms: get(row, "created_at_ms")?,
+    })
+}
+
+async fn exec<C: ConnectionTrait>(conn: &C, sql: &str, values: Vec<Value>) -> MnemeResult<()> {
+    conn.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        sql,
+        values,
+    ))
+    .await
+    .map(|_| ())
+    .map_err(db_err)
+}
+
+fn get<T: sea_orm::TryGetable>(row: &QueryResult, column: &str) -> MnemeResult<T> {
+    row.try_get("", column)
+        .map_err(|err| MnemeError::storage(format!("read blob column '{column}': {err}")))
+}
+
+fn io_err(action: &str, path: &Path, err: std::io::Error) -> MnemeError {
+    MnemeError::storage(format!("{action} '{}': {err}", path.display()))
+}
+
+fn db_err(err: sea_orm::DbErr) -> MnemeError {
+    MnemeError::storage(format!("SeaORM error: {err}"))
+}
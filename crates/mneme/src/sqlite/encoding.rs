@@ -0,0 +1,413 @@
+//! Compressed, deduplicated change-set storage for the SQLite store.
+//!
+//! Since `0007_commit_encoding` a `commits` row keeps only its summary columns: `summary_json` and
+//! `changes_json` are left empty and the change set lives in `metis_commit_payloads` as
+//! deflate-compressed JSON tagged with a format version. Property values whose JSON text reaches
+//! [`SHARED_VALUE_BYTES`] move to `metis_commit_values`, keyed by hash, and the payload records a
+//! JSON pointer for each, so a long description repeated across commits is stored once. Rows
+//! written before the migration still hold plain JSON and read as before until
+//! [`SqliteDb::reencode_commits`] converts them. Encrypted stores seal the compressed payload and
+//! each shared value, and address shared values by a keyed hash.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{Read, Write};
+
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbBackend, EntityTrait, PaginatorTrait, QueryFilter, QuerySelect,
+    Set, Statement, TransactionTrait, Value as DbValue,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::keyring::Sealer;
+use super::{IN_LIST_CHUNK, SqliteDb, blobs, commits, metis_commit_payloads, metis_commit_values};
+use crate::temporal::{ChangeSet, CommitSummary};
+use crate::{MnemeError, MnemeResult, PersistedCommit};
+
+/// Payload encoding written by this build: deflate-compressed `EncodedChangeSet` JSON.
+pub(super) const CHANGE_SET_FORMAT: i32 = 1;
+
+/// [`CHANGE_SET_FORMAT`] sealed with the store's keyring.
+pub(super) const SEALED_CHANGE_SET_FORMAT: i32 = 2;
+
+/// Property values whose JSON text reaches this size are stored once in `metis_commit_values`.
+const SHARED_VALUE_BYTES: usize = 256;
+
+/// Legacy commits converted per transaction by [`SqliteDb::spawn_reencode`].
+const REENCODE_BATCH: usize = 200;
+
+/// Change-set lists whose entries carry `props`.
+const PROP_LISTS: [&str; 4] = ["nodeCreates", "nodeUpdates", "edgeCreates", "edgeUpdates"];
+
+/// Commit summary rebuilt from the `commits` columns; `NULL` when an outer join found no commit.
+/// Sealed fields stay sealed; pass the result through [`Sealer::open_summary`].
+pub(super) const SUMMARY_SQL: &str = "CASE WHEN c.commit_id IS NULL THEN NULL ELSE json_object(\
+     'id', c.commit_id, 'parents', json(c.parents_json), 'branch', c.branch, \
+     'author', c.author, 'time', c.time, 'message', c.message, \
+     'tags', json(c.tags_json), 'changeCount', c.change_count) END";
+
+#[derive(Serialize, Deserialize)]
+struct EncodedChangeSet {
+    /// The change set with every shared value replaced by `null`.
+    changes: Value,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    shared: Vec<SharedSlot>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SharedSlot {
+    /// JSON pointer into `changes`.
+    pointer: String,
+    hash: String,
+}
+
+/// Outcome of [`SqliteDb::reencode_commits`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReencodeReport {
+    pub reencoded_commits: u64,
+    /// Commits still stored as plain JSON.
+    pub remaining_commits: u64,
+    /// `summary_json` and `changes_json` bytes freed by the converted rows.
+    pub legacy_bytes: u64,
+    /// Payload and newly shared value bytes written in their place.
+    pub encoded_bytes: u64,
+}
+
+/// Store `change_set` for `commit_id` using the caller's transaction; returns the bytes written.
+pub(super) async fn write_change_set<C: ConnectionTrait>(
+    conn: &C,
+    sealer: &Sealer,
+    commit_id: &str,
+    change_set: &ChangeSet,
+) -> MnemeResult<u64> {
+    let mut changes = serde_json::to_value(change_set)
+        .map_err(|err| MnemeError::storage(format!("serialise commit changes: {err}")))?;
+    let mut shared = Vec::new();
+    let mut values: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+    for list in PROP_LISTS {
+        let Some(Value::Array(items)) = changes.get_mut(list) else {
+            continue;
+        };
+        for (index, item) in items.iter_mut().enumerate() {
+            let Some(Value::Object(props)) = item.get_mut("props") else {
+                continue;
+            };
+            for (key, value) in props.iter_mut() {
+                let text = value.to_string().into_bytes();
+                if text.len() < SHARED_VALUE_BYTES {
+                    continue;
+                }
+                let hash = sealer.value_hash(&text);
+                shared.push(SharedSlot {
+                    pointer: format!("/{list}/{index}/props/{}", escape_pointer(key)),
+                    hash: hash.clone(),
+                });
+                values.entry(hash).or_insert(text);
+                *value = Value::Null;
+            }
+        }
+    }
+
+    let encoded = serde_json::to_vec(&EncodedChangeSet { changes, shared })
+        .map_err(|err| MnemeError::storage(format!("serialise commit changes: {err}")))?;
+    let payload = sealer.seal_bytes(compress(&encoded)?)?;
+    let format = if sealer.is_sealed() {
+        SEALED_CHANGE_SET_FORMAT
+    } else {
+        CHANGE_SET_FORMAT
+    };
+    let mut written = payload.len() as u64;
+    exec(
+        conn,
+        "INSERT INTO metis_commit_payloads (commit_id, format, payload) VALUES (?, ?, ?)",
+        vec![commit_id.into(), format.into(), DbValue::from(payload)],
+    )
+    .await?;
+    for (hash, text) in values {
+        let value = sealer.seal_bytes(compress(&text)?)?;
+        let size = value.len() as u64;
+        let inserted = exec(
+            conn,
+            "INSERT INTO metis_commit_values (hash, value, size_bytes) VALUES (?, ?, ?) \
+             ON CONFLICT(hash) DO NOTHING",
+            vec![
+                hash.clone().into(),
+                DbValue::from(value),
+                (text.len() as i64).into(),
+            ],
+        )
+        .await?;
+        if inserted > 0 {
+            written += size;
+        }
+        exec(
+            conn,
+            "INSERT INTO metis_commit_value_refs (commit_id, hash) VALUES (?, ?) \
+             ON CONFLICT(commit_id, hash) DO NOTHING",
+            vec![commit_id.into(), hash.into()],
+        )
+        .await?;
+    }
+    Ok(written)
+}
+
+/// Decode a `commits` row, reading its payload unless the row still holds legacy JSON.
+pub(super) async fn load_commit<C: ConnectionTrait>(
+    conn: &C,
+    sealer: &Sealer,
+    rec: commits::Model,
+) -> MnemeResult<PersistedCommit> {
+    let summary = sealer.open_summary(summary_from_model(&rec)?)?;
+    let change_set = if rec.changes_json.is_empty() {
+        read_change_set(conn, sealer, &rec.commit_id).await?
+    } else {
+        serde_json::from_str(&rec.changes_json).map_err(|err| {
+            MnemeError::storage(format!(
+                "decode changes of commit '{}': {err}",
+                rec.commit_id
+            ))
+        })?
+    };
+    let blobs = blobs::read_refs(conn, &rec.commit_id).await?;
+    Ok(PersistedCommit {
+        summary,
+        change_set,
+        blobs,
+    })
+}
+
+/// Drop shared values that no stored commit uses any more.
+pub(super) async fn collect_unreferenced_values<C: ConnectionTrait>(conn: &C) -> MnemeResult<u64> {
+    exec(
+        conn,
+        "DELETE FROM metis_commit_values \
+         WHERE hash NOT IN (SELECT hash FROM metis_commit_value_refs)",
+        Vec::new(),
+    )
+    .await
+}
+
+fn summary_from_model(rec: &commits::Model) -> MnemeResult<CommitSummary> {
+    let decode = |json: &str, label: &str| -> MnemeResult<Vec<String>> {
+        serde_json::from_str(json).map_err(|err| {
+            MnemeError::storage(format!(
+                "decode {label} of commit '{}': {err}",
+                rec.commit_id
+            ))
+        })
+    };
+    Ok(CommitSummary {
+        id: rec.commit_id.clone(),
+        parents: decode(&rec.parents_json, "parents")?,
+        branch: rec.branch.clone(),
+        author: rec.author.clone(),
+        time: rec.time.clone(),
+        message: rec.message.clone(),
+        tags: decode(&rec.tags_json, "tags")?,
+        change_count: rec.change_count as u64,
+    })
+}
+
+async fn read_change_set<C: ConnectionTrait>(
+    conn: &C,
+    sealer: &Sealer,
+    commit_id: &str,
+) -> MnemeResult<ChangeSet> {
+    let row = metis_commit_payloads::Entity::find_by_id(commit_id.to_string())
+        .one(conn)
+        .await
+        .map_err(db_err)?
+        .ok_or_else(|| {
+            MnemeError::storage(format!("commit '{commit_id}' has no stored change set"))
+        })?;
+    let expected = if sealer.is_sealed() {
+        SEALED_CHANGE_SET_FORMAT
+    } else {
+        CHANGE_SET_FORMAT
+    };
+    if row.format != expected {
+        return Err(MnemeError::storage(format!(
+            "commit '{commit_id}' uses change set format {}, this store reads format {expected}",
+            row.format
+        )));
+    }
+    let payload = sealer.open_bytes(row.payload)?;
+    let encoded: EncodedChangeSet =
+        serde_json::from_slice(&decompress(&payload)?).map_err(|err| {
+            MnemeError::storage(format!("decode changes of commit '{commit_id}': {err}"))
+        })?;
+    let mut changes = encoded.changes;
+    if !encoded.shared.is_empty() {
+        let hashes: Vec<String> = encoded
+            .shared
+            .iter()
+            .map(|slot| slot.hash.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let values = read_values(conn, sealer, &hashes).await?;
+        for slot in encoded.shared {
+            let value = values.get(&slot.hash).ok_or_else(|| {
+                MnemeError::storage(format!(
+                    "commit '{commit_id}' references missing shared value '{}'",
+                    slot.hash
+                ))
+            })?;
+            let target = changes.pointer_mut(&slot.pointer).ok_or_else(|| {
+                MnemeError::storage(format!(
+                    "commit '{commit_id}' has no property at '{}'",
+                    slot.pointer
+                ))
+            })?;
+            *target = value.clone();
+        }
+    }
+    serde_json::from_value(changes).map_err(|err| {
+        MnemeError::storage(format!("decode changes of commit '{commit_id}': {err}"))
+    })
+}
+
+async fn read_values<C: ConnectionTrait>(
+    conn: &C,
+    sealer: &Sealer,
+    hashes: &[String],
+) -> MnemeResult<HashMap<String, Value>> {
+    let mut values = HashMap::with_capacity(hashes.len());
+    for chunk in hashes.chunks(IN_LIST_CHUNK) {
+        let rows = metis_commit_values::Entity::find()
+            .filter(metis_commit_values::Column::Hash.is_in(chunk.to_vec()))
+            .all(conn)
+            .await
+            .map_err(db_err)?;
+        for row in rows {
+            let value = serde_json::from_slice(&decompress(&sealer.open_bytes(row.value)?)?)
+                .map_err(|err| {
+                    MnemeError::storage(format!("decode shared value '{}': {err}", row.hash))
+                })?;
+            values.insert(row.hash, value);
+        }
+    }
+    Ok(values)
+}
+
+impl SqliteDb {
+    /// Convert up to `batch_size` commits still stored as plain JSON in one transaction.
+    pub async fn reencode_commits(&self, batch_size: usize) -> MnemeResult<ReencodeReport> {
+        let txn = self.conn.begin().await.map_err(db_err)?;
+        let rows = commits::Entity::find()
+            .filter(commits::Column::ChangesJson.ne(""))
+            .limit(batch_size as u64)
+            .all(&txn)
+            .await
+            .map_err(db_err)?;
+        let mut report = ReencodeReport::default();
+        for row in rows {
+            report.legacy_bytes += (row.summary_json.len() + row.changes_json.len()) as u64;
+            let commit = load_commit(&txn, &self.sealer, row).await?;
+            report.encoded_bytes +=
+                write_change_set(&txn, &self.sealer, &commit.summary.id, &commit.change_set)
+                    .await?;
+            commits::Entity::update(commits::ActiveModel {
+                commit_id: Set(commit.summary.id),
+                summary_json: Set(String::new()),
+                changes_json: Set(String::new()),
+                ..Default::default()
+            })
+            .exec(&txn)
+            .await
+            .map_err(db_err)?;
+            report.reencoded_commits += 1;
+        }
+        report.remaining_commits = commits::Entity::find()
+            .filter(commits::Column::ChangesJson.ne(""))
+            .count(&txn)
+            .await
+            .map_err(db_err)?;
+        txn.commit().await.map_err(db_err)?;
+        Ok(report)
+    }
+
+    /// Re-encode every legacy commit on a background task, one batch per transaction so readers
+    /// and writers are never blocked for long. Unconverted rows stay readable, so a failed or
+    /// abandoned run only leaves work for the next one.
+    pub fn spawn_reencode(&self) -> tokio::task::JoinHandle<MnemeResult<ReencodeReport>> {
+        let db = self.clone();
+        tokio::spawn(async move {
+            let mut total = ReencodeReport::default();
+            loop {
+                let batch = db.reencode_commits(REENCODE_BATCH).await?;
+                total.reencoded_commits += batch.reencoded_commits;
+                total.legacy_bytes += batch.legacy_bytes;
+                total.encoded_bytes += batch.encoded_bytes;
+                total.remaining_commits = batch.remaining_commits;
+                if batch.remaining_commits == 0 || batch.reencoded_commits == 0 {
+                    return Ok(total);
+                }
+                tokio::task::yield_now().await;
+            }
+        })
+    }
+}
+
+/// Escape a property name for use as a JSON pointer segment.
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn compress(bytes: &[u8]) -> MnemeResult<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(bytes)
+        .and_then(|()| encoder.finish())
+        .map_err(|err| MnemeError::storage(format!("compress change set: {err}")))
+}
+
+fn decompress(bytes: &[u8]) -> MnemeResult<Vec<u8>> {
+    let mut out = Vec::new();
+    DeflateDecoder::new(bytes)
+        .read_to_end(&mut out)
+        .map_err(|err| MnemeError::storage(format!("decompress change set: {err}")))?;
+    Ok(out)
+}
+
+async fn exec<C: ConnectionTrait>(conn: &C, sql: &str, values: Vec<DbValue>) -> MnemeResult<u64> {
+    conn.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        sql,
+        values,
+    ))
+    .await
+    .map(|result| result.rows_affected())
+    .map_err(db_err)
+}
+
+fn db_err(err: sea_orm::DbErr) -> MnemeError {
+    MnemeError::storage(format!("SeaORM error: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pointer_segments_escape_reserved_characters() {
+        let mut value = serde_json::json!({ "props": { "a/b~c": 1 } });
+        let pointer = format!("/props/{}", escape_pointer("a/b~c"));
+        assert_eq!(value.pointer_mut(&pointer), Some(&mut serde_json::json!(1)));
+    }
+
+    #[test]
+    fn compression_round_trips() {
+        let text = "description ".repeat(200);
+        let compressed = compress(text.as_bytes()).expect("compress");
+        assert!(compressed.len() < text.len() / 10);
+        assert_eq!(
+            decompress(&compressed).expect("decompress"),
+            text.as_bytes()
+        );
+    }
+}
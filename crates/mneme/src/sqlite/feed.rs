@@ -0,0 +1,115 @@
+//! Durable replay for the SQLite change feed.
+//!
+//! Commit events come from `metis_events.created_at_ms`, ref moves from `ref_log`, and tags from
+//! `snapshot_tags` (current position only; earlier moves of the same tag are not retained).
+//! Commit summaries are rebuilt from the `commits` columns, which every row keeps current, and
+//! opened with the store's sealer.
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement, Value};
+
+use super::encoding::SUMMARY_SQL;
+use super::keyring::Sealer;
+use crate::temporal::CommitSummary;
+use crate::{ChangeEvent, MnemeError, MnemeResult};
+
+pub(super) async fn replay_since(
+    conn: &DatabaseConnection,
+    sealer: &Sealer,
+    since_ms: i64,
+) -> MnemeResult<Vec<ChangeEvent>> {
+    let mut events: Vec<(i64, u8, ChangeEvent)> = Vec::new();
+
+    let rows = query(
+        conn,
+        &format!(
+            "SELECT e.created_at_ms AS at_ms, {SUMMARY_SQL} AS summary_json \
+         FROM metis_events e JOIN commits c ON c.commit_id = e.commit_id \
+         WHERE e.created_at_ms >= ? ORDER BY e.created_at_ms, e.event_id"
+        ),
+        since_ms,
+    )
+    .await?;
+    for row in rows {
+        let at_ms: i64 = get(&row, "at_ms")?;
+        let commit = decode_summary(sealer, get(&row, "summary_json")?)?
+            .ok_or_else(|| MnemeError::storage("metis event without commit summary".to_string()))?;
+        events.push((at_ms, 0, ChangeEvent::CommitPersisted { at_ms, commit }));
+    }
+
+    let rows = query(
+        conn,
+        &format!(
+            "SELECT t.tag AS tag, t.commit_id AS commit_id, t.created_at_ms AS at_ms, \
+         {SUMMARY_SQL} AS summary_json \
+         FROM snapshot_tags t LEFT JOIN commits c ON c.commit_id = t.commit_id \
+         WHERE t.created_at_ms >= ? ORDER BY t.created_at_ms, t.tag"
+        ),
+        since_ms,
+    )
+    .await?;
+    for row in rows {
+        let at_ms: i64 = get(&row, "at_ms")?;
+        let event = ChangeEvent::TagChanged {
+            at_ms,
+            tag: get(&row, "tag")?,
+            commit_id: get(&row, "commit_id")?,
+            commit: decode_summary(sealer, get(&row, "summary_json")?)?,
+        };
+        events.push((at_ms, 1, event));
+    }
+
+    let rows = query(
+        conn,
+        &format!(
+            "SELECT r.branch AS branch, r.old_commit AS old_commit, r.new_commit AS new_commit, \
+         r.updated_at_ms AS at_ms, {SUMMARY_SQL} AS summary_json \
+         FROM ref_log r LEFT JOIN commits c ON c.commit_id = r.new_commit \
+         WHERE r.updated_at_ms >= ? ORDER BY r.id"
+        ),
+        since_ms,
+    )
+    .await?;
+    for row in rows {
+        let at_ms: i64 = get(&row, "at_ms")?;
+        let event = ChangeEvent::RefMoved {
+            at_ms,
+            branch: get(&row, "branch")?,
+            previous: get(&row, "old_commit")?,
+            head: get(&row, "new_commit")?,
+            commit: decode_summary(sealer, get(&row, "summary_json")?)?,
+        };
+        events.push((at_ms, 2, event));
+    }
+
+    // Within one millisecond keep the order in which the engine writes: commit, tag, ref.
+    events.sort_by_key(|(at_ms, rank, _)| (*at_ms, *rank));
+    Ok(events.into_iter().map(|(_, _, event)| event).collect())
+}
+
+async fn query(
+    conn: &DatabaseConnection,
+    sql: &str,
+    since_ms: i64,
+) -> MnemeResult<Vec<sea_orm::QueryResult>> {
+    conn.query_all(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        sql,
+        vec![Value::from(since_ms)],
+    ))
+    .await
+    .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))
+}
+
+fn get<T: sea_orm::TryGetable>(row: &sea_orm::QueryResult, column: &str) -> MnemeResult<T> {
+    row.try_get("", column)
+        .map_err(|err| MnemeError::storage(format!("read change feed column '{column}': {err}")))
+}
+
+fn decode_summary(sealer: &Sealer, json: Option<String>) -> MnemeResult<Option<CommitSummary>> {
+    json.map(|json| {
+        let summary = serde_json::from_str(&json)
+            .map_err(|err| MnemeError::storage(format!("decode commit summary: {err}")))?;
+        sealer.open_summary(summary)
+    })
+    .transpose()
+}
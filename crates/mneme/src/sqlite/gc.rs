@@ -0,0 +1,400 @@
+//! Garbage collection and history compaction for the SQLite store.
+//!
+//! Reachability starts from branch heads, non-snapshot tags, and reflog entries inside the
+//! retention window, and follows every parent edge. Unreachable commits older than the window are
+//! deleted together with their Metis projections and snapshot tags in a single transaction. Blobs
+//! that no remaining commit references are dropped too, their files removed once the transaction
+//! commits; the database is vacuumed afterwards.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseTransaction, DbBackend, EntityTrait, QueryFilter,
+    QuerySelect, Set, Statement, TransactionTrait,
+};
+
+use super::keyring::Sealer;
+use super::{
+    IN_LIST_CHUNK, SqliteDb, blobs, commits, current_time_ms, encoding, insert_commit,
+    metis_blob_refs, metis_commit_payloads, metis_commit_value_refs, metis_consumer_deliveries,
+    metis_edge_changes, metis_events, metis_node_changes, ref_log, refs, serialize_json,
+    snapshot_tags, versions,
+};
+use crate::blob::retained_blob_refs;
+use crate::fold::LineageState;
+use crate::gc::{CompactionOptions, GcOptions, GcReport, SNAPSHOT_TAG_PREFIX};
+use crate::temporal::CommitSummary;
+use crate::{MnemeError, MnemeResult, PersistedCommit};
+
+impl SqliteDb {
+    /// Remove unreachable commits (and optionally compact old history), then vacuum the database.
+    pub async fn collect_garbage(&self, options: &GcOptions) -> MnemeResult<GcReport> {
+        let horizon_ms = current_time_ms().saturating_sub(options.retention.as_millis() as i64);
+        let txn = self.conn.begin().await.map_err(db_err)?;
+        let mut report = GcReport {
+            dry_run: options.dry_run,
+            ..GcReport::default()
+        };
+
+        if let Some(compaction) = &options.compact {
+            compact(&txn, &self.sealer, compaction, &mut report).await?;
+        }
+
+        report.expired_reflog_entries = ref_log::Entity::delete_many()
+            .filter(ref_log::Column::UpdatedAtMs.lt(horizon_ms))
+            .exec(&txn)
+            .await
+            .map_err(db_err)?
+            .rows_affected;
+
+        let graph = load_commit_graph(&txn).await?;
+        let reachable = reachable_commits(&graph, &load_roots(&txn).await?);
+        report.reachable_commits = reachable.len() as u64;
+
+        let persisted_at: HashMap<String, i64> = metis_events::Entity::find()
+            .select_only()
+            .column(metis_events::Column::CommitId)
+            .column(metis_events::Column::CreatedAtMs)
+            .into_tuple::<(String, i64)>()
+            .all(&txn)
+            .await
+            .map_err(db_err)?
+            .into_iter()
+            .collect();
+        let mut doomed = Vec::new();
+        for id in graph.keys().filter(|id| !reachable.contains(*id)) {
+            match persisted_at.get(id) {
+                Some(at) if *at >= horizon_ms => report.retained_commits += 1,
+                _ => doomed.push(id.clone()),
+            }
+        }
+        delete_commits(&txn, &doomed, &mut report).await?;
+        report.removed_commits.extend(doomed);
+        encoding::collect_unreferenced_values(&txn).await?;
+
+        let unreferenced = blobs::collect_unreferenced(&txn, horizon_ms).await?;
+        report.removed_blobs = unreferenced.len() as u64;
+        report.removed_blob_bytes = unreferenced.iter().map(|(_, size)| size).sum();
+
+        if options.dry_run {
+            txn.rollback().await.map_err(db_err)?;
+            return Ok(report);
+        }
+        if report.checkpoint.is_some() || !report.removed_commits.is_empty() {
+            versions::rebuild(&txn, &self.sealer).await?;
+        }
+        txn.commit().await.map_err(db_err)?;
+        let hashes: Vec<String> = unreferenced.into_iter().map(|(hash, _)| hash).collect();
+        self.remove_blob_files(&hashes).await?;
+        self.conn
+            .execute(Statement::from_string(DbBackend::Sqlite, "VACUUM"))
+            .await
+            .map_err(db_err)?;
+        Ok(report)
+    }
+}
+
+async fn load_commit_graph(
+    txn: &DatabaseTransaction,
+) -> MnemeResult<BTreeMap<String, Vec<String>>> {
+    let rows = commits::Entity::find()
+        .select_only()
+        .column(commits::Column::CommitId)
+        .column(commits::Column::ParentsJson)
+        .into_tuple::<(String, String)>()
+        .all(txn)
+        .await
+        .map_err(db_err)?;
+    rows.into_iter()
+        .map(|(id, parents_json)| {
+            let parents: Vec<String> = serde_json::from_str(&parents_json).map_err(|err| {
+                MnemeError::storage(format!("decode parents of commit '{id}': {err}"))
+            })?;
+            Ok((id, parents))
+        })
+        .collect()
+}
+
+async fn load_roots(txn: &DatabaseTransaction) -> MnemeResult<BTreeSet<String>> {
+    let mut roots = BTreeSet::new();
+    for row in refs::Entity::find().all(txn).await.map_err(db_err)? {
+        roots.extend(row.commit_id);
+    }
+    for row in snapshot_tags::Entity::find()
+        .all(txn)
+        .await
+        .map_err(db_err)?
+    {
+        if !row.tag.starts_with(SNAPSHOT_TAG_PREFIX) {
+            roots.insert(row.commit_id);
+        }
+    }
+    for row in ref_log::Entity::find().all(txn).await.map_err(db_err)? {
+        roots.extend(row.old_commit);
+        roots.extend(row.new_commit);
+    }
+    Ok(roots)
+}
+
+fn reachable_commits(
+    graph: &BTreeMap<String, Vec<String>>,
+    roots: &BTreeSet<String>,
+) -> BTreeSet<String> {
+    let mut seen = BTreeSet::new();
+    let mut queue: VecDeque<String> = roots.iter().cloned().collect();
+    while let Some(id) = queue.pop_front() {
+        if !seen.insert(id.clone()) {
+            continue;
+        }
+        if let Some(parents) = graph.get(&id) {
+            queue.extend(parents.iter().cloned());
+        }
+    }
+    seen.retain(|id| graph.contains_key(id));
+    seen
+}
+
+async fn delete_commits(
+    txn: &DatabaseTransaction,
+    ids: &[String],
+    report: &mut GcReport,
+) -> MnemeResult<()> {
+    for chunk in ids.chunks(IN_LIST_CHUNK) {
+        let chunk = chunk.to_vec();
+        report.removed_events += metis_events::Entity::delete_many()
+            .filter(metis_events::Column::CommitId.is_in(chunk.clone()))
+            .exec(txn)
+            .await
+            .map_err(db_err)?
+            .rows_affected;
+        report.removed_node_changes += metis_node_changes::Entity::delete_many()
+            .filter(metis_node_changes::Column::CommitId.is_in(chunk.clone()))
+            .exec(txn)
+            .await
+            .map_err(db_err)?
+            .rows_affected;
+        report.removed_edge_changes += metis_edge_changes::Entity::delete_many()
+            .filter(metis_edge_changes::Column::CommitId.is_in(chunk.clone()))
+            .exec(txn)
+            .await
+            .map_err(db_err)?
+            .rows_affected;
+        report.removed_tags += snapshot_tags::Entity::delete_many()
+            .filter(snapshot_tags::Column::CommitId.is_in(chunk.clone()))
+            .exec(txn)
+            .await
+            .map_err(db_err)?
+            .rows_affected;
+        metis_consumer_deliveries::Entity::delete_many()
+            .filter(metis_consumer_deliveries::Column::EventId.is_in(chunk.clone()))
+            .exec(txn)
+            .await
+            .map_err(db_err)?;
+        metis_blob_refs::Entity::delete_many()
+            .filter(metis_blob_refs::Column::CommitId.is_in(chunk.clone()))
+            .exec(txn)
+            .await
+            .map_err(db_err)?;
+        metis_commit_payloads::Entity::delete_many()
+            .filter(metis_commit_payloads::Column::CommitId.is_in(chunk.clone()))
+            .exec(txn)
+            .await
+            .map_err(db_err)?;
+        metis_commit_value_refs::Entity::delete_many()
+            .filter(metis_commit_value_refs::Column::CommitId.is_in(chunk.clone()))
+            .exec(txn)
+            .await
+            .map_err(db_err)?;
+        commits::Entity::delete_many()
+            .filter(commits::Column::CommitId.is_in(chunk))
+            .exec(txn)
+            .await
+            .map_err(db_err)?;
+    }
+    Ok(())
+}
+
+/// Fold the first-parent lineage ending at the cutoff into a checkpoint commit and re-parent
+/// everything that pointed into the collapsed range.
+async fn compact(
+    txn: &DatabaseTransaction,
+    sealer: &Sealer,
+    options: &CompactionOptions,
+    report: &mut GcReport,
+) -> MnemeResult<()> {
+    let checkpoint_id = options.resolved_checkpoint_id();
+    if commits::Entity::find_by_id(checkpoint_id.clone())
+        .one(txn)
+        .await
+        .map_err(db_err)?
+        .is_some()
+    {
+        return Err(MnemeError::storage(format!(
+            "cannot compact: checkpoint commit '{checkpoint_id}' already exists"
+        )));
+    }
+
+    let mut lineage: Vec<PersistedCommit> = Vec::new();
+    let mut cursor = Some(options.cutoff.clone());
+    while let Some(id) = cursor {
+        let row = commits::Entity::find_by_id(id.clone())
+            .one(txn)
+            .await
+            .map_err(db_err)?
+            .ok_or_else(|| MnemeError::storage(format!("cannot compact: unknown commit '{id}'")))?;
+        let commit = encoding::load_commit(txn, sealer, row).await?;
+        cursor = commit.summary.parents.first().cloned();
+        lineage.push(commit);
+    }
+    lineage.reverse();
+    let collapsed: BTreeSet<String> = lineage.iter().map(|c| c.summary.id.clone()).collect();
+    let cutoff = lineage.last().expect("lineage contains the cutoff").clone();
+
+    // Descendants may only hang off the cutoff itself; anything forking earlier would be replayed
+    // against the wrong base once its parent is replaced by the checkpoint.
+    let graph = load_commit_graph(txn).await?;
+    let mut reparent = Vec::new();
+    for (id, parents) in graph.iter().filter(|(id, _)| !collapsed.contains(*id)) {
+        if let Some(first) = parents.first()
+            && collapsed.contains(first)
+            && first != &cutoff.summary.id
+        {
+            return Err(MnemeError::storage(format!(
+                "cannot compact: commit '{id}' forks from '{first}' before the cutoff"
+            )));
+        }
+        if parents.iter().any(|parent| collapsed.contains(parent)) {
+            reparent.push(id.clone());
+        }
+    }
+    for row in refs::Entity::find().all(txn).await.map_err(db_err)? {
+        if let Some(head) = &row.commit_id
+            && collapsed.contains(head)
+            && head != &cutoff.summary.id
+        {
+            return Err(MnemeError::storage(format!(
+                "cannot compact: branch '{}' points at '{head}' before the cutoff",
+                row.branch
+            )));
+        }
+    }
+    let tags = snapshot_tags::Entity::find()
+        .all(txn)
+        .await
+        .map_err(db_err)?;
+    for row in tags
+        .iter()
+        .filter(|row| !row.tag.starts_with(SNAPSHOT_TAG_PREFIX))
+    {
+        if collapsed.contains(&row.commit_id) && row.commit_id != cutoff.summary.id {
+            return Err(MnemeError::storage(format!(
+                "cannot compact: tag '{}' points at '{}' before the cutoff",
+                row.tag, row.commit_id
+            )));
+        }
+    }
+
+    let mut state = LineageState::default();
+    for commit in &lineage {
+        state.apply(&commit.change_set);
+    }
+    let change_set = state.into_change_set();
+    let blobs = retained_blob_refs(lineage.iter().flat_map(|commit| &commit.blobs), &change_set);
+    let checkpoint = PersistedCommit {
+        summary: CommitSummary {
+            id: checkpoint_id.clone(),
+            parents: Vec::new(),
+            branch: cutoff.summary.branch.clone(),
+            author: Some("mneme-gc".into()),
+            time: cutoff.summary.time.clone(),
+            message: format!("checkpoint: history up to {}", cutoff.summary.id),
+            tags: vec!["checkpoint".into()],
+            change_count: (change_set.node_creates.len() + change_set.edge_creates.len()) as u64,
+        },
+        change_set,
+        blobs,
+    };
+    insert_commit(txn, sealer, &checkpoint).await?;
+
+    for id in reparent {
+        let row = commits::Entity::find_by_id(id.clone())
+            .one(txn)
+            .await
+            .map_err(db_err)?
+            .ok_or_else(|| {
+                MnemeError::storage(format!("commit '{id}' vanished during compaction"))
+            })?;
+        let mut commit = encoding::load_commit(txn, sealer, row).await?;
+        let mut parents: Vec<String> = Vec::new();
+        for parent in &commit.summary.parents {
+            let next = if collapsed.contains(parent) {
+                checkpoint_id.clone()
+            } else {
+                parent.clone()
+            };
+            if !parents.contains(&next) {
+                parents.push(next);
+            }
+        }
+        commit.summary.parents = parents;
+        commits::Entity::update(commits::ActiveModel {
+            commit_id: Set(id),
+            parents_json: Set(serialize_json(&commit.summary.parents, "commit parents")?),
+            summary_json: Set(String::new()),
+            ..Default::default()
+        })
+        .exec(txn)
+        .await
+        .map_err(db_err)?;
+    }
+
+    refs::Entity::update_many()
+        .col_expr(refs::Column::CommitId, checkpoint_id.clone().into())
+        .col_expr(refs::Column::UpdatedAtMs, current_time_ms().into())
+        .filter(refs::Column::CommitId.eq(cutoff.summary.id.clone()))
+        .exec(txn)
+        .await
+        .map_err(db_err)?;
+    for row in tags {
+        if !row.tag.starts_with(SNAPSHOT_TAG_PREFIX) && row.commit_id == cutoff.summary.id {
+            snapshot_tags::Entity::update(snapshot_tags::ActiveModel {
+                tag: Set(row.tag),
+                commit_id: Set(checkpoint_id.clone()),
+                created_at_ms: Set(row.created_at_ms),
+            })
+            .exec(txn)
+            .await
+            .map_err(db_err)?;
+        }
+    }
+    snapshot_tags::Entity::insert(snapshot_tags::ActiveModel {
+        tag: Set(format!("{SNAPSHOT_TAG_PREFIX}{checkpoint_id}")),
+        commit_id: Set(checkpoint_id.clone()),
+        created_at_ms: Set(current_time_ms()),
+    })
+    .exec(txn)
+    .await
+    .map_err(db_err)?;
+
+    // Reflog entries naming collapsed commits would otherwise dangle.
+    let collapsed_ids: Vec<String> = collapsed.iter().cloned().collect();
+    for chunk in collapsed_ids.chunks(IN_LIST_CHUNK) {
+        ref_log::Entity::delete_many()
+            .filter(
+                ref_log::Column::OldCommit
+                    .is_in(chunk.to_vec())
+                    .or(ref_log::Column::NewCommit.is_in(chunk.to_vec())),
+            )
+            .exec(txn)
+            .await
+            .map_err(db_err)?;
+    }
+    delete_commits(txn, &collapsed_ids, report).await?;
+    report.compacted_commits = collapsed_ids.len() as u64;
+    report.checkpoint = Some(checkpoint_id);
+    Ok(())
+}
+
+fn db_err(err: sea_orm::DbErr) -> MnemeError {
+    MnemeError::storage(format!("SeaORM error: {err}"))
+}
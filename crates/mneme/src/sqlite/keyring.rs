@@ -0,0 +1,271 @@
+//! Encryption at rest for the SQLite store.
+//!
+//! An encrypted store records its key derivation in `mneme_keyring` and its wrapped data keys in
+//! `mneme_data_keys`. A [`Sealer`] is threaded through every read and write path: it seals
+//! change-set payloads and shared values, commit messages, authors and tags, Metis event payloads,
+//! and the `props_json` of change projections and version rows. For plaintext stores it is a
+//! no-op. Structural columns (ids, types, branches, parents, timestamps) stay in the clear so
+//! lineage and graph queries still run in SQL, and the full-text index is not kept for encrypted
+//! stores because its terms would be readable.
+
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait, PaginatorTrait, Statement,
+    TransactionTrait, Value,
+};
+
+use super::{SqliteDb, commits, current_time_ms, mneme_data_keys, mneme_keyring};
+use crate::crypto::{EncryptionMode, KeyDerivation, KeySource, Keyring, WrappedKey};
+use crate::temporal::CommitSummary;
+use crate::{MnemeError, MnemeResult};
+
+/// Row id of the single `mneme_keyring` row.
+const KEYRING_ROW: i32 = 1;
+
+/// Seals and opens stored values with the store's keyring, or passes them through unchanged.
+#[derive(Clone, Default)]
+pub(super) struct Sealer {
+    keyring: Option<Keyring>,
+}
+
+impl Sealer {
+    pub(super) fn keyring(&self) -> Option<&Keyring> {
+        self.keyring.as_ref()
+    }
+
+    pub(super) fn is_sealed(&self) -> bool {
+        self.keyring.is_some()
+    }
+
+    pub(super) fn seal_bytes(&self, bytes: Vec<u8>) -> MnemeResult<Vec<u8>> {
+        match &self.keyring {
+            Some(keyring) => keyring.seal(&bytes),
+            None => Ok(bytes),
+        }
+    }
+
+    pub(super) fn open_bytes(&self, bytes: Vec<u8>) -> MnemeResult<Vec<u8>> {
+        match &self.keyring {
+            Some(keyring) => keyring.open(&bytes),
+            None => Ok(bytes),
+        }
+    }
+
+    pub(super) fn seal_text(&self, text: String) -> MnemeResult<String> {
+        match &self.keyring {
+            Some(keyring) => keyring.seal_text(&text),
+            None => Ok(text),
+        }
+    }
+
+    pub(super) fn open_text(&self, text: String) -> MnemeResult<String> {
+        match &self.keyring {
+            Some(keyring) => keyring.open_text(&text),
+            None => Ok(text),
+        }
+    }
+
+    pub(super) fn seal_opt(&self, text: Option<String>) -> MnemeResult<Option<String>> {
+        text.map(|text| self.seal_text(text)).transpose()
+    }
+
+    pub(super) fn open_opt(&self, text: Option<String>) -> MnemeResult<Option<String>> {
+        text.map(|text| self.open_text(text)).transpose()
+    }
+
+    /// Seal the free-text fields of a commit summary. Tags are sealed one by one so `tags_json`
+    /// stays a JSON array.
+    pub(super) fn seal_summary(&self, summary: &CommitSummary) -> MnemeResult<CommitSummary> {
+        Ok(CommitSummary {
+            author: self.seal_opt(summary.author.clone())?,
+            message: self.seal_text(summary.message.clone())?,
+            tags: summary
+                .tags
+                .iter()
+                .map(|tag| self.seal_text(tag.clone()))
+                .collect::<MnemeResult<_>>()?,
+            ..summary.clone()
+        })
+    }
+
+    pub(super) fn open_summary(&self, summary: CommitSummary) -> MnemeResult<CommitSummary> {
+        Ok(CommitSummary {
+            author: self.open_opt(summary.author)?,
+            message: self.open_text(summary.message)?,
+            tags: summary
+                .tags
+                .into_iter()
+                .map(|tag| self.open_text(tag))
+                .collect::<MnemeResult<_>>()?,
+            ..summary
+        })
+    }
+
+    /// Content address for a shared value; keyed for encrypted stores so equal hashes do not
+    /// confirm guessed plaintexts.
+    pub(super) fn value_hash(&self, bytes: &[u8]) -> String {
+        match &self.keyring {
+            Some(keyring) => keyring.index_hash(bytes),
+            None => blake3::hash(bytes).to_hex().to_string(),
+        }
+    }
+}
+
+/// Load the store's keyring, creating one when an empty store is opened with a key.
+pub(super) async fn unlock(
+    conn: &DatabaseConnection,
+    key: Option<&KeySource>,
+) -> MnemeResult<Sealer> {
+    let stored = mneme_keyring::Entity::find_by_id(KEYRING_ROW)
+        .one(conn)
+        .await
+        .map_err(db_err)?;
+    let keyring = match (stored, key) {
+        (None, None) => return Ok(Sealer::default()),
+        (Some(row), None) => {
+            return Err(MnemeError::KeyRequired {
+                mode: decode_derivation(&row)?.mode,
+            });
+        }
+        (Some(row), Some(key)) => {
+            let derivation = decode_derivation(&row)?;
+            let wrapped = load_wrapped(conn).await?;
+            Keyring::unlock(key, &derivation, &wrapped, row.active_key_id as u32)?
+        }
+        (None, Some(key)) => {
+            let existing = commits::Entity::find().count(conn).await.map_err(db_err)?;
+            if existing > 0 {
+                return Err(MnemeError::storage(format!(
+                    "datastore already holds {existing} unencrypted commits; encryption is \
+                     chosen when a datastore is created"
+                )));
+            }
+            let (keyring, derivation, wrapped) = Keyring::create(key)?;
+            let txn = conn.begin().await.map_err(db_err)?;
+            store_keyring(&txn, &derivation, &wrapped, keyring.active_key_id(), true).await?;
+            txn.commit().await.map_err(db_err)?;
+            keyring
+        }
+    };
+    Ok(Sealer {
+        keyring: Some(keyring),
+    })
+}
+
+/// Encryption mode recorded in the keyring table of `conn`, e.g. a backup copy.
+pub(super) async fn stored_mode<C: ConnectionTrait>(conn: &C) -> MnemeResult<EncryptionMode> {
+    match mneme_keyring::Entity::find_by_id(KEYRING_ROW)
+        .one(conn)
+        .await
+        .map_err(db_err)?
+    {
+        Some(row) => Ok(decode_derivation(&row)?.mode),
+        None => Ok(EncryptionMode::None),
+    }
+}
+
+impl SqliteDb {
+    /// Keyring of an encrypted store, e.g. to seal layout snapshots with
+    /// [`SealedSnapshotStore`](crate::SealedSnapshotStore).
+    pub fn keyring(&self) -> Option<Keyring> {
+        self.sealer.keyring().cloned()
+    }
+
+    /// Re-wrap the store's data keys under `next` and start a fresh data key for new writes.
+    /// Existing ciphertext stays readable; the previous key no longer opens the store.
+    pub async fn rotate_key(&self, next: &KeySource) -> MnemeResult<()> {
+        let keyring = self
+            .sealer
+            .keyring()
+            .ok_or_else(|| MnemeError::storage("datastore is not encrypted"))?;
+        let rotation = keyring.prepare_rotation(next)?;
+        let txn = self.conn.begin().await.map_err(db_err)?;
+        store_keyring(
+            &txn,
+            &rotation.derivation,
+            &rotation.wrapped,
+            rotation.active,
+            false,
+        )
+        .await?;
+        txn.commit().await.map_err(db_err)?;
+        keyring.finish_rotation(rotation);
+        Ok(())
+    }
+}
+
+async fn store_keyring<C: ConnectionTrait>(
+    conn: &C,
+    derivation: &KeyDerivation,
+    wrapped: &[WrappedKey],
+    active: u32,
+    create: bool,
+) -> MnemeResult<()> {
+    let derivation_json = serde_json::to_string(derivation)
+        .map_err(|err| MnemeError::storage(format!("serialise key derivation: {err}")))?;
+    let now = current_time_ms();
+    let sql = if create {
+        "INSERT INTO mneme_keyring (derivation_json, active_key_id, updated_at_ms, id) \
+         VALUES (?, ?, ?, ?)"
+    } else {
+        "UPDATE mneme_keyring SET derivation_json = ?, active_key_id = ?, updated_at_ms = ? \
+         WHERE id = ?"
+    };
+    exec(
+        conn,
+        sql,
+        vec![
+            derivation_json.into(),
+            i64::from(active).into(),
+            now.into(),
+            KEYRING_ROW.into(),
+        ],
+    )
+    .await?;
+    for entry in wrapped {
+        exec(
+            conn,
+            "INSERT INTO mneme_data_keys (key_id, wrapped, created_at_ms) VALUES (?, ?, ?) \
+             ON CONFLICT(key_id) DO UPDATE SET wrapped = excluded.wrapped",
+            vec![
+                i64::from(entry.key_id).into(),
+                Value::from(entry.wrapped.clone()),
+                now.into(),
+            ],
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+async fn load_wrapped(conn: &DatabaseConnection) -> MnemeResult<Vec<WrappedKey>> {
+    Ok(mneme_data_keys::Entity::find()
+        .all(conn)
+        .await
+        .map_err(db_err)?
+        .into_iter()
+        .map(|row| WrappedKey {
+            key_id: row.key_id as u32,
+            wrapped: row.wrapped,
+        })
+        .collect())
+}
+
+fn decode_derivation(row: &mneme_keyring::Model) -> MnemeResult<KeyDerivation> {
+    serde_json::from_str(&row.derivation_json)
+        .map_err(|err| MnemeError::storage(format!("decode key derivation: {err}")))
+}
+
+async fn exec<C: ConnectionTrait>(conn: &C, sql: &str, values: Vec<Value>) -> MnemeResult<()> {
+    conn.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        sql,
+        values,
+    ))
+    .await
+    .map(|_| ())
+    .map_err(db_err)
+}
+
+fn db_err(err: sea_orm::DbErr) -> MnemeError {
+    MnemeError::storage(format!("SeaORM error: {err}"))
+}
@@ -0,0 +1,23 @@
+//! SeaORM Entity for the `metis_blob_refs` table (which commits reference which blobs).
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, DeriveEntityModel)]
+#[sea_orm(table_name = "metis_blob_refs")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub commit_id: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub hash: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations")
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,24 @@
+//! SeaORM Entity for the `metis_blobs` table (content-addressed blobs stored beside the database).
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, DeriveEntityModel)]
+#[sea_orm(table_name = "metis_blobs")]
+pub struct Model {
+    /// Hex-encoded BLAKE3 hash of the content.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub hash: String,
+    pub size_bytes: i64,
+    pub created_at_ms: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations")
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
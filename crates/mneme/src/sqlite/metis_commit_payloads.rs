@@ -0,0 +1,24 @@
+//! SeaORM Entity for the `metis_commit_payloads` table (encoded commit change sets).
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, DeriveEntityModel)]
+#[sea_orm(table_name = "metis_commit_payloads")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub commit_id: String,
+    /// Encoding version of `payload`; readers reject versions they do not know.
+    pub format: i32,
+    pub payload: Vec<u8>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations")
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
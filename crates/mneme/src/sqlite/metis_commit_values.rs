@@ -0,0 +1,25 @@
+//! SeaORM Entity for the `metis_commit_values` table (large property values shared by commits).
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, DeriveEntityModel)]
+#[sea_orm(table_name = "metis_commit_values")]
+pub struct Model {
+    /// Hex-encoded BLAKE3 hash of the value's JSON text.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub hash: String,
+    /// Deflate-compressed JSON text.
+    pub value: Vec<u8>,
+    pub size_bytes: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations")
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,30 @@
+//! SeaORM Entity for the `metis_consumer_deliveries` table (acks, pending retries, and dead
+//! letters that have not yet been folded into a consumer's offset).
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, DeriveEntityModel)]
+#[sea_orm(table_name = "metis_consumer_deliveries")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub consumer: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub event_id: String,
+    /// `acked`, `retry`, or `dead`.
+    pub state: String,
+    pub attempts: i64,
+    pub next_attempt_at_ms: Option<i64>,
+    pub last_error: Option<String>,
+    pub updated_at_ms: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations")
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
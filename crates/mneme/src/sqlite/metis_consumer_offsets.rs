@@ -0,0 +1,26 @@
+//! SeaORM Entity for the `metis_consumer_offsets` table (committed cursor per consumer group).
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, DeriveEntityModel)]
+#[sea_orm(table_name = "metis_consumer_offsets")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub consumer: String,
+    /// `metis_events.seq` of the last event the offset covers.
+    pub cursor_seq: i64,
+    pub cursor_at_ms: i64,
+    pub cursor_event_id: String,
+    pub updated_at_ms: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations")
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -5,7 +5,10 @@ use sea_orm::entity::prelude::*;
 #[derive(Clone, Debug, DeriveEntityModel)]
 #[sea_orm(table_name = "metis_events")]
 pub struct Model {
-    #[sea_orm(primary_key, auto_increment = false)]
+    /// Insertion order; consumer offsets follow it. AUTOINCREMENT, so numbers are never reused.
+    #[sea_orm(primary_key)]
+    pub seq: i64,
+    #[sea_orm(unique)]
     pub event_id: String,
     pub commit_id: String,
     pub payload: String,
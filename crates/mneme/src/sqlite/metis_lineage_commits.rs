@@ -0,0 +1,23 @@
+//! SeaORM entity placing each commit at a sequence number within its lineage.
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, DeriveEntityModel)]
+#[sea_orm(table_name = "metis_lineage_commits")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub commit_id: String,
+    pub lineage_id: String,
+    pub seq: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations")
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
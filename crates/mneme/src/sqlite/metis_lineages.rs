@@ -0,0 +1,26 @@
+//! SeaORM entity for branch lineages indexed by the version tables.
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, DeriveEntityModel)]
+#[sea_orm(table_name = "metis_lineages")]
+pub struct Model {
+    /// Id of the first commit in the lineage.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub lineage_id: String,
+    pub branch: String,
+    /// Commit the lineage forked from; its live versions were copied in at sequence 0.
+    pub base_commit: Option<String>,
+    pub head_seq: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations")
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,31 @@
+//! SeaORM entity for node versions valid over a sequence range of a lineage.
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, DeriveEntityModel)]
+#[sea_orm(table_name = "metis_node_versions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub lineage_id: String,
+    pub node_id: String,
+    pub node_type: Option<String>,
+    pub props_json: Option<String>,
+    pub valid_from: i64,
+    pub valid_to: Option<i64>,
+    pub from_commit: String,
+    pub to_commit: Option<String>,
+    /// Row this one was copied from when the lineage forked.
+    pub origin_id: Option<i64>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations")
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
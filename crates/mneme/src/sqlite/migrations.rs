@@ -1,39 +1,262 @@
 //! Schema migration tracking for the SeaORM-backed SQLite store.
+//!
+//! Migrations run in order on open, each in its own transaction together with its
+//! `mneme_migrations` row. Schema steps are DDL built from the entities; data steps rewrite or
+//! seed rows with Rust code. A store that records migrations this build does not know was
+//! written by a newer release and is refused before anything runs.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
 
 use sea_orm::entity::prelude::*;
 use sea_orm::{
-    DatabaseConnection, DbBackend, DbErr, EntityTrait, QueryOrder, Schema, Set, Statement,
-    TransactionTrait,
+    Database, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr, EntityTrait, QueryOrder,
+    Schema, Set, Statement, TransactionTrait,
 };
 use sea_query::SqliteQueryBuilder;
 
 use super::{
-    commits, current_time_ms, metis_edge_changes, metis_events, metis_node_changes, refs,
-    snapshot_tags,
+    SqliteDb, commits, current_time_ms, metis_blob_refs, metis_blobs, metis_commit_payloads,
+    metis_commit_value_refs, metis_commit_values, metis_consumer_deliveries,
+    metis_consumer_offsets, metis_edge_changes, metis_edge_versions, metis_events,
+    metis_lineage_commits, metis_lineages, metis_node_changes, metis_node_versions,
+    mneme_data_keys, mneme_keyring, ref_log, refs, snapshot_tags,
 };
+use crate::lock::DatastoreLock;
+use crate::schema::{MigrationKind, MigrationStatus, PendingMigration, SchemaStatus};
+use crate::{MnemeError, MnemeResult};
+
+type DataMigration = for<'a> fn(
+    &'a DatabaseTransaction,
+) -> Pin<Box<dyn Future<Output = Result<(), DbErr>> + Send + 'a>>;
+
+enum Step {
+    Schema(fn() -> Vec<String>),
+    Data {
+        /// Shown by dry runs in place of SQL.
+        description: &'static str,
+        run: DataMigration,
+    },
+}
 
 struct Migration {
     id: &'static str,
-    build_statements: fn() -> Vec<String>,
+    step: Step,
 }
 
-const MIGRATIONS: &[Migration] = &[Migration {
-    id: "0001_init_schema",
-    build_statements: build_initial_schema_statements,
-}];
+impl Migration {
+    fn kind(&self) -> MigrationKind {
+        match self.step {
+            Step::Schema(_) => MigrationKind::Schema,
+            Step::Data { .. } => MigrationKind::Data,
+        }
+    }
+
+    fn statements(&self) -> Vec<String> {
+        match &self.step {
+            Step::Schema(build) => build(),
+            Step::Data { description, .. } => vec![format!("-- data migration: {description}")],
+        }
+    }
+}
 
-pub(super) async fn apply(conn: &DatabaseConnection) -> Result<(), DbErr> {
-    ensure_history_table(conn).await?;
-    let applied = applied_migrations(conn).await?;
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        id: "0001_init_schema",
+        step: Step::Schema(build_initial_schema_statements),
+    },
+    Migration {
+        id: "0002_ref_log",
+        step: Step::Schema(build_ref_log_statements),
+    },
+    Migration {
+        id: "0003_metis_consumers",
+        step: Step::Schema(build_consumer_statements),
+    },
+    Migration {
+        id: "0004_metis_versions",
+        step: Step::Schema(build_version_statements),
+    },
+    Migration {
+        id: "0005_metis_search",
+        step: Step::Schema(build_search_statements),
+    },
+    Migration {
+        id: "0006_metis_blobs",
+        step: Step::Schema(build_blob_statements),
+    },
+    Migration {
+        id: "0007_commit_encoding",
+        step: Step::Schema(build_commit_encoding_statements),
+    },
+    Migration {
+        id: "0008_keyring",
+        step: Step::Schema(build_keyring_statements),
+    },
+    Migration {
+        id: "0009_seed_ref_log",
+        step: Step::Data {
+            description: "record a ref_log entry for branch heads moved before 0002_ref_log",
+            run: seed_ref_log,
+        },
+    },
+    Migration {
+        id: "0010_metis_event_seq",
+        step: Step::Schema(build_event_seq_statements),
+    },
+];
+
+pub(super) async fn apply(conn: &DatabaseConnection) -> MnemeResult<()> {
+    ensure_history_table(conn).await.map_err(db_err)?;
+    let status = status_of(&applied_migrations(conn).await.map_err(db_err)?);
+    guard_newer(&status)?;
     for migration in MIGRATIONS {
-        if applied.contains(&migration.id.to_string()) {
+        if status
+            .migrations
+            .iter()
+            .any(|known| known.id == migration.id && known.is_applied())
+        {
             continue;
         }
-        apply_migration(conn, migration).await?;
+        apply_migration(conn, migration).await.map_err(|err| {
+            MnemeError::storage(format!("apply migration {}: {err}", migration.id))
+        })?;
+    }
+    Ok(())
+}
+
+/// Migration status of the database at `conn` without changing it.
+pub(super) async fn status(conn: &DatabaseConnection) -> MnemeResult<SchemaStatus> {
+    let exists = conn
+        .query_one(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT 1 AS present FROM sqlite_master WHERE type = 'table' AND name = 'mneme_migrations'",
+        ))
+        .await
+        .map_err(db_err)?
+        .is_some();
+    let applied = if exists {
+        applied_migrations(conn).await.map_err(db_err)?
+    } else {
+        Vec::new()
+    };
+    Ok(status_of(&applied))
+}
+
+fn status_of(applied: &[history::Model]) -> SchemaStatus {
+    let applied_at: HashMap<&str, i64> = applied
+        .iter()
+        .map(|row| (row.migration_id.as_str(), row.applied_at_ms))
+        .collect();
+    SchemaStatus {
+        migrations: MIGRATIONS
+            .iter()
+            .map(|migration| MigrationStatus {
+                id: migration.id.to_string(),
+                kind: migration.kind(),
+                applied_at_ms: applied_at.get(migration.id).copied(),
+            })
+            .collect(),
+        unknown: applied
+            .iter()
+            .filter(|row| MIGRATIONS.iter().all(|known| known.id != row.migration_id))
+            .map(|row| row.migration_id.clone())
+            .collect(),
+    }
+}
+
+/// Fail unless the database at `conn` is fully migrated, for connections that cannot migrate it.
+pub(super) async fn require_current(conn: &DatabaseConnection, path: &Path) -> MnemeResult<()> {
+    let status = status(conn).await?;
+    guard_newer(&status)?;
+    let pending: Vec<_> = status
+        .pending()
+        .map(|pending| pending.id.as_str())
+        .collect();
+    if !pending.is_empty() {
+        return Err(MnemeError::storage(format!(
+            "sqlite store '{}' needs migrations ({}); open it for writing once to apply them",
+            path.display(),
+            pending.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+fn guard_newer(status: &SchemaStatus) -> MnemeResult<()> {
+    if status.is_newer_than_supported() {
+        return Err(MnemeError::SchemaTooNew {
+            unknown: status.unknown.clone(),
+            latest: status.latest().unwrap_or_default().to_string(),
+        });
     }
     Ok(())
 }
 
+impl SqliteDb {
+    /// Migration status of the database at `path` without migrating or unlocking it. A missing
+    /// file reports every migration as pending.
+    pub async fn schema_status(path: impl AsRef<Path>) -> MnemeResult<SchemaStatus> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(status_of(&[]));
+        }
+        let conn = connect_read_only(path).await?;
+        let status = status(&conn).await;
+        conn.close().await.map_err(db_err)?;
+        status
+    }
+
+    /// Migrations opening the database at `path` would apply, with their SQL; nothing is
+    /// written. Fails with [`MnemeError::SchemaTooNew`] for databases from a newer release.
+    pub async fn plan_migrations(path: impl AsRef<Path>) -> MnemeResult<Vec<PendingMigration>> {
+        let status = Self::schema_status(path).await?;
+        guard_newer(&status)?;
+        Ok(MIGRATIONS
+            .iter()
+            .filter(|migration| status.pending().any(|pending| pending.id == migration.id))
+            .map(|migration| PendingMigration {
+                id: migration.id.to_string(),
+                kind: migration.kind(),
+                statements: migration.statements(),
+            })
+            .collect())
+    }
+
+    /// Apply pending migrations to the database at `path` without unlocking it, returning the
+    /// resulting status. Opening a store does the same implicitly. Takes the writer lock for
+    /// the duration.
+    pub async fn migrate(path: impl AsRef<Path>) -> MnemeResult<SchemaStatus> {
+        let path = path.as_ref();
+        let _lock = DatastoreLock::acquire(path)?;
+        let database_url = format!("sqlite://{}?mode=rwc", path.display());
+        let conn = Database::connect(&database_url).await.map_err(|err| {
+            MnemeError::storage(format!("open sqlite store '{}': {err}", path.display()))
+        })?;
+        let outcome = match apply(&conn).await {
+            Ok(()) => status(&conn).await,
+            Err(err) => Err(err),
+        };
+        conn.close().await.map_err(db_err)?;
+        outcome
+    }
+
+    /// Migration status of this open store.
+    pub async fn schema(&self) -> MnemeResult<SchemaStatus> {
+        status(&self.conn).await
+    }
+}
+
+pub(super) async fn connect_read_only(path: &Path) -> MnemeResult<DatabaseConnection> {
+    Database::connect(format!("sqlite://{}?mode=ro", path.display()))
+        .await
+        .map_err(|err| {
+            MnemeError::storage(format!("open sqlite store '{}': {err}", path.display()))
+        })
+}
+
 async fn ensure_history_table(conn: &DatabaseConnection) -> Result<(), DbErr> {
     let backend = DbBackend::Sqlite;
     let schema = Schema::new(backend);
@@ -46,21 +269,24 @@ async fn ensure_history_table(conn: &DatabaseConnection) -> Result<(), DbErr> {
         .map(|_| ())
 }
 
-async fn applied_migrations(conn: &DatabaseConnection) -> Result<Vec<String>, DbErr> {
-    let rows = history::Entity::find()
+async fn applied_migrations(conn: &DatabaseConnection) -> Result<Vec<history::Model>, DbErr> {
+    history::Entity::find()
         .order_by_asc(history::Column::MigrationId)
         .all(conn)
-        .await?;
-    Ok(rows.into_iter().map(|row| row.migration_id).collect())
+        .await
 }
 
 async fn apply_migration(conn: &DatabaseConnection, migration: &Migration) -> Result<(), DbErr> {
     let backend = DbBackend::Sqlite;
-    let statements = (migration.build_statements)();
     let txn = conn.begin().await?;
-    for statement in statements {
-        txn.execute(Statement::from_string(backend, statement))
-            .await?;
+    match &migration.step {
+        Step::Schema(build) => {
+            for statement in build() {
+                txn.execute(Statement::from_string(backend, statement))
+                    .await?;
+            }
+        }
+        Step::Data { run, .. } => run(&txn).await?,
     }
     history::ActiveModel {
         migration_id: Set(migration.id.to_string()),
@@ -71,6 +297,10 @@ async fn apply_migration(conn: &DatabaseConnection, migration: &Migration) -> Re
     txn.commit().await
 }
 
+fn db_err(err: DbErr) -> MnemeError {
+    MnemeError::storage(format!("SeaORM error: {err}"))
+}
+
 fn build_initial_schema_statements() -> Vec<String> {
     let backend = DbBackend::Sqlite;
     let schema = Schema::new(backend);
@@ -122,6 +352,225 @@ fn build_initial_schema_statements() -> Vec<String> {
     statements
 }
 
+fn build_ref_log_statements() -> Vec<String> {
+    let backend = DbBackend::Sqlite;
+    let schema = Schema::new(backend);
+    vec![
+        schema
+            .create_table_from_entity(ref_log::Entity)
+            .if_not_exists()
+            .to_string(SqliteQueryBuilder),
+        String::from("CREATE INDEX IF NOT EXISTS idx_ref_log_branch ON ref_log(branch)"),
+        String::from("CREATE INDEX IF NOT EXISTS idx_ref_log_updated ON ref_log(updated_at_ms)"),
+    ]
+}
+
+fn build_consumer_statements() -> Vec<String> {
+    let backend = DbBackend::Sqlite;
+    let schema = Schema::new(backend);
+    vec![
+        schema
+            .create_table_from_entity(metis_consumer_offsets::Entity)
+            .if_not_exists()
+            .to_string(SqliteQueryBuilder),
+        schema
+            .create_table_from_entity(metis_consumer_deliveries::Entity)
+            .if_not_exists()
+            .to_string(SqliteQueryBuilder),
+        String::from(
+            "CREATE INDEX IF NOT EXISTS idx_metis_events_cursor ON metis_events(created_at_ms, event_id)",
+        ),
+        String::from(
+            "CREATE INDEX IF NOT EXISTS idx_metis_deliveries_due ON metis_consumer_deliveries(consumer, state, next_attempt_at_ms)",
+        ),
+    ]
+}
+
+fn build_version_statements() -> Vec<String> {
+    let backend = DbBackend::Sqlite;
+    let schema = Schema::new(backend);
+    vec![
+        schema
+            .create_table_from_entity(metis_lineages::Entity)
+            .if_not_exists()
+            .to_string(SqliteQueryBuilder),
+        schema
+            .create_table_from_entity(metis_lineage_commits::Entity)
+            .if_not_exists()
+            .to_string(SqliteQueryBuilder),
+        schema
+            .create_table_from_entity(metis_node_versions::Entity)
+            .if_not_exists()
+            .to_string(SqliteQueryBuilder),
+        schema
+            .create_table_from_entity(metis_edge_versions::Entity)
+            .if_not_exists()
+            .to_string(SqliteQueryBuilder),
+        String::from(
+            "CREATE INDEX IF NOT EXISTS idx_metis_lineage_commits_seq ON metis_lineage_commits(lineage_id, seq)",
+        ),
+        String::from(
+            "CREATE INDEX IF NOT EXISTS idx_metis_node_versions_range ON metis_node_versions(lineage_id, valid_from)",
+        ),
+        String::from(
+            "CREATE INDEX IF NOT EXISTS idx_metis_node_versions_node ON metis_node_versions(lineage_id, node_id)",
+        ),
+        String::from(
+            "CREATE INDEX IF NOT EXISTS idx_metis_edge_versions_range ON metis_edge_versions(lineage_id, valid_from)",
+        ),
+        String::from(
+            "CREATE INDEX IF NOT EXISTS idx_metis_edge_versions_ends ON metis_edge_versions(lineage_id, from_node, to_node)",
+        ),
+        String::from(
+            "CREATE INDEX IF NOT EXISTS idx_metis_edge_versions_edge ON metis_edge_versions(lineage_id, edge_id)",
+        ),
+    ]
+}
+
+fn build_search_statements() -> Vec<String> {
+    vec![
+        String::from(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS metis_node_search USING fts5(body, tokenize = 'unicode61 remove_diacritics 2')",
+        ),
+        String::from(
+            "CREATE INDEX IF NOT EXISTS idx_metis_node_versions_origin ON metis_node_versions(origin_id)",
+        ),
+    ]
+}
+
+fn build_blob_statements() -> Vec<String> {
+    let backend = DbBackend::Sqlite;
+    let schema = Schema::new(backend);
+    vec![
+        schema
+            .create_table_from_entity(metis_blobs::Entity)
+            .if_not_exists()
+            .to_string(SqliteQueryBuilder),
+        schema
+            .create_table_from_entity(metis_blob_refs::Entity)
+            .if_not_exists()
+            .to_string(SqliteQueryBuilder),
+        String::from(
+            "CREATE INDEX IF NOT EXISTS idx_metis_blob_refs_hash ON metis_blob_refs(hash)",
+        ),
+    ]
+}
+
+fn build_commit_encoding_statements() -> Vec<String> {
+    let backend = DbBackend::Sqlite;
+    let schema = Schema::new(backend);
+    vec![
+        schema
+            .create_table_from_entity(metis_commit_payloads::Entity)
+            .if_not_exists()
+            .to_string(SqliteQueryBuilder),
+        schema
+            .create_table_from_entity(metis_commit_values::Entity)
+            .if_not_exists()
+            .to_string(SqliteQueryBuilder),
+        schema
+            .create_table_from_entity(metis_commit_value_refs::Entity)
+            .if_not_exists()
+            .to_string(SqliteQueryBuilder),
+        String::from(
+            "CREATE INDEX IF NOT EXISTS idx_metis_commit_value_refs_hash ON metis_commit_value_refs(hash)",
+        ),
+    ]
+}
+
+fn build_keyring_statements() -> Vec<String> {
+    let backend = DbBackend::Sqlite;
+    let schema = Schema::new(backend);
+    vec![
+        schema
+            .create_table_from_entity(mneme_keyring::Entity)
+            .if_not_exists()
+            .to_string(SqliteQueryBuilder),
+        schema
+            .create_table_from_entity(mneme_data_keys::Entity)
+            .if_not_exists()
+            .to_string(SqliteQueryBuilder),
+    ]
+}
+
+/// Rebuild `metis_events` around an AUTOINCREMENT `seq`, numbering existing events in the old
+/// `(created_at_ms, event_id)` cursor order, and move each consumer offset onto it. `seq` is the
+/// rowid, so cursor scans need no separate index and the old `idx_metis_events_cursor` goes.
+fn build_event_seq_statements() -> Vec<String> {
+    vec![
+        String::from(
+            "CREATE TABLE metis_events_seq ( \
+             \"seq\" integer NOT NULL PRIMARY KEY AUTOINCREMENT, \
+             \"event_id\" varchar NOT NULL UNIQUE, \
+             \"commit_id\" varchar NOT NULL, \
+             \"payload\" varchar NOT NULL, \
+             \"created_at_ms\" bigint NOT NULL )",
+        ),
+        String::from(
+            "INSERT INTO metis_events_seq (event_id, commit_id, payload, created_at_ms) \
+             SELECT event_id, commit_id, payload, created_at_ms FROM metis_events \
+             ORDER BY created_at_ms, event_id",
+        ),
+        String::from("DROP TABLE metis_events"),
+        String::from("ALTER TABLE metis_events_seq RENAME TO metis_events"),
+        String::from(
+            "CREATE INDEX IF NOT EXISTS idx_metis_events_commit ON metis_events(commit_id)",
+        ),
+        String::from(
+            "CREATE TABLE metis_consumer_offsets_seq ( \
+             \"consumer\" varchar NOT NULL PRIMARY KEY, \
+             \"cursor_seq\" bigint NOT NULL, \
+             \"cursor_at_ms\" bigint NOT NULL, \
+             \"cursor_event_id\" varchar NOT NULL, \
+             \"updated_at_ms\" bigint NOT NULL )",
+        ),
+        String::from(
+            "INSERT INTO metis_consumer_offsets_seq \
+             (consumer, cursor_seq, cursor_at_ms, cursor_event_id, updated_at_ms) \
+             SELECT o.consumer, COALESCE((SELECT MAX(e.seq) FROM metis_events e \
+               WHERE e.created_at_ms < o.cursor_at_ms \
+                  OR (e.created_at_ms = o.cursor_at_ms AND e.event_id <= o.cursor_event_id)), 0), \
+             o.cursor_at_ms, o.cursor_event_id, o.updated_at_ms FROM metis_consumer_offsets o",
+        ),
+        String::from("DROP TABLE metis_consumer_offsets"),
+        String::from("ALTER TABLE metis_consumer_offsets_seq RENAME TO metis_consumer_offsets"),
+    ]
+}
+
+/// Heads set before `0002_ref_log` have no reflog entry, so GC retention and ref history could
+/// not see how they got there. Record one entry per head that lacks it.
+fn seed_ref_log(
+    txn: &DatabaseTransaction,
+) -> Pin<Box<dyn Future<Output = Result<(), DbErr>> + Send + '_>> {
+    Box::pin(async move {
+        for head in refs::Entity::find()
+            .filter(refs::Column::CommitId.is_not_null())
+            .all(txn)
+            .await?
+        {
+            let logged = ref_log::Entity::find()
+                .filter(ref_log::Column::Branch.eq(head.branch.clone()))
+                .filter(ref_log::Column::NewCommit.eq(head.commit_id.clone()))
+                .one(txn)
+                .await?
+                .is_some();
+            if logged {
+                continue;
+            }
+            ref_log::ActiveModel {
+                branch: Set(head.branch),
+                old_commit: Set(None),
+                new_commit: Set(head.commit_id),
+                updated_at_ms: Set(head.updated_at_ms),
+                ..Default::default()
+            }
+            .insert(txn)
+            .await?;
+        }
+        Ok(())
+    })
+}
+
 mod history {
     use sea_orm::entity::prelude::*;
 
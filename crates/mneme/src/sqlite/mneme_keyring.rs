@@ -0,0 +1,27 @@
+//! SeaORM Entity for the `mneme_keyring` table (key derivation for an encrypted store).
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, DeriveEntityModel)]
+#[sea_orm(table_name = "mneme_keyring")]
+pub struct Model {
+    /// Always `1`: a store has at most one keyring.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i32,
+    /// `KeyDerivation` describing how the key-encryption key is derived.
+    pub derivation_json: String,
+    /// Data key used for new writes.
+    pub active_key_id: i64,
+    pub updated_at_ms: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations")
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,106 +1,168 @@
 //! SeaORM-backed implementation of the Mneme store.
 
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, ConnectionTrait, Database, DatabaseConnection, DbBackend, DbErr,
-    EntityTrait, QueryFilter, Set, Statement, TransactionTrait, Value,
+    ActiveModelTrait, ConnectionTrait, Database, DatabaseConnection, DbBackend, DbErr, EntityTrait,
+    QueryOrder, Set, Statement, TransactionTrait, Value,
 };
 
 use async_trait::async_trait;
 use serde::Serialize;
 
-use crate::{MnemeError, MnemeResult, PersistedCommit, Store};
+use crate::blob::BlobStore;
+use crate::crypto::{EncryptionMode, KeySource};
+use crate::lock::DatastoreLock;
+use crate::search::{SearchHit, SearchQuery};
+use crate::temporal::CommitSummary;
+use crate::versions::{ElementRef, ElementVersion, GraphFilter, GraphState};
+use crate::{
+    ChangeEvent, ChangeFeed, ChangeSubscription, CommitBatch, MnemeError, MnemeResult,
+    PersistedCommit, Store,
+};
 
+mod backup;
+mod blobs;
 mod commits;
+mod encoding;
+pub use encoding::ReencodeReport;
+mod feed;
+mod gc;
+mod keyring;
+mod metis_blob_refs;
+mod metis_blobs;
+mod metis_commit_payloads;
+mod metis_commit_value_refs;
+mod metis_commit_values;
+mod metis_consumer_deliveries;
+mod metis_consumer_offsets;
 mod metis_edge_changes;
+mod metis_edge_versions;
 mod metis_events;
+mod metis_lineage_commits;
+mod metis_lineages;
 mod metis_node_changes;
+mod metis_node_versions;
 mod migrations;
+mod mneme_data_keys;
+mod mneme_keyring;
+mod outbox;
+pub(crate) use backup::copy_database;
+pub use outbox::OutboxConsumer;
 mod projections;
+mod ref_log;
 mod refs;
+mod search;
 mod snapshot_tags;
+mod versions;
+
+/// Keep `IN (...)` lists well below SQLite's bound-parameter limit.
+const IN_LIST_CHUNK: usize = 500;
 
 /// SeaORM-backed implementation of the Mneme store (synonym kept for existing callers).
 #[derive(Clone)]
 pub struct SqliteDb {
     conn: DatabaseConnection,
+    feed: ChangeFeed,
+    blob_dir: PathBuf,
+    sealer: keyring::Sealer,
+    /// Writer lock shared by clones; `None` for read-only opens.
+    lock: Option<DatastoreLock>,
 }
 
 impl SqliteDb {
     /// Open (or create) a SQLite database using SeaORM, apply migrations, and ensure the main branch exists.
+    /// Takes the datastore's writer lock; another live writer fails with [`MnemeError::Locked`].
     pub async fn open(path: impl AsRef<Path>) -> MnemeResult<Self> {
-        let database_url = format!("sqlite://{}?mode=rwc&cache=shared", path.as_ref().display());
+        Self::open_with_key(path.as_ref(), None).await
+    }
+
+    /// Open an encrypted store with `key`. A store without commits is encrypted from here on;
+    /// a wrong key fails with [`MnemeError::WrongKey`].
+    pub async fn open_encrypted(path: impl AsRef<Path>, key: &KeySource) -> MnemeResult<Self> {
+        Self::open_with_key(path.as_ref(), Some(key)).await
+    }
+
+    /// Open an existing, fully migrated store for reading without taking the writer lock, e.g.
+    /// while the desktop app holds it. Writes through this handle fail.
+    pub async fn open_read_only(path: impl AsRef<Path>) -> MnemeResult<Self> {
+        Self::open_read_only_with_key(path.as_ref(), None).await
+    }
+
+    /// [`SqliteDb::open_read_only`] for an encrypted store.
+    pub async fn open_encrypted_read_only(
+        path: impl AsRef<Path>,
+        key: &KeySource,
+    ) -> MnemeResult<Self> {
+        Self::open_read_only_with_key(path.as_ref(), Some(key)).await
+    }
+
+    async fn open_with_key(path: &Path, key: Option<&KeySource>) -> MnemeResult<Self> {
+        let lock = DatastoreLock::acquire(path)?;
+        let database_url = format!("sqlite://{}?mode=rwc&cache=shared", path.display());
         let conn = Database::connect(&database_url).await.map_err(|err| {
-            MnemeError::storage(format!(
-                "open sqlite store '{}': {err}",
-                path.as_ref().display()
-            ))
+            MnemeError::storage(format!("open sqlite store '{}': {err}", path.display()))
         })?;
-        run_migrations(&conn)
-            .await
-            .map_err(|err| MnemeError::storage(format!("sqlite migrations: {err}")))?;
+        migrations::apply(&conn).await?;
+        let sealer = keyring::unlock(&conn, key).await?;
         ensure_main_branch(&conn)
             .await
             .map_err(|err| MnemeError::storage(format!("ensure main branch: {err}")))?;
-        Ok(Self { conn })
+        backfill_versions(&conn, &sealer).await?;
+        lock.spawn_heartbeat();
+        Ok(Self {
+            conn,
+            feed: ChangeFeed::default(),
+            blob_dir: blobs::blob_dir(path),
+            sealer,
+            lock: Some(lock),
+        })
     }
 
-    fn commit_model_from(
-        &self,
-        commit: &PersistedCommit,
-    ) -> Result<commits::ActiveModel, MnemeError> {
-        Ok(commits::ActiveModel {
-            commit_id: Set(commit.summary.id.clone()),
-            branch: Set(commit.summary.branch.clone()),
-            parents_json: Set(serialize_json(&commit.summary.parents, "commit parents")?),
-            author: Set(commit.summary.author.clone()),
-            time: Set(commit.summary.time.clone()),
-            message: Set(commit.summary.message.clone()),
-            tags_json: Set(serialize_json(&commit.summary.tags, "commit tags")?),
-            change_count: Set(commit.summary.change_count as i64),
-            summary_json: Set(serialize_json(&commit.summary, "commit summary")?),
-            changes_json: Set(serialize_json(&commit.change_set, "commit changes")?),
+    async fn open_read_only_with_key(path: &Path, key: Option<&KeySource>) -> MnemeResult<Self> {
+        let conn = migrations::connect_read_only(path).await?;
+        migrations::require_current(&conn, path).await?;
+        if key.is_some() && keyring::stored_mode(&conn).await? == EncryptionMode::None {
+            return Err(MnemeError::storage(format!(
+                "sqlite store '{}' is not encrypted",
+                path.display()
+            )));
+        }
+        let sealer = keyring::unlock(&conn, key).await?;
+        Ok(Self {
+            conn,
+            feed: ChangeFeed::default(),
+            blob_dir: blobs::blob_dir(path),
+            sealer,
+            lock: None,
         })
     }
+
+    /// True when this handle holds the writer lock.
+    pub fn is_writable(&self) -> bool {
+        self.lock.is_some()
+    }
 }
 
 #[async_trait]
 impl Store for SqliteDb {
     async fn put_commit(&self, commit: &PersistedCommit) -> MnemeResult<()> {
         let conn = self.conn.clone();
-        let model = self.commit_model_from(commit)?;
-        let projections = projections::project_commit(commit)?;
         let txn = conn
             .begin()
             .await
             .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
-        model
-            .insert(&txn)
-            .await
-            .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
-        projections
-            .event
-            .insert(&txn)
-            .await
-            .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
-        if !projections.node_changes.is_empty() {
-            metis_node_changes::Entity::insert_many(projections.node_changes)
-                .exec(&txn)
-                .await
-                .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
-        }
-        if !projections.edge_changes.is_empty() {
-            metis_edge_changes::Entity::insert_many(projections.edge_changes)
-                .exec(&txn)
-                .await
-                .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
-        }
+        let at_ms = insert_commit(&txn, &self.sealer, commit).await?;
         txn.commit()
             .await
-            .map(|_| ())
-            .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))
+            .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
+        self.feed.publish(ChangeEvent::CommitPersisted {
+            at_ms,
+            commit: commit.summary.clone(),
+        });
+        Ok(())
     }
 
     async fn get_commit(&self, id: &str) -> MnemeResult<Option<PersistedCommit>> {
@@ -110,18 +172,11 @@ impl Store for SqliteDb {
             .one(&conn)
             .await
             .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
-        if let Some(rec) = record {
-            let summary: crate::temporal::CommitSummary =
-                serde_json::from_str(&rec.summary_json)
-                    .map_err(|err| MnemeError::storage(err.to_string()))?;
-            let change_set: crate::temporal::ChangeSet = serde_json::from_str(&rec.changes_json)
-                .map_err(|err| MnemeError::storage(err.to_string()))?;
-            Ok(Some(PersistedCommit {
-                summary,
-                change_set,
-            }))
-        } else {
-            Ok(None)
+        match record {
+            Some(record) => encoding::load_commit(&conn, &self.sealer, record)
+                .await
+                .map(Some),
+            None => Ok(None),
         }
     }
 
@@ -148,40 +203,28 @@ impl Store for SqliteDb {
         next: Option<&str>,
     ) -> MnemeResult<()> {
         let conn = self.conn.clone();
-        let branch = branch.to_string();
-        let expected = expected.map(|s| s.to_string());
-        let next_commit = next.map(|s| s.to_string());
+        let updated_at_ms = current_time_ms();
         let txn = conn
             .begin()
             .await
             .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
-        let current = refs::Entity::find_by_id(branch.clone())
-            .one(&txn)
-            .await
-            .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
-        let current_head = current.as_ref().and_then(|row| row.commit_id.clone());
-        if current_head.as_deref() != expected.as_deref() {
-            txn.rollback()
-                .await
-                .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
-            return Err(MnemeError::ConcurrencyConflict {
-                branch: branch.clone(),
-                expected,
-                actual: current_head,
-            });
-        }
-        refs::Entity::update(refs::ActiveModel {
-            branch: Set(branch.clone()),
-            commit_id: Set(next_commit.clone()),
-            updated_at_ms: Set(current_time_ms()),
-        })
-        .filter(refs::Column::Branch.eq(branch.clone()))
-        .exec(&txn)
-        .await
-        .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
+        // Dropping the transaction on a conflict rolls it back.
+        swap_ref(&txn, branch, expected, next, updated_at_ms).await?;
         txn.commit()
             .await
-            .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))
+            .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
+        let commit = match next {
+            Some(id) => self.get_commit(id).await?.map(|commit| commit.summary),
+            None => None,
+        };
+        self.feed.publish(ChangeEvent::RefMoved {
+            at_ms: updated_at_ms,
+            branch: branch.to_string(),
+            previous: expected.map(str::to_string),
+            head: next.map(str::to_string),
+            commit,
+        });
+        Ok(())
     }
 
     async fn get_branch_head(&self, branch: &str) -> MnemeResult<Option<String>> {
@@ -197,6 +240,7 @@ impl Store for SqliteDb {
     async fn list_branches(&self) -> MnemeResult<Vec<(String, Option<String>)>> {
         let conn = self.conn.clone();
         let rows = refs::Entity::find()
+            .order_by_asc(refs::Column::Branch)
             .all(&conn)
             .await
             .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
@@ -207,24 +251,19 @@ impl Store for SqliteDb {
     }
 
     async fn put_tag(&self, tag: &str, commit_id: &str) -> MnemeResult<()> {
-        let conn = self.conn.clone();
-        let tag = tag.to_string();
-        let commit = commit_id.to_string();
         let timestamp = current_time_ms();
-        let statement = Statement::from_sql_and_values(
-            DbBackend::Sqlite,
-            "INSERT INTO snapshot_tags (tag, commit_id, created_at_ms) VALUES (?, ?, ?)\
-             ON CONFLICT(tag) DO UPDATE SET commit_id=excluded.commit_id, created_at_ms=excluded.created_at_ms",
-            vec![
-                Value::from(tag.clone()),
-                Value::from(commit.clone()),
-                Value::from(timestamp),
-            ],
-        );
-        conn.execute(statement)
-            .await
-            .map(|_| ())
-            .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))
+        upsert_tag(&self.conn, tag, commit_id, timestamp).await?;
+        let summary = self
+            .get_commit(commit_id)
+            .await?
+            .map(|commit| commit.summary);
+        self.feed.publish(ChangeEvent::TagChanged {
+            at_ms: timestamp,
+            tag: tag.to_string(),
+            commit_id: commit_id.to_string(),
+            commit: summary,
+        });
+        Ok(())
     }
 
     async fn get_tag(&self, tag: &str) -> MnemeResult<Option<String>> {
@@ -240,6 +279,7 @@ impl Store for SqliteDb {
     async fn list_tags(&self) -> MnemeResult<Vec<(String, String)>> {
         let conn = self.conn.clone();
         let rows = snapshot_tags::Entity::find()
+            .order_by_asc(snapshot_tags::Column::Tag)
             .all(&conn)
             .await
             .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
@@ -248,6 +288,123 @@ impl Store for SqliteDb {
             .map(|row| (row.tag, row.commit_id))
             .collect())
     }
+
+    /// Writes the whole batch, projections included, in one transaction; any failure rolls it
+    /// back.
+    async fn put_commits(&self, batch: &CommitBatch) -> MnemeResult<()> {
+        let txn = self
+            .conn
+            .begin()
+            .await
+            .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
+        let mut persisted_at = Vec::with_capacity(batch.commits.len());
+        for commit in &batch.commits {
+            persisted_at.push(insert_commit(&txn, &self.sealer, commit).await?);
+        }
+        let updated_at_ms = current_time_ms();
+        for (tag, commit_id) in &batch.tags {
+            upsert_tag(&txn, tag, commit_id, updated_at_ms).await?;
+        }
+        for update in &batch.refs {
+            swap_ref(
+                &txn,
+                &update.branch,
+                update.expected.as_deref(),
+                update.next.as_deref(),
+                updated_at_ms,
+            )
+            .await?;
+        }
+        txn.commit()
+            .await
+            .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
+
+        let batched: HashMap<&str, &CommitSummary> = batch
+            .commits
+            .iter()
+            .map(|commit| (commit.summary.id.as_str(), &commit.summary))
+            .collect();
+        for (commit, at_ms) in batch.commits.iter().zip(persisted_at) {
+            self.feed.publish(ChangeEvent::CommitPersisted {
+                at_ms,
+                commit: commit.summary.clone(),
+            });
+        }
+        for (tag, commit_id) in &batch.tags {
+            let commit = match batched.get(commit_id.as_str()) {
+                Some(summary) => Some((*summary).clone()),
+                None => self
+                    .get_commit(commit_id)
+                    .await?
+                    .map(|commit| commit.summary),
+            };
+            self.feed.publish(ChangeEvent::TagChanged {
+                at_ms: updated_at_ms,
+                tag: tag.clone(),
+                commit_id: commit_id.clone(),
+                commit,
+            });
+        }
+        for update in &batch.refs {
+            let commit = match update.next.as_deref() {
+                Some(id) => match batched.get(id) {
+                    Some(summary) => Some((*summary).clone()),
+                    None => self.get_commit(id).await?.map(|commit| commit.summary),
+                },
+                None => None,
+            };
+            self.feed.publish(ChangeEvent::RefMoved {
+                at_ms: updated_at_ms,
+                branch: update.branch.clone(),
+                previous: update.expected.clone(),
+                head: update.next.clone(),
+                commit,
+            });
+        }
+        Ok(())
+    }
+
+    async fn subscribe(&self, since_ms: Option<i64>) -> MnemeResult<ChangeSubscription> {
+        let live = self.feed.receiver();
+        let backlog = match since_ms {
+            Some(since) => feed::replay_since(&self.conn, &self.sealer, since).await?,
+            None => Vec::new(),
+        };
+        Ok(ChangeSubscription::new(backlog, live))
+    }
+
+    async fn graph_state(&self, commit_id: &str, filter: &GraphFilter) -> MnemeResult<GraphState> {
+        versions::graph_state(&self.conn, &self.sealer, commit_id, filter).await
+    }
+
+    async fn element_history(
+        &self,
+        commit_id: &str,
+        element: &ElementRef,
+    ) -> MnemeResult<Vec<ElementVersion>> {
+        versions::element_history(&self.conn, &self.sealer, commit_id, element).await
+    }
+
+    async fn search_nodes(
+        &self,
+        commit_id: &str,
+        query: &SearchQuery,
+    ) -> MnemeResult<Vec<SearchHit>> {
+        if self.sealer.is_sealed() {
+            // Encrypted stores keep no full-text index; scan the live state instead.
+            let filter = GraphFilter {
+                node_types: query.node_types.clone(),
+                ..GraphFilter::default()
+            };
+            let state = self.graph_state(commit_id, &filter).await?;
+            return Ok(crate::search::rank_nodes(state.nodes, query));
+        }
+        search::search_nodes(&self.conn, commit_id, query).await
+    }
+
+    fn blobs(&self) -> Option<&dyn BlobStore> {
+        Some(self)
+    }
 }
 
 pub(super) fn current_time_ms() -> i64 {
@@ -257,6 +414,151 @@ pub(super) fn current_time_ms() -> i64 {
         .as_millis() as i64
 }
 
+fn commit_model(
+    commit: &PersistedCommit,
+    sealer: &keyring::Sealer,
+) -> Result<commits::ActiveModel, MnemeError> {
+    let summary = sealer.seal_summary(&commit.summary)?;
+    Ok(commits::ActiveModel {
+        commit_id: Set(summary.id),
+        branch: Set(summary.branch),
+        parents_json: Set(serialize_json(&summary.parents, "commit parents")?),
+        author: Set(summary.author),
+        time: Set(summary.time),
+        message: Set(summary.message),
+        tags_json: Set(serialize_json(&summary.tags, "commit tags")?),
+        change_count: Set(summary.change_count as i64),
+        // Both live elsewhere since 0007_commit_encoding: the summary in the columns above, the
+        // change set in `metis_commit_payloads`.
+        summary_json: Set(String::new()),
+        changes_json: Set(String::new()),
+    })
+}
+
+/// Insert a commit row together with its Metis projections using the caller's transaction.
+/// Returns the `metis_events.created_at_ms` cursor recorded for the commit.
+async fn insert_commit<C: ConnectionTrait>(
+    conn: &C,
+    sealer: &keyring::Sealer,
+    commit: &PersistedCommit,
+) -> MnemeResult<i64> {
+    let model = commit_model(commit, sealer)?;
+    let projections = projections::project_commit(commit, sealer)?;
+    let at_ms = projections.at_ms;
+    model.insert(conn).await.map_err(|err| {
+        if is_unique_violation(&err) {
+            MnemeError::storage(format!("commit '{}' already exists", commit.summary.id))
+        } else {
+            MnemeError::storage(format!("SeaORM error: {err}"))
+        }
+    })?;
+    encoding::write_change_set(conn, sealer, &commit.summary.id, &commit.change_set).await?;
+    projections
+        .event
+        .insert(conn)
+        .await
+        .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
+    if !projections.node_changes.is_empty() {
+        metis_node_changes::Entity::insert_many(projections.node_changes)
+            .exec(conn)
+            .await
+            .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
+    }
+    if !projections.edge_changes.is_empty() {
+        metis_edge_changes::Entity::insert_many(projections.edge_changes)
+            .exec(conn)
+            .await
+            .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
+    }
+    versions::index_commit(conn, sealer, commit).await?;
+    blobs::index_refs(conn, commit).await?;
+    Ok(at_ms)
+}
+
+/// Move `branch` from `expected` to `next` and log the move. A single conditional write keeps
+/// racing writers from both passing the comparison.
+async fn swap_ref<C: ConnectionTrait>(
+    conn: &C,
+    branch: &str,
+    expected: Option<&str>,
+    next: Option<&str>,
+    updated_at_ms: i64,
+) -> MnemeResult<()> {
+    let swap = match expected {
+        None => Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO refs (branch, commit_id, updated_at_ms) VALUES (?, ?, ?) \
+             ON CONFLICT(branch) DO UPDATE SET commit_id=excluded.commit_id, \
+             updated_at_ms=excluded.updated_at_ms WHERE refs.commit_id IS NULL",
+            vec![
+                Value::from(branch.to_string()),
+                Value::from(next.map(str::to_string)),
+                Value::from(updated_at_ms),
+            ],
+        ),
+        Some(current) => Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE refs SET commit_id = ?, updated_at_ms = ? WHERE branch = ? AND commit_id = ?",
+            vec![
+                Value::from(next.map(str::to_string)),
+                Value::from(updated_at_ms),
+                Value::from(branch.to_string()),
+                Value::from(current.to_string()),
+            ],
+        ),
+    };
+    let swapped = conn
+        .execute(swap)
+        .await
+        .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?
+        .rows_affected();
+    if swapped == 0 {
+        let actual = refs::Entity::find_by_id(branch.to_string())
+            .one(conn)
+            .await
+            .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?
+            .and_then(|row| row.commit_id);
+        return Err(MnemeError::ConcurrencyConflict {
+            branch: branch.to_string(),
+            expected: expected.map(str::to_string),
+            actual,
+        });
+    }
+    ref_log::ActiveModel {
+        id: Default::default(),
+        branch: Set(branch.to_string()),
+        old_commit: Set(expected.map(str::to_string)),
+        new_commit: Set(next.map(str::to_string)),
+        updated_at_ms: Set(updated_at_ms),
+    }
+    .insert(conn)
+    .await
+    .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
+    Ok(())
+}
+
+async fn upsert_tag<C: ConnectionTrait>(
+    conn: &C,
+    tag: &str,
+    commit_id: &str,
+    created_at_ms: i64,
+) -> MnemeResult<()> {
+    let statement = Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO snapshot_tags (tag, commit_id, created_at_ms) VALUES (?, ?, ?)\
+         ON CONFLICT(tag) DO UPDATE SET commit_id=excluded.commit_id, created_at_ms=excluded.created_at_ms",
+        vec![
+            Value::from(tag.to_string()),
+            Value::from(commit_id.to_string()),
+            Value::from(created_at_ms),
+        ],
+    );
+    conn.execute(statement)
+        .await
+        .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
+    Ok(())
+}
+
 fn serialize_json<T: Serialize>(value: &T, label: &str) -> Result<String, MnemeError> {
     serde_json::to_string(value)
         .map_err(|err| MnemeError::storage(format!("serialise {label}: {err}")))
@@ -266,8 +568,19 @@ fn is_unique_violation(err: &DbErr) -> bool {
     err.to_string().to_lowercase().contains("unique")
 }
 
-async fn run_migrations(conn: &DatabaseConnection) -> Result<(), DbErr> {
-    migrations::apply(conn).await
+/// Index commits written before the version tables and search index existed.
+async fn backfill_versions(conn: &DatabaseConnection, sealer: &keyring::Sealer) -> MnemeResult<()> {
+    let txn = conn
+        .begin()
+        .await
+        .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))?;
+    versions::backfill(&txn, sealer).await?;
+    if !sealer.is_sealed() {
+        search::backfill(&txn).await?;
+    }
+    txn.commit()
+        .await
+        .map_err(|err| MnemeError::storage(format!("SeaORM error: {err}")))
 }
 
 async fn ensure_main_branch(conn: &DatabaseConnection) -> Result<(), DbErr> {
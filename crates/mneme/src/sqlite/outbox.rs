@@ -0,0 +1,481 @@
+//! Durable `metis_events` consumer groups for the SQLite store.
+//!
+//! Each group keeps a committed offset in `metis_consumer_offsets`. Acks, scheduled retries, and
+//! dead letters for events past the offset live in `metis_consumer_deliveries`; whenever the
+//! oldest unresolved events are settled the offset advances over them and folded acks are pruned.
+//! Offsets follow `metis_events.seq`, which grows with insertion order; timestamps and commit ids
+//! do not, so neither can order the log.
+
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, QueryResult, Statement,
+    TransactionTrait, Value,
+};
+
+use super::keyring::Sealer;
+use super::{SqliteDb, current_time_ms};
+use crate::outbox::{DeadLetter, NackOutcome, OutboxCursor, OutboxEvent, RetryPolicy};
+use crate::{MnemeError, MnemeResult};
+
+const STATE_ACKED: &str = "acked";
+const STATE_RETRY: &str = "retry";
+const STATE_DEAD: &str = "dead";
+
+/// Rows inspected per step while advancing an offset.
+const ADVANCE_BATCH: i64 = 256;
+
+/// Handle for one named consumer group reading `metis_events`.
+#[derive(Clone)]
+pub struct OutboxConsumer {
+    conn: DatabaseConnection,
+    sealer: Sealer,
+    group: String,
+    policy: RetryPolicy,
+}
+
+impl SqliteDb {
+    /// Open a consumer handle for `group`; offsets persist across handles and restarts.
+    pub fn outbox_consumer(&self, group: impl Into<String>, policy: RetryPolicy) -> OutboxConsumer {
+        OutboxConsumer {
+            conn: self.conn.clone(),
+            sealer: self.sealer.clone(),
+            group: group.into(),
+            policy,
+        }
+    }
+}
+
+impl OutboxConsumer {
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    /// Fetch up to `limit` events: retries that are due first, then unseen events after the
+    /// offset. Events stay deliverable until they are acked or nacked.
+    pub async fn poll(&self, limit: usize) -> MnemeResult<Vec<OutboxEvent>> {
+        let limit = limit as i64;
+        let mut events = Vec::new();
+        let retries = query(
+            &self.conn,
+            "SELECT e.event_id, e.commit_id, e.created_at_ms, e.payload, d.attempts \
+             FROM metis_consumer_deliveries d JOIN metis_events e ON e.event_id = d.event_id \
+             WHERE d.consumer = ? AND d.state = ? AND d.next_attempt_at_ms <= ? \
+             ORDER BY d.next_attempt_at_ms, e.seq LIMIT ?",
+            vec![
+                self.group.clone().into(),
+                STATE_RETRY.into(),
+                current_time_ms().into(),
+                limit.into(),
+            ],
+        )
+        .await?;
+        for row in retries {
+            let failed: i64 = get(&row, "attempts")?;
+            events.push(decode_event(&self.sealer, &row, failed as u32 + 1)?);
+        }
+
+        let remaining = limit - events.len() as i64;
+        if remaining > 0 {
+            let cursor = self.offset().await?;
+            let fresh = query(
+                &self.conn,
+                "SELECT e.event_id, e.commit_id, e.created_at_ms, e.payload FROM metis_events e \
+                 LEFT JOIN metis_consumer_deliveries d \
+                   ON d.consumer = ? AND d.event_id = e.event_id \
+                 WHERE d.event_id IS NULL AND e.seq > ? \
+                 ORDER BY e.seq LIMIT ?",
+                vec![
+                    self.group.clone().into(),
+                    cursor_seq(cursor.as_ref()).into(),
+                    remaining.into(),
+                ],
+            )
+            .await?;
+            for row in fresh {
+                events.push(decode_event(&self.sealer, &row, 1)?);
+            }
+        }
+        Ok(events)
+    }
+
+    /// Mark an event as processed.
+    pub async fn ack(&self, event_id: &str) -> MnemeResult<()> {
+        let txn = self.conn.begin().await.map_err(db_err)?;
+        ensure_event(&txn, event_id).await?;
+        upsert_delivery(&txn, &self.group, event_id, STATE_ACKED, 0, None, None).await?;
+        advance_offset(&txn, &self.group).await?;
+        txn.commit().await.map_err(db_err)
+    }
+
+    /// Record a failed delivery and schedule a retry, or dead-letter the event once the retry
+    /// policy is exhausted.
+    pub async fn nack(&self, event_id: &str, error: &str) -> MnemeResult<NackOutcome> {
+        let txn = self.conn.begin().await.map_err(db_err)?;
+        ensure_event(&txn, event_id).await?;
+        let previous = query(
+            &txn,
+            "SELECT attempts FROM metis_consumer_deliveries \
+             WHERE consumer = ? AND event_id = ? AND state = ?",
+            vec![
+                self.group.clone().into(),
+                event_id.into(),
+                STATE_RETRY.into(),
+            ],
+        )
+        .await?;
+        let failed = match previous.first() {
+            Some(row) => get::<i64>(row, "attempts")? as u32 + 1,
+            None => 1,
+        };
+        let outcome = if failed >= self.policy.max_attempts {
+            upsert_delivery(
+                &txn,
+                &self.group,
+                event_id,
+                STATE_DEAD,
+                failed,
+                None,
+                Some(error),
+            )
+            .await?;
+            NackOutcome::DeadLettered {
+                failed_attempts: failed,
+            }
+        } else {
+            let next_attempt_at_ms =
+                current_time_ms() + self.policy.backoff_for(failed).as_millis() as i64;
+            upsert_delivery(
+                &txn,
+                &self.group,
+                event_id,
+                STATE_RETRY,
+                failed,
+                Some(next_attempt_at_ms),
+                Some(error),
+            )
+            .await?;
+            NackOutcome::Retry {
+                failed_attempts: failed,
+                next_attempt_at_ms,
+            }
+        };
+        advance_offset(&txn, &self.group).await?;
+        txn.commit().await.map_err(db_err)?;
+        Ok(outcome)
+    }
+
+    /// Committed offset, or `None` when the group has not settled any event yet.
+    pub async fn offset(&self) -> MnemeResult<Option<OutboxCursor>> {
+        load_offset(&self.conn, &self.group).await
+    }
+
+    /// Events that exhausted their retries, oldest first.
+    pub async fn dead_letters(&self) -> MnemeResult<Vec<DeadLetter>> {
+        let rows = query(
+            &self.conn,
+            "SELECT event_id, attempts, last_error, updated_at_ms FROM metis_consumer_deliveries \
+             WHERE consumer = ? AND state = ? ORDER BY updated_at_ms, event_id",
+            vec![self.group.clone().into(), STATE_DEAD.into()],
+        )
+        .await?;
+        rows.iter()
+            .map(|row| {
+                Ok(DeadLetter {
+                    event_id: get(row, "event_id")?,
+                    failed_attempts: get::<i64>(row, "attempts")? as u32,
+                    last_error: get(row, "last_error")?,
+                    dead_at_ms: get(row, "updated_at_ms")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Move a dead-lettered event back into the retry schedule for immediate redelivery.
+    pub async fn requeue_dead_letter(&self, event_id: &str) -> MnemeResult<()> {
+        let result = self
+            .conn
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "UPDATE metis_consumer_deliveries SET state = ?, attempts = 0, \
+                 next_attempt_at_ms = ?, updated_at_ms = ? \
+                 WHERE consumer = ? AND event_id = ? AND state = ?",
+                vec![
+                    STATE_RETRY.into(),
+                    current_time_ms().into(),
+                    current_time_ms().into(),
+                    self.group.clone().into(),
+                    event_id.into(),
+                    STATE_DEAD.into(),
+                ],
+            ))
+            .await
+            .map_err(db_err)?;
+        if result.rows_affected() == 0 {
+            return Err(MnemeError::storage(format!(
+                "event '{event_id}' is not dead-lettered for consumer '{}'",
+                self.group
+            )));
+        }
+        Ok(())
+    }
+
+    /// Rewind the group so events created at or after `since_ms` (or every event, for `None`) are
+    /// delivered again. Acks, retries, and dead letters for those events are discarded so derived
+    /// state can be rebuilt from scratch. The offset moves back to just before the first such
+    /// event, so events inserted after it with an earlier timestamp may be delivered again too.
+    pub async fn rewind(&self, since_ms: Option<i64>) -> MnemeResult<()> {
+        let txn = self.conn.begin().await.map_err(db_err)?;
+        match since_ms {
+            None => {
+                exec(
+                    &txn,
+                    "DELETE FROM metis_consumer_offsets WHERE consumer = ?",
+                    vec![self.group.clone().into()],
+                )
+                .await?;
+                exec(
+                    &txn,
+                    "DELETE FROM metis_consumer_deliveries WHERE consumer = ?",
+                    vec![self.group.clone().into()],
+                )
+                .await?;
+            }
+            Some(since) => {
+                let first = query(
+                    &txn,
+                    "SELECT MIN(seq) AS seq FROM metis_events WHERE created_at_ms >= ?",
+                    vec![since.into()],
+                )
+                .await?;
+                let first: Option<i64> = match first.first() {
+                    Some(row) => get(row, "seq")?,
+                    None => None,
+                };
+                let current = load_offset(&txn, &self.group).await?;
+                if let Some(first) = first
+                    && current.is_some_and(|cursor| cursor.seq >= first)
+                {
+                    let before = query(
+                        &txn,
+                        "SELECT seq, created_at_ms, event_id FROM metis_events WHERE seq < ? \
+                         ORDER BY seq DESC LIMIT 1",
+                        vec![first.into()],
+                    )
+                    .await?;
+                    match before.first() {
+                        Some(row) => store_offset(&txn, &self.group, &cursor_at(row)?).await?,
+                        None => {
+                            exec(
+                                &txn,
+                                "DELETE FROM metis_consumer_offsets WHERE consumer = ?",
+                                vec![self.group.clone().into()],
+                            )
+                            .await?
+                        }
+                    }
+                }
+                exec(
+                    &txn,
+                    "DELETE FROM metis_consumer_deliveries WHERE consumer = ? AND event_id NOT IN \
+                     (SELECT event_id FROM metis_events WHERE created_at_ms < ?)",
+                    vec![self.group.clone().into(), since.into()],
+                )
+                .await?;
+            }
+        }
+        txn.commit().await.map_err(db_err)
+    }
+}
+
+/// Move the offset across the contiguous run of settled (acked, retrying, or dead) events that
+/// directly follows it, then prune acks the offset now covers.
+async fn advance_offset(txn: &DatabaseTransaction, group: &str) -> MnemeResult<()> {
+    let start = load_offset(txn, group).await?;
+    let mut seq = cursor_seq(start.as_ref());
+    let mut reached = None;
+    loop {
+        let rows = query(
+            txn,
+            "SELECT e.seq, e.event_id, e.created_at_ms, d.state FROM metis_events e \
+             LEFT JOIN metis_consumer_deliveries d ON d.consumer = ? AND d.event_id = e.event_id \
+             WHERE e.seq > ? ORDER BY e.seq LIMIT ?",
+            vec![group.into(), seq.into(), ADVANCE_BATCH.into()],
+        )
+        .await?;
+        let fetched = rows.len() as i64;
+        let mut blocked = false;
+        for row in rows {
+            let state: Option<String> = get(&row, "state")?;
+            if state.is_none() {
+                blocked = true;
+                break;
+            }
+            let cursor = cursor_at(&row)?;
+            seq = cursor.seq;
+            reached = Some(cursor);
+        }
+        if blocked || fetched < ADVANCE_BATCH {
+            break;
+        }
+    }
+    let Some(cursor) = reached else {
+        return Ok(());
+    };
+    store_offset(txn, group, &cursor).await?;
+    exec(
+        txn,
+        "DELETE FROM metis_consumer_deliveries WHERE consumer = ? AND state = ? AND event_id IN \
+         (SELECT event_id FROM metis_events WHERE seq <= ?)",
+        vec![group.into(), STATE_ACKED.into(), cursor.seq.into()],
+    )
+    .await
+}
+
+async fn load_offset<C: ConnectionTrait>(
+    conn: &C,
+    group: &str,
+) -> MnemeResult<Option<OutboxCursor>> {
+    let rows = query(
+        conn,
+        "SELECT cursor_seq, cursor_at_ms, cursor_event_id FROM metis_consumer_offsets \
+         WHERE consumer = ?",
+        vec![group.into()],
+    )
+    .await?;
+    rows.first()
+        .map(|row| {
+            Ok(OutboxCursor {
+                seq: get(row, "cursor_seq")?,
+                created_at_ms: get(row, "cursor_at_ms")?,
+                event_id: get(row, "cursor_event_id")?,
+            })
+        })
+        .transpose()
+}
+
+async fn store_offset(
+    txn: &DatabaseTransaction,
+    group: &str,
+    cursor: &OutboxCursor,
+) -> MnemeResult<()> {
+    exec(
+        txn,
+        "INSERT INTO metis_consumer_offsets \
+         (consumer, cursor_seq, cursor_at_ms, cursor_event_id, updated_at_ms) \
+         VALUES (?, ?, ?, ?, ?) ON CONFLICT(consumer) DO UPDATE SET \
+         cursor_seq = excluded.cursor_seq, cursor_at_ms = excluded.cursor_at_ms, \
+         cursor_event_id = excluded.cursor_event_id, updated_at_ms = excluded.updated_at_ms",
+        vec![
+            group.into(),
+            cursor.seq.into(),
+            cursor.created_at_ms.into(),
+            cursor.event_id.clone().into(),
+            current_time_ms().into(),
+        ],
+    )
+    .await
+}
+
+async fn upsert_delivery(
+    txn: &DatabaseTransaction,
+    group: &str,
+    event_id: &str,
+    state: &str,
+    attempts: u32,
+    next_attempt_at_ms: Option<i64>,
+    last_error: Option<&str>,
+) -> MnemeResult<()> {
+    exec(
+        txn,
+        "INSERT INTO metis_consumer_deliveries \
+         (consumer, event_id, state, attempts, next_attempt_at_ms, last_error, updated_at_ms) \
+         VALUES (?, ?, ?, ?, ?, ?, ?) ON CONFLICT(consumer, event_id) DO UPDATE SET \
+         state = excluded.state, attempts = excluded.attempts, \
+         next_attempt_at_ms = excluded.next_attempt_at_ms, last_error = excluded.last_error, \
+         updated_at_ms = excluded.updated_at_ms",
+        vec![
+            group.into(),
+            event_id.into(),
+            state.into(),
+            (attempts as i64).into(),
+            next_attempt_at_ms.into(),
+            last_error.map(str::to_string).into(),
+            current_time_ms().into(),
+        ],
+    )
+    .await
+}
+
+async fn ensure_event(txn: &DatabaseTransaction, event_id: &str) -> MnemeResult<()> {
+    let rows = query(
+        txn,
+        "SELECT event_id FROM metis_events WHERE event_id = ?",
+        vec![event_id.into()],
+    )
+    .await?;
+    if rows.is_empty() {
+        return Err(MnemeError::storage(format!(
+            "unknown metis event '{event_id}'"
+        )));
+    }
+    Ok(())
+}
+
+/// Sequence number the offset covers; 0 before the first event.
+fn cursor_seq(cursor: Option<&OutboxCursor>) -> i64 {
+    cursor.map_or(0, |cursor| cursor.seq)
+}
+
+/// Cursor at the `metis_events` row selected with `seq`, `created_at_ms` and `event_id`.
+fn cursor_at(row: &QueryResult) -> MnemeResult<OutboxCursor> {
+    Ok(OutboxCursor {
+        seq: get(row, "seq")?,
+        created_at_ms: get(row, "created_at_ms")?,
+        event_id: get(row, "event_id")?,
+    })
+}
+
+fn decode_event(sealer: &Sealer, row: &QueryResult, attempt: u32) -> MnemeResult<OutboxEvent> {
+    let payload = sealer.open_text(get(row, "payload")?)?;
+    Ok(OutboxEvent {
+        event_id: get(row, "event_id")?,
+        commit_id: get(row, "commit_id")?,
+        created_at_ms: get(row, "created_at_ms")?,
+        attempt,
+        payload: serde_json::from_str(&payload)
+            .map_err(|err| MnemeError::storage(format!("decode metis payload: {err}")))?,
+    })
+}
+
+async fn query<C: ConnectionTrait>(
+    conn: &C,
+    sql: &str,
+    values: Vec<Value>,
+) -> MnemeResult<Vec<QueryResult>> {
+    conn.query_all(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        sql,
+        values,
+    ))
+    .await
+    .map_err(db_err)
+}
+
+async fn exec<C: ConnectionTrait>(conn: &C, sql: &str, values: Vec<Value>) -> MnemeResult<()> {
+    conn.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        sql,
+        values,
+    ))
+    .await
+    .map(|_| ())
+    .map_err(db_err)
+}
+
+fn get<T: sea_orm::TryGetable>(row: &QueryResult, column: &str) -> MnemeResult<T> {
+    row.try_get("", column)
+        .map_err(|err| MnemeError::storage(format!("read outbox column '{column}': {err}")))
+}
+
+fn db_err(err: sea_orm::DbErr) -> MnemeError {
+    MnemeError::storage(format!("SeaORM error: {err}"))
+}
@@ -3,7 +3,9 @@ use serde::Serialize;
 
 use crate::{
     MnemeError, PersistedCommit,
-    sqlite::{current_time_ms, metis_edge_changes, metis_events, metis_node_changes},
+    sqlite::{
+        current_time_ms, keyring::Sealer, metis_edge_changes, metis_events, metis_node_changes,
+    },
 };
 
 #[derive(Serialize)]
@@ -36,12 +38,17 @@ struct MetisEventPayload {
 }
 
 pub(super) struct MetisProjections {
+    /// `metis_events.created_at_ms` recorded for the commit.
+    pub at_ms: i64,
     pub event: metis_events::ActiveModel,
     pub node_changes: Vec<metis_node_changes::ActiveModel>,
     pub edge_changes: Vec<metis_edge_changes::ActiveModel>,
 }
 
-pub(super) fn project_commit(commit: &PersistedCommit) -> Result<MetisProjections, MnemeError> {
+pub(super) fn project_commit(
+    commit: &PersistedCommit,
+    sealer: &Sealer,
+) -> Result<MetisProjections, MnemeError> {
     let summary = &commit.summary;
     let change_set = &commit.change_set;
     let timestamp = current_time_ms();
@@ -66,19 +73,21 @@ pub(super) fn project_commit(commit: &PersistedCommit) -> Result<MetisProjection
         change_count: summary.change_count,
         summary: MetisChangeSummary { nodes, edges },
     };
-    let payload_json = serialize_json(&payload, "Metis payload")?;
+    let payload_json = sealer.seal_text(serialize_json(&payload, "Metis payload")?)?;
 
     let event = metis_events::ActiveModel {
         event_id: Set(summary.id.clone()),
         commit_id: Set(summary.id.clone()),
         payload: Set(payload_json),
         created_at_ms: Set(timestamp),
+        ..Default::default()
     };
 
-    let node_changes = project_node_changes(commit, timestamp)?;
-    let edge_changes = project_edge_changes(commit, timestamp)?;
+    let node_changes = project_node_changes(commit, sealer, timestamp)?;
+    let edge_changes = project_edge_changes(commit, sealer, timestamp)?;
 
     Ok(MetisProjections {
+        at_ms: timestamp,
         event,
         node_changes,
         edge_changes,
@@ -87,15 +96,28 @@ pub(super) fn project_commit(commit: &PersistedCommit) -> Result<MetisProjection
 
 fn project_node_changes(
     commit: &PersistedCommit,
+    sealer: &Sealer,
     recorded_at_ms: i64,
 ) -> Result<Vec<metis_node_changes::ActiveModel>, MnemeError> {
     let summary = &commit.summary;
     let mut rows = Vec::new();
     for node in &commit.change_set.node_creates {
-        rows.push(build_node_change(summary, recorded_at_ms, node, "create")?);
+        rows.push(build_node_change(
+            summary,
+            sealer,
+            recorded_at_ms,
+            node,
+            "create",
+        )?);
     }
     for node in &commit.change_set.node_updates {
-        rows.push(build_node_change(summary, recorded_at_ms, node, "update")?);
+        rows.push(build_node_change(
+            summary,
+            sealer,
+            recorded_at_ms,
+            node,
+            "update",
+        )?);
     }
     for tombstone in &commit.change_set.node_deletes {
         rows.push(metis_node_changes::ActiveModel {
@@ -114,15 +136,28 @@ fn project_node_changes(
 
 fn project_edge_changes(
     commit: &PersistedCommit,
+    sealer: &Sealer,
     recorded_at_ms: i64,
 ) -> Result<Vec<metis_edge_changes::ActiveModel>, MnemeError> {
     let summary = &commit.summary;
     let mut rows = Vec::new();
     for edge in &commit.change_set.edge_creates {
-        rows.push(build_edge_change(summary, recorded_at_ms, edge, "create")?);
+        rows.push(build_edge_change(
+            summary,
+            sealer,
+            recorded_at_ms,
+            edge,
+            "create",
+        )?);
     }
     for edge in &commit.change_set.edge_updates {
-        rows.push(build_edge_change(summary, recorded_at_ms, edge, "update")?);
+        rows.push(build_edge_change(
+            summary,
+            sealer,
+            recorded_at_ms,
+            edge,
+            "update",
+        )?);
     }
     for tombstone in &commit.change_set.edge_deletes {
         rows.push(metis_edge_changes::ActiveModel {
@@ -144,11 +179,12 @@ fn project_edge_changes(
 
 fn build_node_change(
     summary: &crate::temporal::CommitSummary,
+    sealer: &Sealer,
     recorded_at_ms: i64,
     node: &crate::temporal::NodeVersion,
     operation: &str,
 ) -> Result<metis_node_changes::ActiveModel, MnemeError> {
-    let props_json = serialize_option_json(&node.props, "node props")?;
+    let props_json = sealer.seal_opt(serialize_option_json(&node.props, "node props")?)?;
     Ok(metis_node_changes::ActiveModel {
         id: Default::default(),
         event_id: Set(summary.id.clone()),
@@ -163,11 +199,12 @@ fn build_node_change(
 
 fn build_edge_change(
     summary: &crate::temporal::CommitSummary,
+    sealer: &Sealer,
     recorded_at_ms: i64,
     edge: &crate::temporal::EdgeVersion,
     operation: &str,
 ) -> Result<metis_edge_changes::ActiveModel, MnemeError> {
-    let props_json = serialize_option_json(&edge.props, "edge props")?;
+    let props_json = sealer.seal_opt(serialize_option_json(&edge.props, "edge props")?)?;
     Ok(metis_edge_changes::ActiveModel {
         id: Default::default(),
         event_id: Set(summary.id.clone()),
@@ -0,0 +1,132 @@
+//! FTS5 full-text index over node versions for the SQLite store.
+//!
+//! `metis_node_search` holds one row per original node version (rowid = `metis_node_versions.id`)
+//! with the node's id, type, and scalar property values. Versions copied into a forked lineage
+//! point back at their original through `origin_id`, so the index never duplicates text and a
+//! search joins matches back to the versions live at the requested commit.
+
+use sea_orm::{ConnectionTrait, DbBackend, QueryResult, Statement, Value};
+
+use super::versions::position;
+use crate::search::{HIGHLIGHT_CLOSE, HIGHLIGHT_OPEN, SNIPPET_WORDS, SearchHit, SearchQuery};
+use crate::{MnemeError, MnemeResult};
+
+/// Searchable text derived in SQL; must stay in step with `search::node_text`.
+const BODY_SQL: &str = "v.node_id || ' ' || COALESCE(v.node_type, '') || ' ' || \
+     COALESCE((SELECT group_concat(t.value, ' ') FROM json_tree(v.props_json) t \
+     WHERE t.type IN ('text', 'integer', 'real')), '')";
+
+/// Index the node versions a commit opened at `seq` in `lineage_id`.
+pub(super) async fn index_versions<C: ConnectionTrait>(
+    conn: &C,
+    lineage_id: &str,
+    seq: i64,
+) -> MnemeResult<()> {
+    exec(
+        conn,
+        &format!(
+            "INSERT INTO metis_node_search (rowid, body) SELECT v.id, {BODY_SQL} \
+             FROM metis_node_versions v \
+             WHERE v.lineage_id = ? AND v.valid_from = ? AND v.origin_id IS NULL"
+        ),
+        vec![lineage_id.into(), seq.into()],
+    )
+    .await
+}
+
+/// Index original node versions that have no search row yet (e.g. written before migration).
+pub(super) async fn backfill<C: ConnectionTrait>(conn: &C) -> MnemeResult<()> {
+    exec(
+        conn,
+        &format!(
+            "INSERT INTO metis_node_search (rowid, body) SELECT v.id, {BODY_SQL} \
+             FROM metis_node_versions v WHERE v.origin_id IS NULL \
+             AND v.id NOT IN (SELECT rowid FROM metis_node_search)"
+        ),
+        Vec::new(),
+    )
+    .await
+}
+
+pub(super) async fn search_nodes<C: ConnectionTrait>(
+    conn: &C,
+    commit_id: &str,
+    query: &SearchQuery,
+) -> MnemeResult<Vec<SearchHit>> {
+    let terms = query.terms();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (lineage_id, seq) = position(conn, commit_id).await?;
+    // Quoted prefix tokens keep user input from being parsed as FTS5 syntax.
+    let fts_query = terms
+        .iter()
+        .map(|term| format!("\"{term}\"*"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut values: Vec<Value> = vec![
+        HIGHLIGHT_OPEN.into(),
+        HIGHLIGHT_CLOSE.into(),
+        (SNIPPET_WORDS as i64).into(),
+        fts_query.into(),
+        lineage_id.into(),
+        seq.into(),
+        seq.into(),
+    ];
+    let mut type_clause = String::new();
+    if !query.node_types.is_empty() {
+        type_clause = format!(
+            " AND v.node_type IN ({})",
+            vec!["?"; query.node_types.len()].join(", ")
+        );
+        values.extend(query.node_types.iter().map(|ty| Value::from(ty.clone())));
+    }
+    values.push((query.effective_limit() as i64).into());
+    let rows = conn
+        .query_all(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            format!(
+                "SELECT v.node_id, v.node_type, -bm25(metis_node_search) AS score, \
+                 snippet(metis_node_search, 0, ?, ?, '…', ?) AS snippet \
+                 FROM metis_node_search \
+                 JOIN metis_node_versions v \
+                   ON v.id = metis_node_search.rowid OR v.origin_id = metis_node_search.rowid \
+                 WHERE metis_node_search MATCH ? AND v.lineage_id = ? AND v.valid_from <= ? \
+                 AND (v.valid_to IS NULL OR v.valid_to > ?){type_clause} \
+                 ORDER BY score DESC, v.node_id LIMIT ?"
+            ),
+            values,
+        ))
+        .await
+        .map_err(db_err)?;
+    rows.iter().map(decode_hit).collect()
+}
+
+fn decode_hit(row: &QueryResult) -> MnemeResult<SearchHit> {
+    Ok(SearchHit {
+        node_id: get(row, "node_id")?,
+        node_type: get(row, "node_type")?,
+        score: get(row, "score")?,
+        snippet: get(row, "snippet")?,
+    })
+}
+
+async fn exec<C: ConnectionTrait>(conn: &C, sql: &str, values: Vec<Value>) -> MnemeResult<()> {
+    conn.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        sql,
+        values,
+    ))
+    .await
+    .map(|_| ())
+    .map_err(db_err)
+}
+
+fn get<T: sea_orm::TryGetable>(row: &QueryResult, column: &str) -> MnemeResult<T> {
+    row.try_get("", column)
+        .map_err(|err| MnemeError::storage(format!("read search column '{column}': {err}")))
+}
+
+fn db_err(err: sea_orm::DbErr) -> MnemeError {
+    MnemeError::storage(format!("SeaORM error: {err}"))
+}
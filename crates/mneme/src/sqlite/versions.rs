@@ -0,0 +1,672 @@
+//! Temporal node/edge version tables for the SQLite store.
+//!
+//! Commits are grouped into lineages: a commit extends its first parent's lineage when that parent
+//! is the lineage tip on the same branch, otherwise it starts a new lineage whose first act is to
+//! copy the versions live at the fork point (sequence 0). Every version row is valid over the
+//! half-open sequence range `[valid_from, valid_to)` of its lineage, so the state at a commit is a
+//! single range query and never requires decoding change sets.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use sea_orm::sea_query::IntoCondition;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DbBackend, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, QueryTrait, Set, Statement, Value,
+};
+
+use super::keyring::Sealer;
+use super::{
+    IN_LIST_CHUNK, commits, encoding, metis_edge_versions, metis_events, metis_lineage_commits,
+    metis_lineages, metis_node_versions, search,
+};
+use crate::temporal::{EdgeVersion, NodeVersion};
+use crate::versions::{ElementRef, ElementState, ElementVersion, GraphFilter, GraphState};
+use crate::{MnemeError, MnemeResult, PersistedCommit};
+
+/// Record the versions written by `commit` using the caller's transaction.
+pub(super) async fn index_commit<C: ConnectionTrait>(
+    conn: &C,
+    sealer: &Sealer,
+    commit: &PersistedCommit,
+) -> MnemeResult<()> {
+    let (lineage_id, seq) = place_commit(conn, commit).await?;
+    let commit_id = commit.summary.id.as_str();
+    let change = &commit.change_set;
+
+    // Only the last write to a node within one commit is ever visible.
+    let mut written: BTreeMap<&str, &NodeVersion> = BTreeMap::new();
+    for node in change.node_creates.iter().chain(change.node_updates.iter()) {
+        written.insert(node.id.as_str(), node);
+    }
+    let touched: Vec<String> = change
+        .node_deletes
+        .iter()
+        .map(|tombstone| tombstone.id.as_str())
+        .chain(written.keys().copied())
+        .map(str::to_string)
+        .collect();
+    for chunk in touched.chunks(IN_LIST_CHUNK) {
+        metis_node_versions::Entity::update_many()
+            .col_expr(metis_node_versions::Column::ValidTo, seq.into())
+            .col_expr(metis_node_versions::Column::ToCommit, commit_id.into())
+            .filter(metis_node_versions::Column::LineageId.eq(lineage_id.as_str()))
+            .filter(metis_node_versions::Column::ValidTo.is_null())
+            .filter(metis_node_versions::Column::NodeId.is_in(chunk.to_vec()))
+            .exec(conn)
+            .await
+            .map_err(db_err)?;
+    }
+    let rows = written
+        .into_values()
+        .map(|node| node_row(sealer, &lineage_id, seq, commit_id, node))
+        .collect::<MnemeResult<Vec<_>>>()?;
+    for chunk in rows.chunks(IN_LIST_CHUNK) {
+        metis_node_versions::Entity::insert_many(chunk.to_vec())
+            .exec(conn)
+            .await
+            .map_err(db_err)?;
+    }
+    if !sealer.is_sealed() {
+        search::index_versions(conn, &lineage_id, seq).await?;
+    }
+
+    // Edges are applied one by one: deletes and updates may address them by endpoints or by id.
+    for tombstone in &change.edge_deletes {
+        let by_endpoints = Condition::all()
+            .add(metis_edge_versions::Column::FromNode.eq(tombstone.from.as_str()))
+            .add(metis_edge_versions::Column::ToNode.eq(tombstone.to.as_str()));
+        close_edges(conn, &lineage_id, seq, commit_id, by_endpoints).await?;
+    }
+    for edge in &change.edge_creates {
+        let same_key = Condition::all()
+            .add(edge_id_condition(edge.id.as_deref()))
+            .add(metis_edge_versions::Column::FromNode.eq(edge.from.as_str()))
+            .add(metis_edge_versions::Column::ToNode.eq(edge.to.as_str()));
+        close_edges(conn, &lineage_id, seq, commit_id, same_key).await?;
+        insert_edge(conn, sealer, &lineage_id, seq, commit_id, edge).await?;
+    }
+    for edge in &change.edge_updates {
+        let replaced = match &edge.id {
+            Some(id) => metis_edge_versions::Column::EdgeId
+                .eq(id.as_str())
+                .into_condition(),
+            None => Condition::all()
+                .add(metis_edge_versions::Column::FromNode.eq(edge.from.as_str()))
+                .add(metis_edge_versions::Column::ToNode.eq(edge.to.as_str())),
+        };
+        close_edges(conn, &lineage_id, seq, commit_id, replaced).await?;
+        insert_edge(conn, sealer, &lineage_id, seq, commit_id, edge).await?;
+    }
+    metis_edge_versions::Entity::delete_many()
+        .filter(metis_edge_versions::Column::LineageId.eq(lineage_id.as_str()))
+        .filter(metis_edge_versions::Column::ValidFrom.eq(seq))
+        .filter(metis_edge_versions::Column::ValidTo.eq(seq))
+        .exec(conn)
+        .await
+        .map_err(db_err)?;
+    Ok(())
+}
+
+/// Index every commit that has no lineage position yet, first parents before children.
+pub(super) async fn backfill<C: ConnectionTrait>(conn: &C, sealer: &Sealer) -> MnemeResult<u64> {
+    let indexed: HashSet<String> = metis_lineage_commits::Entity::find()
+        .select_only()
+        .column(metis_lineage_commits::Column::CommitId)
+        .into_tuple::<String>()
+        .all(conn)
+        .await
+        .map_err(db_err)?
+        .into_iter()
+        .collect();
+    let persisted_at: HashMap<String, i64> = metis_events::Entity::find()
+        .select_only()
+        .column(metis_events::Column::CommitId)
+        .column(metis_events::Column::CreatedAtMs)
+        .into_tuple::<(String, i64)>()
+        .all(conn)
+        .await
+        .map_err(db_err)?
+        .into_iter()
+        .collect();
+    let mut pending: Vec<(String, Option<String>)> = Vec::new();
+    for (id, parents_json) in commits::Entity::find()
+        .select_only()
+        .column(commits::Column::CommitId)
+        .column(commits::Column::ParentsJson)
+        .into_tuple::<(String, String)>()
+        .all(conn)
+        .await
+        .map_err(db_err)?
+    {
+        if indexed.contains(&id) {
+            continue;
+        }
+        let parents: Vec<String> = serde_json::from_str(&parents_json)
+            .map_err(|err| MnemeError::storage(format!("decode parents of '{id}': {err}")))?;
+        pending.push((id, parents.into_iter().next()));
+    }
+    pending.sort_by_key(|(id, _)| {
+        (
+            persisted_at.get(id).copied().unwrap_or_default(),
+            id.clone(),
+        )
+    });
+
+    let first_parent: HashMap<String, Option<String>> = pending.iter().cloned().collect();
+    let mut done: HashSet<String> = HashSet::new();
+    let mut order = Vec::new();
+    for (id, _) in &pending {
+        let mut chain = Vec::new();
+        let mut cursor = Some(id.clone());
+        while let Some(current) = cursor.take() {
+            if done.contains(&current) || !first_parent.contains_key(&current) {
+                break;
+            }
+            cursor = first_parent[&current].clone();
+            done.insert(current.clone());
+            chain.push(current);
+        }
+        order.extend(chain.into_iter().rev());
+    }
+
+    for id in &order {
+        let row = commits::Entity::find_by_id(id.clone())
+            .one(conn)
+            .await
+            .map_err(db_err)?
+            .ok_or_else(|| {
+                MnemeError::storage(format!("commit '{id}' vanished during backfill"))
+            })?;
+        let commit = encoding::load_commit(conn, sealer, row).await?;
+        index_commit(conn, sealer, &commit).await?;
+    }
+    Ok(order.len() as u64)
+}
+
+/// Drop and re-derive all version tables, e.g. after garbage collection rewrote history.
+pub(super) async fn rebuild<C: ConnectionTrait>(conn: &C, sealer: &Sealer) -> MnemeResult<u64> {
+    for table in [
+        "metis_node_search",
+        "metis_node_versions",
+        "metis_edge_versions",
+        "metis_lineage_commits",
+        "metis_lineages",
+    ] {
+        conn.execute(Statement::from_string(
+            DbBackend::Sqlite,
+            format!("DELETE FROM {table}"),
+        ))
+        .await
+        .map_err(db_err)?;
+    }
+    backfill(conn, sealer).await
+}
+
+pub(super) async fn graph_state<C: ConnectionTrait>(
+    conn: &C,
+    sealer: &Sealer,
+    commit_id: &str,
+    filter: &GraphFilter,
+) -> MnemeResult<GraphState> {
+    let (lineage_id, seq) = position(conn, commit_id).await?;
+    // `ids` narrows the nodes to one chunk of `filter.node_ids`, keeping IN lists bounded.
+    let node_condition = |select: sea_orm::Select<metis_node_versions::Entity>,
+                          ids: Option<&[String]>| {
+        let mut select = select
+            .filter(metis_node_versions::Column::LineageId.eq(lineage_id.as_str()))
+            .filter(metis_node_versions::Column::ValidFrom.lte(seq))
+            .filter(
+                Condition::any()
+                    .add(metis_node_versions::Column::ValidTo.is_null())
+                    .add(metis_node_versions::Column::ValidTo.gt(seq)),
+            );
+        if let Some(ids) = ids {
+            select = select.filter(metis_node_versions::Column::NodeId.is_in(ids.to_vec()));
+        }
+        if !filter.node_types.is_empty() {
+            select = select
+                .filter(metis_node_versions::Column::NodeType.is_in(filter.node_types.clone()));
+        }
+        select
+    };
+    let live_edges = || {
+        metis_edge_versions::Entity::find()
+            .filter(metis_edge_versions::Column::LineageId.eq(lineage_id.as_str()))
+            .filter(metis_edge_versions::Column::ValidFrom.lte(seq))
+            .filter(
+                Condition::any()
+                    .add(metis_edge_versions::Column::ValidTo.is_null())
+                    .add(metis_edge_versions::Column::ValidTo.gt(seq)),
+            )
+    };
+
+    let (node_rows, edge_rows) = if filter.node_ids.is_empty() {
+        let node_rows = node_condition(metis_node_versions::Entity::find(), None)
+            .order_by_asc(metis_node_versions::Column::NodeId)
+            .all(conn)
+            .await
+            .map_err(db_err)?;
+        let mut edges = live_edges();
+        if !filter.is_empty() {
+            let live_nodes = node_condition(metis_node_versions::Entity::find(), None)
+                .select_only()
+                .column(metis_node_versions::Column::NodeId)
+                .into_query();
+            edges = edges
+                .filter(metis_edge_versions::Column::FromNode.in_subquery(live_nodes.clone()))
+                .filter(metis_edge_versions::Column::ToNode.in_subquery(live_nodes));
+        }
+        let edge_rows = edges
+            .order_by_asc(metis_edge_versions::Column::EdgeId)
+            .order_by_asc(metis_edge_versions::Column::FromNode)
+            .order_by_asc(metis_edge_versions::Column::ToNode)
+            .all(conn)
+            .await
+            .map_err(db_err)?;
+        (node_rows, edge_rows)
+    } else {
+        // Too many ids for one statement: query by chunk, then keep the edges between the
+        // selected nodes and restore the single-query order.
+        let mut node_rows = Vec::new();
+        for chunk in filter.node_ids.chunks(IN_LIST_CHUNK) {
+            node_rows.extend(
+                node_condition(metis_node_versions::Entity::find(), Some(chunk))
+                    .all(conn)
+                    .await
+                    .map_err(db_err)?,
+            );
+        }
+        node_rows.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+        node_rows.dedup_by(|a, b| a.node_id == b.node_id);
+        let live: HashSet<&str> = node_rows.iter().map(|row| row.node_id.as_str()).collect();
+        let live_ids: Vec<String> = live.iter().map(|id| id.to_string()).collect();
+        let mut edge_rows = Vec::new();
+        for chunk in live_ids.chunks(IN_LIST_CHUNK) {
+            edge_rows.extend(
+                live_edges()
+                    .filter(metis_edge_versions::Column::FromNode.is_in(chunk.to_vec()))
+                    .all(conn)
+                    .await
+                    .map_err(db_err)?
+                    .into_iter()
+                    .filter(|row| live.contains(row.to_node.as_str())),
+            );
+        }
+        edge_rows.sort_by(|a, b| {
+            (&a.edge_id, &a.from_node, &a.to_node).cmp(&(&b.edge_id, &b.from_node, &b.to_node))
+        });
+        (node_rows, edge_rows)
+    };
+    let nodes = node_rows
+        .into_iter()
+        .map(|row| decode_node(sealer, row))
+        .collect::<MnemeResult<Vec<_>>>()?;
+    let edges = edge_rows
+        .into_iter()
+        .map(|row| decode_edge(sealer, row))
+        .collect::<MnemeResult<Vec<_>>>()?;
+
+    Ok(GraphState {
+        commit_id: commit_id.to_string(),
+        nodes,
+        edges,
+    })
+}
+
+pub(super) async fn element_history<C: ConnectionTrait>(
+    conn: &C,
+    sealer: &Sealer,
+    commit_id: &str,
+    element: &ElementRef,
+) -> MnemeResult<Vec<ElementVersion>> {
+    // Lineages from the queried commit back to the root, each with the last visible sequence.
+    let mut chain: Vec<(String, i64)> = Vec::new();
+    let mut cursor = Some(commit_id.to_string());
+    while let Some(current) = cursor.take() {
+        let (lineage_id, seq) = position(conn, &current).await?;
+        cursor = metis_lineages::Entity::find_by_id(lineage_id.clone())
+            .one(conn)
+            .await
+            .map_err(db_err)?
+            .and_then(|lineage| lineage.base_commit);
+        chain.push((lineage_id, seq));
+    }
+    let depth: HashMap<&str, (usize, i64)> = chain
+        .iter()
+        .enumerate()
+        .map(|(idx, (lineage, seq))| (lineage.as_str(), (idx, *seq)))
+        .collect();
+    let lineages: Vec<String> = chain.iter().map(|(lineage, _)| lineage.clone()).collect();
+
+    let rows: Vec<VersionRow> = match element {
+        ElementRef::Node { id } => metis_node_versions::Entity::find()
+            .filter(metis_node_versions::Column::LineageId.is_in(lineages))
+            .filter(metis_node_versions::Column::NodeId.eq(id.as_str()))
+            .order_by_asc(metis_node_versions::Column::Id)
+            .all(conn)
+            .await
+            .map_err(db_err)?
+            .into_iter()
+            .map(|row| VersionRow::node(sealer, row))
+            .collect::<MnemeResult<_>>()?,
+        ElementRef::Edge { .. } | ElementRef::EdgeBetween { .. } => {
+            let matches = match element {
+                ElementRef::Edge { id } => metis_edge_versions::Column::EdgeId
+                    .eq(id.as_str())
+                    .into_condition(),
+                ElementRef::EdgeBetween { from, to } => Condition::all()
+                    .add(metis_edge_versions::Column::FromNode.eq(from.as_str()))
+                    .add(metis_edge_versions::Column::ToNode.eq(to.as_str())),
+                ElementRef::Node { .. } => unreachable!("handled above"),
+            };
+            metis_edge_versions::Entity::find()
+                .filter(metis_edge_versions::Column::LineageId.is_in(lineages))
+                .filter(matches)
+                .order_by_asc(metis_edge_versions::Column::Id)
+                .all(conn)
+                .await
+                .map_err(db_err)?
+                .into_iter()
+                .map(|row| VersionRow::edge(sealer, row))
+                .collect::<MnemeResult<_>>()?
+        }
+    };
+
+    // A version copied into nearer lineages reports its end from the nearest copy.
+    let mut nearest: HashMap<i64, (usize, Option<String>)> = HashMap::new();
+    for row in &rows {
+        let (idx, cutoff) = depth[row.lineage_id.as_str()];
+        let origin = row.origin_id.unwrap_or(row.id);
+        let ended = row
+            .valid_to
+            .filter(|to| *to <= cutoff)
+            .and(row.to_commit.clone());
+        match nearest.get(&origin) {
+            Some((best, _)) if *best <= idx => {}
+            _ => {
+                nearest.insert(origin, (idx, ended));
+            }
+        }
+    }
+
+    let mut originals: Vec<&VersionRow> = rows
+        .iter()
+        .filter(|row| row.origin_id.is_none() && row.valid_from <= depth[row.lineage_id.as_str()].1)
+        .collect();
+    originals.sort_by_key(|row| {
+        (
+            std::cmp::Reverse(depth[row.lineage_id.as_str()].0),
+            row.valid_from,
+            row.id,
+        )
+    });
+    Ok(originals
+        .into_iter()
+        .map(|row| ElementVersion {
+            valid_from: row.from_commit.clone(),
+            valid_to: nearest.get(&row.id).and_then(|(_, ended)| ended.clone()),
+            element: row.element.clone(),
+        })
+        .collect())
+}
+
+/// Assign `commit` a lineage and sequence number, forking a new lineage when needed.
+async fn place_commit<C: ConnectionTrait>(
+    conn: &C,
+    commit: &PersistedCommit,
+) -> MnemeResult<(String, i64)> {
+    let summary = &commit.summary;
+    let parent = match summary.parents.first() {
+        Some(parent) => metis_lineage_commits::Entity::find_by_id(parent.clone())
+            .one(conn)
+            .await
+            .map_err(db_err)?,
+        None => None,
+    };
+    if let Some(parent) = &parent {
+        // Extend the parent's lineage only while the parent is still its tip on the same branch.
+        let extended = metis_lineages::Entity::update_many()
+            .col_expr(metis_lineages::Column::HeadSeq, (parent.seq + 1).into())
+            .filter(metis_lineages::Column::LineageId.eq(parent.lineage_id.as_str()))
+            .filter(metis_lineages::Column::Branch.eq(summary.branch.as_str()))
+            .filter(metis_lineages::Column::HeadSeq.eq(parent.seq))
+            .exec(conn)
+            .await
+            .map_err(db_err)?
+            .rows_affected;
+        if extended == 1 {
+            record_position(conn, &summary.id, &parent.lineage_id, parent.seq + 1).await?;
+            return Ok((parent.lineage_id.clone(), parent.seq + 1));
+        }
+    }
+
+    let lineage_id = summary.id.clone();
+    metis_lineages::ActiveModel {
+        lineage_id: Set(lineage_id.clone()),
+        branch: Set(summary.branch.clone()),
+        base_commit: Set(parent.as_ref().map(|row| row.commit_id.clone())),
+        head_seq: Set(1),
+    }
+    .insert(conn)
+    .await
+    .map_err(db_err)?;
+    if let Some(parent) = &parent {
+        for (table, columns) in [
+            ("metis_node_versions", "node_id, node_type, props_json"),
+            (
+                "metis_edge_versions",
+                "edge_id, from_node, to_node, edge_type, directed, props_json",
+            ),
+        ] {
+            conn.execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                format!(
+                    "INSERT INTO {table} (lineage_id, {columns}, valid_from, valid_to, \
+                     from_commit, to_commit, origin_id) \
+                     SELECT ?, {columns}, 0, NULL, from_commit, NULL, COALESCE(origin_id, id) \
+                     FROM {table} WHERE lineage_id = ? AND valid_from <= ? \
+                     AND (valid_to IS NULL OR valid_to > ?)"
+                ),
+                vec![
+                    Value::from(lineage_id.clone()),
+                    Value::from(parent.lineage_id.clone()),
+                    Value::from(parent.seq),
+                    Value::from(parent.seq),
+                ],
+            ))
+            .await
+            .map_err(db_err)?;
+        }
+    }
+    record_position(conn, &summary.id, &lineage_id, 1).await?;
+    Ok((lineage_id, 1))
+}
+
+async fn record_position<C: ConnectionTrait>(
+    conn: &C,
+    commit_id: &str,
+    lineage_id: &str,
+    seq: i64,
+) -> MnemeResult<()> {
+    metis_lineage_commits::ActiveModel {
+        commit_id: Set(commit_id.to_string()),
+        lineage_id: Set(lineage_id.to_string()),
+        seq: Set(seq),
+    }
+    .insert(conn)
+    .await
+    .map(|_| ())
+    .map_err(db_err)
+}
+
+pub(super) async fn position<C: ConnectionTrait>(
+    conn: &C,
+    commit_id: &str,
+) -> MnemeResult<(String, i64)> {
+    metis_lineage_commits::Entity::find_by_id(commit_id.to_string())
+        .one(conn)
+        .await
+        .map_err(db_err)?
+        .map(|row| (row.lineage_id, row.seq))
+        .ok_or_else(|| MnemeError::storage(format!("unknown commit '{commit_id}'")))
+}
+
+async fn close_edges<C: ConnectionTrait>(
+    conn: &C,
+    lineage_id: &str,
+    seq: i64,
+    commit_id: &str,
+    matching: Condition,
+) -> MnemeResult<()> {
+    metis_edge_versions::Entity::update_many()
+        .col_expr(metis_edge_versions::Column::ValidTo, seq.into())
+        .col_expr(metis_edge_versions::Column::ToCommit, commit_id.into())
+        .filter(metis_edge_versions::Column::LineageId.eq(lineage_id))
+        .filter(metis_edge_versions::Column::ValidTo.is_null())
+        .filter(matching)
+        .exec(conn)
+        .await
+        .map(|_| ())
+        .map_err(db_err)
+}
+
+async fn insert_edge<C: ConnectionTrait>(
+    conn: &C,
+    sealer: &Sealer,
+    lineage_id: &str,
+    seq: i64,
+    commit_id: &str,
+    edge: &EdgeVersion,
+) -> MnemeResult<()> {
+    metis_edge_versions::ActiveModel {
+        id: Default::default(),
+        lineage_id: Set(lineage_id.to_string()),
+        edge_id: Set(edge.id.clone()),
+        from_node: Set(edge.from.clone()),
+        to_node: Set(edge.to.clone()),
+        edge_type: Set(edge.r#type.clone()),
+        directed: Set(edge.directed),
+        props_json: Set(encode_props(sealer, &edge.props)?),
+        valid_from: Set(seq),
+        valid_to: Set(None),
+        from_commit: Set(commit_id.to_string()),
+        to_commit: Set(None),
+        origin_id: Set(None),
+    }
+    .insert(conn)
+    .await
+    .map(|_| ())
+    .map_err(db_err)
+}
+
+fn node_row(
+    sealer: &Sealer,
+    lineage_id: &str,
+    seq: i64,
+    commit_id: &str,
+    node: &NodeVersion,
+) -> MnemeResult<metis_node_versions::ActiveModel> {
+    Ok(metis_node_versions::ActiveModel {
+        id: Default::default(),
+        lineage_id: Set(lineage_id.to_string()),
+        node_id: Set(node.id.clone()),
+        node_type: Set(node.r#type.clone()),
+        props_json: Set(encode_props(sealer, &node.props)?),
+        valid_from: Set(seq),
+        valid_to: Set(None),
+        from_commit: Set(commit_id.to_string()),
+        to_commit: Set(None),
+        origin_id: Set(None),
+    })
+}
+
+fn edge_id_condition(id: Option<&str>) -> Condition {
+    match id {
+        Some(id) => metis_edge_versions::Column::EdgeId.eq(id).into_condition(),
+        None => metis_edge_versions::Column::EdgeId
+            .is_null()
+            .into_condition(),
+    }
+}
+
+/// Version row reduced to what history resolution needs.
+struct VersionRow {
+    id: i64,
+    lineage_id: String,
+    valid_from: i64,
+    valid_to: Option<i64>,
+    from_commit: String,
+    to_commit: Option<String>,
+    origin_id: Option<i64>,
+    element: ElementState,
+}
+
+impl VersionRow {
+    fn node(sealer: &Sealer, row: metis_node_versions::Model) -> MnemeResult<Self> {
+        Ok(Self {
+            id: row.id,
+            lineage_id: row.lineage_id.clone(),
+            valid_from: row.valid_from,
+            valid_to: row.valid_to,
+            from_commit: row.from_commit.clone(),
+            to_commit: row.to_commit.clone(),
+            origin_id: row.origin_id,
+            element: ElementState::Node(decode_node(sealer, row)?),
+        })
+    }
+
+    fn edge(sealer: &Sealer, row: metis_edge_versions::Model) -> MnemeResult<Self> {
+        Ok(Self {
+            id: row.id,
+            lineage_id: row.lineage_id.clone(),
+            valid_from: row.valid_from,
+            valid_to: row.valid_to,
+            from_commit: row.from_commit.clone(),
+            to_commit: row.to_commit.clone(),
+            origin_id: row.origin_id,
+            element: ElementState::Edge(decode_edge(sealer, row)?),
+        })
+    }
+}
+
+fn decode_node(sealer: &Sealer, row: metis_node_versions::Model) -> MnemeResult<NodeVersion> {
+    Ok(NodeVersion {
+        id: row.node_id,
+        r#type: row.node_type,
+        props: decode_props(sealer, row.props_json)?,
+    })
+}
+
+fn decode_edge(sealer: &Sealer, row: metis_edge_versions::Model) -> MnemeResult<EdgeVersion> {
+    Ok(EdgeVersion {
+        id: row.edge_id,
+        from: row.from_node,
+        to: row.to_node,
+        r#type: row.edge_type,
+        directed: row.directed,
+        props: decode_props(sealer, row.props_json)?,
+    })
+}
+
+fn encode_props(sealer: &Sealer, props: &Option<serde_json::Value>) -> MnemeResult<Option<String>> {
+    props
+        .as_ref()
+        .map(|value| {
+            serde_json::to_string(value)
+                .map_err(|err| MnemeError::storage(format!("serialise version props: {err}")))
+                .and_then(|json| sealer.seal_text(json))
+        })
+        .transpose()
+}
+
+fn decode_props(sealer: &Sealer, json: Option<String>) -> MnemeResult<Option<serde_json::Value>> {
+    json.map(|json| {
+        serde_json::from_str(&sealer.open_text(json)?)
+            .map_err(|err| MnemeError::storage(format!("decode version props: {err}")))
+    })
+    .transpose()
+}
+
+fn db_err(err: sea_orm::DbErr) -> MnemeError {
+    MnemeError::storage(format!("SeaORM error: {err}"))
+}
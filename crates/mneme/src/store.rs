@@ -2,7 +2,10 @@
 
 use async_trait::async_trait;
 
-use crate::{MnemeResult, PersistedCommit};
+use crate::blob::BlobStore;
+use crate::search::{self, SearchHit, SearchQuery};
+use crate::versions::{self, ElementRef, ElementVersion, GraphFilter, GraphState};
+use crate::{ChangeSubscription, CommitBatch, MnemeError, MnemeResult, PersistedCommit};
 
 #[async_trait]
 pub trait Store: Send + Sync {
@@ -20,4 +23,57 @@ pub trait Store: Send + Sync {
     async fn put_tag(&self, tag: &str, commit_id: &str) -> MnemeResult<()>;
     async fn get_tag(&self, tag: &str) -> MnemeResult<Option<String>>;
     async fn list_tags(&self) -> MnemeResult<Vec<(String, String)>>;
+    /// Persist a batch of commits, tags and branch moves. Backends override this to write the
+    /// batch atomically, as the bundled backends do; the default applies each write in turn and
+    /// stops at the first failure, leaving earlier writes in place.
+    async fn put_commits(&self, batch: &CommitBatch) -> MnemeResult<()> {
+        for commit in &batch.commits {
+            self.put_commit(commit).await?;
+        }
+        for (tag, commit_id) in &batch.tags {
+            self.put_tag(tag, commit_id).await?;
+        }
+        for update in &batch.refs {
+            self.compare_and_swap_branch(
+                &update.branch,
+                update.expected.as_deref(),
+                update.next.as_deref(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+    /// Stream change events, replaying those at or after `since_ms` before switching to live ones.
+    /// The default reports that the store publishes no change events.
+    async fn subscribe(&self, _since_ms: Option<i64>) -> MnemeResult<ChangeSubscription> {
+        Err(MnemeError::storage(
+            "this store does not publish change events",
+        ))
+    }
+    /// Live nodes and edges at `commit_id` along its first-parent lineage, optionally restricted
+    /// to a subgraph. The default replays the lineage; backends with version tables override it.
+    async fn graph_state(&self, commit_id: &str, filter: &GraphFilter) -> MnemeResult<GraphState> {
+        versions::replay_graph_state(self, commit_id, filter).await
+    }
+    /// Versions of one node or edge along the first-parent lineage ending at `commit_id`, oldest
+    /// first.
+    async fn element_history(
+        &self,
+        commit_id: &str,
+        element: &ElementRef,
+    ) -> MnemeResult<Vec<ElementVersion>> {
+        versions::replay_element_history(self, commit_id, element).await
+    }
+    /// Full-text search over node properties in the state at `commit_id`, best matches first.
+    async fn search_nodes(
+        &self,
+        commit_id: &str,
+        query: &SearchQuery,
+    ) -> MnemeResult<Vec<SearchHit>> {
+        search::replay_search(self, commit_id, query).await
+    }
+    /// Content-addressed blob storage kept alongside the commits, when the backend has one.
+    fn blobs(&self) -> Option<&dyn BlobStore> {
+        None
+    }
 }
@@ -1,9 +1,40 @@
+use crate::blob::BlobRef;
 use crate::temporal::{ChangeSet, CommitSummary};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 /// Full commit payload persisted to storage.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PersistedCommit {
     pub summary: CommitSummary,
     pub change_set: ChangeSet,
+    /// Blobs held by the change set's `Blob` attributes. The writer resolves these from its
+    /// meta-model; stores count them as the commit's blob references.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub blobs: BTreeSet<BlobRef>,
+}
+
+/// Branch move applied by a [`CommitBatch`], checked like `Store::compare_and_swap_branch`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RefUpdate {
+    pub branch: String,
+    pub expected: Option<String>,
+    pub next: Option<String>,
+}
+
+/// Commits persisted together with the tags and branch moves that publish them. Commits are
+/// written in order, so a commit may name an earlier one in the batch as its parent.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CommitBatch {
+    pub commits: Vec<PersistedCommit>,
+    /// `(tag, commit_id)` pairs, upserted like `Store::put_tag`.
+    pub tags: Vec<(String, String)>,
+    /// Applied after the commits and tags, in order.
+    pub refs: Vec<RefUpdate>,
+}
+
+impl CommitBatch {
+    pub fn is_empty(&self) -> bool {
+        self.commits.is_empty() && self.tags.is_empty() && self.refs.is_empty()
+    }
 }
@@ -0,0 +1,255 @@
+//! Point-in-time graph queries answered by Mneme stores.
+//!
+//! The state at a commit is the fold of every change set along its first-parent lineage. The
+//! default [`Store`] implementations replay that lineage; the SQLite backend answers the same
+//! questions from its node/edge version tables without decoding any change sets.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fold::LineageState;
+use crate::temporal::{ChangeSet, CommitId, EdgeVersion, NodeVersion};
+use crate::{MnemeError, MnemeResult, PersistedCommit, Store};
+
+/// Restricts a [`GraphState`] to a subgraph. Empty lists match everything; edges are kept only
+/// when both endpoints survive the node filter.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphFilter {
+    #[serde(default)]
+    pub node_ids: Vec<String>,
+    #[serde(default)]
+    pub node_types: Vec<String>,
+}
+
+impl GraphFilter {
+    pub fn is_empty(&self) -> bool {
+        self.node_ids.is_empty() && self.node_types.is_empty()
+    }
+
+    pub(crate) fn matches_node(&self, node: &NodeVersion) -> bool {
+        (self.node_ids.is_empty() || self.node_ids.contains(&node.id))
+            && (self.node_types.is_empty()
+                || node
+                    .r#type
+                    .as_ref()
+                    .is_some_and(|ty| self.node_types.contains(ty)))
+    }
+}
+
+/// Live nodes and edges at a commit. Nodes are ordered by id, edges by `(id, from, to)`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphState {
+    pub commit_id: CommitId,
+    pub nodes: Vec<NodeVersion>,
+    pub edges: Vec<EdgeVersion>,
+}
+
+/// Element whose history is requested.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ElementRef {
+    Node {
+        id: String,
+    },
+    /// Edge addressed by its id.
+    Edge {
+        id: String,
+    },
+    /// Every edge between two nodes, matching how edge tombstones address edges.
+    EdgeBetween {
+        from: String,
+        to: String,
+    },
+}
+
+/// Node or edge payload of an [`ElementVersion`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "camelCase")]
+pub enum ElementState {
+    Node(NodeVersion),
+    Edge(EdgeVersion),
+}
+
+/// One version of an element along a lineage. `valid_to` is the commit that replaced or deleted
+/// it, or `None` when the version is still live at the queried commit.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementVersion {
+    pub valid_from: CommitId,
+    pub valid_to: Option<CommitId>,
+    pub element: ElementState,
+}
+
+/// Replay the first-parent lineage ending at `commit_id` (used by backends without version tables).
+pub(crate) async fn replay_graph_state<S: Store + ?Sized>(
+    store: &S,
+    commit_id: &str,
+    filter: &GraphFilter,
+) -> MnemeResult<GraphState> {
+    let mut state = LineageState::default();
+    for commit in first_parent_lineage(store, commit_id).await? {
+        state.apply(&commit.change_set);
+    }
+    let nodes: Vec<NodeVersion> = state
+        .nodes
+        .into_values()
+        .filter(|node| filter.matches_node(node))
+        .collect();
+    let edges = if filter.is_empty() {
+        state.edges.into_values().collect()
+    } else {
+        let kept: BTreeSet<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+        state
+            .edges
+            .into_values()
+            .filter(|edge| kept.contains(edge.from.as_str()) && kept.contains(edge.to.as_str()))
+            .collect()
+    };
+    Ok(GraphState {
+        commit_id: commit_id.to_string(),
+        nodes,
+        edges,
+    })
+}
+
+/// Replay the first-parent lineage ending at `commit_id`, tracking versions of one element.
+pub(crate) async fn replay_element_history<S: Store + ?Sized>(
+    store: &S,
+    commit_id: &str,
+    element: &ElementRef,
+) -> MnemeResult<Vec<ElementVersion>> {
+    let mut history = ElementHistory::new(element);
+    for commit in first_parent_lineage(store, commit_id).await? {
+        history.apply(&commit.summary.id, &commit.change_set);
+    }
+    Ok(history.entries)
+}
+
+/// Commits from the root to `commit_id` following first parents. Parents missing from the store
+/// end the walk, matching how the engine treats them.
+async fn first_parent_lineage<S: Store + ?Sized>(
+    store: &S,
+    commit_id: &str,
+) -> MnemeResult<Vec<PersistedCommit>> {
+    let mut lineage = Vec::new();
+    let mut cursor = Some(
+        store
+            .get_commit(commit_id)
+            .await?
+            .ok_or_else(|| MnemeError::storage(format!("unknown commit '{commit_id}'")))?,
+    );
+    while let Some(commit) = cursor.take() {
+        if let Some(parent) = commit.summary.parents.first() {
+            cursor = store.get_commit(parent).await?;
+        }
+        lineage.push(commit);
+    }
+    lineage.reverse();
+    Ok(lineage)
+}
+
+/// Versions of a single element, applying change sets in `LineageState` order. A version opened
+/// and closed by the same commit never became visible and is dropped.
+struct ElementHistory<'a> {
+    element: &'a ElementRef,
+    entries: Vec<ElementVersion>,
+}
+
+impl<'a> ElementHistory<'a> {
+    fn new(element: &'a ElementRef) -> Self {
+        Self {
+            element,
+            entries: Vec::new(),
+        }
+    }
+
+    fn apply(&mut self, commit_id: &str, change: &ChangeSet) {
+        if let ElementRef::Node { id } = self.element {
+            if change
+                .node_deletes
+                .iter()
+                .any(|tombstone| &tombstone.id == id)
+            {
+                self.close(commit_id, |_| true);
+            }
+            for node in change
+                .node_creates
+                .iter()
+                .chain(change.node_updates.iter())
+                .filter(|node| &node.id == id)
+            {
+                self.close(commit_id, |_| true);
+                self.open(commit_id, ElementState::Node(node.clone()));
+            }
+            return;
+        }
+        for tombstone in &change.edge_deletes {
+            self.close(commit_id, |edge| {
+                edge.from == tombstone.from && edge.to == tombstone.to
+            });
+        }
+        for edge in &change.edge_creates {
+            self.close(commit_id, |open| {
+                open.id == edge.id && open.from == edge.from && open.to == edge.to
+            });
+            if self.matches_edge(edge) {
+                self.open(commit_id, ElementState::Edge(edge.clone()));
+            }
+        }
+        for edge in &change.edge_updates {
+            match &edge.id {
+                Some(id) => self.close(commit_id, |open| open.id.as_ref() == Some(id)),
+                None => self.close(commit_id, |open| {
+                    open.from == edge.from && open.to == edge.to
+                }),
+            }
+            if self.matches_edge(edge) {
+                self.open(commit_id, ElementState::Edge(edge.clone()));
+            }
+        }
+    }
+
+    fn matches_edge(&self, edge: &EdgeVersion) -> bool {
+        match self.element {
+            ElementRef::Node { .. } => false,
+            ElementRef::Edge { id } => edge.id.as_ref() == Some(id),
+            ElementRef::EdgeBetween { from, to } => &edge.from == from && &edge.to == to,
+        }
+    }
+
+    fn open(&mut self, commit_id: &str, element: ElementState) {
+        self.entries.push(ElementVersion {
+            valid_from: commit_id.to_string(),
+            valid_to: None,
+            element,
+        });
+    }
+
+    /// Close live versions accepted by `predicate` (always true for nodes).
+    fn close(&mut self, commit_id: &str, predicate: impl Fn(&EdgeVersion) -> bool) {
+        self.entries.retain_mut(|entry| {
+            if entry.valid_to.is_some() {
+                return true;
+            }
+            let hit = match &entry.element {
+                ElementState::Node(_) => true,
+                ElementState::Edge(edge) => predicate(edge),
+            };
+            if !hit {
+                return true;
+            }
+            if entry.valid_from == commit_id {
+                return false;
+            }
+            entry.valid_to = Some(commit_id.to_string());
+            true
+        });
+    }
+}
@@ -0,0 +1,367 @@
+//! Catalogue of named workspaces, each its own datastore, under one storage root.
+//!
+//! `workspaces.json` in the storage root lists every workspace with its metadata and names the
+//! default one. New workspaces live in `workspaces/<id>/`, which is an ordinary datastore base
+//! (`datastore.json`, the SQLite file, blobs and backups). A storage root that predates the
+//! catalogue is adopted as the `default` workspace in place, so existing paths keep working.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::backup::{copy_tree, io_err};
+use crate::crypto::EncryptionMode;
+use crate::datastore::{create_datastore, datastore_encryption, datastore_path};
+use crate::lock::DatastoreLock;
+use crate::sqlite::copy_database;
+use crate::{MnemeError, MnemeResult};
+
+/// Current catalogue file version; readers reject anything newer.
+pub const CATALOGUE_VERSION: u32 = 1;
+
+const CATALOGUE_FILE: &str = "workspaces.json";
+const WORKSPACES_DIR: &str = "workspaces";
+const LEGACY_ID: &str = "default";
+
+/// One workspace and what the host last learned about it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Workspace {
+    /// Stable identifier; also the directory name for workspaces created by the catalogue.
+    pub id: String,
+    pub display_name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Datastore base relative to the storage root (`.` for an adopted legacy datastore).
+    pub dir: PathBuf,
+    pub created_at_ms: i64,
+    #[serde(default)]
+    pub last_opened_at_ms: Option<i64>,
+    /// Newest applied migration when the workspace was last opened.
+    #[serde(default)]
+    pub schema_version: Option<String>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CatalogueFile {
+    version: u32,
+    #[serde(default)]
+    default: Option<String>,
+    #[serde(default)]
+    workspaces: Vec<Workspace>,
+}
+
+/// Registry of the workspaces under a storage root. Every call reads and rewrites
+/// `workspaces.json`, so several handles on one root stay consistent.
+#[derive(Clone, Debug)]
+pub struct WorkspaceCatalogue {
+    root: PathBuf,
+}
+
+impl WorkspaceCatalogue {
+    /// Open the catalogue under `root`, adopting a pre-catalogue datastore as `default`.
+    pub fn open(root: impl Into<PathBuf>) -> MnemeResult<Self> {
+        let catalogue = Self { root: root.into() };
+        fs::create_dir_all(&catalogue.root)
+            .map_err(|err| io_err("create storage root", &catalogue.root, err))?;
+        if !catalogue.root.join(CATALOGUE_FILE).exists() && datastore_path(&catalogue.root).is_ok()
+        {
+            let legacy = Workspace {
+                id: LEGACY_ID.into(),
+                display_name: "Default".into(),
+                description: None,
+                dir: PathBuf::from("."),
+                created_at_ms: now_ms(),
+                last_opened_at_ms: None,
+                schema_version: None,
+            };
+            catalogue.save(&CatalogueFile {
+                version: CATALOGUE_VERSION,
+                default: Some(legacy.id.clone()),
+                workspaces: vec![legacy],
+            })?;
+        }
+        Ok(catalogue)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Datastore base of `workspace`, for `create_datastore`, `datastore_path` and backups.
+    pub fn base(&self, workspace: &Workspace) -> PathBuf {
+        self.root.join(&workspace.dir)
+    }
+
+    /// All workspaces in creation order.
+    pub fn list(&self) -> MnemeResult<Vec<Workspace>> {
+        Ok(self.load()?.workspaces)
+    }
+
+    pub fn get(&self, id: &str) -> MnemeResult<Workspace> {
+        find(&self.load()?, id).cloned()
+    }
+
+    /// Id of the workspace the host opens at startup.
+    pub fn default_id(&self) -> MnemeResult<Option<String>> {
+        Ok(self.load()?.default)
+    }
+
+    /// The default workspace, creating and selecting one when the catalogue is empty.
+    pub fn ensure_default(&self) -> MnemeResult<Workspace> {
+        let file = self.load()?;
+        if let Some(id) = &file.default {
+            return find(&file, id).cloned();
+        }
+        let workspace = match file.workspaces.first() {
+            Some(first) => first.clone(),
+            None => self.create("Default", None, None)?,
+        };
+        self.set_default(&workspace.id)?;
+        Ok(workspace)
+    }
+
+    /// Provision a new, empty datastore and register it. `encryption` is recorded in its
+    /// `datastore.json`; the store is keyed on first open.
+    pub fn create(
+        &self,
+        display_name: &str,
+        description: Option<&str>,
+        encryption: Option<EncryptionMode>,
+    ) -> MnemeResult<Workspace> {
+        let mut file = self.load()?;
+        let workspace = self.new_entry(&file, display_name, description)?;
+        create_datastore(&self.base(&workspace), None, encryption)?;
+        file.workspaces.push(workspace.clone());
+        self.save(&file)?;
+        Ok(workspace)
+    }
+
+    pub fn rename(&self, id: &str, display_name: &str) -> MnemeResult<Workspace> {
+        let display_name = checked_name(display_name)?;
+        self.update(id, |workspace| workspace.display_name = display_name)
+    }
+
+    pub fn set_description(&self, id: &str, description: Option<&str>) -> MnemeResult<Workspace> {
+        self.update(id, |workspace| {
+            workspace.description = description.map(str::to_string)
+        })
+    }
+
+    /// Copy `id` into a new workspace named `display_name`. The database is copied
+    /// consistently even while the source is open, and without its key.
+    pub async fn duplicate(&self, id: &str, display_name: &str) -> MnemeResult<Workspace> {
+        let source = self.get(id)?;
+        let source_base = self.base(&source);
+        let source_db = datastore_path(&source_base)?;
+        let mut workspace =
+            self.new_entry(&self.load()?, display_name, source.description.as_deref())?;
+        workspace.schema_version = source.schema_version.clone();
+        let base = self.base(&workspace);
+        let copied = async {
+            fs::create_dir_all(&base).map_err(|err| io_err("create workspace", &base, err))?;
+            let name = source_db
+                .file_name()
+                .and_then(|name| name.to_str())
+                .ok_or_else(|| MnemeError::storage("datastore file name is not UTF-8"))?;
+            let target_db = base.join(name);
+            if fs::metadata(&source_db).is_ok_and(|meta| meta.len() > 0) {
+                copy_database(&source_db, &target_db).await?;
+                copy_tree(
+                    &source_db.with_extension("blobs"),
+                    &target_db.with_extension("blobs"),
+                )
+                .await?;
+            }
+            create_datastore(&base, Some(name), Some(datastore_encryption(&source_base)))
+        }
+        .await;
+        if let Err(err) = copied {
+            let _ = fs::remove_dir_all(&base);
+            return Err(err);
+        }
+        // Reload: the catalogue may have changed while the copy ran.
+        let mut file = self.load()?;
+        file.workspaces.push(workspace.clone());
+        self.save(&file)?;
+        Ok(workspace)
+    }
+
+    /// Remove `id` and its files. The default workspace, a legacy datastore living in the
+    /// storage root itself, and a workspace another process is writing to cannot be deleted.
+    pub fn delete(&self, id: &str) -> MnemeResult<()> {
+        let mut file = self.load()?;
+        let workspace = find(&file, id)?.clone();
+        if file.default.as_deref() == Some(id) {
+            return Err(MnemeError::storage(format!(
+                "workspace '{id}' is the default; choose another default before deleting it"
+            )));
+        }
+        if workspace.dir == Path::new(".") {
+            return Err(MnemeError::storage(format!(
+                "workspace '{id}' lives in the storage root and cannot be deleted"
+            )));
+        }
+        let base = self.base(&workspace);
+        let _lock = match datastore_path(&base) {
+            Ok(path) => Some(DatastoreLock::acquire(&path)?),
+            Err(_) => None,
+        };
+        match fs::remove_dir_all(&base) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(io_err("remove workspace", &base, err)),
+        }
+        file.workspaces.retain(|entry| entry.id != id);
+        self.save(&file)
+    }
+
+    pub fn set_default(&self, id: &str) -> MnemeResult<()> {
+        let mut file = self.load()?;
+        find(&file, id)?;
+        file.default = Some(id.to_string());
+        self.save(&file)
+    }
+
+    /// Record that `id` was opened, with its schema version at that point.
+    pub fn mark_opened(&self, id: &str, schema_version: Option<&str>) -> MnemeResult<Workspace> {
+        self.update(id, |workspace| {
+            workspace.last_opened_at_ms = Some(now_ms());
+            workspace.schema_version = schema_version.map(str::to_string);
+        })
+    }
+
+    fn update(&self, id: &str, change: impl FnOnce(&mut Workspace)) -> MnemeResult<Workspace> {
+        let mut file = self.load()?;
+        let workspace = file
+            .workspaces
+            .iter_mut()
+            .find(|workspace| workspace.id == id)
+            .ok_or_else(|| unknown(id))?;
+        change(workspace);
+        let updated = workspace.clone();
+        self.save(&file)?;
+        Ok(updated)
+    }
+
+    fn new_entry(
+        &self,
+        file: &CatalogueFile,
+        display_name: &str,
+        description: Option<&str>,
+    ) -> MnemeResult<Workspace> {
+        let display_name = checked_name(display_name)?;
+        let stem = slug(&display_name);
+        let taken = |candidate: &str| {
+            file.workspaces.iter().any(|entry| entry.id == candidate)
+                || self.root.join(WORKSPACES_DIR).join(candidate).exists()
+        };
+        let id = (1..)
+            .map(|n| match n {
+                1 => stem.clone(),
+                n => format!("{stem}-{n}"),
+            })
+            .find(|candidate| !taken(candidate))
+            .unwrap_or(stem);
+        Ok(Workspace {
+            dir: Path::new(WORKSPACES_DIR).join(&id),
+            id,
+            display_name,
+            description: description.map(str::to_string),
+            created_at_ms: now_ms(),
+            last_opened_at_ms: None,
+            schema_version: None,
+        })
+    }
+
+    fn load(&self) -> MnemeResult<CatalogueFile> {
+        let path = self.root.join(CATALOGUE_FILE);
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(CatalogueFile {
+                    version: CATALOGUE_VERSION,
+                    ..CatalogueFile::default()
+                });
+            }
+            Err(err) => return Err(io_err("read workspace catalogue", &path, err)),
+        };
+        let file: CatalogueFile = serde_json::from_slice(&data)
+            .map_err(|err| MnemeError::storage(format!("decode workspace catalogue: {err}")))?;
+        if file.version > CATALOGUE_VERSION {
+            return Err(MnemeError::storage(format!(
+                "workspace catalogue version {} is newer than supported version \
+                 {CATALOGUE_VERSION}",
+                file.version
+            )));
+        }
+        Ok(file)
+    }
+
+    /// Write via a temporary file and rename so readers never see a partial catalogue.
+    fn save(&self, file: &CatalogueFile) -> MnemeResult<()> {
+        let path = self.root.join(CATALOGUE_FILE);
+        let staging = path.with_extension("json.tmp");
+        let json = serde_json::to_vec_pretty(file)
+            .map_err(|err| MnemeError::storage(format!("encode workspace catalogue: {err}")))?;
+        fs::write(&staging, json)
+            .map_err(|err| io_err("write workspace catalogue", &staging, err))?;
+        fs::rename(&staging, &path).map_err(|err| io_err("write workspace catalogue", &path, err))
+    }
+}
+
+fn find<'a>(file: &'a CatalogueFile, id: &str) -> MnemeResult<&'a Workspace> {
+    file.workspaces
+        .iter()
+        .find(|workspace| workspace.id == id)
+        .ok_or_else(|| unknown(id))
+}
+
+fn unknown(id: &str) -> MnemeError {
+    MnemeError::storage(format!("unknown workspace '{id}'"))
+}
+
+fn checked_name(display_name: &str) -> MnemeResult<String> {
+    let trimmed = display_name.trim();
+    if trimmed.is_empty() {
+        return Err(MnemeError::storage("workspace name must not be empty"));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Directory-safe id from a display name: lowercase ASCII alphanumerics joined by dashes.
+fn slug(display_name: &str) -> String {
+    let slug = display_name
+        .split(|ch: char| !ch.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "workspace".into()
+    } else {
+        slug
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::slug;
+
+    #[test]
+    fn slugs_are_directory_safe() {
+        assert_eq!(slug("Acme Corp — FY25"), "acme-corp-fy25");
+        assert_eq!(slug("  "), "workspace");
+        assert_eq!(slug("../etc"), "etc");
+    }
+}
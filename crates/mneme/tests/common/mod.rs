@@ -0,0 +1,43 @@
+//! Fixtures shared by the Mneme integration tests.
+
+#![allow(dead_code)]
+
+use aideon_mneme::PersistedCommit;
+use aideon_mneme::temporal::{ChangeSet, CommitSummary, NodeVersion};
+use serde_json::Value;
+
+/// A commit on `main` applying `change_set`; tests adjust the summary where it matters.
+pub fn commit(id: &str, parents: &[&str], change_set: ChangeSet) -> PersistedCommit {
+    let change_count = change_set.node_creates.len()
+        + change_set.node_updates.len()
+        + change_set.node_deletes.len()
+        + change_set.edge_creates.len()
+        + change_set.edge_updates.len()
+        + change_set.edge_deletes.len();
+    PersistedCommit {
+        summary: CommitSummary {
+            id: id.into(),
+            parents: parents.iter().map(|p| p.to_string()).collect(),
+            branch: "main".into(),
+            author: None,
+            time: None,
+            message: format!("commit {id}"),
+            tags: vec![],
+            change_count: change_count as u64,
+        },
+        change_set,
+        blobs: Default::default(),
+    }
+}
+
+/// A change set creating a single node.
+pub fn create_node(id: &str, node_type: &str, props: Option<Value>) -> ChangeSet {
+    ChangeSet {
+        node_creates: vec![NodeVersion {
+            id: id.into(),
+            r#type: Some(node_type.into()),
+            props,
+        }],
+        ..ChangeSet::default()
+    }
+}
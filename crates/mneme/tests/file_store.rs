@@ -0,0 +1,157 @@
+use std::fs;
+
+use aideon_mneme::{CommitBatch, FileStore, MnemeError, PersistedCommit, RefUpdate, Store};
+use tempfile::tempdir;
+
+mod common;
+
+use common::create_node;
+
+fn commit(id: &str, parents: &[&str]) -> PersistedCommit {
+    common::commit(
+        id,
+        parents,
+        create_node(&format!("n-{id}"), "Capability", None),
+    )
+}
+
+#[tokio::test]
+async fn persists_commits_refs_and_tags_across_reopen() {
+    let dir = tempdir().expect("tempdir");
+    {
+        let store = FileStore::open(dir.path()).expect("open");
+        store.put_commit(&commit("c1", &[])).await.unwrap();
+        store.put_commit(&commit("c2", &["c1"])).await.unwrap();
+        store
+            .compare_and_swap_branch("main", None, Some("c2"))
+            .await
+            .unwrap();
+        store.put_tag("snapshot/c2", "c2").await.unwrap();
+    }
+
+    let store = FileStore::open(dir.path()).expect("reopen");
+    let loaded = store.get_commit("c2").await.unwrap().expect("commit");
+    assert_eq!(loaded.summary.parents, vec!["c1".to_string()]);
+    assert_eq!(loaded.change_set.node_creates[0].id, "n-c2");
+    assert_eq!(
+        store.get_branch_head("main").await.unwrap().as_deref(),
+        Some("c2")
+    );
+    assert_eq!(
+        store.get_tag("snapshot/c2").await.unwrap().as_deref(),
+        Some("c2")
+    );
+    assert!(dir.path().join("refs.json").exists());
+    assert!(!dir.path().join("refs.json.tmp").exists());
+}
+
+#[tokio::test]
+async fn rejects_duplicates_and_conflicting_swaps() {
+    let dir = tempdir().expect("tempdir");
+    let store = FileStore::open(dir.path()).expect("open");
+    store.put_commit(&commit("c1", &[])).await.unwrap();
+    let err = store.put_commit(&commit("c1", &[])).await.unwrap_err();
+    assert!(matches!(err, MnemeError::Storage { .. }));
+
+    store
+        .compare_and_swap_branch("main", None, Some("c1"))
+        .await
+        .unwrap();
+    let err = store
+        .compare_and_swap_branch("main", None, Some("c2"))
+        .await
+        .unwrap_err();
+    match err {
+        MnemeError::ConcurrencyConflict { branch, actual, .. } => {
+            assert_eq!(branch, "main");
+            assert_eq!(actual.as_deref(), Some("c1"));
+        }
+        other => panic!("unexpected error {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn rolls_segments_and_recovers_torn_tail() {
+    let dir = tempdir().expect("tempdir");
+    {
+        let store = FileStore::open_with_segment_size(dir.path(), 256).expect("open");
+        for (idx, id) in ["c1", "c2", "c3"].iter().enumerate() {
+            let parents: Vec<&str> = if idx == 0 { vec![] } else { vec!["c1"] };
+            store.put_commit(&commit(id, &parents)).await.unwrap();
+        }
+    }
+    let segments = dir.path().join("segments");
+    let mut files: Vec<_> = fs::read_dir(&segments)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+    assert!(files.len() > 1, "expected segment rollover");
+
+    // Simulate a crash in the middle of an append.
+    let last = files.last().unwrap();
+    let mut bytes = fs::read(last).unwrap();
+    bytes.extend_from_slice(b"deadbeef {\"summary\":");
+    fs::write(last, &bytes).unwrap();
+
+    let store = FileStore::open_with_segment_size(dir.path(), 256).expect("reopen");
+    for id in ["c1", "c2", "c3"] {
+        assert!(store.get_commit(id).await.unwrap().is_some(), "{id}");
+    }
+    store.put_commit(&commit("c4", &["c3"])).await.unwrap();
+    assert!(store.get_commit("c4").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn rolls_back_a_batch_whose_refs_write_fails() {
+    let dir = tempdir().expect("tempdir");
+    let segments = dir.path().join("segments");
+    {
+        let store = FileStore::open_with_segment_size(dir.path(), 256).expect("open");
+        store.put_commit(&commit("c1", &[])).await.unwrap();
+        let before = fs::read_dir(&segments).unwrap().count();
+
+        // A directory in the way of the temp file makes the refs write fail.
+        fs::create_dir(dir.path().join("refs.json.tmp")).unwrap();
+        let batch = CommitBatch {
+            commits: vec![commit("c2", &["c1"]), commit("c3", &["c2"])],
+            tags: vec![("snapshot/c3".into(), "c3".into())],
+            refs: vec![RefUpdate {
+                branch: "main".into(),
+                expected: None,
+                next: Some("c3".into()),
+            }],
+        };
+        let err = store.put_commits(&batch).await.unwrap_err();
+        assert!(matches!(err, MnemeError::Storage { .. }));
+        assert!(store.get_commit("c2").await.unwrap().is_none());
+        assert!(store.get_commit("c3").await.unwrap().is_none());
+        assert_eq!(store.get_branch_head("main").await.unwrap(), None);
+        assert_eq!(fs::read_dir(&segments).unwrap().count(), before);
+
+        fs::remove_dir(dir.path().join("refs.json.tmp")).unwrap();
+        store.put_commits(&batch).await.unwrap();
+    }
+
+    let store = FileStore::open_with_segment_size(dir.path(), 256).expect("reopen");
+    assert!(store.get_commit("c3").await.unwrap().is_some());
+    assert_eq!(
+        store.get_branch_head("main").await.unwrap().as_deref(),
+        Some("c3")
+    );
+}
+
+#[tokio::test]
+async fn detects_checksum_mismatch() {
+    let dir = tempdir().expect("tempdir");
+    {
+        let store = FileStore::open(dir.path()).expect("open");
+        store.put_commit(&commit("c1", &[])).await.unwrap();
+    }
+    let segment = dir.path().join("segments").join("00000001.log");
+    let text = fs::read_to_string(&segment).unwrap();
+    fs::write(&segment, text.replace("commit c1", "commit cX")).unwrap();
+
+    let err = FileStore::open(dir.path()).err().expect("corruption error");
+    assert!(err.to_string().contains("checksum mismatch"));
+}
@@ -0,0 +1,117 @@
+use aideon_mneme::{
+    BlobStore, PersistedCommit, SqliteDb, Store, backups_dir, datastore_path, list_backups,
+    restore_backup,
+};
+use tempfile::tempdir;
+
+mod common;
+
+use common::create_node;
+
+fn commit(id: &str, parents: &[&str]) -> PersistedCommit {
+    let props = serde_json::json!({ "title": id });
+    common::commit(
+        id,
+        parents,
+        create_node(&format!("node-{id}"), "Document", Some(props)),
+    )
+}
+
+async fn seeded(path: &std::path::Path) -> SqliteDb {
+    let db = SqliteDb::open(path).await.expect("open");
+    db.put_commit(&commit("c1", &[])).await.unwrap();
+    db.put_commit(&commit("c2", &["c1"])).await.unwrap();
+    db.compare_and_swap_branch("main", None, Some("c2"))
+        .await
+        .unwrap();
+    db.compare_and_swap_branch("draft", None, Some("c1"))
+        .await
+        .unwrap();
+    db
+}
+
+#[tokio::test]
+async fn backup_of_a_live_store_restores_into_a_new_datastore() {
+    let dir = tempdir().expect("tempdir");
+    let db = seeded(&dir.path().join("live.sqlite")).await;
+    let blob = db.put_blob(b"diagram").await.unwrap();
+
+    let backup = dir.path().join("snapshot");
+    let manifest = db.backup_to(&backup).await.expect("backup");
+    assert_eq!(manifest.commit_count, 2);
+    assert_eq!(manifest.blob_count, 1);
+    assert_eq!(manifest.heads["main"].as_deref(), Some("c2"));
+    assert_eq!(manifest.heads["draft"].as_deref(), Some("c1"));
+
+    // Writes after the backup are not part of it.
+    db.put_commit(&commit("c3", &["c2"])).await.unwrap();
+    db.compare_and_swap_branch("main", Some("c2"), Some("c3"))
+        .await
+        .unwrap();
+
+    let base = dir.path().join("restored");
+    let restored_path = restore_backup(&backup, &base, None).await.expect("restore");
+    assert_eq!(datastore_path(&base).unwrap(), restored_path);
+    let restored = SqliteDb::open(&restored_path).await.expect("open restored");
+    assert_eq!(
+        restored.get_branch_head("main").await.unwrap().as_deref(),
+        Some("c2")
+    );
+    assert!(restored.get_commit("c3").await.unwrap().is_none());
+    let stored = restored.get_commit("c2").await.unwrap().expect("commit");
+    assert_eq!(stored.change_set, commit("c2", &["c1"]).change_set);
+    assert_eq!(
+        restored.read_blob(&blob.blob).await.unwrap().as_deref(),
+        Some(&b"diagram"[..])
+    );
+}
+
+#[tokio::test]
+async fn rotating_backups_keep_the_newest() {
+    let dir = tempdir().expect("tempdir");
+    let db = seeded(&dir.path().join("live.sqlite")).await;
+    let root = backups_dir(dir.path());
+    let mut taken = Vec::new();
+    for _ in 0..4 {
+        taken.push(db.backup_rotating(&root, 2).await.expect("backup").dir);
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+    // An interrupted backup has no manifest and is ignored.
+    std::fs::create_dir_all(root.join("backup-incomplete")).unwrap();
+
+    let kept: Vec<_> = list_backups(&root)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|info| info.dir)
+        .collect();
+    assert_eq!(kept, taken[2..].to_vec());
+    assert!(
+        db.backup_to(&kept[0]).await.is_err(),
+        "backups are not overwritten"
+    );
+}
+
+#[tokio::test]
+async fn restore_refuses_existing_data_and_corrupt_copies() {
+    let dir = tempdir().expect("tempdir");
+    let live = dir.path().join("praxis.sqlite");
+    let db = seeded(&live).await;
+    let backup = dir.path().join("snapshot");
+    db.backup_to(&backup).await.expect("backup");
+
+    let err = restore_backup(&backup, dir.path(), None)
+        .await
+        .expect_err("target holds data");
+    assert!(err.to_string().contains("already holds data"), "{err}");
+
+    let copy = backup.join("datastore.sqlite");
+    let mut bytes = std::fs::read(&copy).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xff;
+    std::fs::write(&copy, bytes).unwrap();
+    let err = restore_backup(&backup, &dir.path().join("restored"), None)
+        .await
+        .expect_err("corrupt");
+    assert!(err.to_string().contains("corrupt"), "{err}");
+}
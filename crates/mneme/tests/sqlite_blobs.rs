@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use aideon_mneme::bundle::{export_bundle, import_bundle};
+use aideon_mneme::{BlobRef, BlobStore, GcOptions, MemoryStore, PersistedCommit, SqliteDb, Store};
+use tempfile::tempdir;
+
+mod common;
+
+use common::create_node;
+
+/// A commit creating a `Document` with `props`, listing `blobs` as its blob references.
+fn commit(
+    id: &str,
+    parents: &[&str],
+    props: serde_json::Value,
+    blobs: &[&BlobRef],
+) -> PersistedCommit {
+    let mut commit = common::commit(
+        id,
+        parents,
+        create_node(&format!("node-{id}"), "Document", Some(props)),
+    );
+    commit.blobs = blobs.iter().map(|blob| (*blob).clone()).collect();
+    commit
+}
+
+#[tokio::test]
+async fn gc_removes_only_unreferenced_blobs() {
+    let dir = tempdir().expect("tempdir");
+    let db = SqliteDb::open(dir.path().join("mneme.sqlite"))
+        .await
+        .expect("open");
+    let kept = db.put_blob(b"diagram").await.unwrap();
+    let orphan = db.put_blob(b"abandoned upload").await.unwrap();
+    db.put_commit(&commit(
+        "c1",
+        &[],
+        serde_json::json!({ "diagram": kept.blob.to_string() }),
+        &[&kept.blob],
+    ))
+    .await
+    .unwrap();
+    db.compare_and_swap_branch("main", None, Some("c1"))
+        .await
+        .unwrap();
+
+    let report = db
+        .collect_garbage(&GcOptions::default())
+        .await
+        .expect("gc within retention");
+    assert_eq!(report.removed_blobs, 0, "fresh uploads survive the window");
+
+    let dry_run = db
+        .collect_garbage(&GcOptions {
+            retention: Duration::ZERO,
+            dry_run: true,
+            ..GcOptions::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(dry_run.removed_blobs, 1);
+    assert!(db.blob_info(&orphan.blob).await.unwrap().is_some());
+
+    let report = db
+        .collect_garbage(&GcOptions {
+            retention: Duration::ZERO,
+            ..GcOptions::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(report.removed_blobs, 1);
+    assert_eq!(report.removed_blob_bytes, orphan.size);
+    assert!(db.read_blob(&orphan.blob).await.unwrap().is_none());
+    assert_eq!(
+        db.read_blob(&kept.blob).await.unwrap().as_deref(),
+        Some(&b"diagram"[..])
+    );
+    let orphan_file = dir
+        .path()
+        .join("mneme.blobs")
+        .join(&orphan.blob.hash()[..2])
+        .join(orphan.blob.hash());
+    assert!(!orphan_file.exists(), "collected blob files are deleted");
+}
+
+#[tokio::test]
+async fn bundles_carry_referenced_blobs() {
+    let dir = tempdir().expect("tempdir");
+    let source = SqliteDb::open(dir.path().join("source.sqlite"))
+        .await
+        .expect("open");
+    let attachment = source.put_blob(b"architecture.pdf bytes").await.unwrap();
+    source.put_blob(b"never referenced").await.unwrap();
+    source
+        .put_commit(&commit(
+            "c1",
+            &[],
+            serde_json::json!({ "name": "plain" }),
+            &[],
+        ))
+        .await
+        .unwrap();
+    source
+        .put_commit(&commit(
+            "c2",
+            &["c1"],
+            serde_json::json!({ "file": attachment.blob.to_string() }),
+            &[&attachment.blob],
+        ))
+        .await
+        .unwrap();
+
+    let bundle_dir = dir.path().join("bundle");
+    let manifest = export_bundle(&source, &["c2".into()], &bundle_dir)
+        .await
+        .expect("export");
+    assert_eq!(manifest.commits, vec!["c1", "c2"]);
+    assert_eq!(manifest.blobs.len(), 1);
+    assert_eq!(manifest.blobs[0].blob, attachment.blob);
+
+    let target = MemoryStore::default();
+    let imported = import_bundle(&target, &bundle_dir).await.expect("import");
+    assert_eq!(imported.imported_commits, vec!["c1", "c2"]);
+    assert_eq!(imported.imported_blobs, 1);
+    let info = target
+        .blob_info(&attachment.blob)
+        .await
+        .unwrap()
+        .expect("blob imported");
+    assert_eq!(info.ref_count, 1);
+
+    let again = import_bundle(&target, &bundle_dir).await.expect("reimport");
+    assert!(again.imported_commits.is_empty());
+    assert_eq!(again.skipped_commits, vec!["c1", "c2"]);
+
+    std::fs::write(
+        bundle_dir.join("blobs").join(attachment.blob.hash()),
+        b"tampered",
+    )
+    .unwrap();
+    let err = import_bundle(&MemoryStore::default(), &bundle_dir)
+        .await
+        .expect_err("corrupt blob rejected");
+    assert!(err.to_string().contains("corrupt"));
+}
+
+#[tokio::test]
+async fn only_listed_blobs_are_referenced() {
+    let dir = tempdir().expect("tempdir");
+    let db = SqliteDb::open(dir.path().join("mneme.sqlite"))
+        .await
+        .expect("open");
+    let upload = db.put_blob(b"release notes").await.unwrap();
+    // A text attribute that happens to hold the same checksum does not keep the blob alive.
+    db.put_commit(&commit(
+        "c1",
+        &[],
+        serde_json::json!({ "checksum": upload.blob.to_string() }),
+        &[],
+    ))
+    .await
+    .unwrap();
+    db.compare_and_swap_branch("main", None, Some("c1"))
+        .await
+        .unwrap();
+    let info = db.blob_info(&upload.blob).await.unwrap().expect("stored");
+    assert_eq!(info.ref_count, 0);
+    assert!(db.get_commit("c1").await.unwrap().unwrap().blobs.is_empty());
+
+    let report = db
+        .collect_garbage(&GcOptions {
+            retention: Duration::ZERO,
+            ..GcOptions::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(report.removed_blobs, 1);
+}
@@ -0,0 +1,146 @@
+use std::path::Path;
+
+use aideon_mneme::{PersistedCommit, SqliteDb, Store};
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbBackend, Statement};
+use tempfile::tempdir;
+
+mod common;
+
+use common::create_node;
+
+fn commit(id: &str, parents: &[&str], description: &str) -> PersistedCommit {
+    let props = serde_json::json!({
+        "name": id,
+        "description": description,
+        "a/b~c": description,
+    });
+    let mut commit = common::commit(
+        id,
+        parents,
+        create_node(&format!("node-{id}"), "Capability", Some(props)),
+    );
+    commit.summary.author = Some("importer".into());
+    commit.summary.tags = vec!["baseline".into()];
+    commit
+}
+
+async fn connect(path: &Path) -> DatabaseConnection {
+    let url = format!("sqlite://{}?mode=rwc&cache=shared", path.display());
+    Database::connect(&url).await.expect("connect")
+}
+
+async fn count(conn: &DatabaseConnection, sql: &str) -> i64 {
+    let row = conn
+        .query_one(Statement::from_string(DbBackend::Sqlite, sql.to_string()))
+        .await
+        .expect("query")
+        .expect("row");
+    row.try_get_by_index(0).expect("count")
+}
+
+async fn assert_stored(db: &SqliteDb, id: &str, expected: &PersistedCommit) {
+    let stored = db.get_commit(id).await.unwrap().expect("stored commit");
+    assert_eq!(stored.summary, expected.summary);
+    assert_eq!(stored.change_set, expected.change_set);
+}
+
+#[tokio::test]
+async fn stores_change_sets_compressed_with_shared_values() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let db = SqliteDb::open(&path).await.expect("open");
+    let description = "A long imported description. ".repeat(100);
+    let first = commit("c1", &[], &description);
+    let second = commit("c2", &["c1"], &description);
+    db.put_commit(&first).await.unwrap();
+    db.put_commit(&second).await.unwrap();
+
+    assert_stored(&db, "c1", &first).await;
+    assert_stored(&db, "c2", &second).await;
+
+    let conn = connect(&path).await;
+    assert_eq!(
+        count(
+            &conn,
+            "SELECT COUNT(*) FROM commits WHERE summary_json = '' AND changes_json = ''"
+        )
+        .await,
+        2
+    );
+    assert_eq!(
+        count(
+            &conn,
+            "SELECT COUNT(*) FROM metis_commit_payloads WHERE format = 1"
+        )
+        .await,
+        2
+    );
+    assert_eq!(
+        count(&conn, "SELECT COUNT(*) FROM metis_commit_values").await,
+        1,
+        "the repeated description is stored once"
+    );
+    let stored = count(
+        &conn,
+        "SELECT SUM(length(payload)) FROM metis_commit_payloads",
+    )
+    .await
+        + count(&conn, "SELECT SUM(length(value)) FROM metis_commit_values").await;
+    assert!(
+        (stored as usize) < description.len(),
+        "stored {stored} bytes for four copies of a {} byte value",
+        description.len()
+    );
+}
+
+#[tokio::test]
+async fn reads_and_reencodes_legacy_rows() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let db = SqliteDb::open(&path).await.expect("open");
+    let description = "Legacy description text. ".repeat(40);
+    let legacy = commit("c1", &[], &description);
+    let current = commit("c2", &["c1"], "short");
+    db.put_commit(&legacy).await.unwrap();
+    db.put_commit(&current).await.unwrap();
+
+    // Rewrite c1 the way rows were stored before 0007_commit_encoding.
+    let conn = connect(&path).await;
+    conn.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "UPDATE commits SET summary_json = ?, changes_json = ? WHERE commit_id = 'c1'",
+        vec![
+            serde_json::to_string(&legacy.summary).unwrap().into(),
+            serde_json::to_string(&legacy.change_set).unwrap().into(),
+        ],
+    ))
+    .await
+    .unwrap();
+    for table in ["metis_commit_payloads", "metis_commit_value_refs"] {
+        conn.execute(Statement::from_string(
+            DbBackend::Sqlite,
+            format!("DELETE FROM {table} WHERE commit_id = 'c1'"),
+        ))
+        .await
+        .unwrap();
+    }
+    assert_stored(&db, "c1", &legacy).await;
+
+    let report = db.reencode_commits(10).await.expect("reencode");
+    assert_eq!(report.reencoded_commits, 1);
+    assert_eq!(report.remaining_commits, 0);
+    assert!(report.encoded_bytes < report.legacy_bytes);
+    assert_stored(&db, "c1", &legacy).await;
+    assert_stored(&db, "c2", &current).await;
+    assert_eq!(
+        count(
+            &conn,
+            "SELECT COUNT(*) FROM commits WHERE changes_json != ''"
+        )
+        .await,
+        0
+    );
+
+    let rerun = db.spawn_reencode().await.expect("join").expect("reencode");
+    assert_eq!(rerun.reencoded_commits, 0);
+}
@@ -0,0 +1,256 @@
+use std::path::{Path, PathBuf};
+
+use aideon_mneme::{
+    BlobRef, BlobStore, ChangeEvent, GraphFilter, KeySource, MnemeError, PersistedCommit,
+    RetryPolicy, SearchQuery, SqliteDb, Store,
+};
+use sea_orm::{ConnectionTrait, Database, DbBackend, Statement};
+use tempfile::tempdir;
+
+mod common;
+
+use common::create_node;
+
+const SECRET: &str = "Vendor contract: Initech renewal at 1.2M";
+
+fn commit(id: &str, parents: &[&str]) -> PersistedCommit {
+    let props = serde_json::json!({
+        "classification": "Restricted",
+        "notes": SECRET,
+        "terms": SECRET.repeat(20),
+    });
+    let mut commit = common::commit(
+        id,
+        parents,
+        create_node(&format!("contract-{id}"), "DataEntity", Some(props)),
+    );
+    commit.summary.author = Some("procurement".into());
+    commit.summary.message = format!("record {id}");
+    commit.summary.tags = vec!["confidential".into()];
+    commit
+}
+
+fn key_file(dir: &Path, name: &str, byte: u8) -> KeySource {
+    let path: PathBuf = dir.join(name);
+    std::fs::write(&path, [byte; 32]).expect("write key file");
+    KeySource::KeyFile(path)
+}
+
+/// Every text and blob cell in the database, concatenated.
+async fn raw_contents(path: &Path) -> String {
+    let url = format!("sqlite://{}?mode=rwc&cache=shared", path.display());
+    let conn = Database::connect(&url).await.expect("connect");
+    let tables = conn
+        .query_all(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT name FROM sqlite_master WHERE type = 'table'",
+        ))
+        .await
+        .expect("tables");
+    let mut out = String::new();
+    for table in tables {
+        let name: String = table.try_get("", "name").expect("name");
+        let columns = conn
+            .query_all(Statement::from_string(
+                DbBackend::Sqlite,
+                format!("SELECT name FROM pragma_table_info('{name}')"),
+            ))
+            .await
+            .expect("columns");
+        for column in columns {
+            let column: String = column.try_get("", "name").expect("column");
+            let rows = conn
+                .query_all(Statement::from_string(
+                    DbBackend::Sqlite,
+                    format!("SELECT CAST(\"{column}\" AS BLOB) AS cell FROM \"{name}\""),
+                ))
+                .await
+                .expect("cells");
+            for row in rows {
+                if let Ok(Some(cell)) = row.try_get::<Option<Vec<u8>>>("", "cell") {
+                    out.push_str(&String::from_utf8_lossy(&cell));
+                    out.push('\n');
+                }
+            }
+        }
+    }
+    out
+}
+
+#[tokio::test]
+async fn encrypted_store_round_trips_without_plaintext_on_disk() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let key = key_file(dir.path(), "praxis.key", 7);
+    let db = SqliteDb::open_encrypted(&path, &key).await.expect("open");
+    let first = commit("c1", &[]);
+    let second = commit("c2", &["c1"]);
+    db.put_commit(&first).await.unwrap();
+    db.put_commit(&second).await.unwrap();
+    db.compare_and_swap_branch("main", None, Some("c2"))
+        .await
+        .unwrap();
+
+    let stored = db.get_commit("c2").await.unwrap().expect("commit");
+    assert_eq!(stored.summary, second.summary);
+    assert_eq!(stored.change_set, second.change_set);
+    let state = db.graph_state("c2", &GraphFilter::default()).await.unwrap();
+    assert_eq!(state.nodes.len(), 2);
+    assert_eq!(state.nodes[0].props, first.change_set.node_creates[0].props);
+    let hits = db
+        .search_nodes("c2", &SearchQuery::new("initech"))
+        .await
+        .unwrap();
+    assert_eq!(hits.len(), 2);
+    let events = db
+        .outbox_consumer("audit", RetryPolicy::default())
+        .poll(10)
+        .await
+        .unwrap();
+    assert_eq!(events[0].payload["message"], "record c1");
+    let mut replayed = db.subscribe(Some(0)).await.unwrap();
+    match replayed.recv().await.unwrap() {
+        ChangeEvent::CommitPersisted { commit, .. } => assert_eq!(commit, first.summary),
+        other => panic!("unexpected event {other:?}"),
+    }
+    drop(replayed);
+    drop(db);
+
+    let raw = raw_contents(&path).await;
+    for plaintext in [
+        "Initech",
+        "Restricted",
+        "procurement",
+        "record c1",
+        "confidential",
+    ] {
+        assert!(
+            !raw.contains(plaintext),
+            "'{plaintext}' stored in the clear"
+        );
+    }
+    assert!(
+        raw.contains("contract-c1"),
+        "structural columns stay readable"
+    );
+
+    let reopened = SqliteDb::open_encrypted(&path, &key).await.expect("reopen");
+    let stored = reopened.get_commit("c1").await.unwrap().expect("commit");
+    assert_eq!(stored.change_set, first.change_set);
+}
+
+#[tokio::test]
+async fn opening_with_a_missing_or_wrong_key_fails_cleanly() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let key = key_file(dir.path(), "praxis.key", 7);
+    let db = SqliteDb::open_encrypted(&path, &key).await.expect("open");
+    db.put_commit(&commit("c1", &[])).await.unwrap();
+    drop(db);
+
+    let err = SqliteDb::open(&path).await.err().expect("key required");
+    assert!(matches!(err, MnemeError::KeyRequired { .. }), "{err}");
+
+    let wrong = key_file(dir.path(), "other.key", 9);
+    let err = SqliteDb::open_encrypted(&path, &wrong)
+        .await
+        .err()
+        .expect("wrong key");
+    assert!(matches!(err, MnemeError::WrongKey { .. }), "{err}");
+
+    let passphrase = KeySource::Passphrase("not a key file".into());
+    let err = SqliteDb::open_encrypted(&path, &passphrase)
+        .await
+        .err()
+        .expect("wrong mode");
+    assert!(matches!(err, MnemeError::WrongKey { .. }), "{err}");
+}
+
+#[tokio::test]
+async fn plaintext_stores_with_history_cannot_be_encrypted_in_place() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let db = SqliteDb::open(&path).await.expect("open");
+    db.put_commit(&commit("c1", &[])).await.unwrap();
+    drop(db);
+
+    let key = key_file(dir.path(), "praxis.key", 7);
+    let err = SqliteDb::open_encrypted(&path, &key)
+        .await
+        .err()
+        .expect("refused");
+    assert!(err.to_string().contains("unencrypted"), "{err}");
+}
+
+#[tokio::test]
+async fn rotating_the_key_keeps_history_readable() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let old = key_file(dir.path(), "old.key", 1);
+    let new = key_file(dir.path(), "new.key", 2);
+    let db = SqliteDb::open_encrypted(&path, &old).await.expect("open");
+    db.put_commit(&commit("c1", &[])).await.unwrap();
+    db.rotate_key(&new).await.expect("rotate");
+    db.put_commit(&commit("c2", &["c1"])).await.unwrap();
+    drop(db);
+
+    let err = SqliteDb::open_encrypted(&path, &old)
+        .await
+        .err()
+        .expect("old key retired");
+    assert!(matches!(err, MnemeError::WrongKey { .. }), "{err}");
+
+    let db = SqliteDb::open_encrypted(&path, &new).await.expect("reopen");
+    for id in ["c1", "c2"] {
+        let stored = db.get_commit(id).await.unwrap().expect("commit");
+        assert_eq!(stored.change_set, commit(id, &[]).change_set);
+    }
+}
+
+#[tokio::test]
+async fn encrypted_stores_seal_blob_files() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let key = key_file(dir.path(), "store.key", 7);
+
+    // Uploaded before the store was encrypted, so written in the clear.
+    let legacy = {
+        let db = SqliteDb::open(&path).await.expect("open");
+        db.put_blob(b"legacy attachment").await.expect("put_blob")
+    };
+
+    let db = SqliteDb::open_encrypted(&path, &key).await.expect("open");
+    // Several chunks, so the file holds more than one sealed frame.
+    let payload = SECRET.repeat(5_000).into_bytes();
+    let info = db.put_blob(&payload).await.expect("put_blob");
+    assert_eq!(
+        info.blob,
+        BlobRef::for_bytes(&payload),
+        "hash is over plaintext"
+    );
+    assert_eq!(info.size, payload.len() as u64);
+    assert_eq!(
+        db.read_blob(&info.blob).await.unwrap(),
+        Some(payload.clone())
+    );
+    assert_eq!(
+        db.read_blob(&legacy.blob).await.unwrap().as_deref(),
+        Some(&b"legacy attachment"[..])
+    );
+
+    let file = dir
+        .path()
+        .join("mneme.blobs")
+        .join(&info.blob.hash()[..2])
+        .join(info.blob.hash());
+    let on_disk = std::fs::read(&file).expect("blob file");
+    assert!(!String::from_utf8_lossy(&on_disk).contains(SECRET));
+
+    let mut tampered = on_disk;
+    let last = tampered.len() - 1;
+    tampered[last] ^= 1;
+    std::fs::write(&file, tampered).expect("tamper");
+    db.read_blob(&info.blob)
+        .await
+        .expect_err("tampered blob is rejected");
+}
@@ -0,0 +1,209 @@
+use std::time::Duration;
+
+use aideon_mneme::{
+    CompactionOptions, GcOptions, SqliteDb, Store,
+    temporal::{ChangeSet, NodeTombstone, NodeVersion},
+};
+use sea_orm::{ConnectionTrait, Database, DbBackend, Statement};
+use tempfile::tempdir;
+
+mod common;
+
+use common::commit;
+
+fn create(id: &str) -> ChangeSet {
+    ChangeSet {
+        node_creates: vec![NodeVersion {
+            id: id.into(),
+            r#type: Some("Capability".into()),
+            props: None,
+        }],
+        ..ChangeSet::default()
+    }
+}
+
+async fn count(path: &std::path::Path, sql: &str) -> i64 {
+    let url = format!("sqlite://{}?mode=rwc&cache=shared", path.display());
+    let conn = Database::connect(&url).await.expect("connect");
+    let row = conn
+        .query_one(Statement::from_string(DbBackend::Sqlite, sql.to_string()))
+        .await
+        .expect("query")
+        .expect("row");
+    row.try_get_by_index(0).expect("count")
+}
+
+#[tokio::test]
+async fn removes_unreachable_commits_and_projections() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let db = SqliteDb::open(&path).await.expect("open");
+
+    db.put_commit(&commit("c1", &[], create("a")))
+        .await
+        .unwrap();
+    db.put_commit(&commit("c2", &["c1"], create("b")))
+        .await
+        .unwrap();
+    db.put_commit(&commit("orphan", &["c1"], create("x")))
+        .await
+        .unwrap();
+    db.put_tag("snapshot/orphan", "orphan").await.unwrap();
+    db.compare_and_swap_branch("main", None, Some("c2"))
+        .await
+        .unwrap();
+
+    // Within the retention window nothing is removed.
+    let report = db.collect_garbage(&GcOptions::default()).await.unwrap();
+    assert!(report.removed_commits.is_empty());
+    assert_eq!(report.retained_commits, 1);
+
+    let dry = db
+        .collect_garbage(&GcOptions {
+            retention: Duration::ZERO,
+            dry_run: true,
+            ..GcOptions::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(dry.removed_commits, vec!["orphan".to_string()]);
+    assert!(db.get_commit("orphan").await.unwrap().is_some());
+
+    let report = db
+        .collect_garbage(&GcOptions {
+            retention: Duration::ZERO,
+            ..GcOptions::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(report.removed_commits, vec!["orphan".to_string()]);
+    assert_eq!(report.removed_events, 1);
+    assert_eq!(report.removed_node_changes, 1);
+    assert_eq!(report.removed_tags, 1);
+    assert_eq!(report.expired_reflog_entries, 1);
+    assert!(db.get_commit("orphan").await.unwrap().is_none());
+    assert!(db.get_commit("c1").await.unwrap().is_some());
+    assert!(db.get_tag("snapshot/orphan").await.unwrap().is_none());
+    assert_eq!(
+        count(
+            &path,
+            "SELECT COUNT(*) FROM metis_events WHERE commit_id = 'orphan'"
+        )
+        .await,
+        0
+    );
+}
+
+#[tokio::test]
+async fn named_tags_keep_commits_alive() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let db = SqliteDb::open(&path).await.expect("open");
+
+    db.put_commit(&commit("c1", &[], create("a")))
+        .await
+        .unwrap();
+    db.put_tag("release-1", "c1").await.unwrap();
+
+    let report = db
+        .collect_garbage(&GcOptions {
+            retention: Duration::ZERO,
+            ..GcOptions::default()
+        })
+        .await
+        .unwrap();
+    assert!(report.removed_commits.is_empty());
+    assert_eq!(report.reachable_commits, 1);
+}
+
+#[tokio::test]
+async fn compacts_history_into_checkpoint() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let db = SqliteDb::open(&path).await.expect("open");
+
+    db.put_commit(&commit("c1", &[], create("a")))
+        .await
+        .unwrap();
+    db.put_commit(&commit("c2", &["c1"], create("b")))
+        .await
+        .unwrap();
+    let delete_a = ChangeSet {
+        node_deletes: vec![NodeTombstone { id: "a".into() }],
+        ..ChangeSet::default()
+    };
+    db.put_commit(&commit("c3", &["c2"], delete_a))
+        .await
+        .unwrap();
+    db.put_commit(&commit("c4", &["c3"], create("c")))
+        .await
+        .unwrap();
+    db.compare_and_swap_branch("main", None, Some("c4"))
+        .await
+        .unwrap();
+
+    let report = db
+        .collect_garbage(&GcOptions {
+            compact: Some(CompactionOptions::new("c3")),
+            ..GcOptions::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(report.checkpoint.as_deref(), Some("checkpoint-c3"));
+    assert_eq!(report.compacted_commits, 3);
+
+    for id in ["c1", "c2", "c3"] {
+        assert!(db.get_commit(id).await.unwrap().is_none());
+    }
+    let checkpoint = db
+        .get_commit("checkpoint-c3")
+        .await
+        .unwrap()
+        .expect("checkpoint");
+    assert!(checkpoint.summary.parents.is_empty());
+    let ids: Vec<_> = checkpoint
+        .change_set
+        .node_creates
+        .iter()
+        .map(|n| n.id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["b"]);
+
+    let head = db.get_commit("c4").await.unwrap().expect("head");
+    assert_eq!(head.summary.parents, vec!["checkpoint-c3".to_string()]);
+    assert_eq!(
+        db.get_tag("snapshot/checkpoint-c3")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("checkpoint-c3")
+    );
+}
+
+#[tokio::test]
+async fn refuses_to_compact_past_branch_heads() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let db = SqliteDb::open(&path).await.expect("open");
+
+    db.put_commit(&commit("c1", &[], create("a")))
+        .await
+        .unwrap();
+    db.put_commit(&commit("c2", &["c1"], create("b")))
+        .await
+        .unwrap();
+    db.ensure_branch("feature").await.unwrap();
+    db.compare_and_swap_branch("feature", None, Some("c1"))
+        .await
+        .unwrap();
+
+    let err = db
+        .collect_garbage(&GcOptions {
+            compact: Some(CompactionOptions::new("c2")),
+            ..GcOptions::default()
+        })
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("feature"));
+    assert!(db.get_commit("c1").await.unwrap().is_some());
+}
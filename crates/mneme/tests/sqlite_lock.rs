@@ -0,0 +1,109 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use aideon_mneme::{
+    LockHolder, MnemeError, SqliteDb, Store, create_datastore, lock_holder, lock_path,
+    temporal::ChangeSet,
+};
+use tempfile::tempdir;
+
+mod common;
+
+use common::commit;
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64
+}
+
+fn plant_lock(db_path: &Path, pid: u32, host: &str, heartbeat_at_ms: i64) -> LockHolder {
+    let holder = LockHolder {
+        pid,
+        host: host.into(),
+        started_at_ms: heartbeat_at_ms,
+        heartbeat_at_ms,
+        token: "foreign".into(),
+    };
+    std::fs::write(lock_path(db_path), serde_json::to_vec(&holder).unwrap()).unwrap();
+    holder
+}
+
+async fn seeded(path: &Path) {
+    let db = SqliteDb::open(path).await.expect("open");
+    db.put_commit(&commit("c1", &[], ChangeSet::default()))
+        .await
+        .unwrap();
+    db.compare_and_swap_branch("main", None, Some("c1"))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn refuses_a_second_writer_but_allows_readers() {
+    let dir = tempdir().expect("tempdir");
+    let path = create_datastore(dir.path(), None, None).unwrap();
+    seeded(&path).await;
+    assert!(!lock_path(&path).exists(), "released on drop");
+
+    let holder = plant_lock(&path, 4242, "other-host", now_ms());
+    let err = SqliteDb::open(&path).await.err().expect("locked");
+    match &err {
+        MnemeError::Locked { holder: found, .. } => assert_eq!(found, &holder),
+        other => panic!("expected Locked, got {other:?}"),
+    }
+    assert!(err.to_string().contains("pid 4242 on other-host"), "{err}");
+    assert!(matches!(
+        create_datastore(dir.path(), None, None),
+        Err(MnemeError::Locked { .. })
+    ));
+
+    let reader = SqliteDb::open_read_only(&path).await.expect("read-only");
+    assert!(!reader.is_writable());
+    assert_eq!(
+        reader.get_branch_head("main").await.unwrap().as_deref(),
+        Some("c1")
+    );
+    assert!(
+        reader
+            .put_commit(&commit("c2", &[], ChangeSet::default()))
+            .await
+            .is_err()
+    );
+    assert_eq!(lock_holder(&path).unwrap(), Some(holder));
+}
+
+#[tokio::test]
+async fn recovers_stale_locks() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("store.sqlite");
+    plant_lock(&path, 4242, "other-host", now_ms() - 10 * 60 * 1000);
+
+    let db = SqliteDb::open(&path).await.expect("stale lock recovered");
+    let ours = lock_holder(&path).unwrap().expect("lock taken over");
+    assert_eq!(ours.pid, std::process::id());
+    assert!(db.is_writable());
+    drop(db);
+    assert!(!lock_path(&path).exists());
+
+    if cfg!(target_os = "linux") {
+        // A fresh heartbeat from a process that no longer exists on this host.
+        plant_lock(&path, u32::MAX, &ours.host, now_ms());
+        SqliteDb::open(&path).await.expect("dead holder recovered");
+    }
+}
+
+#[tokio::test]
+async fn opens_in_one_process_share_the_lock() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("store.sqlite");
+    let first = SqliteDb::open(&path).await.expect("first");
+    let second = SqliteDb::open(&path).await.expect("second");
+    let holder = lock_holder(&path).unwrap().expect("held");
+
+    drop(first);
+    assert_eq!(lock_holder(&path).unwrap(), Some(holder));
+    drop(second);
+    assert!(lock_holder(&path).unwrap().is_none());
+}
@@ -34,6 +34,7 @@ fn sample_commit() -> PersistedCommit {
             edge_updates: vec![],
             edge_deletes: vec![],
         },
+        blobs: Default::default(),
     }
 }
 
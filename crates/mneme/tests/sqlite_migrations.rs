@@ -1,7 +1,21 @@
-use aideon_mneme::SqliteDb;
-use sea_orm::{ConnectionTrait, Database, DbBackend, Statement};
+use aideon_mneme::{
+    MigrationKind, MnemeError, PersistedCommit, RetryPolicy, SqliteDb, Store,
+    temporal::{ChangeSet, CommitSummary},
+};
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbBackend, Statement};
 use tempfile::tempdir;
 
+async fn raw(path: &std::path::Path) -> DatabaseConnection {
+    let database_url = format!("sqlite://{}?mode=rwc&cache=shared", path.display());
+    Database::connect(&database_url).await.expect("connect")
+}
+
+async fn exec(conn: &DatabaseConnection, sql: &str) {
+    conn.execute(Statement::from_string(DbBackend::Sqlite, sql))
+        .await
+        .expect("execute");
+}
+
 #[tokio::test]
 async fn applies_initial_migration_once() {
     let dir = tempdir().expect("tempdir");
@@ -22,7 +36,184 @@ async fn applies_initial_migration_once() {
         ))
         .await
         .expect("query");
+    let ids: Vec<String> = rows
+        .iter()
+        .map(|row| row.try_get("", "migration_id").expect("migration id"))
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            "0001_init_schema",
+            "0002_ref_log",
+            "0003_metis_consumers",
+            "0004_metis_versions",
+            "0005_metis_search",
+            "0006_metis_blobs",
+            "0007_commit_encoding",
+            "0008_keyring",
+            "0009_seed_ref_log",
+            "0010_metis_event_seq"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn reports_status_and_plans_without_writing() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+
+    let status = SqliteDb::schema_status(&path).await.expect("status");
+    assert_eq!(status.version(), None);
+    assert_eq!(status.pending().count(), status.migrations.len());
+    let plan = SqliteDb::plan_migrations(&path).await.expect("plan");
+    assert_eq!(plan[0].id, "0001_init_schema");
+    assert!(plan[0].statements[0].starts_with("CREATE TABLE"));
+    let seed = plan
+        .iter()
+        .find(|migration| migration.id == "0009_seed_ref_log")
+        .expect("data migration");
+    assert_eq!(seed.kind, MigrationKind::Data);
+    assert!(seed.statements[0].starts_with("-- data migration"));
+    assert!(!path.exists(), "dry runs do not create the database");
+
+    let status = SqliteDb::migrate(&path).await.expect("migrate");
+    assert!(status.is_current());
+    assert_eq!(status.version(), Some("0010_metis_event_seq"));
+    assert!(SqliteDb::plan_migrations(&path).await.unwrap().is_empty());
+    let db = SqliteDb::open(&path).await.expect("open");
+    assert_eq!(db.schema().await.unwrap(), status);
+}
+
+#[tokio::test]
+async fn refuses_databases_from_a_newer_release() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    SqliteDb::open(&path).await.expect("initial open");
+    exec(
+        &raw(&path).await,
+        "INSERT INTO mneme_migrations (migration_id, applied_at_ms) VALUES ('9999_future', 0)",
+    )
+    .await;
+
+    let status = SqliteDb::schema_status(&path).await.expect("status");
+    assert!(status.is_newer_than_supported());
+    assert_eq!(status.unknown, vec!["9999_future"]);
+    let err = SqliteDb::open(&path).await.err().expect("refused");
+    assert!(
+        matches!(err, MnemeError::SchemaTooNew { ref unknown, .. } if unknown == &["9999_future"]),
+        "{err}"
+    );
+    assert!(matches!(
+        SqliteDb::plan_migrations(&path).await,
+        Err(MnemeError::SchemaTooNew { .. })
+    ));
+}
+
+#[tokio::test]
+async fn data_migration_seeds_ref_log_for_legacy_heads() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let db = SqliteDb::open(&path).await.expect("open");
+    db.put_commit(&PersistedCommit {
+        summary: CommitSummary {
+            id: "c1".into(),
+            parents: vec![],
+            branch: "main".into(),
+            author: None,
+            time: None,
+            message: "c1".into(),
+            tags: vec![],
+            change_count: 0,
+        },
+        change_set: ChangeSet::default(),
+        blobs: Default::default(),
+    })
+    .await
+    .unwrap();
+    db.compare_and_swap_branch("main", None, Some("c1"))
+        .await
+        .unwrap();
+    drop(db);
+
+    // Simulate a store whose head moved before the reflog existed.
+    let conn = raw(&path).await;
+    exec(&conn, "DELETE FROM ref_log").await;
+    exec(
+        &conn,
+        "DELETE FROM mneme_migrations WHERE migration_id = '0009_seed_ref_log'",
+    )
+    .await;
+    let pending = SqliteDb::plan_migrations(&path).await.unwrap();
+    assert_eq!(pending.len(), 1);
+
+    SqliteDb::open(&path).await.expect("reopen");
+    let rows = conn
+        .query_all(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT branch, new_commit FROM ref_log",
+        ))
+        .await
+        .expect("query");
     assert_eq!(rows.len(), 1);
-    let id: String = rows[0].try_get("", "migration_id").expect("migration id");
-    assert_eq!(id, "0001_init_schema");
+    let branch: String = rows[0].try_get("", "branch").unwrap();
+    let head: Option<String> = rows[0].try_get("", "new_commit").unwrap();
+    assert_eq!((branch.as_str(), head.as_deref()), ("main", Some("c1")));
+}
+
+#[tokio::test]
+async fn event_seq_migration_keeps_consumer_offsets() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let db = SqliteDb::open(&path).await.expect("open");
+    for id in ["c1", "c2", "c3"] {
+        db.put_commit(&PersistedCommit {
+            summary: CommitSummary {
+                id: id.into(),
+                parents: vec![],
+                branch: "main".into(),
+                author: None,
+                time: None,
+                message: id.into(),
+                tags: vec![],
+                change_count: 0,
+            },
+            change_set: ChangeSet::default(),
+            blobs: Default::default(),
+        })
+        .await
+        .unwrap();
+    }
+    let consumer = db.outbox_consumer("stats", RetryPolicy::default());
+    consumer.ack("c1").await.unwrap();
+    consumer.ack("c2").await.unwrap();
+    drop((consumer, db));
+
+    // Simulate a store from before 0010: no `seq` on events or offsets.
+    let conn = raw(&path).await;
+    for sql in [
+        "CREATE TABLE legacy_events (event_id varchar NOT NULL PRIMARY KEY, \
+         commit_id varchar NOT NULL, payload varchar NOT NULL, created_at_ms bigint NOT NULL)",
+        "INSERT INTO legacy_events SELECT event_id, commit_id, payload, created_at_ms \
+         FROM metis_events",
+        "DROP TABLE metis_events",
+        "ALTER TABLE legacy_events RENAME TO metis_events",
+        "CREATE TABLE legacy_offsets (consumer varchar NOT NULL PRIMARY KEY, \
+         cursor_at_ms bigint NOT NULL, cursor_event_id varchar NOT NULL, \
+         updated_at_ms bigint NOT NULL)",
+        "INSERT INTO legacy_offsets SELECT consumer, cursor_at_ms, cursor_event_id, updated_at_ms \
+         FROM metis_consumer_offsets",
+        "DROP TABLE metis_consumer_offsets",
+        "ALTER TABLE legacy_offsets RENAME TO metis_consumer_offsets",
+        "DELETE FROM mneme_migrations WHERE migration_id = '0010_metis_event_seq'",
+    ] {
+        exec(&conn, sql).await;
+    }
+
+    let db = SqliteDb::open(&path).await.expect("reopen");
+    let consumer = db.outbox_consumer("stats", RetryPolicy::default());
+    let offset = consumer.offset().await.unwrap().expect("offset");
+    assert_eq!((offset.seq, offset.event_id.as_str()), (2, "c2"));
+    let events = consumer.poll(10).await.unwrap();
+    let ids: Vec<&str> = events.iter().map(|event| event.event_id.as_str()).collect();
+    assert_eq!(ids, vec!["c3"]);
 }
@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use aideon_mneme::{NackOutcome, RetryPolicy, SqliteDb, Store, temporal::ChangeSet};
+use sea_orm::{ConnectionTrait, Database, DbBackend, Statement};
+use tempfile::tempdir;
+
+mod common;
+
+use common::commit;
+
+fn immediate_retries(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        initial_backoff: Duration::ZERO,
+        max_backoff: Duration::ZERO,
+    }
+}
+
+async fn seeded(ids: &[&str]) -> (tempfile::TempDir, SqliteDb) {
+    let dir = tempdir().expect("tempdir");
+    let db = SqliteDb::open(dir.path().join("mneme.sqlite"))
+        .await
+        .expect("open");
+    for id in ids {
+        db.put_commit(&commit(id, &[], ChangeSet::default()))
+            .await
+            .expect("put commit");
+    }
+    (dir, db)
+}
+
+fn ids(events: &[aideon_mneme::OutboxEvent]) -> Vec<&str> {
+    events.iter().map(|event| event.event_id.as_str()).collect()
+}
+
+#[tokio::test]
+async fn redelivers_until_acked_and_persists_offsets() {
+    let (_dir, db) = seeded(&["c1", "c2", "c3"]).await;
+    let consumer = db.outbox_consumer("metis-stats", RetryPolicy::default());
+
+    let first = consumer.poll(2).await.unwrap();
+    assert_eq!(ids(&first), vec!["c1", "c2"]);
+    assert_eq!(first[0].payload["commitId"], "c1");
+    assert_eq!(first[0].attempt, 1);
+    // Nothing acked yet: the same events come back.
+    assert_eq!(ids(&consumer.poll(2).await.unwrap()), vec!["c1", "c2"]);
+
+    // Acking out of order leaves the offset behind the unacked event.
+    consumer.ack("c2").await.unwrap();
+    assert!(consumer.offset().await.unwrap().is_none());
+    assert_eq!(ids(&consumer.poll(10).await.unwrap()), vec!["c1", "c3"]);
+    consumer.ack("c1").await.unwrap();
+    assert_eq!(consumer.offset().await.unwrap().unwrap().event_id, "c2");
+
+    // A fresh handle for the same group resumes from the stored offset; other groups start over.
+    let reopened = db.outbox_consumer("metis-stats", RetryPolicy::default());
+    assert_eq!(ids(&reopened.poll(10).await.unwrap()), vec!["c3"]);
+    let other = db.outbox_consumer("continuum", RetryPolicy::default());
+    assert_eq!(ids(&other.poll(10).await.unwrap()), vec!["c1", "c2", "c3"]);
+
+    assert!(consumer.ack("missing").await.is_err());
+}
+
+#[tokio::test]
+async fn retries_with_backoff_then_dead_letters() {
+    let (_dir, db) = seeded(&["c1", "c2"]).await;
+    let delayed = db.outbox_consumer(
+        "delayed",
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_secs(60),
+            max_backoff: Duration::from_secs(60),
+        },
+    );
+    match delayed.nack("c1", "boom").await.unwrap() {
+        NackOutcome::Retry {
+            failed_attempts, ..
+        } => assert_eq!(failed_attempts, 1),
+        other => panic!("unexpected outcome {other:?}"),
+    }
+    // The retry is not due yet, but the offset moved past it so later events flow.
+    assert_eq!(ids(&delayed.poll(10).await.unwrap()), vec!["c2"]);
+
+    let consumer = db.outbox_consumer("flaky", immediate_retries(2));
+    consumer.nack("c1", "first failure").await.unwrap();
+    let retry = consumer.poll(1).await.unwrap();
+    assert_eq!(ids(&retry), vec!["c1"]);
+    assert_eq!(retry[0].attempt, 2);
+    let outcome = consumer.nack("c1", "second failure").await.unwrap();
+    assert_eq!(outcome, NackOutcome::DeadLettered { failed_attempts: 2 });
+
+    assert_eq!(ids(&consumer.poll(10).await.unwrap()), vec!["c2"]);
+    let dead = consumer.dead_letters().await.unwrap();
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0].event_id, "c1");
+    assert_eq!(dead[0].last_error.as_deref(), Some("second failure"));
+
+    consumer.requeue_dead_letter("c1").await.unwrap();
+    let requeued = consumer.poll(1).await.unwrap();
+    assert_eq!(ids(&requeued), vec!["c1"]);
+    consumer.ack("c1").await.unwrap();
+    assert!(consumer.dead_letters().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn rewind_replays_events() {
+    let (_dir, db) = seeded(&["c1", "c2"]).await;
+    let consumer = db.outbox_consumer("rebuild", immediate_retries(1));
+    consumer.ack("c1").await.unwrap();
+    consumer.nack("c2", "fatal").await.unwrap();
+    assert!(consumer.poll(10).await.unwrap().is_empty());
+    assert_eq!(consumer.dead_letters().await.unwrap().len(), 1);
+
+    consumer.rewind(None).await.unwrap();
+    assert!(consumer.offset().await.unwrap().is_none());
+    assert!(consumer.dead_letters().await.unwrap().is_empty());
+    assert_eq!(ids(&consumer.poll(10).await.unwrap()), vec!["c1", "c2"]);
+
+    consumer.ack("c1").await.unwrap();
+    consumer.ack("c2").await.unwrap();
+    let since = consumer.poll(10).await.unwrap();
+    assert!(since.is_empty());
+    let first = db
+        .outbox_consumer("probe", RetryPolicy::default())
+        .poll(1)
+        .await
+        .unwrap();
+    consumer.rewind(Some(first[0].created_at_ms)).await.unwrap();
+    assert!(ids(&consumer.poll(10).await.unwrap()).contains(&"c1"));
+}
+
+#[tokio::test]
+async fn delivers_events_inserted_behind_the_acked_offset() {
+    let (dir, db) = seeded(&["zz"]).await;
+    let consumer = db.outbox_consumer("late", RetryPolicy::default());
+    let first = consumer.poll(10).await.unwrap();
+    consumer.ack("zz").await.unwrap();
+
+    // A smaller id at the acked millisecond, and one timestamped before it, as from a writer
+    // that read the clock before `zz` was committed.
+    for id in ["aa", "ab"] {
+        db.put_commit(&commit(id, &[], ChangeSet::default()))
+            .await
+            .unwrap();
+    }
+    let path = dir.path().join("mneme.sqlite");
+    let conn = Database::connect(format!("sqlite://{}?mode=rw", path.display()))
+        .await
+        .expect("connect");
+    for (id, at_ms) in [
+        ("aa", first[0].created_at_ms),
+        ("ab", first[0].created_at_ms - 1),
+    ] {
+        conn.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "UPDATE metis_events SET created_at_ms = ? WHERE event_id = ?",
+            [at_ms.into(), id.into()],
+        ))
+        .await
+        .expect("backdate event");
+    }
+
+    assert_eq!(ids(&consumer.poll(10).await.unwrap()), vec!["aa", "ab"]);
+    consumer.ack("aa").await.unwrap();
+    consumer.ack("ab").await.unwrap();
+    assert_eq!(consumer.offset().await.unwrap().unwrap().event_id, "ab");
+    assert!(consumer.poll(10).await.unwrap().is_empty());
+}
@@ -0,0 +1,182 @@
+use std::time::Duration;
+
+use aideon_mneme::{
+    CompactionOptions, ElementRef, GcOptions, GraphFilter, MemoryStore, PersistedCommit,
+    SearchQuery, SqliteDb, Store,
+    temporal::{ChangeSet, EdgeVersion, NodeTombstone, NodeVersion},
+};
+use sea_orm::{ConnectionTrait, Database, DbBackend, Statement};
+use tempfile::tempdir;
+
+mod common;
+
+use common::commit;
+
+fn upsert(creates: &[&str], updates: &[&str], deletes: &[&str], rev: u64) -> ChangeSet {
+    let node = |id: &&str| NodeVersion {
+        id: id.to_string(),
+        r#type: Some("Capability".into()),
+        props: Some(serde_json::json!({ "rev": rev })),
+    };
+    ChangeSet {
+        node_creates: creates.iter().map(node).collect(),
+        node_updates: updates.iter().map(node).collect(),
+        node_deletes: deletes
+            .iter()
+            .map(|id| NodeTombstone { id: id.to_string() })
+            .collect(),
+        ..ChangeSet::default()
+    }
+}
+
+fn history() -> Vec<PersistedCommit> {
+    vec![
+        commit("c1", &[], upsert(&["a", "b"], &[], &[], 1)),
+        commit("c2", &["c1"], upsert(&["c"], &["a"], &[], 2)),
+        commit("c3", &["c2"], upsert(&[], &["b"], &["c"], 3)),
+        commit("c4", &["c3"], upsert(&["d"], &["a"], &[], 4)),
+    ]
+}
+
+async fn execute(path: &std::path::Path, sql: &str) {
+    let url = format!("sqlite://{}?mode=rwc&cache=shared", path.display());
+    let conn = Database::connect(&url).await.expect("connect");
+    conn.execute(Statement::from_string(DbBackend::Sqlite, sql.to_string()))
+        .await
+        .expect("execute");
+}
+
+#[tokio::test]
+async fn backfills_commits_written_before_version_tables() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let replay = MemoryStore::default();
+    {
+        let db = SqliteDb::open(&path).await.expect("open");
+        for write in history() {
+            db.put_commit(&write).await.unwrap();
+            replay.put_commit(&write).await.unwrap();
+        }
+    }
+    // Simulate a datastore that predates the version tables.
+    for table in [
+        "metis_node_search",
+        "metis_node_versions",
+        "metis_edge_versions",
+        "metis_lineage_commits",
+        "metis_lineages",
+    ] {
+        execute(&path, &format!("DELETE FROM {table}")).await;
+    }
+
+    let db = SqliteDb::open(&path).await.expect("reopen");
+    let all = GraphFilter::default();
+    for id in ["c1", "c2", "c3", "c4"] {
+        assert_eq!(
+            db.graph_state(id, &all).await.unwrap(),
+            replay.graph_state(id, &all).await.unwrap(),
+            "state at {id}"
+        );
+    }
+    let node_a = ElementRef::Node { id: "a".into() };
+    let versions = db.element_history("c4", &node_a).await.unwrap();
+    assert_eq!(
+        versions,
+        replay.element_history("c4", &node_a).await.unwrap()
+    );
+    assert_eq!(versions.len(), 3);
+    let hits = db.search_nodes("c4", &SearchQuery::new("d")).await.unwrap();
+    assert_eq!(
+        hits.iter()
+            .map(|hit| hit.node_id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["d"]
+    );
+}
+
+#[tokio::test]
+async fn version_tables_follow_compaction() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("mneme.sqlite");
+    let db = SqliteDb::open(&path).await.expect("open");
+    for write in history() {
+        db.put_commit(&write).await.unwrap();
+    }
+    db.compare_and_swap_branch("main", None, Some("c4"))
+        .await
+        .unwrap();
+    let before = db.graph_state("c4", &GraphFilter::default()).await.unwrap();
+
+    db.collect_garbage(&GcOptions {
+        retention: Duration::ZERO,
+        compact: Some(CompactionOptions::new("c2")),
+        dry_run: false,
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(
+        db.graph_state("c4", &GraphFilter::default())
+            .await
+            .unwrap()
+            .nodes,
+        before.nodes
+    );
+    assert!(db.graph_state("c1", &GraphFilter::default()).await.is_err());
+    let versions = db
+        .element_history("c4", &ElementRef::Node { id: "a".into() })
+        .await
+        .unwrap();
+    let spans: Vec<_> = versions
+        .iter()
+        .map(|version| (version.valid_from.as_str(), version.valid_to.as_deref()))
+        .collect();
+    assert_eq!(spans, vec![("checkpoint-c2", Some("c4")), ("c4", None)]);
+}
+
+#[tokio::test]
+async fn graph_state_filters_by_more_ids_than_one_statement_binds() {
+    let dir = tempdir().expect("tempdir");
+    let db = SqliteDb::open(dir.path().join("mneme.sqlite"))
+        .await
+        .expect("open");
+    let replay = MemoryStore::default();
+    let ids: Vec<String> = (0..1_200).map(|idx| format!("n{idx:04}")).collect();
+    let mut changes = upsert(
+        &ids.iter().map(String::as_str).collect::<Vec<_>>(),
+        &[],
+        &[],
+        1,
+    );
+    changes.edge_creates = ids
+        .windows(2)
+        .map(|pair| EdgeVersion {
+            id: Some(format!("e-{}", pair[0])),
+            from: pair[0].clone(),
+            to: pair[1].clone(),
+            r#type: Some("dependsOn".into()),
+            directed: Some(true),
+            props: None,
+        })
+        .collect();
+    let write = commit("c1", &[], changes);
+    db.put_commit(&write).await.unwrap();
+    replay.put_commit(&write).await.unwrap();
+
+    // More ids than SQLite binds in one statement, with repeats and unknown ids mixed in.
+    let mut node_ids: Vec<String> = (0..40_000).map(|idx| format!("missing-{idx}")).collect();
+    node_ids.extend(ids[..1_000].iter().cloned());
+    node_ids.extend(ids[500..600].iter().cloned());
+    let filter = GraphFilter {
+        node_ids,
+        node_types: vec![],
+    };
+    let state = db.graph_state("c1", &filter).await.expect("graph_state");
+    assert_eq!(state.nodes.len(), 1_000);
+    assert_eq!(
+        state.edges.len(),
+        999,
+        "edges leaving the selection are dropped"
+    );
+    assert_eq!(state, replay.graph_state("c1", &filter).await.unwrap());
+}
@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use aideon_mneme::{FileStore, MemoryStore, SqliteDb, conformance};
+use tempfile::tempdir;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn memory_store_conforms() {
+    conformance::run_all(Arc::new(MemoryStore::default())).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn sqlite_store_conforms() {
+    let dir = tempdir().expect("tempdir");
+    let store = SqliteDb::open(dir.path().join("conformance.sqlite"))
+        .await
+        .expect("open sqlite");
+    conformance::run_all(Arc::new(store)).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn file_store_conforms() {
+    let dir = tempdir().expect("tempdir");
+    let store = FileStore::open(dir.path()).expect("open file store");
+    conformance::run_all(Arc::new(store)).await;
+}
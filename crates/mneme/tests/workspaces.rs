@@ -0,0 +1,123 @@
+use aideon_mneme::{
+    EncryptionMode, SqliteDb, Store, WorkspaceCatalogue, create_datastore, datastore_encryption,
+    datastore_path, temporal::ChangeSet,
+};
+use tempfile::tempdir;
+
+mod common;
+
+use common::commit;
+
+#[tokio::test]
+async fn manages_named_workspaces() {
+    let dir = tempdir().expect("tempdir");
+    let catalogue = WorkspaceCatalogue::open(dir.path()).expect("open");
+    assert!(catalogue.list().unwrap().is_empty());
+
+    let default = catalogue.ensure_default().expect("default");
+    assert_eq!(default.id, "default");
+    let acme = catalogue
+        .create("Acme Corp", Some("FY25 target state"), None)
+        .expect("create");
+    let again = catalogue.create("Acme Corp", None, None).expect("create");
+    assert_eq!(
+        (acme.id.as_str(), again.id.as_str()),
+        ("acme-corp", "acme-corp-2")
+    );
+    assert!(datastore_path(&catalogue.base(&acme)).is_ok());
+
+    let renamed = catalogue.rename(&again.id, "Initech").expect("rename");
+    assert_eq!(renamed.id, "acme-corp-2", "ids are stable across renames");
+    assert_eq!(
+        catalogue.get("acme-corp-2").unwrap().display_name,
+        "Initech"
+    );
+    assert!(catalogue.rename("acme-corp", "  ").is_err());
+
+    catalogue.set_default(&acme.id).expect("set default");
+    assert_eq!(catalogue.ensure_default().unwrap().id, "acme-corp");
+    assert!(
+        catalogue.delete("acme-corp").is_err(),
+        "default is protected"
+    );
+    catalogue.delete("acme-corp-2").expect("delete");
+    assert!(!dir.path().join("workspaces/acme-corp-2").exists());
+    assert!(catalogue.get("acme-corp-2").is_err());
+
+    let opened = catalogue
+        .mark_opened("acme-corp", Some("0009_seed_ref_log"))
+        .expect("mark opened");
+    assert!(opened.last_opened_at_ms.is_some());
+    let ids: Vec<_> = WorkspaceCatalogue::open(dir.path())
+        .unwrap()
+        .list()
+        .unwrap()
+        .into_iter()
+        .map(|workspace| (workspace.id, workspace.schema_version))
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            ("default".to_string(), None),
+            (
+                "acme-corp".to_string(),
+                Some("0009_seed_ref_log".to_string())
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn duplicates_an_open_workspace() {
+    let dir = tempdir().expect("tempdir");
+    let catalogue = WorkspaceCatalogue::open(dir.path()).expect("open");
+    let source = catalogue
+        .create("Client A", None, Some(EncryptionMode::None))
+        .unwrap();
+    let db = SqliteDb::open(datastore_path(&catalogue.base(&source)).unwrap())
+        .await
+        .expect("open source");
+    db.put_commit(&commit("c1", &[], ChangeSet::default()))
+        .await
+        .unwrap();
+    db.compare_and_swap_branch("main", None, Some("c1"))
+        .await
+        .unwrap();
+
+    let copy = catalogue
+        .duplicate(&source.id, "Client A (what-if)")
+        .await
+        .expect("duplicate");
+    assert_eq!(copy.id, "client-a-what-if");
+    db.put_commit(&commit("c2", &[], ChangeSet::default()))
+        .await
+        .unwrap();
+
+    let copied = SqliteDb::open(datastore_path(&catalogue.base(&copy)).unwrap())
+        .await
+        .expect("open copy");
+    assert_eq!(
+        copied.get_branch_head("main").await.unwrap().as_deref(),
+        Some("c1")
+    );
+    assert!(copied.get_commit("c2").await.unwrap().is_none());
+}
+
+#[test]
+fn adopts_a_pre_catalogue_datastore() {
+    let dir = tempdir().expect("tempdir");
+    let path = create_datastore(dir.path(), None, Some(EncryptionMode::Passphrase)).unwrap();
+    let catalogue = WorkspaceCatalogue::open(dir.path()).expect("open");
+    let legacy = catalogue.ensure_default().expect("adopted");
+    assert_eq!(legacy.id, "default");
+    assert_eq!(datastore_path(&catalogue.base(&legacy)).unwrap(), path);
+    assert_eq!(
+        datastore_encryption(&catalogue.base(&legacy)),
+        EncryptionMode::Passphrase
+    );
+
+    let other = catalogue.create("Other", None, None).unwrap();
+    catalogue.set_default(&other.id).unwrap();
+    let err = catalogue.delete("default").expect_err("root datastore");
+    assert!(err.to_string().contains("storage root"), "{err}");
+}